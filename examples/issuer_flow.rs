@@ -0,0 +1,167 @@
+//! An issuer's workflow end to end: create a DID, publish a schema as a DID-Linked Resource, then
+//! resolve both back and verify the schema's checksum.
+//!
+//! By default this runs against an in-process mock node. Set `CHEQD_MNEMONIC` to the mnemonic of
+//! a funded testnet account to run it against testnet instead:
+//!
+//! ```sh
+//! cargo run --example issuer_flow --features registrar,test-utils
+//! CHEQD_MNEMONIC="..." cargo run --example issuer_flow --features registrar,test-utils
+//! ```
+
+use std::{
+    error::Error,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use did_resolver_cheqd::{
+    proto::{
+        cheqd::resource::v2::MsgCreateResourcePayload,
+        prelude::{DidDoc, VerificationMethod},
+    },
+    registrar::{
+        CheqdRegistrar, RegistrarConfiguration,
+        keys::{Ed25519DidSigner, Secp256k1Signer},
+        resource::resource_checksum,
+    },
+    resolution::resolver::{DidCheqdResolver, DidCheqdResolverConfiguration},
+    test_utils::{LedgerFixtures, MockCheqdNode},
+};
+use sha2::{Digest, Sha256};
+
+/// The mnemonic of the mock node's account, which doesn't need funds
+const MOCK_MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon \
+                             abandon abandon abandon about";
+
+const SCHEMA: &str = r#"{"name": "ExampleSchema", "version": "1.0", "attrNames": ["name", "age"]}"#;
+
+type ExampleResult<T> = Result<T, Box<dyn Error>>;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExampleResult<()> {
+    run(std::env::var("CHEQD_MNEMONIC").ok()).await
+}
+
+/// Run the workflow against testnet with the account of `mnemonic`, or against a mock node
+async fn run(mnemonic: Option<String>) -> ExampleResult<()> {
+    // keep the mock node running for the whole workflow
+    let mut mock_node = None;
+    let (registrar_config, resolver_config) = match &mnemonic {
+        Some(_) => (
+            RegistrarConfiguration::testnet(),
+            DidCheqdResolverConfiguration::default(),
+        ),
+        None => {
+            let node = MockCheqdNode::start(LedgerFixtures::new()).await?;
+            let configs = (
+                RegistrarConfiguration {
+                    grpc_url: node.url(),
+                    ..RegistrarConfiguration::testnet()
+                },
+                DidCheqdResolverConfiguration::builder()
+                    .networks(vec![node.network("testnet")])
+                    .build(),
+            );
+            mock_node = Some(node);
+            configs
+        }
+    };
+    let account = Secp256k1Signer::from_mnemonic(mnemonic.as_deref().unwrap_or(MOCK_MNEMONIC))?;
+    let registrar = CheqdRegistrar::connect(registrar_config, Arc::new(account)).await?;
+    let resolver = DidCheqdResolver::new(resolver_config);
+
+    // a fresh key, and a DID identified by a UUID derived from it
+    let seed = Sha256::digest(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)?
+            .as_nanos()
+            .to_le_bytes(),
+    );
+    let did = format!("did:cheqd:testnet:{}", uuid(&seed));
+    let key = Ed25519DidSigner::new(&format!("{did}#key-1"), &seed)?;
+    let did_doc = DidDoc {
+        context: vec![
+            "https://www.w3.org/ns/did/v1".into(),
+            "https://w3id.org/security/suites/ed25519-2020/v1".into(),
+        ],
+        id: did.clone(),
+        controller: vec![did.clone()],
+        verification_method: vec![VerificationMethod {
+            id: format!("{did}#key-1"),
+            verification_method_type: "Ed25519VerificationKey2020".into(),
+            controller: did.clone(),
+            verification_material: key.public_key_multibase(),
+        }],
+        authentication: vec![format!("{did}#key-1")],
+        ..Default::default()
+    };
+
+    // 1. create the DID
+    let tx = registrar
+        .create_did(did_doc.clone(), &uuid(&Sha256::digest(seed)), &[&key])
+        .await?;
+    registrar.wait_for_tx(&tx.txhash).await?;
+    println!("created {did} in {}", tx.txhash);
+
+    // 2. publish a schema in the DID's collection
+    let payload = MsgCreateResourcePayload {
+        data: SCHEMA.as_bytes().to_vec(),
+        id: uuid(&Sha256::digest(SCHEMA)),
+        name: "ExampleSchema".into(),
+        version: "1.0".into(),
+        resource_type: "anonCredsSchema".into(),
+        ..Default::default()
+    };
+    let created = registrar.create_resource(&did, payload, &[&key]).await?;
+    registrar.wait_for_tx(&created.tx.txhash).await?;
+    println!("published {} in {}", created.did_url, created.tx.txhash);
+
+    // 3. resolve both back
+    let resolved = resolver.resolve_did_doc(resolver.parse(&did)?).await?;
+    if resolved.did_doc != did_doc {
+        return Err(format!("resolved DID document differs: {:?}", resolved.did_doc).into());
+    }
+    let did_url = format!("{}?checksum={}", created.did_url, created.checksum);
+    let resource = resolver
+        .resolve_resource(&did_url, resolver.parse(&did_url)?)
+        .await?;
+
+    // 4. verify the schema: the resolver checked its data against the `checksum` parameter, and
+    // the ledger recorded the checksum of the published data
+    let checksum = resource_checksum(SCHEMA.as_bytes());
+    if resource.data != SCHEMA.as_bytes() || resource.metadata.checksum != checksum {
+        return Err(format!("resolved resource differs: {:?}", resource.metadata).into());
+    }
+    println!("resolved {did_url} ({})", resource.metadata.media_type);
+
+    drop(mock_node);
+    Ok(())
+}
+
+/// Format 16 bytes of a digest as a version 4 UUID
+fn uuid(digest: &[u8]) -> String {
+    let mut bytes = [0; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_issuer_flow_against_mock_node() {
+        run(None).await.unwrap();
+    }
+}
@@ -0,0 +1,19 @@
+#![no_main]
+
+use did_resolver_cheqd::{
+    proto::cheqd::did::v2::DidDoc,
+    resolution::transformer::{cheqd_diddoc_to_json, to_canonical_json_vec},
+};
+use libfuzzer_sys::fuzz_target;
+use prost::Message;
+
+// arbitrary (potentially malformed) DID documents from the ledger must be transformed or rejected
+// with an error, never a panic
+fuzz_target!(|data: &[u8]| {
+    let Ok(doc) = DidDoc::decode(data) else {
+        return;
+    };
+    if let Ok(json) = cheqd_diddoc_to_json(doc) {
+        to_canonical_json_vec(&json).expect("transformed DID documents are serializable");
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use did_resolver_cheqd::resolution::parser::DidCheqdParser;
+use libfuzzer_sys::fuzz_target;
+
+// arbitrary (potentially hostile) DIDs & DID URLs must be rejected with an error, never a panic
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = DidCheqdParser::parse(input) {
+        let _ = parsed.did_url_metadata();
+    }
+});
@@ -0,0 +1,45 @@
+use std::fmt;
+
+/// Structured context describing what a [super::DidCheqdError] refers to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// the DID (or DID URL) being resolved
+    pub did: Option<String>,
+    /// the namespace of the network being queried
+    pub namespace: Option<String>,
+    /// the gRPC URL of the node being queried
+    pub endpoint: Option<String>,
+    /// the operation being performed, e.g. a resolver method or gRPC method name
+    pub operation: Option<String>,
+}
+
+impl ErrorContext {
+    /// Fill any fields missing from this context with those of `other`
+    pub(crate) fn merge(&mut self, other: ErrorContext) {
+        self.did = self.did.take().or(other.did);
+        self.namespace = self.namespace.take().or(other.namespace);
+        self.endpoint = self.endpoint.take().or(other.endpoint);
+        self.operation = self.operation.take().or(other.operation);
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields = [
+            ("operation", &self.operation),
+            ("did", &self.did),
+            ("namespace", &self.namespace),
+            ("endpoint", &self.endpoint),
+        ];
+        let mut first = true;
+        for (name, value) in fields {
+            let Some(value) = value else { continue };
+            if !first {
+                f.write_str(", ")?;
+            }
+            write!(f, "{name}: {value}")?;
+            first = false;
+        }
+        Ok(())
+    }
+}
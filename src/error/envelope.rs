@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+use super::DidCheqdError;
+
+/// A serializable description of a [DidCheqdError], following the error properties of
+/// [DID resolution metadata](https://w3c-ccg.github.io/did-resolution/#did-resolution-metadata),
+/// e.g. for the bodies of HTTP error responses:
+///
+/// ```json
+/// {"error": "notFound", "errorMessage": "...", "details": {"did": "did:cheqd:mainnet:..."}}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorEnvelope {
+    /// the DID resolution error value: `invalidDid`, `invalidDidUrl`, `notFound`,
+    /// `methodNotSupported`, `representationNotSupported` or `internalError`
+    pub error: String,
+    /// a human readable description of the error
+    pub error_message: String,
+    /// cheqd-specific details of what the error refers to
+    #[serde(default, skip_serializing_if = "ErrorDetails::is_empty")]
+    pub details: ErrorDetails,
+}
+
+/// The cheqd-specific details of an [ErrorEnvelope], see [super::context::ErrorContext]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub did: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+    /// the gRPC status code returned by the node, see [DidCheqdError::status_code]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grpc_status: Option<i32>,
+    /// how many seconds the node asked callers to wait before retrying, see
+    /// [DidCheqdError::retry_after]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<u64>,
+}
+
+impl ErrorDetails {
+    pub fn is_empty(&self) -> bool {
+        *self == ErrorDetails::default()
+    }
+}
+
+impl From<&DidCheqdError> for ErrorEnvelope {
+    fn from(e: &DidCheqdError) -> Self {
+        let error = match e.code() {
+            "unsupportedNetwork" => "methodNotSupported",
+            code => code,
+        };
+        let context = e.context().cloned().unwrap_or_default();
+        ErrorEnvelope {
+            error: error.to_owned(),
+            error_message: e.root().to_string(),
+            details: ErrorDetails {
+                did: context.did,
+                namespace: context.namespace,
+                endpoint: context.endpoint,
+                operation: context.operation,
+                grpc_status: e.status_code().map(i32::from),
+                retry_after: e.retry_after().map(|delay| delay.as_secs()),
+            },
+        }
+    }
+}
+
+impl DidCheqdError {
+    /// A serializable description of the error, see [ErrorEnvelope]
+    pub fn to_envelope(&self) -> ErrorEnvelope {
+        ErrorEnvelope::from(self)
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::error::context::ErrorContext;
+
+    #[test]
+    fn test_error_envelope_serialization() {
+        let e = DidCheqdError::NonSuccessResponse(Box::new(tonic::Status::not_found("missing")))
+            .with_context(ErrorContext {
+                did: Some("did:cheqd:mainnet:abc".into()),
+                operation: Some("query_did_doc".into()),
+                ..Default::default()
+            });
+        assert_eq!(
+            serde_json::to_value(e.to_envelope()).unwrap(),
+            json!({
+                "error": "notFound",
+                "errorMessage": e.root().to_string(),
+                "details": {
+                    "did": "did:cheqd:mainnet:abc",
+                    "operation": "query_did_doc",
+                    "grpcStatus": 5,
+                },
+            })
+        );
+
+        let e = DidCheqdError::InvalidDidUrl("bad path".into());
+        assert_eq!(
+            serde_json::to_value(e.to_envelope()).unwrap(),
+            json!({"error": "invalidDidUrl", "errorMessage": "Invalid DID Url: bad path"})
+        );
+        assert_eq!(
+            DidCheqdError::DeadlineExceeded.to_envelope().error,
+            "internalError"
+        );
+    }
+}
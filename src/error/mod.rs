@@ -1,10 +1,33 @@
+use std::time::Duration;
+
+use context::ErrorContext;
 use parsing::ParsingErrorSource;
+use prost::Message;
 use thiserror::Error;
 
+pub mod context;
+pub mod envelope;
 pub mod parsing;
 
 pub type DidCheqdResult<T> = Result<T, DidCheqdError>;
 
+/// An error of the resolver, registrar or parser.
+///
+/// Errors of resolutions & queries carry context about what failed (the DID, network, node &
+/// operation), so are returned as [DidCheqdError::WithContext] wrapping the underlying error,
+/// rather than as that error itself. Match on [DidCheqdError::root] to tell them apart:
+///
+/// ```
+/// use did_resolver_cheqd::error::DidCheqdError;
+///
+/// fn is_missing(e: &DidCheqdError) -> bool {
+///     matches!(e.root(), DidCheqdError::ResourceNotFound(_))
+/// }
+///
+/// let e = DidCheqdError::ResourceNotFound("r1".into()).with_context(Default::default());
+/// assert!(matches!(e, DidCheqdError::WithContext { .. }));
+/// assert!(is_missing(&e));
+/// ```
 #[derive(Error, Debug)]
 #[non_exhaustive]
 pub enum DidCheqdError {
@@ -20,14 +43,299 @@ pub enum DidCheqdError {
     NonSuccessResponse(#[from] Box<tonic::Status>),
     #[error("Response from resolver is invalid: {0}")]
     InvalidResponse(String),
+    #[error("Response from node exceeds the maximum size: {0}")]
+    ResponseTooLarge(String),
     #[error("Invalid DID Document structure resolved: {0}")]
     InvalidDidDocument(String),
+    #[error("Invalid DID: {0}")]
+    InvalidDid(String),
     #[error("Invalid DID Url: {0}")]
     InvalidDidUrl(String),
+    #[error("Inconsistent responses from quorum nodes: {0}")]
+    InconsistentResponses(String),
     #[error("Resource could not be found: {0}")]
     ResourceNotFound(String),
+    #[error("Resource exceeds the maximum size: {0}")]
+    ResourceTooLarge(String),
+    #[error("Resource does not match the expected checksum: {0}")]
+    ChecksumMismatch(String),
+    #[error("Unexpected resource media type: {0}")]
+    UnexpectedMediaType(String),
+    #[error("Transaction failed: {0}")]
+    TxFailed(String),
+    #[error("Transaction ran out of gas: {0}")]
+    OutOfGas(String),
+    #[error("Transaction is not authorized: {0}")]
+    Unauthorized(String),
+    #[error("Transaction fee is insufficient: {0}")]
+    InsufficientFee(String),
+    #[error("Transaction was not confirmed in time: {0}")]
+    TxTimeout(String),
+    #[error("Resolution deadline exceeded")]
+    DeadlineExceeded,
+    #[error("Resolver has been shut down")]
+    ShutDown,
     #[error("Parsing error: {0}")]
     ParsingError(#[from] ParsingErrorSource),
     #[error(transparent)]
     Other(#[from] Box<dyn std::error::Error + Send + Sync>),
+    /// An error with structured context about what it refers to. See [DidCheqdError::context]
+    /// & [DidCheqdError::root].
+    #[error("{source} ({context})")]
+    WithContext {
+        context: Box<ErrorContext>,
+        source: Box<DidCheqdError>,
+    },
+}
+
+impl DidCheqdError {
+    /// Attach context to this error, merging it with any existing context (existing fields
+    /// take precedence, as they are more specific).
+    pub fn with_context(self, context: ErrorContext) -> Self {
+        match self {
+            DidCheqdError::WithContext {
+                context: mut existing,
+                source,
+            } => {
+                existing.merge(context);
+                DidCheqdError::WithContext {
+                    context: existing,
+                    source,
+                }
+            }
+            source => DidCheqdError::WithContext {
+                context: Box::new(context),
+                source: Box::new(source),
+            },
+        }
+    }
+
+    /// The underlying error, without any attached context
+    pub fn root(&self) -> &DidCheqdError {
+        match self {
+            DidCheqdError::WithContext { source, .. } => source.root(),
+            e => e,
+        }
+    }
+
+    /// The structured context attached to this error, if any
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            DidCheqdError::WithContext { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// The DID (or DID URL) this error refers to, if known
+    pub fn did(&self) -> Option<&str> {
+        self.context().and_then(|c| c.did.as_deref())
+    }
+
+    /// The namespace of the network this error refers to, if known
+    pub fn namespace(&self) -> Option<&str> {
+        self.context().and_then(|c| c.namespace.as_deref())
+    }
+
+    /// The gRPC URL of the node this error refers to, if known
+    pub fn endpoint(&self) -> Option<&str> {
+        self.context().and_then(|c| c.endpoint.as_deref())
+    }
+
+    /// The operation this error occurred during, if known
+    pub fn operation(&self) -> Option<&str> {
+        self.context().and_then(|c| c.operation.as_deref())
+    }
+
+    /// A stable, machine-readable code for the error, for API layers to branch on instead of
+    /// the [Display](std::fmt::Display) text. The codes follow the DID resolution error values:
+    /// - `invalidDid`: a malformed DID
+    /// - `invalidDidUrl`: a malformed DID URL, e.g. with unsupported or conflicting parameters
+    /// - `notFound`: the DID (version) or resource doesn't exist
+    /// - `methodNotSupported`: not a did:cheqd DID
+    /// - `unsupportedNetwork`: a did:cheqd DID of a namespace without a configured network
+    /// - `representationNotSupported`: a resource media type which can't be served
+    /// - `internalError`: any other failure, e.g. of the network or the node
+    ///
+    /// `unsupportedNetwork` is cheqd-specific: [ErrorEnvelope](envelope::ErrorEnvelope)s report
+    /// it as `methodNotSupported`.
+    pub fn code(&self) -> &'static str {
+        match self.root() {
+            DidCheqdError::InvalidDid(_) => "invalidDid",
+            DidCheqdError::InvalidDidUrl(_) => "invalidDidUrl",
+            DidCheqdError::ResourceNotFound(_) => "notFound",
+            DidCheqdError::NonSuccessResponse(status) if status.code() == tonic::Code::NotFound => {
+                "notFound"
+            }
+            DidCheqdError::MethodNotSupported(_) => "methodNotSupported",
+            DidCheqdError::NetworkNotSupported(_) => "unsupportedNetwork",
+            DidCheqdError::UnexpectedMediaType(_) => "representationNotSupported",
+            _ => "internalError",
+        }
+    }
+
+    /// The gRPC status code returned by the node, if this is a [DidCheqdError::NonSuccessResponse]
+    pub fn status_code(&self) -> Option<tonic::Code> {
+        match self.root() {
+            DidCheqdError::NonSuccessResponse(status) => Some(status.code()),
+            _ => None,
+        }
+    }
+
+    /// How long the node asked callers to wait before retrying, if this is a
+    /// [DidCheqdError::NonSuccessResponse] carrying a hint.
+    ///
+    /// Hints are read from a `retry-after` metadata entry (in seconds), or from a
+    /// `google.rpc.RetryInfo` in the status details.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self.root() {
+            DidCheqdError::NonSuccessResponse(status) => retry_after_hint(status),
+            _ => None,
+        }
+    }
+}
+
+const RETRY_INFO_TYPE_URL: &str = "type.googleapis.com/google.rpc.RetryInfo";
+
+/// `google.rpc.Status`, as encoded in the `grpc-status-details-bin` trailer
+#[derive(Clone, PartialEq, Message)]
+struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    code: i32,
+    #[prost(string, tag = "2")]
+    message: String,
+    #[prost(message, repeated, tag = "3")]
+    details: Vec<prost_types::Any>,
+}
+
+/// `google.rpc.RetryInfo`
+#[derive(Clone, PartialEq, Message)]
+struct RetryInfo {
+    #[prost(message, optional, tag = "1")]
+    retry_delay: Option<prost_types::Duration>,
+}
+
+fn retry_after_hint(status: &tonic::Status) -> Option<Duration> {
+    let from_metadata = status
+        .metadata()
+        .get("retry-after")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs);
+    if from_metadata.is_some() {
+        return from_metadata;
+    }
+
+    let details = RpcStatus::decode(status.details()).ok()?;
+    details
+        .details
+        .iter()
+        .filter(|any| any.type_url == RETRY_INFO_TYPE_URL)
+        .find_map(|any| RetryInfo::decode(any.value.as_slice()).ok()?.retry_delay)
+        .and_then(|delay| Duration::try_from(delay).ok())
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use tonic::{Code, Status};
+
+    use super::*;
+
+    #[test]
+    fn test_context_is_merged_and_displayed() {
+        let e = DidCheqdError::ResourceNotFound("r1".into())
+            .with_context(ErrorContext {
+                endpoint: Some("https://grpc.cheqd.net:443".into()),
+                operation: Some("Resource".into()),
+                ..Default::default()
+            })
+            .with_context(ErrorContext {
+                did: Some("did:cheqd:mainnet:abc".into()),
+                operation: Some("query_resource".into()),
+                ..Default::default()
+            });
+
+        assert!(matches!(e.root(), DidCheqdError::ResourceNotFound(_)));
+        assert_eq!(e.did(), Some("did:cheqd:mainnet:abc"));
+        assert_eq!(e.operation(), Some("Resource"));
+        assert_eq!(
+            e.to_string(),
+            "Resource could not be found: r1 (operation: Resource, did: did:cheqd:mainnet:abc, \
+             endpoint: https://grpc.cheqd.net:443)"
+        );
+    }
+
+    #[test]
+    fn test_error_codes() {
+        #[cfg(feature = "resolver")]
+        {
+            use crate::resolution::parser::{DidCheqdParser, ParserOptions};
+
+            let parse = |input: &str| {
+                let options = ParserOptions {
+                    require_namespace: true,
+                    ..Default::default()
+                };
+                DidCheqdParser::parse_with_options(input, options)
+                    .unwrap_err()
+                    .code()
+            };
+            assert_eq!(parse("did:key:z6Mk"), "methodNotSupported");
+            assert_eq!(parse("did:cheqd:abc"), "invalidDid");
+            assert_eq!(parse("did:cheqd:mainnet:abc/unknown"), "invalidDidUrl");
+        }
+
+        let not_found = DidCheqdError::NonSuccessResponse(Box::new(Status::not_found("missing")))
+            .with_context(ErrorContext::default());
+        assert_eq!(not_found.code(), "notFound");
+        let network = DidCheqdError::NetworkNotSupported("devnet".into());
+        assert_eq!(network.code(), "unsupportedNetwork");
+        assert_eq!(network.to_envelope().error, "methodNotSupported");
+        assert_eq!(DidCheqdError::DeadlineExceeded.code(), "internalError");
+    }
+
+    #[test]
+    fn test_status_code_and_retry_after_from_metadata() {
+        let mut status = Status::resource_exhausted("rate limited");
+        status
+            .metadata_mut()
+            .insert("retry-after", "7".parse().unwrap());
+        let e = DidCheqdError::NonSuccessResponse(Box::new(status)).with_context(ErrorContext {
+            endpoint: Some("https://grpc.cheqd.net:443".into()),
+            ..Default::default()
+        });
+
+        assert_eq!(e.status_code(), Some(Code::ResourceExhausted));
+        assert_eq!(e.retry_after(), Some(Duration::from_secs(7)));
+    }
+
+    #[test]
+    fn test_retry_after_from_status_details() {
+        let retry_info = RetryInfo {
+            retry_delay: Some(prost_types::Duration {
+                seconds: 2,
+                nanos: 500_000_000,
+            }),
+        };
+        let details = RpcStatus {
+            code: Code::Unavailable as i32,
+            message: "node syncing".into(),
+            details: vec![prost_types::Any {
+                type_url: RETRY_INFO_TYPE_URL.into(),
+                value: retry_info.encode_to_vec(),
+            }],
+        };
+        let status = Status::with_details(
+            Code::Unavailable,
+            "node syncing",
+            details.encode_to_vec().into(),
+        );
+        let e = DidCheqdError::NonSuccessResponse(Box::new(status));
+
+        assert_eq!(e.status_code(), Some(Code::Unavailable));
+        assert_eq!(e.retry_after(), Some(Duration::from_millis(2500)));
+        assert_eq!(
+            DidCheqdError::ResourceNotFound("r1".into()).status_code(),
+            None
+        );
+    }
 }
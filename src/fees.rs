@@ -0,0 +1,166 @@
+//! Fee estimation for cheqd ledger writes (DIDs & DID-Linked Resources), for integrators which
+//! resolve with this crate but also write to the ledger.
+//!
+//! Fees are priced by the ledger's feemarket module: a transaction pays its gas limit times the
+//! current gas price of the fee denom. [FeeQueryClient] queries the gas price and computes the fee
+//! to attach to a transaction; simulating the transaction for its gas limit is left to the
+//! transaction builder.
+
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    proto::prelude::{Coin, DecCoin, FeemarketQueryClient, GasPriceRequest},
+};
+
+/// The denom fees are paid in on cheqd networks
+pub const CHEQD_FEE_DENOM: &str = "ncheq";
+
+/// The number of decimal places of a cosmos `LegacyDec`, which is encoded as an integer in proto
+/// messages (e.g. a gas price of `0.5` as `500000000000000000`)
+const DEC_PRECISION: u32 = 18;
+
+/// A thin client for a cheqd node's feemarket queries
+#[derive(Debug, Clone)]
+pub struct FeeQueryClient {
+    client: FeemarketQueryClient<Channel>,
+}
+
+impl FeeQueryClient {
+    /// Connect to the gRPC endpoint of a cheqd node, e.g. `https://grpc.cheqd.net:443`
+    pub async fn connect(grpc_url: &str) -> DidCheqdResult<Self> {
+        Ok(Self::new(connect_channel(grpc_url).await?))
+    }
+
+    /// Query over an already established channel, e.g. one shared with other clients
+    pub fn new(channel: Channel) -> Self {
+        Self {
+            client: FeemarketQueryClient::new(channel),
+        }
+    }
+
+    /// The current gas price in a denom, with its amount as a decimal (e.g. `"5000.0"`)
+    pub async fn gas_price(&mut self, denom: &str) -> DidCheqdResult<DecCoin> {
+        let request = GasPriceRequest {
+            denom: denom.to_string(),
+        };
+        let response = self
+            .client
+            .gas_price(request)
+            .await
+            .map_err(|e| DidCheqdError::NonSuccessResponse(Box::new(e)))?;
+        let price = response
+            .into_inner()
+            .price
+            .ok_or(DidCheqdError::InvalidResponse(
+                "GasPrice query did not return a price".into(),
+            ))?;
+        Ok(DecCoin {
+            amount: format_dec(parse_dec(&price.amount)?),
+            ..price
+        })
+    }
+
+    /// The fee, at the current gas price in a denom, of a transaction with the given gas limit
+    pub async fn estimate_fee(&mut self, gas_limit: u64, denom: &str) -> DidCheqdResult<Coin> {
+        let price = self.gas_price(denom).await?;
+        fee_for_gas(&price, gas_limit)
+    }
+}
+
+/// Connect a channel to the gRPC endpoint of a cheqd node, with TLS for `https` URLs
+pub(crate) async fn connect_channel(grpc_url: &str) -> DidCheqdResult<Channel> {
+    Endpoint::new(grpc_url.to_string())
+        .map_err(|_e| DidCheqdError::BadConfiguration("Failed to parse GRPC url".to_string()))?
+        .tls_config(ClientTlsConfig::new().with_webpki_roots())
+        .map_err(|e| DidCheqdError::TransportError(Box::new(e)))?
+        .connect()
+        .await
+        .map_err(|e| DidCheqdError::TransportError(Box::new(e)))
+}
+
+/// The fee for a gas limit at a gas price, rounded up to a whole amount of the price's denom
+pub fn fee_for_gas(price: &DecCoin, gas_limit: u64) -> DidCheqdResult<Coin> {
+    let scale = 10u128.pow(DEC_PRECISION);
+    let fee = parse_dec(&price.amount)?
+        .checked_mul(gas_limit.into())
+        .map(|scaled| scaled.div_ceil(scale))
+        .ok_or_else(|| {
+            DidCheqdError::InvalidResponse(format!(
+                "fee for {gas_limit} gas at {} {} overflows",
+                price.amount, price.denom
+            ))
+        })?;
+    Ok(Coin {
+        denom: price.denom.clone(),
+        amount: fee.to_string(),
+    })
+}
+
+/// Parse a `LegacyDec` amount to its integer encoding. Amounts from gRPC are already encoded,
+/// amounts with a decimal point (e.g. from the REST gateway) are scaled.
+fn parse_dec(amount: &str) -> DidCheqdResult<u128> {
+    let invalid = || DidCheqdError::InvalidResponse(format!("invalid decimal amount `{amount}`"));
+    let Some((whole, fraction)) = amount.split_once('.') else {
+        return amount.parse().map_err(|_| invalid());
+    };
+    if fraction.len() > DEC_PRECISION as usize || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let whole: u128 = whole.parse().map_err(|_| invalid())?;
+    let fraction: u128 = format!("{fraction:0<width$}", width = DEC_PRECISION as usize)
+        .parse()
+        .map_err(|_| invalid())?;
+    whole
+        .checked_mul(10u128.pow(DEC_PRECISION))
+        .and_then(|whole| whole.checked_add(fraction))
+        .ok_or_else(invalid)
+}
+
+/// Format an integer encoded `LegacyDec` as a decimal, without trailing zeros
+fn format_dec(dec: u128) -> String {
+    let scale = 10u128.pow(DEC_PRECISION);
+    let fraction = format!("{:0width$}", dec % scale, width = DEC_PRECISION as usize);
+    let fraction = fraction.trim_end_matches('0');
+    format!(
+        "{}.{}",
+        dec / scale,
+        if fraction.is_empty() { "0" } else { fraction }
+    )
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    fn price(amount: &str) -> DecCoin {
+        DecCoin {
+            denom: CHEQD_FEE_DENOM.into(),
+            amount: amount.into(),
+        }
+    }
+
+    #[test]
+    fn test_parse_dec_amounts() {
+        assert_eq!(parse_dec("500000000000000000").unwrap(), 5 * 10u128.pow(17));
+        assert_eq!(parse_dec("0.5").unwrap(), 5 * 10u128.pow(17));
+        assert_eq!(
+            parse_dec("5000.000000000000000000").unwrap(),
+            5 * 10u128.pow(21)
+        );
+        assert!(parse_dec("1.0000000000000000001").is_err());
+        assert!(parse_dec("-1").is_err());
+        assert_eq!(format_dec(5 * 10u128.pow(17)), "0.5");
+        assert_eq!(format_dec(5 * 10u128.pow(21)), "5000.0");
+    }
+
+    #[test]
+    fn test_fee_for_gas() {
+        let fee = fee_for_gas(&price("5000.0"), 200_000).unwrap();
+        assert_eq!(fee.denom, "ncheq");
+        assert_eq!(fee.amount, "1000000000");
+        // partial units round up
+        assert_eq!(fee_for_gas(&price("0.3"), 5).unwrap().amount, "2");
+        assert!(fee_for_gas(&price(&u128::MAX.to_string()), 2).is_err());
+    }
+}
@@ -22,119 +22,517 @@
 //!
 //! ```
 //! use did_resolver_cheqd::DIDCheqd;
+//! use did_resolver_cheqd::resolution::resolver::{DidCheqdResolverConfiguration, NetworkConfiguration};
 //! use ssi_dids_core::DIDMethod;
 //! // Confirm the API constant and that we can construct the value
 //! assert_eq!(DIDCheqd::DID_METHOD_NAME, "cheqd");
 //! let _ = DIDCheqd::default();
 //! let _ = DIDCheqd::new(None);
-//! let _ = DIDCheqd::new(Some(DidCheqdResolverConfiguration {
-//!     networks: vec![
-//!         NetworkConfiguration {
-//!             grpc_url: "https://grpc.cheqd.net:443".to_string(),
-//!             namespace: "mainnet".to_string(),
-//!         },
-//!     ],
-//! }));
+//! let _ = DIDCheqd::new(Some(
+//!     DidCheqdResolverConfiguration::builder()
+//!         .networks(vec![
+//!             NetworkConfiguration::builder()
+//!                 .grpc_url("https://grpc.cheqd.net:443")
+//!                 .namespace("mainnet")
+//!                 .build(),
+//!         ])
+//!         .build(),
+//! ));
 //! ```
 //!
 //! # Library features
 //!
 //! - Implements a `DIDMethodResolver` for the `did:cheqd` DID method.
+//! - Resolves verification methods & issuer DID documents for ssi's credential verification, via
+//!   `DIDCheqd::into_verification_method_resolver`.
 //! - Exposes `resolution`, `proto` and `error` modules for integration.
+//!
+//! # Cargo features
+//!
+//! - `resolver` (default): the [`DIDCheqd`] resolver & `resolution` module. Enables both proto features.
+//! - `proto-did`: the generated `cheqd.did.v2` types & query client.
+//! - `proto-resource`: the generated `cheqd.resource.v2` types & query client.
+//! - `reflection`: `proto::reflection`, the embedded file descriptor set of the generated protos,
+//!   for tools inspecting cheqd's services at runtime or serving gRPC reflection.
+//! - `fees`: the generated feemarket types & query client, and `fees::FeeQueryClient`, estimating
+//!   the fees of DID & resource writes from the network's current gas price.
+//! - `registrar`: `registrar::CheqdRegistrar`, creating, updating & deactivating DIDs and creating
+//!   resources, by broadcasting transactions signed through the `registrar::signer` hooks, or with
+//!   the in-memory mnemonic & ed25519 keys of `registrar::keys`.
+//! - `global`: `DidCheqdResolver::global()`, a shared resolver configured from `DID_CHEQD_*`
+//!   environment variables (see `DidCheqdResolverConfiguration::from_env`).
+//! - `validate`: optional checks of resolved DID documents against the DID Core invariants,
+//!   reported as warnings or errors (see `DidCheqdResolverConfiguration::validation`).
+//! - `rest`: the REST gRPC-gateway transport (`Transport::Rest`), selectable per network for
+//!   environments where gRPC egress is blocked.
+//! - `axum`: extractors resolving the DID URL in a request path with a shared resolver (see
+//!   `resolution::extract`).
+//! - `disk-cache`: persists the resolution cache to an SQLite database (see
+//!   `resolution::disk_cache`), so CLI tools & serverless functions reuse earlier results.
+//! - `hickory-dns`: `resolution::connector::HickoryDnsResolver`, resolving node hostnames with the
+//!   hickory DNS resolver rather than blocking a thread on the system resolver.
+//! - `cbor`: converts JSON resources to CBOR & back when the `Accept` of a resolution (see
+//!   `resolution::options::ResolutionOptions::accept`) prefers the other media type.
+//! - `config-watch`: `DidCheqdResolver::watch_config`, reloading the networks (e.g. node endpoints)
+//!   of a resolver when a configuration file changes (see `resolution::config_watch`), so resolver
+//!   gateways are reconfigured without restarts.
+//! - `test-utils`: `test_utils::MockCheqdNode`, an in-process cheqd node serving DID documents &
+//!   resources from fixtures, for tests which shouldn't depend on live networks, and
+//!   `test_utils::MockResolver`, answering `CheqdResolve` calls from the fixtures directly.
+//!
+//! Consumers only needing the generated gRPC clients can disable default features and opt into
+//! the proto features they need, importing from `proto::prelude`.
 
+#[cfg(feature = "resolver")]
+use crate::error::DidCheqdError;
+#[cfg(feature = "resolver")]
 use crate::resolution::parser::DidCheqdParser;
-use crate::resolution::resolver::{DidCheqdResolver, DidCheqdResolverConfiguration};
-use crate::resolution::transformer::cheqd_diddoc_to_json;
-use serde_json::to_vec;
+#[cfg(feature = "resolver")]
+use crate::resolution::resolver::{
+    DidCheqdResolver, DidCheqdResolverConfiguration, Provenance, Transport,
+};
+#[cfg(feature = "resolver")]
+use crate::resolution::transformer::{
+    CheqdResourceMetadataWithUri, cheqd_diddoc_metadata_to_json, cheqd_diddoc_to_json,
+    cheqd_resource_metadata_with_uri_to_json, to_canonical_json_vec,
+};
+#[cfg(feature = "validate")]
+use crate::resolution::validation::{ValidationMode, Violation, validate_did_document};
+#[cfg(feature = "resolver")]
 use ssi_dids_core::{
-    DIDMethod, DIDResolver,
+    DIDMethod, DIDResolver, VerificationMethodDIDResolver,
     document::{self, representation::MediaType},
     resolution::{Error, Metadata as ResolutionMetadata, Options, Output},
 };
 
 pub mod error;
+#[cfg(feature = "fees")]
+pub mod fees;
 pub mod proto;
+#[cfg(feature = "registrar")]
+pub mod registrar;
+#[cfg(feature = "resolver")]
 pub mod resolution;
+#[cfg(all(feature = "resolver", any(test, feature = "test-utils")))]
+pub mod test_utils;
 
+#[cfg(feature = "resolver")]
 pub struct DIDCheqd {
-    /// Resolver configuration used when resolving DIDs/resources.
-    pub config: DidCheqdResolverConfiguration,
+    /// Resolver configuration used when resolving DIDs/resources, which the resolver is assembled
+    /// from in [DIDCheqd::new]
+    config: DidCheqdResolverConfiguration,
+    resolver: DidCheqdResolver,
 }
 
+#[cfg(feature = "resolver")]
 impl DIDCheqd {
     /// Create a resolver using an optional custom configuration.
     /// If `None` is provided, it defaults to `DidCheqdResolverConfiguration::default()`.
     pub fn new(config: Option<DidCheqdResolverConfiguration>) -> Self {
+        let config = config.unwrap_or_default();
         Self {
-            config: config.unwrap_or_default(),
+            resolver: DidCheqdResolver::new(config.clone()),
+            config,
+        }
+    }
+
+    /// The configuration DIDs/resources are resolved with. To resolve with another, create a new
+    /// [DIDCheqd].
+    pub fn config(&self) -> &DidCheqdResolverConfiguration {
+        &self.config
+    }
+
+    /// The resolver resolving DIDs/resources, shared by every resolution so its cache, node
+    /// connections & stats are reused
+    pub fn resolver(&self) -> &DidCheqdResolver {
+        &self.resolver
+    }
+}
+
+/// The output of resolving a did:cheqd DID document.
+#[cfg(feature = "resolver")]
+pub struct DocumentOutput {
+    /// the serialized DID document, with its document & resolution metadata. Object keys are
+    /// serialized in lexicographic order, so the bytes are reproducible for hashing & signing
+    pub output: Output<Vec<u8>>,
+    /// how the input DID was interpreted (the resolution metadata `didUrl` property), see
+    /// [`resolution::parser::DidCheqdParsed::did_url_metadata`]
+    pub did_url: serde_json::Value,
+    /// the transport which served the DID document, i.e. whether the REST fallback was used
+    pub transport: Transport,
+    /// whether a cached DID document was served after expiring, because it was being refreshed
+    /// or the network was unreachable (see `CacheConfiguration`)
+    pub stale: bool,
+    /// which endpoint served the DID document, whether it was cached, how many retries it took
+    /// & how long resolution took
+    pub provenance: Provenance,
+    /// DID Core invariants the document violates, when
+    /// [`DidCheqdResolverConfiguration::validation`] is [`ValidationMode::Warn`]
+    #[cfg(feature = "validate")]
+    pub warnings: Vec<Violation>,
+}
+
+/// The output of dereferencing a did:cheqd resource DID URL.
+#[cfg(feature = "resolver")]
+pub struct ResourceOutput {
+    /// the resource content, with its media type as the resolution metadata content type
+    pub output: Output<Vec<u8>>,
+    /// the DID URL dereferencing `contentMetadata` of the resource, i.e. its URI, collection id,
+    /// id, name, type, version, media type, created time & checksum. If the media type was
+    /// inferred from the content (see `sniff_media_types`), `mediaTypeInferred` is `true`.
+    pub content_metadata: serde_json::Value,
+    /// how the input DID URL was interpreted (the resolution metadata `didUrl` property), see
+    /// [`resolution::parser::DidCheqdParsed::did_url_metadata`]
+    pub did_url: serde_json::Value,
+    /// the transport which served the resource, i.e. whether the REST fallback was used
+    pub transport: Transport,
+    /// which endpoint served the resource, whether it was cached, how many retries it took & how
+    /// long resolution took
+    pub provenance: Provenance,
+}
+
+/// The output of dereferencing a did:cheqd `/metadata` DID URL.
+#[cfg(feature = "resolver")]
+pub struct MetadataOutput {
+    /// the DID document metadata or resource metadata, serialized as JSON
+    pub output: Output<Vec<u8>>,
+    /// how the input DID URL was interpreted (the resolution metadata `didUrl` property), see
+    /// [`resolution::parser::DidCheqdParsed::did_url_metadata`]
+    pub did_url: serde_json::Value,
+    /// the transport which served the metadata, i.e. whether the REST fallback was used
+    pub transport: Transport,
+    /// which endpoint served the metadata, whether it was cached, how many retries it took & how
+    /// long resolution took
+    pub provenance: Provenance,
+}
+
+#[cfg(feature = "resolver")]
+impl DIDCheqd {
+    /// Resolve a DID (optionally with a `/versions/<id>` path or `versionId` query) to its DID
+    /// document, serialized as JSON with the given content type (default JSON-LD).
+    pub async fn resolve_document(
+        &self,
+        did: &str,
+        accept: Option<MediaType>,
+    ) -> Result<DocumentOutput, Error> {
+        let resolver = &self.resolver;
+        let parsed = DidCheqdParser::parse_with_options(did, self.config.parser)
+            .map_err(|e| Error::InvalidMethodSpecificId(e.to_string()))?;
+        let did_url = parsed.did_url_metadata();
+
+        let resolved = resolver
+            .resolve_did_doc(parsed)
+            .await
+            .map_err(resolution_error)?;
+
+        // convert proto DIDDoc to a JSON representation and serialize
+        let json_value = cheqd_diddoc_to_json(resolved.did_doc)
+            .map_err(|e| Error::internal(format!("cheqd transform error: {e:?}")))?;
+        #[cfg(feature = "validate")]
+        let warnings = self.validate_document(&json_value)?;
+        let json = to_canonical_json_vec(&json_value)
+            .map_err(|e| Error::internal(format!("failed to serialize DID document: {e}")))?;
+
+        let content_type = accept.unwrap_or(MediaType::JsonLd);
+
+        Ok(DocumentOutput {
+            output: Output::new(
+                json,
+                match resolved.metadata {
+                    Some(meta) => document::Metadata {
+                        deactivated: Some(meta.deactivated),
+                    },
+                    None => document::Metadata { deactivated: None },
+                },
+                ResolutionMetadata::from_content_type(Some(content_type.to_string())),
+            ),
+            did_url,
+            transport: resolved.transport,
+            stale: resolved.stale,
+            provenance: resolved.provenance,
+            #[cfg(feature = "validate")]
+            warnings,
+        })
+    }
+
+    /// Validate a DID document according to the configured [`ValidationMode`], returning the
+    /// violations to report as warnings
+    #[cfg(feature = "validate")]
+    fn validate_document(&self, doc: &serde_json::Value) -> Result<Vec<Violation>, Error> {
+        if self.config.validation == ValidationMode::Off {
+            return Ok(Vec::new());
+        }
+        let violations = validate_did_document(doc);
+        if self.config.validation == ValidationMode::Deny && !violations.is_empty() {
+            let violations: Vec<_> = violations.iter().map(Violation::to_string).collect();
+            let e = DidCheqdError::InvalidDidDocument(violations.join("; "));
+            return Err(Error::internal(format!("cheqd validation error: {e:?}")));
+        }
+        Ok(violations)
+    }
+
+    /// Dereference a resource DID URL (e.g. `did:cheqd:mainnet:<id>/resources/<resource_id>`, or
+    /// a `resourceName` & `resourceType` query), returning the resource with its content metadata.
+    pub async fn dereference_resource(&self, did_url: &str) -> Result<ResourceOutput, Error> {
+        let resolver = &self.resolver;
+        let parsed = DidCheqdParser::parse_with_options(did_url, self.config.parser)
+            .map_err(|e| Error::InvalidMethodSpecificId(e.to_string()))?;
+        let namespace = parsed.namespace.clone();
+        let did_url_metadata = parsed.did_url_metadata();
+
+        let resolved = resolver
+            .resolve_resource(did_url, parsed)
+            .await
+            .map_err(resolution_error)?;
+        let (content, meta) = (resolved.data, resolved.metadata);
+
+        let effective_media_type = resolver.effective_media_type(&meta, &content);
+        let mut content_metadata = cheqd_resource_metadata_with_uri_to_json(
+            CheqdResourceMetadataWithUri::new(&namespace, meta),
+        )
+        .map_err(|e| Error::internal(format!("cheqd transform error: {e:?}")))?;
+        let media_type = effective_media_type.map(|(media_type, inferred)| {
+            if inferred {
+                content_metadata["mediaType"] = media_type.clone().into();
+                content_metadata["mediaTypeInferred"] = true.into();
+            }
+            media_type
+        });
+
+        Ok(ResourceOutput {
+            output: Output::new(
+                content,
+                document::Metadata::default(),
+                ResolutionMetadata::from_content_type(media_type),
+            ),
+            content_metadata,
+            did_url: did_url_metadata,
+            transport: resolved.transport,
+            provenance: resolved.provenance,
+        })
+    }
+
+    /// Dereference a `/metadata` DID URL: `did:cheqd:<namespace>:<id>/metadata` (optionally of a
+    /// `/versions/<id>`) to the DID document metadata, or
+    /// `did:cheqd:<namespace>:<id>/resources/<resource_id>/metadata` to the resource's content
+    /// metadata (as [ResourceOutput::content_metadata]), serialized as JSON.
+    pub async fn dereference_metadata(&self, did_url: &str) -> Result<MetadataOutput, Error> {
+        let parsed = DidCheqdParser::parse_with_options(did_url, self.config.parser)
+            .map_err(|e| Error::InvalidMethodSpecificId(e.to_string()))?;
+        let did_url_metadata = parsed.did_url_metadata();
+
+        let (metadata, transport, provenance) = if parsed.is_resource() {
+            let resource = self.dereference_resource(did_url).await?;
+            (
+                resource.content_metadata,
+                resource.transport,
+                resource.provenance,
+            )
+        } else {
+            let resolver = &self.resolver;
+            let resolved = resolver
+                .resolve_did_doc(parsed)
+                .await
+                .map_err(resolution_error)?;
+            let metadata = resolved
+                .metadata
+                .ok_or_else(|| Error::internal("cheqd node returned no DID document metadata"))?;
+            let metadata = cheqd_diddoc_metadata_to_json(metadata)
+                .map_err(|e| Error::internal(format!("cheqd transform error: {e:?}")))?;
+            (metadata, resolved.transport, resolved.provenance)
+        };
+        let json = to_canonical_json_vec(&metadata)
+            .map_err(|e| Error::internal(format!("failed to serialize metadata: {e}")))?;
+
+        Ok(MetadataOutput {
+            output: Output::new(
+                json,
+                document::Metadata::default(),
+                ResolutionMetadata::from_content_type(Some("application/json".to_string())),
+            ),
+            did_url: did_url_metadata,
+            transport,
+            provenance,
+        })
+    }
+}
+
+#[cfg(feature = "resolver")]
+impl DIDCheqd {
+    /// Dereference metadata & resource DID URLs, otherwise resolve the (possibly versioned) DID
+    /// document, as [DIDResolver::resolve_representation]
+    async fn dereference_did_url(
+        &self,
+        did_url: &str,
+        accept: Option<MediaType>,
+    ) -> Result<Output<Vec<u8>>, Error> {
+        // We will use the internal cheqd resolver to fetch a DidDocument or a resource and
+        // then convert it into bytes (JSON-LD) to match the did:key style Output.
+        let parsed = DidCheqdParser::parse_with_options(did_url, self.config.parser)
+            .map_err(|e| Error::InvalidMethodSpecificId(e.to_string()))?;
+
+        if parsed.metadata {
+            return self
+                .dereference_metadata(did_url)
+                .await
+                .map(|metadata| metadata.output);
+        }
+        if parsed.is_resource() {
+            return self
+                .dereference_resource(did_url)
+                .await
+                .map(|resource| resource.output);
         }
+        if parsed
+            .query
+            .as_ref()
+            .is_some_and(|q| q.contains_key("service"))
+        {
+            let resolver = &self.resolver;
+            let dereferenced = resolver
+                .dereference_service(did_url)
+                .await
+                .map_err(resolution_error)?;
+            return Ok(Output::new(
+                dereferenced.content_stream().into_bytes(),
+                document::Metadata::default(),
+                ResolutionMetadata::from_content_type(Some(
+                    dereferenced.content_type().to_string(),
+                )),
+            ));
+        }
+
+        self.resolve_document(did_url, accept)
+            .await
+            .map(|document| document.output)
+    }
+}
+
+/// Resolves the verification methods of did:cheqd DIDs, & their controllers (e.g. credential
+/// issuers' DID documents), for ssi's credential verification: it implements
+/// `ssi_verification_methods::VerificationMethodResolver` (for verification method types `M`),
+/// `ssi_verification_methods::ControllerProvider` & `ssi_jwk::JWKResolver`.
+/// See [DIDCheqd::into_verification_method_resolver].
+#[cfg(feature = "resolver")]
+pub type CheqdVerificationMethodResolver<M> = VerificationMethodDIDResolver<DIDCheqd, M>;
+
+#[cfg(feature = "resolver")]
+impl DIDCheqd {
+    /// Adapt the resolver for verifying credentials & presentations with ssi, resolving
+    /// verification methods such as `did:cheqd:mainnet:<id>#key-1` from their DID documents.
+    pub fn into_verification_method_resolver<M>(self) -> CheqdVerificationMethodResolver<M> {
+        VerificationMethodDIDResolver::new(self)
     }
 }
 
+/// The DID resolution error for a resolver error, so callers such as ssi's verifiers can tell
+/// DIDs which don't exist from failures to resolve them
+#[cfg(feature = "resolver")]
+fn resolution_error(e: DidCheqdError) -> Error {
+    match e.root() {
+        DidCheqdError::ResourceNotFound(_) => Error::NotFound,
+        DidCheqdError::NonSuccessResponse(_) if e.status_code() == Some(tonic::Code::NotFound) => {
+            Error::NotFound
+        }
+        DidCheqdError::InvalidDid(_)
+        | DidCheqdError::InvalidDidUrl(_)
+        | DidCheqdError::ParsingError(_) => Error::InvalidMethodSpecificId(e.to_string()),
+        DidCheqdError::MethodNotSupported(_) => Error::MethodNotSupported(e.to_string()),
+        DidCheqdError::UnexpectedMediaType(_) => Error::RepresentationNotSupported(e.to_string()),
+        _ => Error::internal(format!("cheqd resolver error: {e:?}")),
+    }
+}
+
+#[cfg(feature = "resolver")]
 impl Default for DIDCheqd {
     fn default() -> Self {
         Self::new(None)
     }
 }
 
+#[cfg(feature = "resolver")]
 impl DIDMethod for DIDCheqd {
     const DID_METHOD_NAME: &'static str = "cheqd";
 }
 
+#[cfg(feature = "resolver")]
 impl DIDResolver for DIDCheqd {
     async fn resolve_representation<'a>(
         &'a self,
         did: &'a ssi_dids_core::DID,
         options: Options,
     ) -> Result<Output<Vec<u8>>, Error> {
-        // Try parse as a DID URL (resource) first, otherwise as a DID
-        // We will use the internal cheqd resolver to fetch a DidDocument or a resource and
-        // then convert it into bytes (JSON-LD) to match the did:key style Output.
-        // Use the resolver configuration provided to this DIDCheqd instance.
-        let cfg = self.config.clone();
-        let resolver = DidCheqdResolver::new(cfg);
+        self.dereference_did_url(did.as_str(), options.accept).await
+    }
+}
 
-        // Check if it's a DidUrl (resource)
-        let parsed = DidCheqdParser::parse(did.as_str())
-            .map_err(|e| Error::InvalidMethodSpecificId(e.to_string()))?;
+#[cfg(all(test, feature = "resolver"))]
+mod unit_tests {
+    use ssi_dids_core::{DID, DIDURL};
 
-        if parsed.query.is_some() {
-            // treat as a full did URL
-            match resolver.query_resource_by_str(did.as_str(), parsed).await {
-                Ok((content_bytes, media_type)) => {
-                    return Ok(Output::new(
-                        content_bytes,
-                        document::Metadata::default(),
-                        ResolutionMetadata::from_content_type(media_type),
-                    ));
-                }
-                Err(e) => return Err(Error::internal(format!("cheqd resolver error: {e:?}"))),
-            }
-        }
+    use super::*;
+    use crate::{
+        proto::cheqd::did::v2::{DidDoc, VerificationMethod},
+        test_utils::{LedgerFixtures, MockCheqdNode},
+    };
 
-        match resolver.query_did_doc_by_str(did.as_str(), parsed).await {
-            Ok((proto_doc, metadata)) => {
-                // convert proto DIDDoc to a JSON representation and serialize
-                let json_value = cheqd_diddoc_to_json(proto_doc)
-                    .map_err(|e| Error::internal(format!("cheqd transform error: {e:?}")))?;
-                let json = to_vec(&json_value).map_err(|e| {
-                    Error::internal(format!("failed to serialize DID document: {e}"))
-                })?;
-
-                let content_type = options.accept.unwrap_or(MediaType::JsonLd);
-
-                Ok(Output::new(
-                    json,
-                    match metadata {
-                        Some(meta) => document::Metadata {
-                            deactivated: Some(meta.deactivated),
-                        },
-                        None => document::Metadata { deactivated: None },
-                    },
-                    ResolutionMetadata::from_content_type(Some(content_type.to_string())),
-                ))
-            }
-            Err(e) => Err(Error::internal(format!("cheqd resolver error: {e:?}"))),
+    const DID: &str = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+
+    #[tokio::test]
+    async fn test_verification_method_resolver() {
+        let did_doc = DidDoc {
+            id: DID.into(),
+            controller: vec![DID.into()],
+            verification_method: vec![VerificationMethod {
+                id: format!("{DID}#key-1"),
+                verification_method_type: "Ed25519VerificationKey2020".into(),
+                controller: DID.into(),
+                verification_material: "z6MkszZtxCmA2Ce4vUV132PCuLQmwnaDD5mw2L23fGNnsiX3".into(),
+            }],
+            authentication: vec![format!("{DID}#key-1")],
+            ..Default::default()
+        };
+        let fixtures = LedgerFixtures::new().with_did_doc(did_doc, Default::default());
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DIDCheqd::new(Some(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            ..Default::default()
+        }))
+        .into_verification_method_resolver::<()>();
+
+        let key_id = format!("{DID}#key-1");
+        let dereferenced = resolver
+            .dereference(DIDURL::new(key_id.as_bytes()).unwrap())
+            .await
+            .unwrap();
+        let method = dereferenced.content.into_verification_method().unwrap();
+        assert_eq!(method.id.as_str(), key_id);
+
+        // missing DIDs are reported as such, rather than as resolver failures
+        let missing = DID::new(b"did:cheqd:testnet:missing").unwrap();
+        let e = resolver.resolve(missing).await.unwrap_err();
+        assert!(matches!(e, Error::NotFound), "{e:?}");
+    }
+
+    #[tokio::test]
+    async fn test_resolutions_share_the_resolver() {
+        let did_doc = DidDoc {
+            id: DID.into(),
+            ..Default::default()
+        };
+        let fixtures = LedgerFixtures::new().with_did_doc(did_doc, Default::default());
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DIDCheqd::new(Some(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            cache: Some(Default::default()),
+            ..Default::default()
+        }));
+
+        for _ in 0..2 {
+            let did = DID::new(DID.as_bytes()).unwrap();
+            resolver.resolve(did).await.unwrap();
         }
+        let stats = resolver.resolver().stats().await;
+        assert_eq!((stats.queries, stats.cache_hits), (1, 1));
     }
 }
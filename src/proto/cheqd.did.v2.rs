@@ -226,6 +226,207 @@ pub struct QueryAllDidDocVersionsMetadataResponse {
     pub pagination:
         ::core::option::Option<super::super::super::cosmos::base::query::v1beta1::PageResponse>,
 }
+/// MsgCreateDidDoc defines the Msg/CreateDidDoc request type.
+/// It describes the parameters of a request for creating a new DID document.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgCreateDidDoc {
+    /// Payload containing the DID Document to be created
+    #[prost(message, optional, tag = "1")]
+    pub payload: ::core::option::Option<MsgCreateDidDocPayload>,
+    /// Signatures of the DID Document's controller(s)
+    #[prost(message, repeated, tag = "2")]
+    pub signatures: ::prost::alloc::vec::Vec<SignInfo>,
+}
+/// MsgUpdateDidDoc defines the Msg/UpdateDidDoc request type.
+/// It describes the parameters of a request for updating an existing DID document.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgUpdateDidDoc {
+    /// Payload containing the DID Document to be updated. This should be updated the DID Document.
+    #[prost(message, optional, tag = "1")]
+    pub payload: ::core::option::Option<MsgUpdateDidDocPayload>,
+    /// Signatures of the DID Document's controller(s)
+    #[prost(message, repeated, tag = "2")]
+    pub signatures: ::prost::alloc::vec::Vec<SignInfo>,
+}
+/// MsgDeactivateDidDoc defines the Msg/DeactivateDidDoc request type.
+/// It describes the parameters of a request for deactivating an existing DID document.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgDeactivateDidDoc {
+    /// Payload containing the DID Document to be deactivated
+    #[prost(message, optional, tag = "1")]
+    pub payload: ::core::option::Option<MsgDeactivateDidDocPayload>,
+    /// Signatures of the DID Document's controller(s)
+    #[prost(message, repeated, tag = "2")]
+    pub signatures: ::prost::alloc::vec::Vec<SignInfo>,
+}
+/// SignInfo defines the structure of a DID Document controller's signature
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SignInfo {
+    /// Verification method ID of the DID Controller
+    #[prost(string, tag = "1")]
+    pub verification_method_id: ::prost::alloc::string::String,
+    /// Signature of the DID Document controller
+    #[prost(bytes = "vec", tag = "2")]
+    pub signature: ::prost::alloc::vec::Vec<u8>,
+}
+/// MsgCreateDidDocPayload defines the structure of the payload for creating a new DID document
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgCreateDidDocPayload {
+    /// context is a list of URIs used to identify the context of DID document.
+    /// Default: <https://www.w3.org/ns/did/v1>
+    #[prost(string, repeated, tag = "1")]
+    pub context: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// id is the DID of the DID document.
+    /// Format: did:cheqd:`<namespace>`:`<unique-identifier>`
+    #[prost(string, tag = "2")]
+    pub id: ::prost::alloc::string::String,
+    /// controller is a list of DIDs that are allowed to control the DID document.
+    #[prost(string, repeated, tag = "3")]
+    pub controller: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// verificationMethod is a list of verification methods that can be used to
+    /// verify a digital signature or cryptographic proof.
+    /// Documentation: <https://www.w3.org/TR/did-core/#verification-methods>
+    ///
+    /// Required fields:
+    /// - id: A unique identifier for the verification method
+    /// - type: A supported verification method type (supported: Ed25519VerificationKey2018, Ed25519VerificationKey2020, JsonWebKey2020)
+    /// - controller: DID of the controller of the verification method
+    /// - verification_material: Public key of the verification method (supported: publicJwk, publicKeyBase58, publicKeyMultibase)
+    #[prost(message, repeated, tag = "4")]
+    pub verification_method: ::prost::alloc::vec::Vec<VerificationMethod>,
+    /// authentication is a list of verification methods that can be used to
+    /// authenticate as the DID subject.
+    #[prost(string, repeated, tag = "5")]
+    pub authentication: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// assertionMethod is a list of verification methods that can be used to
+    /// assert statements as the DID subject.
+    #[prost(string, repeated, tag = "6")]
+    pub assertion_method: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// capabilityInvocation is a list of verification methods that can be used to
+    /// invoke capabilities as the DID subject.
+    #[prost(string, repeated, tag = "7")]
+    pub capability_invocation: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// capabilityDelegation is a list of verification methods that can be used to
+    /// delegate capabilities as the DID subject.
+    #[prost(string, repeated, tag = "8")]
+    pub capability_delegation: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// keyAgreement is a list of verification methods that can be used to perform
+    /// key agreement as the DID subject.
+    #[prost(string, repeated, tag = "9")]
+    pub key_agreement: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// alsoKnownAs is a list of DIDs that are known to refer to the same DID subject.
+    #[prost(string, repeated, tag = "10")]
+    pub also_known_as: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// service is a list of services that can be used to interact with the DID subject.
+    /// Documentation: <https://www.w3.org/TR/did-core/#services>
+    ///
+    /// Required fields:
+    /// - id: A unique identifier for the service
+    /// - type: A service type defined in DID Specification Registries
+    /// - service_endpoint: Service endpoint(s), provided as a URI or set of URIs
+    #[prost(message, repeated, tag = "11")]
+    pub service: ::prost::alloc::vec::Vec<Service>,
+    /// Version ID of the DID Document to be created
+    ///
+    /// Format: `<uuid>`
+    #[prost(string, tag = "12")]
+    pub version_id: ::prost::alloc::string::String,
+}
+/// MsgCreateDidDocResponse defines response type for Msg/CreateDidDoc.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgCreateDidDocResponse {
+    /// Return the created DID Document with metadata
+    #[prost(message, optional, tag = "1")]
+    pub value: ::core::option::Option<DidDocWithMetadata>,
+}
+/// MsgUpdateDidDocPayload defines the structure of the payload for updating an existing DID document
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgUpdateDidDocPayload {
+    /// context is a list of URIs used to identify the context of DID document.
+    /// Default: <https://www.w3.org/ns/did/v1>
+    #[prost(string, repeated, tag = "1")]
+    pub context: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// id is the DID of the DID document.
+    /// Format: did:cheqd:`<namespace>`:`<unique-identifier>`
+    #[prost(string, tag = "2")]
+    pub id: ::prost::alloc::string::String,
+    /// controller is a list of DIDs that are allowed to control the DID document.
+    #[prost(string, repeated, tag = "3")]
+    pub controller: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// verificationMethod is a list of verification methods that can be used to
+    /// verify a digital signature or cryptographic proof.
+    /// Documentation: <https://www.w3.org/TR/did-core/#verification-methods>
+    ///
+    /// Required fields:
+    /// - id: A unique identifier for the verification method
+    /// - type: A supported verification method type (supported: Ed25519VerificationKey2018, Ed25519VerificationKey2020, JsonWebKey2020)
+    /// - controller: DID of the controller of the verification method
+    /// - verification_material: Public key of the verification method (supported: publicJwk, publicKeyBase58, publicKeyMultibase)
+    #[prost(message, repeated, tag = "4")]
+    pub verification_method: ::prost::alloc::vec::Vec<VerificationMethod>,
+    /// authentication is a list of verification methods that can be used to
+    /// authenticate as the DID subject.
+    #[prost(string, repeated, tag = "5")]
+    pub authentication: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// assertionMethod is a list of verification methods that can be used to
+    /// assert statements as the DID subject.
+    #[prost(string, repeated, tag = "6")]
+    pub assertion_method: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// capabilityInvocation is a list of verification methods that can be used to
+    /// invoke capabilities as the DID subject.
+    #[prost(string, repeated, tag = "7")]
+    pub capability_invocation: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// capabilityDelegation is a list of verification methods that can be used to
+    /// delegate capabilities as the DID subject.
+    #[prost(string, repeated, tag = "8")]
+    pub capability_delegation: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// keyAgreement is a list of verification methods that can be used to perform
+    /// key agreement as the DID subject.
+    #[prost(string, repeated, tag = "9")]
+    pub key_agreement: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// alsoKnownAs is a list of DIDs that are known to refer to the same DID subject.
+    #[prost(string, repeated, tag = "10")]
+    pub also_known_as: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// service is a list of services that can be used to interact with the DID subject.
+    /// Documentation: <https://www.w3.org/TR/did-core/#services>
+    ///
+    /// Required fields:
+    /// - id: A unique identifier for the service
+    /// - type: A service type defined in DID Specification Registries
+    /// - service_endpoint: Service endpoint(s), provided as a URI or set of URIs
+    #[prost(message, repeated, tag = "11")]
+    pub service: ::prost::alloc::vec::Vec<Service>,
+    /// Updated version ID of the DID Document.
+    /// Links to next/previous versions of the DID Document will be automatically updated.
+    ///
+    /// Format: `<uuid>`
+    #[prost(string, tag = "12")]
+    pub version_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgUpdateDidDocResponse {
+    /// Return the updated DID Document with metadata
+    #[prost(message, optional, tag = "1")]
+    pub value: ::core::option::Option<DidDocWithMetadata>,
+}
+/// MsgDeactivateDidDocPayload defines the structure of the payload for deactivating an existing DID document
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgDeactivateDidDocPayload {
+    /// Unique identifier of the DID Document to be deactivated
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    /// Version ID of the DID Document to be deactivated
+    /// This is primarily used as a sanity check to ensure that the correct DID Document is being deactivated.
+    #[prost(string, tag = "2")]
+    pub version_id: ::prost::alloc::string::String,
+}
+/// MsgDeactivateDidDocResponse defines response type for Msg/DeactivateDidDoc.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgDeactivateDidDocResponse {
+    /// Return the deactivated DID Document with metadata
+    #[prost(message, optional, tag = "1")]
+    pub value: ::core::option::Option<DidDocWithMetadata>,
+}
 /// Generated client implementations.
 pub mod query_client {
     #![allow(
@@ -373,3 +574,141 @@ pub mod query_client {
         }
     }
 }
+/// Generated client implementations.
+pub mod msg_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value
+    )]
+    use tonic::codegen::http::Uri;
+    use tonic::codegen::*;
+    /// Msg defines the Cosmos SDK Msg service for the cheqd.did.v2 module.
+    #[derive(Debug, Clone)]
+    pub struct MsgClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl MsgClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> MsgClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> MsgClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                    http::Request<tonic::body::BoxBody>,
+                    Response = http::Response<
+                        <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                    >,
+                >,
+            <T as tonic::codegen::Service<http::Request<tonic::body::BoxBody>>>::Error:
+                Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            MsgClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// CreateDidDoc defines a method for creating a new DID document
+        pub async fn create_did_doc(
+            &mut self,
+            request: impl tonic::IntoRequest<super::MsgCreateDidDoc>,
+        ) -> std::result::Result<tonic::Response<super::MsgCreateDidDocResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/cheqd.did.v2.Msg/CreateDidDoc");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("cheqd.did.v2.Msg", "CreateDidDoc"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// UpdateDidDoc defines a method for updating an existing DID document
+        pub async fn update_did_doc(
+            &mut self,
+            request: impl tonic::IntoRequest<super::MsgUpdateDidDoc>,
+        ) -> std::result::Result<tonic::Response<super::MsgUpdateDidDocResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/cheqd.did.v2.Msg/UpdateDidDoc");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("cheqd.did.v2.Msg", "UpdateDidDoc"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// DeactivateDidDoc defines a method for deactivating an existing DID document
+        pub async fn deactivate_did_doc(
+            &mut self,
+            request: impl tonic::IntoRequest<super::MsgDeactivateDidDoc>,
+        ) -> std::result::Result<tonic::Response<super::MsgDeactivateDidDocResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/cheqd.did.v2.Msg/DeactivateDidDoc");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("cheqd.did.v2.Msg", "DeactivateDidDoc"));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
@@ -169,6 +169,69 @@ pub struct QueryCollectionResourcesResponse {
     pub pagination:
         ::core::option::Option<super::super::super::cosmos::base::query::v1beta1::PageResponse>,
 }
+/// MsgCreateResource defines the Msg/CreateResource request type.
+/// It describes the parameters of a request for creating a resource.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgCreateResource {
+    /// Payload containing the resource to be created.
+    #[prost(message, optional, tag = "1")]
+    pub payload: ::core::option::Option<MsgCreateResourcePayload>,
+    /// Signatures of the corresponding DID Document's controller(s).
+    #[prost(message, repeated, tag = "2")]
+    pub signatures: ::prost::alloc::vec::Vec<super::super::did::v2::SignInfo>,
+}
+/// MsgCreateResourcePayload defines the structure of the payload for creating a resource.
+///
+/// If a resource with the given id already exists, the request will be rejected.
+/// If a resource with the given name exists, this resource will be added to the list of resources with the same name.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgCreateResourcePayload {
+    /// data is a byte-representation of the actual Data the user wants to store.
+    #[prost(bytes = "vec", tag = "1")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+    /// collection_id is an identifier of the DidDocument the resource belongs to.
+    /// Format: `<unique-identifier>`
+    ///
+    /// Examples:
+    /// - c82f2b02-bdab-4dd7-b833-3e143745d612
+    /// - wGHEXrZvJxR8vw5P3UWH1j
+    #[prost(string, tag = "2")]
+    pub collection_id: ::prost::alloc::string::String,
+    /// id is a unique id of the resource.
+    /// Format: `<uuid>`
+    #[prost(string, tag = "3")]
+    pub id: ::prost::alloc::string::String,
+    /// name is a human-readable name of the resource.
+    /// Format: `<string>`
+    ///
+    /// Does not change between different versions.
+    /// Example: PassportSchema, EducationTrustRegistry
+    #[prost(string, tag = "4")]
+    pub name: ::prost::alloc::string::String,
+    /// version is a version of the resource.
+    /// Format: `<string>`
+    /// Stored as a string. OPTIONAL.
+    ///
+    /// Example: 1.0.0, v2.1.0
+    #[prost(string, tag = "5")]
+    pub version: ::prost::alloc::string::String,
+    /// resource_type is a type of the resource.
+    /// Format: `<string>`
+    ///
+    /// This is NOT the same as the resource's media type.
+    /// Example: AnonCredsSchema, StatusList2021
+    #[prost(string, tag = "6")]
+    pub resource_type: ::prost::alloc::string::String,
+    /// also_known_as is a list of URIs that can be used to get the resource.
+    #[prost(message, repeated, tag = "7")]
+    pub also_known_as: ::prost::alloc::vec::Vec<AlternativeUri>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgCreateResourceResponse {
+    /// Return the created resource metadata.
+    #[prost(message, optional, tag = "1")]
+    pub resource: ::core::option::Option<Metadata>,
+}
 /// Generated client implementations.
 pub mod query_client {
     #![allow(
@@ -319,3 +382,110 @@ pub mod query_client {
         }
     }
 }
+/// Generated client implementations.
+pub mod msg_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value
+    )]
+    use tonic::codegen::http::Uri;
+    use tonic::codegen::*;
+    /// Msg defines the Cosmos SDK Msg service for the cheqd.resource.v2 module.
+    #[derive(Debug, Clone)]
+    pub struct MsgClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl MsgClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> MsgClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> MsgClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                    http::Request<tonic::body::BoxBody>,
+                    Response = http::Response<
+                        <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                    >,
+                >,
+            <T as tonic::codegen::Service<http::Request<tonic::body::BoxBody>>>::Error:
+                Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            MsgClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// CreateResource defines a method for creating a resource.
+        pub async fn create_resource(
+            &mut self,
+            request: impl tonic::IntoRequest<super::MsgCreateResource>,
+        ) -> std::result::Result<tonic::Response<super::MsgCreateResourceResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/cheqd.resource.v2.Msg/CreateResource");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("cheqd.resource.v2.Msg", "CreateResource"));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
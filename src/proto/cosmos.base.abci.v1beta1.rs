@@ -0,0 +1,71 @@
+// This file is @generated by prost-build.
+/// TxResponse defines a structure containing relevant tx data and metadata. The
+/// tags are stringified and the log is JSON decoded.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TxResponse {
+    /// The block height
+    #[prost(int64, tag = "1")]
+    pub height: i64,
+    /// The transaction hash.
+    #[prost(string, tag = "2")]
+    pub txhash: ::prost::alloc::string::String,
+    /// Namespace for the Code
+    #[prost(string, tag = "3")]
+    pub codespace: ::prost::alloc::string::String,
+    /// Response code.
+    #[prost(uint32, tag = "4")]
+    pub code: u32,
+    /// Result bytes, if any.
+    #[prost(string, tag = "5")]
+    pub data: ::prost::alloc::string::String,
+    /// The output of the application's logger (raw string). May be
+    /// non-deterministic.
+    #[prost(string, tag = "6")]
+    pub raw_log: ::prost::alloc::string::String,
+    /// Additional information. May be non-deterministic.
+    #[prost(string, tag = "8")]
+    pub info: ::prost::alloc::string::String,
+    /// Amount of gas requested for transaction.
+    #[prost(int64, tag = "9")]
+    pub gas_wanted: i64,
+    /// Amount of gas consumed by transaction.
+    #[prost(int64, tag = "10")]
+    pub gas_used: i64,
+    /// The request transaction bytes.
+    #[prost(message, optional, tag = "11")]
+    pub tx: ::core::option::Option<::prost_types::Any>,
+    /// Time of the previous block. For heights > 1, it's the weighted median of
+    /// the timestamps of the valid votes in the block.LastCommit. For height == 1,
+    /// it's genesis time.
+    #[prost(string, tag = "12")]
+    pub timestamp: ::prost::alloc::string::String,
+}
+/// GasInfo defines tx execution gas context.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GasInfo {
+    /// GasWanted is the maximum units of work we allow this tx to perform.
+    #[prost(uint64, tag = "1")]
+    pub gas_wanted: u64,
+    /// GasUsed is the amount of gas actually consumed.
+    #[prost(uint64, tag = "2")]
+    pub gas_used: u64,
+}
+/// Result is the union of ResponseFormat and ResponseCheckTx.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Result {
+    /// Data is any data returned from message or handler execution. It MUST be
+    /// length prefixed in order to separate data from multiple message executions.
+    /// Deprecated. This field is still populated, but prefer msg_response instead
+    /// because it also contains the Msg response typeURL.
+    #[deprecated]
+    #[prost(bytes = "vec", tag = "1")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+    /// Log contains the log information from message or handler execution.
+    #[prost(string, tag = "2")]
+    pub log: ::prost::alloc::string::String,
+    /// msg_responses contains the Msg handler responses type packed in Anys.
+    ///
+    /// Since: cosmos-sdk 0.46
+    #[prost(message, repeated, tag = "4")]
+    pub msg_responses: ::prost::alloc::vec::Vec<::prost_types::Any>,
+}
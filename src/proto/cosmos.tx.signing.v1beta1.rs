@@ -0,0 +1,73 @@
+// This file is @generated by prost-build.
+/// SignMode represents a signing mode with its own security guarantees.
+///
+/// This enum should be considered a registry of all known sign modes
+/// in the Cosmos ecosystem. Apps are not expected to support all known
+/// sign modes. Apps that would like to support custom  sign modes are
+/// encouraged to open a small PR against this file to add a new case
+/// to this SignMode enum describing their sign mode so that different
+/// apps have a consistent version of this enum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum SignMode {
+    /// SIGN_MODE_UNSPECIFIED specifies an unknown signing mode and will be
+    /// rejected.
+    Unspecified = 0,
+    /// SIGN_MODE_DIRECT specifies a signing mode which uses SignDoc and is
+    /// verified with raw bytes from Tx.
+    Direct = 1,
+    /// SIGN_MODE_TEXTUAL is a future signing mode that will verify some
+    /// human-readable textual representation on top of the binary representation
+    /// from SIGN_MODE_DIRECT.
+    ///
+    /// Since: cosmos-sdk 0.50
+    Textual = 2,
+    /// SIGN_MODE_DIRECT_AUX specifies a signing mode which uses
+    /// SignDocDirectAux. As opposed to SIGN_MODE_DIRECT, this sign mode does not
+    /// require signers signing over other signers' `signer_info`.
+    ///
+    /// Since: cosmos-sdk 0.46
+    DirectAux = 3,
+    /// SIGN_MODE_LEGACY_AMINO_JSON is a backwards compatibility mode which uses
+    /// Amino JSON and will be removed in the future.
+    LegacyAminoJson = 127,
+    /// SIGN_MODE_EIP_191 specifies the sign mode for EIP 191 signing on the Cosmos
+    /// SDK. Ref: <https://eips.ethereum.org/EIPS/eip-191>
+    ///
+    /// Currently, SIGN_MODE_EIP_191 is registered as a SignMode enum variant,
+    /// but is not implemented on the SDK by default. To enable EIP-191, you need
+    /// to pass a custom `TxConfig` that has an implementation of
+    /// `SignModeHandler` for EIP-191. The SDK may decide to fully support
+    /// EIP-191 in the future.
+    ///
+    /// Since: cosmos-sdk 0.45.2
+    Eip191 = 191,
+}
+impl SignMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "SIGN_MODE_UNSPECIFIED",
+            Self::Direct => "SIGN_MODE_DIRECT",
+            Self::Textual => "SIGN_MODE_TEXTUAL",
+            Self::DirectAux => "SIGN_MODE_DIRECT_AUX",
+            Self::LegacyAminoJson => "SIGN_MODE_LEGACY_AMINO_JSON",
+            Self::Eip191 => "SIGN_MODE_EIP_191",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "SIGN_MODE_UNSPECIFIED" => Some(Self::Unspecified),
+            "SIGN_MODE_DIRECT" => Some(Self::Direct),
+            "SIGN_MODE_TEXTUAL" => Some(Self::Textual),
+            "SIGN_MODE_DIRECT_AUX" => Some(Self::DirectAux),
+            "SIGN_MODE_LEGACY_AMINO_JSON" => Some(Self::LegacyAminoJson),
+            "SIGN_MODE_EIP_191" => Some(Self::Eip191),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,416 @@
+// This file is @generated by prost-build.
+/// Tx is the standard type used for broadcasting transactions.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Tx {
+    /// body is the processable content of the transaction
+    #[prost(message, optional, tag = "1")]
+    pub body: ::core::option::Option<TxBody>,
+    /// auth_info is the authorization related content of the transaction,
+    /// specifically signers, signer modes and fee
+    #[prost(message, optional, tag = "2")]
+    pub auth_info: ::core::option::Option<AuthInfo>,
+    /// signatures is a list of signatures that matches the length and order of
+    /// AuthInfo's signer_infos to allow connecting signature meta information like
+    /// public key and signing mode by position.
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub signatures: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+/// TxRaw is a variant of Tx that pins the signer's exact binary representation
+/// of body and auth_info. This is used for signing, broadcasting and
+/// verification. The binary `serialize(tx: TxRaw)` is stored in Tendermint and
+/// the hash `sha256(serialize(tx: TxRaw))` becomes the "txhash", commonly used
+/// as the transaction ID.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TxRaw {
+    /// body_bytes is a protobuf serialization of a TxBody that matches the
+    /// representation in SignDoc.
+    #[prost(bytes = "vec", tag = "1")]
+    pub body_bytes: ::prost::alloc::vec::Vec<u8>,
+    /// auth_info_bytes is a protobuf serialization of an AuthInfo that matches the
+    /// representation in SignDoc.
+    #[prost(bytes = "vec", tag = "2")]
+    pub auth_info_bytes: ::prost::alloc::vec::Vec<u8>,
+    /// signatures is a list of signatures that matches the length and order of
+    /// AuthInfo's signer_infos to allow connecting signature meta information like
+    /// public key and signing mode by position.
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub signatures: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+/// SignDoc is the type used for generating sign bytes for SIGN_MODE_DIRECT.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SignDoc {
+    /// body_bytes is protobuf serialization of a TxBody that matches the
+    /// representation in TxRaw.
+    #[prost(bytes = "vec", tag = "1")]
+    pub body_bytes: ::prost::alloc::vec::Vec<u8>,
+    /// auth_info_bytes is a protobuf serialization of an AuthInfo that matches the
+    /// representation in TxRaw.
+    #[prost(bytes = "vec", tag = "2")]
+    pub auth_info_bytes: ::prost::alloc::vec::Vec<u8>,
+    /// chain_id is the unique identifier of the chain this transaction targets.
+    /// It prevents signed transactions from being used on another chain by an
+    /// attacker
+    #[prost(string, tag = "3")]
+    pub chain_id: ::prost::alloc::string::String,
+    /// account_number is the account number of the account in state
+    #[prost(uint64, tag = "4")]
+    pub account_number: u64,
+}
+/// TxBody is the body of a transaction that all signers sign over.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TxBody {
+    /// messages is a list of messages to be executed. The required signers of
+    /// those messages define the number and order of elements in AuthInfo's
+    /// signer_infos and Tx's signatures. Each required signer address is added to
+    /// the list only the first time it occurs.
+    /// By convention, the first required signer (usually from the first message)
+    /// is referred to as the primary signer and pays the fee for the whole
+    /// transaction.
+    #[prost(message, repeated, tag = "1")]
+    pub messages: ::prost::alloc::vec::Vec<::prost_types::Any>,
+    /// memo is any arbitrary note/comment to be added to the transaction.
+    /// WARNING: in clients, any publicly exposed text should not be called memo,
+    /// but should be called `note` instead (see
+    /// <https://github.com/cosmos/cosmos-sdk/issues/9122>).
+    #[prost(string, tag = "2")]
+    pub memo: ::prost::alloc::string::String,
+    /// timeout is the block height after which this transaction will not
+    /// be processed by the chain
+    #[prost(uint64, tag = "3")]
+    pub timeout_height: u64,
+    /// extension_options are arbitrary options that can be added by chains
+    /// when the default options are not sufficient. If any of these are present
+    /// and can't be handled, the transaction will be rejected
+    #[prost(message, repeated, tag = "1023")]
+    pub extension_options: ::prost::alloc::vec::Vec<::prost_types::Any>,
+    /// extension_options are arbitrary options that can be added by chains
+    /// when the default options are not sufficient. If any of these are present
+    /// and can't be handled, they will be ignored
+    #[prost(message, repeated, tag = "2047")]
+    pub non_critical_extension_options: ::prost::alloc::vec::Vec<::prost_types::Any>,
+}
+/// AuthInfo describes the fee and signer modes that are used to sign a
+/// transaction.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AuthInfo {
+    /// signer_infos defines the signing modes for the required signers. The number
+    /// and order of elements must match the required signers from TxBody's
+    /// messages. The first element is the primary signer and the one which pays
+    /// the fee.
+    #[prost(message, repeated, tag = "1")]
+    pub signer_infos: ::prost::alloc::vec::Vec<SignerInfo>,
+    /// Fee is the fee and gas limit for the transaction. The first signer is the
+    /// primary signer and the one which pays the fee. The fee can be calculated
+    /// based on the cost of evaluating the body and doing signature verification
+    /// of the signers. This can be estimated via simulation.
+    #[prost(message, optional, tag = "2")]
+    pub fee: ::core::option::Option<Fee>,
+}
+/// SignerInfo describes the public key and signing mode of a single top-level
+/// signer.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SignerInfo {
+    /// public_key is the public key of the signer. It is optional for accounts
+    /// that already exist in state. If unset, the verifier can use the required
+    /// signer address for this position and lookup the public key.
+    #[prost(message, optional, tag = "1")]
+    pub public_key: ::core::option::Option<::prost_types::Any>,
+    /// mode_info describes the signing mode of the signer and is a nested
+    /// structure to support nested multisig pubkey's
+    #[prost(message, optional, tag = "2")]
+    pub mode_info: ::core::option::Option<ModeInfo>,
+    /// sequence is the sequence of the account, which describes the
+    /// number of committed transactions signed by a given address. It is used to
+    /// prevent replay attacks.
+    #[prost(uint64, tag = "3")]
+    pub sequence: u64,
+}
+/// ModeInfo describes the signing mode of a single or nested multisig signer.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ModeInfo {
+    /// sum is the oneof that specifies whether this represents a single or nested
+    /// multisig signer
+    #[prost(oneof = "mode_info::Sum", tags = "1")]
+    pub sum: ::core::option::Option<mode_info::Sum>,
+}
+/// Nested message and enum types in `ModeInfo`.
+pub mod mode_info {
+    /// Single is the mode info for a single signer. It is structured as a message
+    /// to allow for additional fields such as locale for SIGN_MODE_TEXTUAL in the
+    /// future
+    #[derive(Clone, Copy, PartialEq, ::prost::Message)]
+    pub struct Single {
+        /// mode is the signing mode of the single signer
+        #[prost(enumeration = "super::super::signing::v1beta1::SignMode", tag = "1")]
+        pub mode: i32,
+    }
+    /// sum is the oneof that specifies whether this represents a single or nested
+    /// multisig signer
+    #[derive(Clone, Copy, PartialEq, ::prost::Oneof)]
+    pub enum Sum {
+        /// single represents a single signer
+        #[prost(message, tag = "1")]
+        Single(Single),
+    }
+}
+/// Fee includes the amount of coins paid in fees and the maximum
+/// gas to be used by the transaction. The ratio yields an effective "gasprice",
+/// which must be above some miminum to be accepted into the mempool.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Fee {
+    /// amount is the amount of coins to be paid as a fee
+    #[prost(message, repeated, tag = "1")]
+    pub amount: ::prost::alloc::vec::Vec<super::super::base::v1beta1::Coin>,
+    /// gas_limit is the maximum gas that can be used in transaction processing
+    /// before an out of gas error occurs
+    #[prost(uint64, tag = "2")]
+    pub gas_limit: u64,
+    /// if unset, the first signer is responsible for paying the fees. If set, the specified account must pay the fees.
+    /// the payer must be a tx signer (and thus have signed this field in AuthInfo).
+    /// setting this field does *not* change the ordering of required signers for the transaction.
+    #[prost(string, tag = "3")]
+    pub payer: ::prost::alloc::string::String,
+    /// if set, the fee payer (either the first signer or the value of the payer field) requests that a fee grant be used
+    /// to pay fees instead of the fee payer's own balance. If an appropriate fee grant does not exist or the chain does
+    /// not support fee grants, this will fail
+    #[prost(string, tag = "4")]
+    pub granter: ::prost::alloc::string::String,
+}
+/// SimulateRequest is the request type for the Service.Simulate
+/// RPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SimulateRequest {
+    /// tx_bytes is the raw transaction.
+    ///
+    /// Since: cosmos-sdk 0.43
+    #[prost(bytes = "vec", tag = "2")]
+    pub tx_bytes: ::prost::alloc::vec::Vec<u8>,
+}
+/// SimulateResponse is the response type for the
+/// Service.SimulateRPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SimulateResponse {
+    /// gas_info is the information about gas used in the simulation.
+    #[prost(message, optional, tag = "1")]
+    pub gas_info: ::core::option::Option<super::super::base::abci::v1beta1::GasInfo>,
+    /// result is the result of the simulation.
+    #[prost(message, optional, tag = "2")]
+    pub result: ::core::option::Option<super::super::base::abci::v1beta1::Result>,
+}
+/// GetTxRequest is the request type for the Service.GetTx
+/// RPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTxRequest {
+    /// hash is the tx hash to query, encoded as a hex string.
+    #[prost(string, tag = "1")]
+    pub hash: ::prost::alloc::string::String,
+}
+/// GetTxResponse is the response type for the Service.GetTx method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTxResponse {
+    /// tx is the queried transaction.
+    #[prost(message, optional, tag = "1")]
+    pub tx: ::core::option::Option<Tx>,
+    /// tx_response is the queried TxResponses.
+    #[prost(message, optional, tag = "2")]
+    pub tx_response: ::core::option::Option<super::super::base::abci::v1beta1::TxResponse>,
+}
+/// BroadcastTxRequest is the request type for the Service.BroadcastTxRequest
+/// RPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BroadcastTxRequest {
+    /// tx_bytes is the raw transaction.
+    #[prost(bytes = "vec", tag = "1")]
+    pub tx_bytes: ::prost::alloc::vec::Vec<u8>,
+    #[prost(enumeration = "BroadcastMode", tag = "2")]
+    pub mode: i32,
+}
+/// BroadcastTxResponse is the response type for the
+/// Service.BroadcastTx method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BroadcastTxResponse {
+    /// tx_response is the queried TxResponses.
+    #[prost(message, optional, tag = "1")]
+    pub tx_response: ::core::option::Option<super::super::base::abci::v1beta1::TxResponse>,
+}
+/// BroadcastMode specifies the broadcast mode for the TxService.Broadcast RPC
+/// method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum BroadcastMode {
+    /// zero-value for mode ordering
+    Unspecified = 0,
+    /// DEPRECATED: use BROADCAST_MODE_SYNC instead,
+    /// BROADCAST_MODE_BLOCK is not supported by the SDK from v0.47.x onwards.
+    Block = 1,
+    /// BROADCAST_MODE_SYNC defines a tx broadcasting mode where the client waits
+    /// for a CheckTx execution response only.
+    Sync = 2,
+    /// BROADCAST_MODE_ASYNC defines a tx broadcasting mode where the client
+    /// returns immediately.
+    Async = 3,
+}
+impl BroadcastMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "BROADCAST_MODE_UNSPECIFIED",
+            Self::Block => "BROADCAST_MODE_BLOCK",
+            Self::Sync => "BROADCAST_MODE_SYNC",
+            Self::Async => "BROADCAST_MODE_ASYNC",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "BROADCAST_MODE_UNSPECIFIED" => Some(Self::Unspecified),
+            "BROADCAST_MODE_BLOCK" => Some(Self::Block),
+            "BROADCAST_MODE_SYNC" => Some(Self::Sync),
+            "BROADCAST_MODE_ASYNC" => Some(Self::Async),
+            _ => None,
+        }
+    }
+}
+/// Generated client implementations.
+pub mod service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value
+    )]
+    use tonic::codegen::http::Uri;
+    use tonic::codegen::*;
+    /// Service defines a gRPC service for interacting with transactions.
+    #[derive(Debug, Clone)]
+    pub struct ServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl ServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> ServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> ServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                    http::Request<tonic::body::BoxBody>,
+                    Response = http::Response<
+                        <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                    >,
+                >,
+            <T as tonic::codegen::Service<http::Request<tonic::body::BoxBody>>>::Error:
+                Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            ServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// Simulate simulates executing a transaction for estimating gas usage.
+        pub async fn simulate(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SimulateRequest>,
+        ) -> std::result::Result<tonic::Response<super::SimulateResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/cosmos.tx.v1beta1.Service/Simulate");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("cosmos.tx.v1beta1.Service", "Simulate"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// GetTx fetches a tx by hash.
+        pub async fn get_tx(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetTxRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetTxResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/cosmos.tx.v1beta1.Service/GetTx");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("cosmos.tx.v1beta1.Service", "GetTx"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// BroadcastTx broadcast transaction.
+        pub async fn broadcast_tx(
+            &mut self,
+            request: impl tonic::IntoRequest<super::BroadcastTxRequest>,
+        ) -> std::result::Result<tonic::Response<super::BroadcastTxResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/cosmos.tx.v1beta1.Service/BroadcastTx");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("cosmos.tx.v1beta1.Service", "BroadcastTx"));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
@@ -1,11 +1,17 @@
 //! module structure wrapper over the generated proto types
+//!
+//! The generated modules mirror the proto package paths, which can shift when the protos are
+//! regenerated. Prefer importing from [prelude], which re-exports the types used by this crate
+//! under stable names.
 
 pub mod cheqd {
+    #[cfg(feature = "proto-did")]
     pub mod did {
         pub mod v2 {
             include!("cheqd.did.v2.rs");
         }
     }
+    #[cfg(feature = "proto-resource")]
     pub mod resource {
         pub mod v2 {
             include!("cheqd.resource.v2.rs");
@@ -14,11 +20,104 @@ pub mod cheqd {
 }
 
 pub mod cosmos {
+    #[cfg(feature = "registrar")]
+    pub mod auth {
+        pub mod v1beta1 {
+            include!("cosmos.auth.v1beta1.rs");
+        }
+    }
     pub mod base {
+        #[cfg(feature = "registrar")]
+        pub mod abci {
+            pub mod v1beta1 {
+                include!("cosmos.base.abci.v1beta1.rs");
+            }
+        }
         pub mod query {
             pub mod v1beta1 {
                 include!("cosmos.base.query.v1beta1.rs");
             }
         }
+        #[cfg(feature = "fees")]
+        pub mod v1beta1 {
+            include!("cosmos.base.v1beta1.rs");
+        }
+    }
+    #[cfg(feature = "registrar")]
+    pub mod crypto {
+        pub mod secp256k1 {
+            include!("cosmos.crypto.secp256k1.rs");
+        }
+    }
+    #[cfg(feature = "registrar")]
+    pub mod tx {
+        pub mod signing {
+            pub mod v1beta1 {
+                include!("cosmos.tx.signing.v1beta1.rs");
+            }
+        }
+        pub mod v1beta1 {
+            include!("cosmos.tx.v1beta1.rs");
+        }
     }
 }
+
+#[cfg(feature = "fees")]
+pub mod feemarket {
+    // mirrors the `feemarket.feemarket.v1` proto package
+    #[allow(clippy::module_inception)]
+    pub mod feemarket {
+        pub mod v1 {
+            include!("feemarket.feemarket.v1.rs");
+        }
+    }
+}
+
+#[cfg(feature = "reflection")]
+pub mod reflection;
+
+/// Curated re-exports of the generated proto types & query clients.
+pub mod prelude {
+    pub use super::cosmos::base::query::v1beta1::{PageRequest, PageResponse};
+
+    #[cfg(feature = "proto-did")]
+    pub use super::cheqd::did::v2::{
+        DidDoc, DidDocWithMetadata, Metadata as DidDocMetadata,
+        QueryAllDidDocVersionsMetadataRequest, QueryAllDidDocVersionsMetadataResponse,
+        QueryDidDocRequest, QueryDidDocResponse, QueryDidDocVersionRequest,
+        QueryDidDocVersionResponse, Service, VerificationMethod,
+        query_client::QueryClient as DidQueryClient,
+    };
+
+    #[cfg(feature = "proto-did")]
+    pub use super::cheqd::did::v2::{
+        MsgCreateDidDoc, MsgCreateDidDocPayload, MsgDeactivateDidDoc, MsgDeactivateDidDocPayload,
+        MsgUpdateDidDoc, MsgUpdateDidDocPayload, SignInfo,
+    };
+
+    #[cfg(feature = "proto-resource")]
+    pub use super::cheqd::resource::v2::{
+        AlternativeUri, Metadata as ResourceMetadata, QueryCollectionResourcesRequest,
+        QueryCollectionResourcesResponse, QueryResourceMetadataRequest,
+        QueryResourceMetadataResponse, QueryResourceRequest, QueryResourceResponse, Resource,
+        ResourceWithMetadata, query_client::QueryClient as ResourceQueryClient,
+    };
+
+    #[cfg(feature = "proto-resource")]
+    pub use super::cheqd::resource::v2::{
+        MsgCreateResource, MsgCreateResourcePayload, MsgCreateResourceResponse,
+    };
+
+    #[cfg(feature = "fees")]
+    pub use super::{
+        cosmos::base::v1beta1::{Coin, DecCoin},
+        feemarket::feemarket::v1::{
+            GasPriceRequest, GasPriceResponse, GasPricesRequest, GasPricesResponse,
+            Params as FeemarketParams, ParamsRequest as FeemarketParamsRequest,
+            ParamsResponse as FeemarketParamsResponse,
+            query_client::QueryClient as FeemarketQueryClient,
+        },
+    };
+
+    pub use prost_types::Timestamp;
+}
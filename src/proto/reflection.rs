@@ -0,0 +1,102 @@
+//! The descriptors of the generated protos, for tools inspecting cheqd's services at runtime (e.g.
+//! generic gRPC debugging UIs) without regenerating code.
+//!
+//! [FILE_DESCRIPTOR_SET] is written by `cargo xtask proto-gen` alongside the generated code, so it
+//! describes exactly the checked-in types & services. It can also be registered with a gRPC
+//! server reflection service, e.g. `tonic_reflection`'s `register_encoded_file_descriptor_set`.
+
+use std::sync::OnceLock;
+
+use prost::Message;
+use prost_types::{DescriptorProto, FileDescriptorSet, ServiceDescriptorProto};
+
+/// The encoded `google.protobuf.FileDescriptorSet` of the generated protos & their imports
+pub const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!("file_descriptor_set.bin");
+
+/// The decoded [FILE_DESCRIPTOR_SET], decoded once on first use & shared process wide
+pub fn file_descriptor_set() -> &'static FileDescriptorSet {
+    static DECODED: OnceLock<FileDescriptorSet> = OnceLock::new();
+    DECODED.get_or_init(|| {
+        FileDescriptorSet::decode(FILE_DESCRIPTOR_SET)
+            .expect("the embedded file descriptor set is valid")
+    })
+}
+
+/// The fully qualified names of the described services, e.g. `cheqd.did.v2.Query`
+pub fn service_names() -> impl Iterator<Item = String> {
+    file_descriptor_set().file.iter().flat_map(|file| {
+        file.service
+            .iter()
+            .map(move |service| format!("{}.{}", file.package(), service.name()))
+    })
+}
+
+/// The descriptor of a service by its fully qualified name, e.g. `cheqd.resource.v2.Query`
+pub fn find_service(full_name: &str) -> Option<&'static ServiceDescriptorProto> {
+    let full_name = full_name.trim_start_matches('.');
+    file_descriptor_set().file.iter().find_map(|file| {
+        let name = full_name.strip_prefix(file.package())?.strip_prefix('.')?;
+        file.service.iter().find(|service| service.name() == name)
+    })
+}
+
+/// The descriptor of a message by its fully qualified name, as used by the `input_type`s &
+/// `output_type`s of methods and the `type_name`s of fields, e.g. `.cheqd.did.v2.DidDoc`.
+/// Nested messages are found by their path, e.g. `cosmos.tx.v1beta1.ModeInfo.Single`.
+pub fn find_message(full_name: &str) -> Option<&'static DescriptorProto> {
+    let full_name = full_name.trim_start_matches('.');
+    file_descriptor_set().file.iter().find_map(|file| {
+        let path = full_name.strip_prefix(file.package())?.strip_prefix('.')?;
+        let mut names = path.split('.');
+        let first = names.next()?;
+        let mut message = file.message_type.iter().find(|m| m.name() == first)?;
+        for name in names {
+            message = message.nested_type.iter().find(|m| m.name() == name)?;
+        }
+        Some(message)
+    })
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use prost_types::field_descriptor_proto::Type;
+
+    use super::*;
+
+    #[test]
+    fn test_descriptors_describe_query_services() {
+        let services: Vec<_> = service_names().collect();
+        for service in ["cheqd.did.v2.Query", "cheqd.resource.v2.Query"] {
+            assert!(services.iter().any(|s| s == service), "{services:?}");
+        }
+        let query = find_service("cheqd.did.v2.Query").unwrap();
+        let did_doc = query.method.iter().find(|m| m.name() == "DidDoc").unwrap();
+        assert_eq!(did_doc.input_type(), ".cheqd.did.v2.QueryDidDocRequest");
+        let response = find_message(did_doc.output_type()).unwrap();
+        assert_eq!(response.field[0].name(), "value");
+        assert!(find_message("cosmos.tx.v1beta1.ModeInfo.Single").is_some());
+        assert!(find_service("cheqd.did.v2.Unknown").is_none());
+    }
+
+    #[test]
+    fn test_descriptor_set_is_self_contained() {
+        // every type a field or method refers to is described, so tools can build a pool
+        let set = file_descriptor_set();
+        let mut fields: Vec<&DescriptorProto> =
+            set.file.iter().flat_map(|f| &f.message_type).collect();
+        while let Some(message) = fields.pop() {
+            fields.extend(&message.nested_type);
+            for field in &message.field {
+                if field.r#type() == Type::Message {
+                    assert!(find_message(field.type_name()).is_some(), "{field:?}");
+                }
+            }
+        }
+        for file in &set.file {
+            for method in file.service.iter().flat_map(|s| &s.method) {
+                assert!(find_message(method.input_type()).is_some(), "{method:?}");
+                assert!(find_message(method.output_type()).is_some(), "{method:?}");
+            }
+        }
+    }
+}
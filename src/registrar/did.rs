@@ -0,0 +1,110 @@
+use futures_util::future::try_join_all;
+use prost::Message;
+
+use crate::{
+    error::DidCheqdResult,
+    proto::cheqd::did::v2::{
+        DidDoc, MsgCreateDidDoc, MsgCreateDidDocPayload, MsgDeactivateDidDoc,
+        MsgDeactivateDidDocPayload, MsgUpdateDidDoc, MsgUpdateDidDocPayload, SignInfo,
+    },
+    registrar::signer::DidSigner,
+};
+
+/// The type URL of [MsgCreateDidDoc], for packing it into a transaction
+pub const MSG_CREATE_DID_DOC: &str = "/cheqd.did.v2.MsgCreateDidDoc";
+/// The type URL of [MsgUpdateDidDoc], for packing it into a transaction
+pub const MSG_UPDATE_DID_DOC: &str = "/cheqd.did.v2.MsgUpdateDidDoc";
+/// The type URL of [MsgDeactivateDidDoc], for packing it into a transaction
+pub const MSG_DEACTIVATE_DID_DOC: &str = "/cheqd.did.v2.MsgDeactivateDidDoc";
+
+/// Sign a payload with each of the signers. The ledger requires a signature from a verification
+/// method of every controller of the DID (and, when updating, of the previous controllers).
+pub async fn sign_payload(
+    payload: &impl Message,
+    signers: &[&dyn DidSigner],
+) -> DidCheqdResult<Vec<SignInfo>> {
+    let bytes = payload.encode_to_vec();
+    try_join_all(signers.iter().map(|signer| {
+        let bytes = &bytes;
+        async move {
+            Ok(SignInfo {
+                verification_method_id: signer.verification_method_id(),
+                signature: signer.sign(bytes).await?,
+            })
+        }
+    }))
+    .await
+}
+
+/// A signed message creating a DID, with the given version id (a UUID) for its first version
+pub async fn create_did_msg(
+    did_doc: DidDoc,
+    version_id: &str,
+    signers: &[&dyn DidSigner],
+) -> DidCheqdResult<MsgCreateDidDoc> {
+    let payload = MsgCreateDidDocPayload {
+        context: did_doc.context,
+        id: did_doc.id,
+        controller: did_doc.controller,
+        verification_method: did_doc.verification_method,
+        authentication: did_doc.authentication,
+        assertion_method: did_doc.assertion_method,
+        capability_invocation: did_doc.capability_invocation,
+        capability_delegation: did_doc.capability_delegation,
+        key_agreement: did_doc.key_agreement,
+        also_known_as: did_doc.also_known_as,
+        service: did_doc.service,
+        version_id: version_id.to_owned(),
+    };
+    let signatures = sign_payload(&payload, signers).await?;
+    Ok(MsgCreateDidDoc {
+        payload: Some(payload),
+        signatures,
+    })
+}
+
+/// A signed message replacing a DID's document, with the given version id (a UUID) for the new
+/// version
+pub async fn update_did_msg(
+    did_doc: DidDoc,
+    version_id: &str,
+    signers: &[&dyn DidSigner],
+) -> DidCheqdResult<MsgUpdateDidDoc> {
+    let payload = MsgUpdateDidDocPayload {
+        context: did_doc.context,
+        id: did_doc.id,
+        controller: did_doc.controller,
+        verification_method: did_doc.verification_method,
+        authentication: did_doc.authentication,
+        assertion_method: did_doc.assertion_method,
+        capability_invocation: did_doc.capability_invocation,
+        capability_delegation: did_doc.capability_delegation,
+        key_agreement: did_doc.key_agreement,
+        also_known_as: did_doc.also_known_as,
+        service: did_doc.service,
+        version_id: version_id.to_owned(),
+    };
+    let signatures = sign_payload(&payload, signers).await?;
+    Ok(MsgUpdateDidDoc {
+        payload: Some(payload),
+        signatures,
+    })
+}
+
+/// A signed message deactivating a DID, with the given version id (a UUID) for its deactivated
+/// version
+pub async fn deactivate_did_msg(
+    did: &str,
+    version_id: &str,
+    signers: &[&dyn DidSigner],
+) -> DidCheqdResult<MsgDeactivateDidDoc> {
+    let payload = MsgDeactivateDidDocPayload {
+        id: did.to_owned(),
+        version_id: version_id.to_owned(),
+    };
+    let signatures = sign_payload(&payload, signers).await?;
+    Ok(MsgDeactivateDidDoc {
+        payload: Some(payload),
+        signatures,
+    })
+}
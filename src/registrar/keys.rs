@@ -0,0 +1,205 @@
+//! Local keys for the [Signer] & [DidSigner] hooks: a secp256k1 account key, derived from a
+//! mnemonic as cosmos wallets do, paying for transactions, and ed25519 keys signing DID payloads.
+//!
+//! Keys are held in memory; implement the hooks instead to keep keys in an HSM or KMS.
+
+use bech32::{Bech32, Hrp};
+use bip32::{DerivationPath, XPrv};
+use bip39::Mnemonic;
+use ed25519_dalek::Signer as _;
+use futures_util::future::BoxFuture;
+use k256::ecdsa::{Signature, SigningKey};
+use prost_types::Any;
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    proto::cosmos::crypto::secp256k1::PubKey,
+    registrar::{
+        pack,
+        signer::{DidSigner, Signer},
+    },
+};
+
+/// The bech32 prefix of cheqd account addresses
+pub const CHEQD_ADDRESS_PREFIX: &str = "cheqd";
+
+/// The HD path of the first account of a mnemonic, with the cosmos coin type (118) used by cheqd
+/// wallets
+pub const COSMOS_HD_PATH: &str = "m/44'/118'/0'/0/0";
+
+/// The type URL of secp256k1 account public keys
+const SECP256K1_PUB_KEY: &str = "/cosmos.crypto.secp256k1.PubKey";
+
+/// The multicodec prefix of ed25519 public keys, for multibase encoding
+const ED25519_MULTICODEC: [u8; 2] = [0xed, 0x01];
+
+/// The bech32 address, with the given prefix (e.g. [CHEQD_ADDRESS_PREFIX]), of an account's
+/// compressed secp256k1 public key
+pub fn account_address(prefix: &str, public_key: &[u8]) -> DidCheqdResult<String> {
+    let hrp = Hrp::parse(prefix)
+        .map_err(|e| DidCheqdError::BadConfiguration(format!("invalid address prefix: {e}")))?;
+    let hash = Ripemd160::digest(Sha256::digest(public_key));
+    bech32::encode::<Bech32>(hrp, &hash)
+        .map_err(|e| DidCheqdError::BadConfiguration(format!("invalid address: {e}")))
+}
+
+/// A secp256k1 account key paying for transactions, signing as cosmos wallets do
+#[derive(Clone)]
+pub struct Secp256k1Signer {
+    key: SigningKey,
+    address: String,
+}
+
+impl Secp256k1Signer {
+    /// The key of the first account of a BIP-39 mnemonic, as derived by cheqd wallets
+    pub fn from_mnemonic(mnemonic: &str) -> DidCheqdResult<Self> {
+        Self::from_mnemonic_with_path(mnemonic, COSMOS_HD_PATH)
+    }
+
+    /// The key of a BIP-39 mnemonic at an HD path, e.g. `m/44'/118'/0'/0/1` for the second
+    /// account
+    pub fn from_mnemonic_with_path(mnemonic: &str, path: &str) -> DidCheqdResult<Self> {
+        let mnemonic = Mnemonic::parse_normalized(mnemonic.trim())
+            .map_err(|e| DidCheqdError::BadConfiguration(format!("invalid mnemonic: {e}")))?;
+        let path: DerivationPath = path
+            .parse()
+            .map_err(|e| DidCheqdError::BadConfiguration(format!("invalid HD path: {e}")))?;
+        let key = XPrv::derive_from_path(mnemonic.to_seed(""), &path)
+            .map_err(|e| DidCheqdError::BadConfiguration(format!("invalid HD path: {e}")))?;
+        Self::new(key.private_key().clone())
+    }
+
+    /// A signer of a raw 32 byte private key
+    pub fn from_bytes(private_key: &[u8]) -> DidCheqdResult<Self> {
+        let key = SigningKey::from_slice(private_key)
+            .map_err(|e| DidCheqdError::BadConfiguration(format!("invalid secp256k1 key: {e}")))?;
+        Self::new(key)
+    }
+
+    fn new(key: SigningKey) -> DidCheqdResult<Self> {
+        let public_key = key.verifying_key().to_sec1_bytes();
+        let address = account_address(CHEQD_ADDRESS_PREFIX, &public_key)?;
+        Ok(Self { key, address })
+    }
+
+    /// The compressed public key
+    pub fn public_key_bytes(&self) -> Vec<u8> {
+        self.key.verifying_key().to_sec1_bytes().to_vec()
+    }
+}
+
+impl Signer for Secp256k1Signer {
+    fn address(&self) -> String {
+        self.address.clone()
+    }
+
+    fn public_key(&self) -> Any {
+        let key = PubKey {
+            key: self.public_key_bytes(),
+        };
+        pack(SECP256K1_PUB_KEY, &key)
+    }
+
+    /// Sign the SHA-256 digest of the bytes, as a 64 byte low-S `r || s` signature
+    fn sign<'a>(&'a self, sign_bytes: &'a [u8]) -> BoxFuture<'a, DidCheqdResult<Vec<u8>>> {
+        let signature: Signature = self.key.sign(sign_bytes);
+        let signature = signature.normalize_s().unwrap_or(signature);
+        Box::pin(async move { Ok(signature.to_bytes().to_vec()) })
+    }
+}
+
+/// An ed25519 key of a DID's verification method (e.g. an `Ed25519VerificationKey2020`),
+/// signing DID & resource payloads
+#[derive(Clone)]
+pub struct Ed25519DidSigner {
+    verification_method_id: String,
+    key: ed25519_dalek::SigningKey,
+}
+
+impl Ed25519DidSigner {
+    /// The key of a verification method, from its 32 byte private key seed
+    pub fn new(verification_method_id: &str, seed: &[u8]) -> DidCheqdResult<Self> {
+        let seed: &[u8; 32] = seed.try_into().map_err(|_| {
+            DidCheqdError::BadConfiguration(format!(
+                "ed25519 seeds are 32 bytes, got {}",
+                seed.len()
+            ))
+        })?;
+        Ok(Self {
+            verification_method_id: verification_method_id.to_owned(),
+            key: ed25519_dalek::SigningKey::from_bytes(seed),
+        })
+    }
+
+    /// The raw 32 byte public key, e.g. for an `Ed25519VerificationKey2018`'s `publicKeyBase58`
+    pub fn public_key_bytes(&self) -> [u8; 32] {
+        self.key.verifying_key().to_bytes()
+    }
+
+    /// The public key as the `publicKeyMultibase` of an `Ed25519VerificationKey2020`
+    pub fn public_key_multibase(&self) -> String {
+        let key = [ED25519_MULTICODEC.as_slice(), &self.public_key_bytes()].concat();
+        format!("z{}", bs58::encode(key).into_string())
+    }
+}
+
+impl DidSigner for Ed25519DidSigner {
+    fn verification_method_id(&self) -> String {
+        self.verification_method_id.clone()
+    }
+
+    fn sign<'a>(&'a self, payload: &'a [u8]) -> BoxFuture<'a, DidCheqdResult<Vec<u8>>> {
+        let signature = self.key.sign(payload);
+        Box::pin(async move { Ok(signature.to_bytes().to_vec()) })
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use ed25519_dalek::Verifier as _;
+
+    use super::*;
+
+    /// the BIP-39 test mnemonic
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon \
+                            abandon abandon abandon about";
+
+    #[tokio::test]
+    async fn test_secp256k1_signer_from_mnemonic() {
+        let signer = Secp256k1Signer::from_mnemonic(MNEMONIC).unwrap();
+        // the address cosmos wallets derive for the test mnemonic, with cheqd's prefix
+        let cosmos = account_address("cosmos", &signer.public_key_bytes()).unwrap();
+        assert_eq!(cosmos, "cosmos19rl4cm2hmr8afy4kldpxz3fka4jguq0auqdal4");
+        assert!(signer.address().starts_with("cheqd1"));
+        assert_eq!(signer.public_key().type_url, SECP256K1_PUB_KEY);
+
+        let signature = Signer::sign(&signer, b"sign doc").await.unwrap();
+        let signature = Signature::from_slice(&signature).unwrap();
+        assert!(signature.normalize_s().is_none());
+        signer
+            .key
+            .verifying_key()
+            .verify(b"sign doc", &signature)
+            .unwrap();
+
+        let second = Secp256k1Signer::from_mnemonic_with_path(MNEMONIC, "m/44'/118'/0'/0/1");
+        assert_ne!(second.unwrap().address(), signer.address());
+        assert!(Secp256k1Signer::from_mnemonic("abandon abandon").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ed25519_did_signer() {
+        let signer = Ed25519DidSigner::new("did:cheqd:testnet:abc#key-1", &[7; 32]).unwrap();
+        assert!(signer.public_key_multibase().starts_with("z6Mk"));
+        let signature = DidSigner::sign(&signer, b"payload").await.unwrap();
+        let signature = ed25519_dalek::Signature::from_slice(&signature).unwrap();
+        signer
+            .key
+            .verifying_key()
+            .verify(b"payload", &signature)
+            .unwrap();
+        assert!(Ed25519DidSigner::new("did:cheqd:testnet:abc#key-1", &[7; 31]).is_err());
+    }
+}
@@ -0,0 +1,693 @@
+//! Writing did:cheqd DIDs & DID-Linked Resources to the ledger: building `cheqd.did.v2` &
+//! `cheqd.resource.v2` messages signed by the DID's controllers, wrapping them in cosmos transactions signed by a fee paying account, and
+//! broadcasting those to a cheqd node over gRPC.
+//!
+//! DID payloads are signed by [signer::DidSigner]s and transactions by a [signer::Signer], which
+//! applications implement over their key storage, or use the in-memory keys of [keys].
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use prost::Message;
+use prost_types::Any;
+use tonic::{Code, transport::Channel};
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    fees::{CHEQD_FEE_DENOM, connect_channel},
+    proto::{
+        cheqd::{did::v2::DidDoc, resource::v2::MsgCreateResourcePayload},
+        cosmos::{
+            auth::v1beta1::{BaseAccount, QueryAccountRequest, query_client::QueryClient},
+            base::{
+                abci::v1beta1::{GasInfo, TxResponse},
+                v1beta1::Coin,
+            },
+            tx::{
+                signing::v1beta1::SignMode,
+                v1beta1::{
+                    AuthInfo, BroadcastMode, BroadcastTxRequest, Fee, GetTxRequest, ModeInfo,
+                    SignDoc, SignerInfo, SimulateRequest, TxBody, TxRaw, mode_info,
+                    service_client::ServiceClient,
+                },
+            },
+        },
+    },
+    resolution::resolver::{MAINNET_DEFAULT_GRPC, TESTNET_DEFAULT_GRPC},
+};
+
+pub mod did;
+pub mod keys;
+pub mod resource;
+pub mod signer;
+
+use did::{
+    MSG_CREATE_DID_DOC, MSG_DEACTIVATE_DID_DOC, MSG_UPDATE_DID_DOC, create_did_msg,
+    deactivate_did_msg, update_did_msg,
+};
+use resource::{MSG_CREATE_RESOURCE, ResourceFees, create_resource_msg, resource_checksum};
+use signer::{DidSigner, Signer};
+
+/// The type URL of the account type returned for fee paying accounts
+const BASE_ACCOUNT: &str = "/cosmos.auth.v1beta1.BaseAccount";
+
+/// The fees the ledger charges for DID writes. These are governance parameters of the ledger, so
+/// may change; the defaults are those of mainnet & testnet at the time of writing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DidFees {
+    /// the fee for creating a DID, 50 CHEQ
+    pub create: Coin,
+    /// the fee for updating a DID, 25 CHEQ
+    pub update: Coin,
+    /// the fee for deactivating a DID, 10 CHEQ
+    pub deactivate: Coin,
+}
+
+impl Default for DidFees {
+    fn default() -> Self {
+        Self {
+            create: ncheq(50_000_000_000),
+            update: ncheq(25_000_000_000),
+            deactivate: ncheq(10_000_000_000),
+        }
+    }
+}
+
+/// An amount of `ncheq`
+pub fn ncheq(amount: u128) -> Coin {
+    Coin {
+        denom: CHEQD_FEE_DENOM.to_owned(),
+        amount: amount.to_string(),
+    }
+}
+
+/// The factor simulated gas is multiplied by by default, leaving headroom for state changing
+/// between simulating & executing a transaction
+pub const DEFAULT_GAS_ADJUSTMENT: f64 = 1.3;
+
+/// Configuration for a [CheqdRegistrar], defaulting to mainnet
+#[derive(Debug, Clone)]
+pub struct RegistrarConfiguration {
+    /// the gRPC URL of the cheqd node transactions are broadcast to
+    pub grpc_url: String,
+    /// the chain id of the network, which transactions are signed for
+    pub chain_id: String,
+    /// the factor the gas used by simulating a transaction is multiplied by for its gas limit,
+    /// or `None` to skip simulating & use [RegistrarConfiguration::gas_limit]
+    pub gas_adjustment: Option<f64>,
+    /// the gas limit of transactions when not simulating them
+    pub gas_limit: u64,
+    /// the fees of DID writes
+    pub did_fees: DidFees,
+    /// the fees of resource writes
+    pub resource_fees: ResourceFees,
+    /// a note attached to every transaction
+    pub memo: String,
+    /// how long [CheqdRegistrar::wait_for_tx] waits for a transaction to be committed
+    pub confirmation_timeout: Duration,
+    /// how often [CheqdRegistrar::wait_for_tx] polls for a transaction
+    pub confirmation_poll_interval: Duration,
+}
+
+impl Default for RegistrarConfiguration {
+    fn default() -> Self {
+        Self {
+            grpc_url: MAINNET_DEFAULT_GRPC.to_owned(),
+            chain_id: "cheqd-mainnet-1".to_owned(),
+            gas_adjustment: Some(DEFAULT_GAS_ADJUSTMENT),
+            gas_limit: 360_000,
+            did_fees: DidFees::default(),
+            resource_fees: ResourceFees::default(),
+            memo: String::new(),
+            confirmation_timeout: Duration::from_secs(60),
+            confirmation_poll_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RegistrarConfiguration {
+    /// A configuration for writing to testnet
+    pub fn testnet() -> Self {
+        Self {
+            grpc_url: TESTNET_DEFAULT_GRPC.to_owned(),
+            chain_id: "cheqd-testnet-6".to_owned(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A resource created by [CheqdRegistrar::create_resource]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatedResource {
+    /// the response of the creating transaction
+    pub tx: TxResponse,
+    /// the DID URL of the resource, e.g. `did:cheqd:testnet:<id>/resources/<uuid>`
+    pub did_url: String,
+    /// the hex encoded SHA-256 digest of the resource's data, as recorded by the ledger
+    pub checksum: String,
+}
+
+/// Writes DIDs & resources to a cheqd network, paying fees from the account of its [Signer].
+///
+/// Transactions are signed with the account's current sequence, so transactions of one account
+/// should be broadcast one at a time, each after the previous one was accepted.
+#[derive(Clone)]
+pub struct CheqdRegistrar {
+    config: Arc<RegistrarConfiguration>,
+    signer: Arc<dyn Signer>,
+    channel: Channel,
+}
+
+impl CheqdRegistrar {
+    /// Connect to the node configured in `config`
+    pub async fn connect(
+        config: RegistrarConfiguration,
+        signer: Arc<dyn Signer>,
+    ) -> DidCheqdResult<Self> {
+        let channel = connect_channel(&config.grpc_url).await?;
+        Ok(Self::new(channel, config, signer))
+    }
+
+    /// Broadcast over an already established channel, e.g. one shared with other clients
+    pub fn new(channel: Channel, config: RegistrarConfiguration, signer: Arc<dyn Signer>) -> Self {
+        Self {
+            config: Arc::new(config),
+            signer,
+            channel,
+        }
+    }
+
+    /// Create a DID, signed by the verification methods of its controllers. `version_id` (a
+    /// UUID) identifies the DID document's first version.
+    pub async fn create_did(
+        &self,
+        did_doc: DidDoc,
+        version_id: &str,
+        signers: &[&dyn DidSigner],
+    ) -> DidCheqdResult<TxResponse> {
+        let msg = create_did_msg(did_doc, version_id, signers).await?;
+        let fee = self.config.did_fees.create.clone();
+        self.write(vec![pack(MSG_CREATE_DID_DOC, &msg)], fee).await
+    }
+
+    /// Replace a DID's document, signed by the verification methods of its current & new
+    /// controllers. `version_id` (a UUID) identifies the new version.
+    pub async fn update_did(
+        &self,
+        did_doc: DidDoc,
+        version_id: &str,
+        signers: &[&dyn DidSigner],
+    ) -> DidCheqdResult<TxResponse> {
+        let msg = update_did_msg(did_doc, version_id, signers).await?;
+        let fee = self.config.did_fees.update.clone();
+        self.write(vec![pack(MSG_UPDATE_DID_DOC, &msg)], fee).await
+    }
+
+    /// Deactivate a DID, signed by the verification methods of its controllers. `version_id` (a
+    /// UUID) identifies the deactivated version.
+    pub async fn deactivate_did(
+        &self,
+        did: &str,
+        version_id: &str,
+        signers: &[&dyn DidSigner],
+    ) -> DidCheqdResult<TxResponse> {
+        let msg = deactivate_did_msg(did, version_id, signers).await?;
+        let fee = self.config.did_fees.deactivate.clone();
+        self.write(vec![pack(MSG_DEACTIVATE_DID_DOC, &msg)], fee)
+            .await
+    }
+
+    /// Create a resource in the collection of the DID `did` (e.g. a schema or status list),
+    /// signed by the verification methods of the DID's controllers. The payload's
+    /// `collection_id` is set from `did`, and the fee is chosen by the media type of its data.
+    pub async fn create_resource(
+        &self,
+        did: &str,
+        mut payload: MsgCreateResourcePayload,
+        signers: &[&dyn DidSigner],
+    ) -> DidCheqdResult<CreatedResource> {
+        let collection_id = did.rsplit(':').next().unwrap_or_default();
+        payload.collection_id = collection_id.to_owned();
+        let did_url = format!("{did}/resources/{}", payload.id);
+        let checksum = resource_checksum(&payload.data);
+        let fee = self.config.resource_fees.for_data(&payload.data).clone();
+        let msg = create_resource_msg(payload, signers).await?;
+        let tx = self
+            .write(vec![pack(MSG_CREATE_RESOURCE, &msg)], fee)
+            .await?;
+        Ok(CreatedResource {
+            tx,
+            did_url,
+            checksum,
+        })
+    }
+
+    /// The fee paying account, with its account number & the sequence of its next transaction
+    pub async fn account(&self) -> DidCheqdResult<BaseAccount> {
+        let request = QueryAccountRequest {
+            address: self.signer.address(),
+        };
+        let response = QueryClient::new(self.channel.clone())
+            .account(request)
+            .await
+            .map_err(|e| DidCheqdError::NonSuccessResponse(Box::new(e)))?
+            .into_inner();
+        let account = response.account.ok_or(DidCheqdError::InvalidResponse(
+            "Account query did not return an account".into(),
+        ))?;
+        if account.type_url != BASE_ACCOUNT {
+            return Err(DidCheqdError::InvalidResponse(format!(
+                "unsupported account type {}",
+                account.type_url
+            )));
+        }
+        BaseAccount::decode(account.value.as_slice())
+            .map_err(|e| DidCheqdError::InvalidResponse(format!("invalid account: {e}")))
+    }
+
+    /// Simulate a transaction of the given messages, returning the gas it would use. Signatures
+    /// aren't verified when simulating, so the transaction is left unsigned. A transaction the
+    /// ledger would reject is a [DidCheqdError::TxFailed] error.
+    pub async fn simulate(&self, messages: Vec<Any>) -> DidCheqdResult<GasInfo> {
+        let account = self.account().await?;
+        let (body_bytes, auth_info_bytes) = self.tx_bytes(messages, Fee::default(), &account);
+        let tx = TxRaw {
+            body_bytes,
+            auth_info_bytes,
+            signatures: vec![Vec::new()],
+        };
+        let request = SimulateRequest {
+            tx_bytes: tx.encode_to_vec(),
+        };
+        let response = ServiceClient::new(self.channel.clone())
+            .simulate(request)
+            .await
+            .map_err(|e| match e.code() {
+                // nodes fail simulations of failing transactions with an unknown status
+                Code::Unknown => {
+                    DidCheqdError::TxFailed(format!("simulation failed: {}", e.message()))
+                }
+                _ => DidCheqdError::NonSuccessResponse(Box::new(e)),
+            })?
+            .into_inner();
+        response.gas_info.ok_or(DidCheqdError::InvalidResponse(
+            "Simulate did not return gas info".into(),
+        ))
+    }
+
+    /// The gas limit for a transaction of the given messages: the gas used by simulating it times
+    /// [RegistrarConfiguration::gas_adjustment], or the configured gas limit when not simulating
+    pub async fn estimate_gas(&self, messages: Vec<Any>) -> DidCheqdResult<u64> {
+        let Some(adjustment) = self.config.gas_adjustment else {
+            return Ok(self.config.gas_limit);
+        };
+        let gas_info = self.simulate(messages).await?;
+        Ok((gas_info.gas_used as f64 * adjustment).ceil() as u64)
+    }
+
+    /// Build & sign a transaction of the given messages, paying `fee`
+    pub async fn sign_tx(&self, messages: Vec<Any>, fee: Fee) -> DidCheqdResult<TxRaw> {
+        let account = self.account().await?;
+        let (body_bytes, auth_info_bytes) = self.tx_bytes(messages, fee, &account);
+        let sign_doc = SignDoc {
+            body_bytes,
+            auth_info_bytes,
+            chain_id: self.config.chain_id.clone(),
+            account_number: account.account_number,
+        };
+        let signature = self.signer.sign(&sign_doc.encode_to_vec()).await?;
+        Ok(TxRaw {
+            body_bytes: sign_doc.body_bytes,
+            auth_info_bytes: sign_doc.auth_info_bytes,
+            signatures: vec![signature],
+        })
+    }
+
+    /// The encoded body & auth info of a transaction of the account
+    fn tx_bytes(&self, messages: Vec<Any>, fee: Fee, account: &BaseAccount) -> (Vec<u8>, Vec<u8>) {
+        let body = TxBody {
+            messages,
+            memo: self.config.memo.clone(),
+            ..Default::default()
+        };
+        let auth_info = AuthInfo {
+            signer_infos: vec![SignerInfo {
+                public_key: Some(self.signer.public_key()),
+                mode_info: Some(ModeInfo {
+                    sum: Some(mode_info::Sum::Single(mode_info::Single {
+                        mode: SignMode::Direct.into(),
+                    })),
+                }),
+                sequence: account.sequence,
+            }],
+            fee: Some(fee),
+        };
+        (body.encode_to_vec(), auth_info.encode_to_vec())
+    }
+
+    /// Sign & broadcast a transaction of the given messages, paying `fee`. Returns once the node
+    /// accepted the transaction into its mempool, which doesn't mean it was committed yet.
+    pub async fn broadcast(&self, messages: Vec<Any>, fee: Fee) -> DidCheqdResult<TxResponse> {
+        let tx = self.sign_tx(messages, fee).await?;
+        let request = BroadcastTxRequest {
+            tx_bytes: tx.encode_to_vec(),
+            mode: BroadcastMode::Sync.into(),
+        };
+        let response = ServiceClient::new(self.channel.clone())
+            .broadcast_tx(request)
+            .await
+            .map_err(|e| DidCheqdError::NonSuccessResponse(Box::new(e)))?
+            .into_inner();
+        let response = response.tx_response.ok_or(DidCheqdError::InvalidResponse(
+            "BroadcastTx did not return a response".into(),
+        ))?;
+        check_tx(response)
+    }
+
+    /// Wait for a broadcast transaction (by its hash) to be committed, polling the node every
+    /// [RegistrarConfiguration::confirmation_poll_interval]. Returns the committed transaction's
+    /// result, or an error if it failed (e.g. [DidCheqdError::OutOfGas]) or wasn't committed within
+    /// [RegistrarConfiguration::confirmation_timeout] ([DidCheqdError::TxTimeout]).
+    pub async fn wait_for_tx(&self, hash: &str) -> DidCheqdResult<TxResponse> {
+        let deadline = Instant::now() + self.config.confirmation_timeout;
+        let mut client = ServiceClient::new(self.channel.clone());
+        loop {
+            let request = GetTxRequest {
+                hash: hash.to_owned(),
+            };
+            match client.get_tx(request).await {
+                Ok(response) => {
+                    let response =
+                        response
+                            .into_inner()
+                            .tx_response
+                            .ok_or(DidCheqdError::InvalidResponse(
+                                "GetTx did not return a response".into(),
+                            ))?;
+                    return check_tx(response);
+                }
+                // not committed yet
+                Err(status) if status.code() == Code::NotFound => {}
+                Err(status) => return Err(DidCheqdError::NonSuccessResponse(Box::new(status))),
+            }
+            let interval = self.config.confirmation_poll_interval;
+            if Instant::now() + interval > deadline {
+                return Err(DidCheqdError::TxTimeout(format!(
+                    "{hash} not committed within {:?}",
+                    self.config.confirmation_timeout
+                )));
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// Broadcast a write of the given messages, paying the write's fee `amount`
+    async fn write(&self, messages: Vec<Any>, amount: Coin) -> DidCheqdResult<TxResponse> {
+        let fee = Fee {
+            amount: vec![amount],
+            gas_limit: self.estimate_gas(messages.clone()).await?,
+            ..Default::default()
+        };
+        self.broadcast(messages, fee).await
+    }
+}
+
+/// Pack a message into an `Any` of the given type URL, for a transaction's messages
+pub fn pack(type_url: &str, msg: &impl Message) -> Any {
+    Any {
+        type_url: type_url.to_owned(),
+        value: msg.encode_to_vec(),
+    }
+}
+
+/// The response of a transaction, or an error if the ledger rejected it. Out of gas, signature &
+/// fee failures have their own errors, so callers can e.g. retry with a higher gas limit.
+fn check_tx(response: TxResponse) -> DidCheqdResult<TxResponse> {
+    if response.code == 0 {
+        return Ok(response);
+    }
+    let message = format!(
+        "{} failed with {} code {}: {}",
+        response.txhash, response.codespace, response.code, response.raw_log
+    );
+    Err(match (response.codespace.as_str(), response.code) {
+        ("sdk", 11) => DidCheqdError::OutOfGas(message),
+        // sdk: unauthorized, invalid pubkey; cheqd: invalid or missing DID signatures
+        ("sdk", 4 | 8) | ("cheqd", 1100 | 1101) => DidCheqdError::Unauthorized(message),
+        // insufficient funds, insufficient fee
+        ("sdk", 5 | 13) => DidCheqdError::InsufficientFee(message),
+        _ => DidCheqdError::TxFailed(message),
+    })
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use futures_util::future::BoxFuture;
+
+    use super::*;
+    use crate::{
+        proto::cheqd::did::v2::{MsgCreateDidDoc, VerificationMethod},
+        resolution::{
+            parser::DidCheqdParser,
+            resolver::{DidCheqdResolver, DidCheqdResolverConfiguration},
+        },
+        test_utils::{LedgerFixtures, MockCheqdNode},
+    };
+
+    /// Signs by prefixing the signed bytes with a tag, so tests can check what was signed
+    struct TagSigner(&'static str);
+
+    impl Signer for TagSigner {
+        fn address(&self) -> String {
+            "cheqd1test".into()
+        }
+
+        fn public_key(&self) -> Any {
+            pack("/cosmos.crypto.secp256k1.PubKey", &ncheq(0))
+        }
+
+        fn sign<'a>(&'a self, bytes: &'a [u8]) -> BoxFuture<'a, DidCheqdResult<Vec<u8>>> {
+            Box::pin(async move { Ok([self.0.as_bytes(), bytes].concat()) })
+        }
+    }
+
+    impl DidSigner for TagSigner {
+        fn verification_method_id(&self) -> String {
+            format!("did:cheqd:testnet:abc#{}", self.0)
+        }
+
+        fn sign<'a>(&'a self, bytes: &'a [u8]) -> BoxFuture<'a, DidCheqdResult<Vec<u8>>> {
+            Signer::sign(self, bytes)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_did() {
+        let node = MockCheqdNode::start(LedgerFixtures::new()).await.unwrap();
+        let config = RegistrarConfiguration {
+            grpc_url: node.url(),
+            chain_id: "cheqd-mocknet".into(),
+            ..RegistrarConfiguration::testnet()
+        };
+        let registrar = CheqdRegistrar::connect(config, Arc::new(TagSigner("account")))
+            .await
+            .unwrap();
+        let did = "did:cheqd:testnet:abc";
+        let did_doc = DidDoc {
+            id: did.into(),
+            controller: vec![did.into()],
+            verification_method: vec![VerificationMethod {
+                id: format!("{did}#key-1"),
+                verification_method_type: "Ed25519VerificationKey2020".into(),
+                controller: did.into(),
+                verification_material: "z6Mk".into(),
+            }],
+            ..Default::default()
+        };
+
+        let key = TagSigner("key-1");
+        let response = registrar
+            .create_did(did_doc.clone(), "v1", &[&key])
+            .await
+            .unwrap();
+        assert_eq!(response.code, 0);
+
+        // the transaction pays the creation fee, signed for the chain & account's sequence
+        let tx = node.transactions().pop().unwrap();
+        let body = TxBody::decode(tx.body_bytes.as_slice()).unwrap();
+        let auth_info = AuthInfo::decode(tx.auth_info_bytes.as_slice()).unwrap();
+        let fee = auth_info.fee.unwrap();
+        assert_eq!(fee.amount, [ncheq(50_000_000_000)]);
+        // the simulated gas, adjusted
+        assert_eq!(fee.gas_limit, 130_000);
+        let sign_doc = SignDoc {
+            body_bytes: tx.body_bytes.clone(),
+            auth_info_bytes: tx.auth_info_bytes.clone(),
+            chain_id: "cheqd-mocknet".into(),
+            account_number: 1,
+        };
+        assert_eq!(
+            tx.signatures,
+            [[b"account".as_slice(), &sign_doc.encode_to_vec()].concat()]
+        );
+        let msg = MsgCreateDidDoc::decode(body.messages[0].value.as_slice()).unwrap();
+        let payload = msg.payload.unwrap();
+        assert_eq!(payload.version_id, "v1");
+        assert_eq!(
+            msg.signatures[0].signature,
+            [b"key-1".as_slice(), &payload.encode_to_vec()].concat()
+        );
+
+        // the DID is resolvable, and can't be created again
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            ..Default::default()
+        });
+        let resolved = resolver
+            .resolve_did_doc(DidCheqdParser::parse(did).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resolved.did_doc, did_doc);
+        let e = registrar
+            .create_did(did_doc, "v2", &[&key])
+            .await
+            .unwrap_err();
+        assert!(matches!(e, DidCheqdError::TxFailed(_)), "{e:?}");
+
+        registrar.deactivate_did(did, "v3", &[&key]).await.unwrap();
+        let resolved = resolver
+            .resolve_did_doc(DidCheqdParser::parse(did).unwrap())
+            .await
+            .unwrap();
+        assert!(resolved.metadata.unwrap().deactivated);
+    }
+
+    #[tokio::test]
+    async fn test_create_resource() {
+        let did = "did:cheqd:testnet:abc";
+        let fixtures = LedgerFixtures::new().with_did_doc(
+            DidDoc {
+                id: did.into(),
+                ..Default::default()
+            },
+            Default::default(),
+        );
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let config = RegistrarConfiguration {
+            grpc_url: node.url(),
+            ..RegistrarConfiguration::testnet()
+        };
+        let registrar = CheqdRegistrar::connect(config, Arc::new(TagSigner("account")))
+            .await
+            .unwrap();
+        let key = TagSigner("key-1");
+        let schema = br#"{"attrNames": ["name"]}"#.to_vec();
+        let payload = |id: &str| MsgCreateResourcePayload {
+            data: schema.clone(),
+            id: id.into(),
+            name: "ExampleSchema".into(),
+            resource_type: "anonCredsSchema".into(),
+            ..Default::default()
+        };
+
+        let created = registrar
+            .create_resource(did, payload("r1"), &[&key])
+            .await
+            .unwrap();
+        assert_eq!(created.did_url, format!("{did}/resources/r1"));
+        assert_eq!(created.checksum, resource::resource_checksum(&schema));
+
+        // JSON resources pay the JSON fee
+        let tx = node.transactions().pop().unwrap();
+        let auth_info = AuthInfo::decode(tx.auth_info_bytes.as_slice()).unwrap();
+        assert_eq!(auth_info.fee.unwrap().amount, [ncheq(2_500_000_000)]);
+
+        // the resource resolves, with the checksum the registrar computed
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            ..Default::default()
+        });
+        let did_url = format!("{}?checksum={}", created.did_url, created.checksum);
+        let resolved = resolver
+            .resolve_resource(&did_url, DidCheqdParser::parse(&did_url).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resolved.data, schema);
+        assert_eq!(resolved.metadata.media_type, "application/json");
+
+        // resources can't be created twice, or in the collection of a missing DID
+        let e = registrar
+            .create_resource(did, payload("r1"), &[&key])
+            .await
+            .unwrap_err();
+        assert!(matches!(e, DidCheqdError::TxFailed(_)), "{e:?}");
+        let e = registrar
+            .create_resource("did:cheqd:testnet:missing", payload("r2"), &[&key])
+            .await
+            .unwrap_err();
+        assert!(e.to_string().contains("not found"), "{e}");
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_tx() {
+        let node = MockCheqdNode::start(LedgerFixtures::new()).await.unwrap();
+        let config = RegistrarConfiguration {
+            grpc_url: node.url(),
+            confirmation_timeout: Duration::from_millis(50),
+            confirmation_poll_interval: Duration::from_millis(10),
+            ..RegistrarConfiguration::testnet()
+        };
+        let registrar = CheqdRegistrar::connect(config.clone(), Arc::new(TagSigner("account")))
+            .await
+            .unwrap();
+        let did_doc = |id: &str| DidDoc {
+            id: format!("did:cheqd:testnet:{id}"),
+            ..Default::default()
+        };
+        let key = TagSigner("key-1");
+
+        let broadcast = registrar
+            .create_did(did_doc("abc"), "v1", &[&key])
+            .await
+            .unwrap();
+        let committed = registrar.wait_for_tx(&broadcast.txhash).await.unwrap();
+        assert_eq!(committed.txhash, broadcast.txhash);
+        assert_eq!(committed.height, 1);
+        assert!(committed.gas_used > 0);
+
+        let e = registrar.wait_for_tx("0BADC0DE").await.unwrap_err();
+        assert!(matches!(e, DidCheqdError::TxTimeout(_)), "{e:?}");
+
+        // failures are typed by their code
+        let starved = CheqdRegistrar::connect(
+            RegistrarConfiguration {
+                gas_adjustment: None,
+                gas_limit: 1_000,
+                ..config
+            },
+            Arc::new(TagSigner("account")),
+        )
+        .await
+        .unwrap();
+        let e = starved
+            .create_did(did_doc("def"), "v1", &[&key])
+            .await
+            .unwrap_err();
+        assert!(matches!(e, DidCheqdError::OutOfGas(_)), "{e:?}");
+        let response = |codespace: &str, code| TxResponse {
+            codespace: codespace.into(),
+            code,
+            ..Default::default()
+        };
+        assert!(matches!(
+            check_tx(response("sdk", 13)),
+            Err(DidCheqdError::InsufficientFee(_))
+        ));
+        assert!(matches!(
+            check_tx(response("cheqd", 1100)),
+            Err(DidCheqdError::Unauthorized(_))
+        ));
+    }
+}
@@ -0,0 +1,97 @@
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::DidCheqdResult,
+    proto::{
+        cheqd::resource::v2::{MsgCreateResource, MsgCreateResourcePayload},
+        cosmos::base::v1beta1::Coin,
+    },
+    registrar::{did::sign_payload, ncheq, signer::DidSigner},
+    resolution::media_type::{is_json_media_type, sniff_media_type},
+};
+
+/// The type URL of [MsgCreateResource], for packing it into a transaction
+pub const MSG_CREATE_RESOURCE: &str = "/cheqd.resource.v2.MsgCreateResource";
+
+/// The fees the ledger charges for creating resources, which depend on the media type the ledger
+/// detects for a resource's data. These are governance parameters of the ledger, so may change;
+/// the defaults are those of mainnet & testnet at the time of writing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceFees {
+    /// the fee for an image resource, 10 CHEQ
+    pub image: Coin,
+    /// the fee for a JSON resource (e.g. a schema or status list), 2.5 CHEQ
+    pub json: Coin,
+    /// the fee for any other resource, 5 CHEQ
+    pub default: Coin,
+}
+
+impl Default for ResourceFees {
+    fn default() -> Self {
+        Self {
+            image: ncheq(10_000_000_000),
+            json: ncheq(2_500_000_000),
+            default: ncheq(5_000_000_000),
+        }
+    }
+}
+
+impl ResourceFees {
+    /// The fee for creating a resource of the given data
+    pub fn for_data(&self, data: &[u8]) -> &Coin {
+        match resource_media_type(data) {
+            Some(media_type) if media_type.starts_with("image/") => &self.image,
+            Some(media_type) if is_json_media_type(media_type) => &self.json,
+            _ => &self.default,
+        }
+    }
+}
+
+/// The media type the ledger would detect for a resource's data, as far as it can be inferred
+/// locally. Images other than PNG are recognized by their magic bytes, as they are priced apart.
+pub fn resource_media_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\xff\xd8\xff") {
+        return Some("image/jpeg");
+    }
+    if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        return Some("image/gif");
+    }
+    sniff_media_type(data)
+}
+
+/// The checksum the ledger records for a resource's data: its hex encoded SHA-256 digest, as
+/// given by a DID URL's `checksum` parameter
+pub fn resource_checksum(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// A signed message creating a resource in the collection of a DID, signed by the verification
+/// methods of the DID's controllers
+pub async fn create_resource_msg(
+    payload: MsgCreateResourcePayload,
+    signers: &[&dyn DidSigner],
+) -> DidCheqdResult<MsgCreateResource> {
+    let signatures = sign_payload(&payload, signers).await?;
+    Ok(MsgCreateResource {
+        payload: Some(payload),
+        signatures,
+    })
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_fees() {
+        let fees = ResourceFees::default();
+        assert_eq!(fees.for_data(br#"{"type": "schema"}"#), &fees.json);
+        assert_eq!(fees.for_data(b"\x89PNG\r\n\x1a\n...."), &fees.image);
+        assert_eq!(fees.for_data(b"\xff\xd8\xff\xe0...."), &fees.image);
+        assert_eq!(fees.for_data(b"plain text"), &fees.default);
+        assert_eq!(
+            resource_checksum(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
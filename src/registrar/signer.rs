@@ -0,0 +1,28 @@
+use futures_util::future::BoxFuture;
+use prost_types::Any;
+
+use crate::error::DidCheqdResult;
+
+/// Signs transactions for the cosmos account paying for ledger writes. Implement it to keep the
+/// account's key in an HSM or KMS, or use an in-memory [Secp256k1Signer](super::keys::Secp256k1Signer).
+pub trait Signer: Send + Sync {
+    /// The account's bech32 address, e.g. `cheqd1...`
+    fn address(&self) -> String;
+
+    /// The account's public key, as placed in a transaction's signer info (e.g. a
+    /// `/cosmos.crypto.secp256k1.PubKey`)
+    fn public_key(&self) -> Any;
+
+    /// Sign the bytes of a transaction's `SignDoc` (`SIGN_MODE_DIRECT`)
+    fn sign<'a>(&'a self, sign_bytes: &'a [u8]) -> BoxFuture<'a, DidCheqdResult<Vec<u8>>>;
+}
+
+/// Signs DID & resource payloads with a verification method of a DID, proving control of the DID
+/// to the ledger. See [Ed25519DidSigner](super::keys::Ed25519DidSigner) for in-memory ed25519 keys.
+pub trait DidSigner: Send + Sync {
+    /// The id of the verification method signing, e.g. `did:cheqd:testnet:<id>#key-1`
+    fn verification_method_id(&self) -> String;
+
+    /// Sign the protobuf encoding of a payload
+    fn sign<'a>(&'a self, payload: &'a [u8]) -> BoxFuture<'a, DidCheqdResult<Vec<u8>>>;
+}
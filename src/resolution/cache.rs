@@ -0,0 +1,637 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    hash::Hash,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use prost::Message;
+
+#[cfg(feature = "disk-cache")]
+use crate::resolution::disk_cache::{DiskCache, DiskCacheConfiguration, DiskKey, DiskValue};
+use crate::{
+    proto::cheqd::resource::v2::Metadata as CheqdResourceMetadata,
+    resolution::{
+        parser::DID_CHEQD_PREFIX,
+        resolver::{ResolvedDidDoc, ResolvedResource},
+        transformer::RESOURCES_PATH,
+    },
+};
+
+/// Configuration for caching resolution results in memory.
+///
+/// Results which are immutable on the ledger (DID document versions & resources by id) are cached
+/// separately from those which change as the ledger is updated (the latest DID document &
+/// resource name + type lookups), so each can have an appropriate TTL.
+#[derive(Debug, Clone)]
+pub struct CacheConfiguration {
+    /// how long DID document versions & resources looked up by id are cached for. `None` caches
+    /// them for the lifetime of the resolver.
+    pub immutable_ttl: Option<Duration>,
+    /// how long latest DID documents & resources looked up by name & type are cached for
+    pub latest_ttl: Duration,
+    /// how long after expiring a cached DID document is still served immediately, while it is
+    /// refreshed in the background. `None` disables stale-while-revalidate.
+    pub stale_while_revalidate: Option<Duration>,
+    /// how long after expiring a cached DID document is still served when refreshing it fails
+    /// because the network is unreachable. `None` disables stale-if-error.
+    pub stale_if_error: Option<Duration>,
+    /// the approximate memory cached DID documents may use, beyond which the least recently used
+    /// are evicted. `None` leaves them unbounded.
+    pub did_doc_max_bytes: Option<usize>,
+    /// the approximate memory cached resources may use, beyond which the least recently used are
+    /// evicted. `None` leaves them unbounded, which is inadvisable as resources (e.g. status lists
+    /// & images) may be large.
+    pub resource_max_bytes: Option<usize>,
+    /// how long the metadata listings of collections, fetched to look up resources by name &
+    /// type, are used without checking the ledger. Once expired, a listing is revalidated with a
+    /// single query comparing the collection's resource count & latest resource, rather than
+    /// fetched again in full. Listings are held in memory only. `None` disables caching them.
+    pub collection_ttl: Option<Duration>,
+    /// persist cached results to disk, so they outlive the resolver. Requires the `disk-cache`
+    /// feature.
+    #[cfg(feature = "disk-cache")]
+    pub disk: Option<DiskCacheConfiguration>,
+}
+
+/// The default [CacheConfiguration::resource_max_bytes]: 32 MiB
+pub const DEFAULT_RESOURCE_MAX_BYTES: usize = 32 * 1024 * 1024;
+
+impl Default for CacheConfiguration {
+    fn default() -> Self {
+        Self {
+            immutable_ttl: None,
+            latest_ttl: Duration::from_secs(60),
+            stale_while_revalidate: None,
+            stale_if_error: None,
+            did_doc_max_bytes: None,
+            resource_max_bytes: Some(DEFAULT_RESOURCE_MAX_BYTES),
+            collection_ttl: Some(Duration::from_secs(10)),
+            #[cfg(feature = "disk-cache")]
+            disk: None,
+        }
+    }
+}
+
+/// Key of a cached DID document: the DID (with the network's namespace) & the version, if pinned
+pub(crate) type DidDocKey = (String, Option<String>);
+
+/// Key of a cached resource
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum ResourceKey {
+    /// a resource by its id: the network namespace, collection id & resource id
+    ById(String, String, String),
+    /// a resource by name & type query: the network namespace, collection id, name, type & the
+    /// version time, if given
+    ByQuery(String, String, String, String, Option<String>),
+}
+
+impl ResourceKey {
+    fn is_immutable(&self) -> bool {
+        matches!(self, ResourceKey::ById(..))
+    }
+
+    /// A DID URL identifying the cached resource
+    pub(crate) fn did_url(&self) -> String {
+        match self {
+            ResourceKey::ById(namespace, collection, id) => {
+                format!("{DID_CHEQD_PREFIX}{namespace}:{collection}/{RESOURCES_PATH}/{id}")
+            }
+            ResourceKey::ByQuery(namespace, collection, name, rtyp, time) => {
+                let mut did_url = format!(
+                    "{DID_CHEQD_PREFIX}{namespace}:{collection}?resourceName={name}&resourceType={rtyp}"
+                );
+                if let Some(time) = time {
+                    did_url.push_str(&format!("&resourceVersionTime={time}"));
+                }
+                did_url
+            }
+        }
+    }
+}
+
+/// Key of a cached collection metadata listing: the network namespace & collection id
+pub(crate) type CollectionKey = (String, String);
+
+/// How long after expiring a collection metadata listing is kept for revalidation
+const COLLECTION_REVALIDATE_FOR: Duration = Duration::from_secs(60 * 60);
+
+/// The result of looking up a collection metadata listing in the cache
+pub(crate) enum CachedCollection {
+    /// the cached listing has not expired
+    Fresh(Arc<Vec<CheqdResourceMetadata>>),
+    /// the cached listing has expired, so may only be used once revalidated against the ledger
+    Revalidate(Arc<Vec<CheqdResourceMetadata>>),
+    /// there is no listing cached
+    Miss,
+}
+
+/// The result of looking up a DID document in the cache
+pub(crate) enum CachedDidDoc {
+    /// the cached DID document has not expired
+    Fresh(ResolvedDidDoc),
+    /// the cached DID document has expired, but may be served while it is refreshed
+    Revalidate(ResolvedDidDoc),
+    /// there is no servable DID document cached
+    Miss,
+}
+
+/// In-memory cache of resolution results, see [CacheConfiguration]
+pub(crate) struct ResolutionCache {
+    config: CacheConfiguration,
+    did_docs: TtlCache<DidDocKey, ResolvedDidDoc>,
+    resources: TtlCache<ResourceKey, ResolvedResource>,
+    collection_listings: TtlCache<CollectionKey, Arc<Vec<CheqdResourceMetadata>>>,
+    /// DID documents currently being refreshed in the background
+    refreshing: Mutex<HashSet<DidDocKey>>,
+    #[cfg(feature = "disk-cache")]
+    disk: Option<DiskCache>,
+}
+
+impl ResolutionCache {
+    pub(crate) fn new(config: CacheConfiguration) -> Self {
+        Self {
+            #[cfg(feature = "disk-cache")]
+            disk: config.disk.as_ref().and_then(DiskCache::open),
+            did_docs: TtlCache::new(config.did_doc_max_bytes),
+            resources: TtlCache::new(config.resource_max_bytes),
+            collection_listings: TtlCache::new(None),
+            refreshing: Default::default(),
+            config,
+        }
+    }
+
+    pub(crate) fn did_doc(&self, key: &DidDocKey) -> CachedDidDoc {
+        match self.get(&self.did_docs, key) {
+            Some((doc, None)) => CachedDidDoc::Fresh(doc),
+            Some((doc, Some(expired_for)))
+                if self
+                    .config
+                    .stale_while_revalidate
+                    .is_some_and(|window| expired_for < window) =>
+            {
+                CachedDidDoc::Revalidate(ResolvedDidDoc { stale: true, ..doc })
+            }
+            _ => CachedDidDoc::Miss,
+        }
+    }
+
+    /// The cached latest DID document, if it is the given version & has not expired
+    pub(crate) fn latest_did_doc_at_version(&self, did: &str, version: &str) -> CachedDidDoc {
+        let key = (did.to_owned(), None);
+        match self.get(&self.did_docs, &key) {
+            Some((doc, None))
+                if doc
+                    .metadata
+                    .as_ref()
+                    .is_some_and(|metadata| metadata.version_id == version) =>
+            {
+                CachedDidDoc::Fresh(doc)
+            }
+            _ => CachedDidDoc::Miss,
+        }
+    }
+
+    /// The cached DID document to serve in place of one which failed to resolve, if any
+    pub(crate) fn did_doc_if_error(&self, key: &DidDocKey) -> Option<ResolvedDidDoc> {
+        let window = self.config.stale_if_error?;
+        match self.get(&self.did_docs, key)? {
+            (doc, None) => Some(doc),
+            (doc, Some(expired_for)) => {
+                (expired_for < window).then_some(ResolvedDidDoc { stale: true, ..doc })
+            }
+        }
+    }
+
+    pub(crate) fn insert_did_doc(&self, key: DidDocKey, value: ResolvedDidDoc) {
+        let ttl = match key.1 {
+            Some(_) => self.config.immutable_ttl,
+            None => Some(self.config.latest_ttl),
+        };
+        // expired DID documents are kept for as long as they may still be served
+        let stale_for = Option::max(
+            self.config.stale_while_revalidate,
+            self.config.stale_if_error,
+        );
+        self.insert(
+            &self.did_docs,
+            key,
+            value,
+            ttl,
+            stale_for.unwrap_or_default(),
+        );
+    }
+
+    /// Mark a DID document as being refreshed, returning false if it already is
+    pub(crate) fn start_refresh(&self, key: &DidDocKey) -> bool {
+        let mut refreshing = self.refreshing.lock().unwrap_or_else(|e| e.into_inner());
+        refreshing.insert(key.clone())
+    }
+
+    pub(crate) fn finish_refresh(&self, key: &DidDocKey) {
+        let mut refreshing = self.refreshing.lock().unwrap_or_else(|e| e.into_inner());
+        refreshing.remove(key);
+    }
+
+    pub(crate) fn resource(&self, key: &ResourceKey) -> Option<ResolvedResource> {
+        match self.get(&self.resources, key)? {
+            (resource, None) => Some(resource),
+            (_, Some(_)) => None,
+        }
+    }
+
+    pub(crate) fn insert_resource(&self, key: ResourceKey, value: ResolvedResource) {
+        let ttl = match key.is_immutable() {
+            true => self.config.immutable_ttl,
+            false => Some(self.config.latest_ttl),
+        };
+        self.insert(&self.resources, key, value, ttl, Duration::ZERO);
+    }
+
+    pub(crate) fn collection_listing(&self, key: &CollectionKey) -> CachedCollection {
+        match self.collection_listings.get(key) {
+            Some((resources, None)) => CachedCollection::Fresh(resources),
+            Some((resources, Some(_))) => CachedCollection::Revalidate(resources),
+            None => CachedCollection::Miss,
+        }
+    }
+
+    /// Cache a complete listing of a collection's resource metadata, unless disabled
+    pub(crate) fn insert_collection_listing(
+        &self,
+        key: CollectionKey,
+        resources: Arc<Vec<CheqdResourceMetadata>>,
+    ) {
+        if let Some(ttl) = self.config.collection_ttl {
+            let cache = &self.collection_listings;
+            cache.insert(key, resources, Some(ttl), COLLECTION_REVALIDATE_FOR);
+        }
+    }
+
+    /// The number & total size in bytes of the cached DID documents
+    pub(crate) fn did_doc_usage(&self) -> (usize, usize) {
+        self.did_docs.usage()
+    }
+
+    /// The number & total size in bytes of the cached resources
+    pub(crate) fn resource_usage(&self) -> (usize, usize) {
+        self.resources.usage()
+    }
+
+    /// The ids of the collections (i.e. DIDs) on a network with DID documents or resources
+    /// currently cached in memory, in order
+    pub(crate) fn collections(&self, namespace: &str) -> Vec<String> {
+        let prefix = format!("{DID_CHEQD_PREFIX}{namespace}:");
+        let did_doc_collections = self
+            .did_docs
+            .keys()
+            .into_iter()
+            .filter_map(|(did, _)| did.strip_prefix(&prefix).map(str::to_owned));
+        let resource_collections = self.resources.keys().into_iter().filter_map(|key| {
+            let (ResourceKey::ById(ns, collection, _) | ResourceKey::ByQuery(ns, collection, ..)) =
+                key;
+            (ns == namespace).then_some(collection)
+        });
+        let collections: BTreeSet<_> = did_doc_collections.chain(resource_collections).collect();
+        collections.into_iter().collect()
+    }
+
+    pub(crate) fn clear(&self) {
+        self.did_docs.clear();
+        self.resources.clear();
+        self.collection_listings.clear();
+        #[cfg(feature = "disk-cache")]
+        if let Some(disk) = &self.disk {
+            disk.clear();
+        }
+    }
+
+    /// The value cached for `key` in memory, or else on disk, with how long ago it expired if it
+    /// has
+    #[cfg(not(feature = "disk-cache"))]
+    fn get<K: Eq + Hash + Clone, V: Clone + Weigh>(
+        &self,
+        cache: &TtlCache<K, V>,
+        key: &K,
+    ) -> Option<(V, Option<Duration>)> {
+        cache.get(key)
+    }
+
+    #[cfg(feature = "disk-cache")]
+    fn get<K: Eq + Hash + Clone + DiskKey, V: Clone + Weigh + DiskValue>(
+        &self,
+        cache: &TtlCache<K, V>,
+        key: &K,
+    ) -> Option<(V, Option<Duration>)> {
+        cache.get(key).or_else(|| {
+            let (value, expires_at, stale_for) = self.disk.as_ref()?.load(key)?;
+            cache.insert_expiring(key.clone(), value, expires_at, stale_for);
+            cache.get(key)
+        })
+    }
+
+    /// Cache a value in memory, & on disk
+    #[cfg(not(feature = "disk-cache"))]
+    fn insert<K: Eq + Hash + Clone, V: Clone + Weigh>(
+        &self,
+        cache: &TtlCache<K, V>,
+        key: K,
+        value: V,
+        ttl: Option<Duration>,
+        stale_for: Duration,
+    ) {
+        cache.insert(key, value, ttl, stale_for);
+    }
+
+    #[cfg(feature = "disk-cache")]
+    fn insert<K: Eq + Hash + Clone + DiskKey, V: Clone + Weigh + DiskValue>(
+        &self,
+        cache: &TtlCache<K, V>,
+        key: K,
+        value: V,
+        ttl: Option<Duration>,
+        stale_for: Duration,
+    ) {
+        if let Some(disk) = &self.disk {
+            disk.store(&key, &value, ttl, stale_for);
+        }
+        cache.insert(key, value, ttl, stale_for);
+    }
+}
+
+/// A cached value's approximate size in memory, counted against its cache's byte budget
+trait Weigh {
+    fn weight(&self) -> usize;
+}
+
+impl Weigh for ResolvedDidDoc {
+    fn weight(&self) -> usize {
+        self.did_doc.encoded_len() + self.metadata.as_ref().map_or(0, Message::encoded_len)
+    }
+}
+
+impl Weigh for ResolvedResource {
+    fn weight(&self) -> usize {
+        self.data.len() + self.metadata.encoded_len()
+    }
+}
+
+impl Weigh for Arc<Vec<CheqdResourceMetadata>> {
+    fn weight(&self) -> usize {
+        self.iter().map(Message::encoded_len).sum()
+    }
+}
+
+struct CacheEntry<V> {
+    value: V,
+    expires_at: Option<Instant>,
+    /// how long the entry is kept after expiring
+    stale_for: Duration,
+    weight: usize,
+    /// when the entry was last inserted or read, as a [TtlCacheState::tick]
+    last_used: u64,
+}
+
+impl<V> CacheEntry<V> {
+    fn is_evictable(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|at| at + self.stale_for <= now)
+    }
+}
+
+struct TtlCacheState<K, V> {
+    entries: HashMap<K, CacheEntry<V>>,
+    /// the total weight of the entries
+    bytes: usize,
+    /// incremented on every use of an entry, ordering entries by recency
+    tick: u64,
+}
+
+impl<K: Eq + Hash + Clone, V> TtlCacheState<K, V> {
+    fn remove(&mut self, key: &K) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.bytes -= entry.weight;
+        }
+    }
+
+    fn evict_expired(&mut self, now: Instant) {
+        let evictable: Vec<K> = self
+            .entries
+            .iter()
+            .filter(|(_, e)| e.is_evictable(now))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &evictable {
+            self.remove(key);
+        }
+    }
+
+    /// Evict the least recently used entries until `bytes` more fit within `max_bytes`
+    fn evict_lru(&mut self, bytes: usize, max_bytes: usize) {
+        if self.bytes + bytes <= max_bytes {
+            return;
+        }
+        let mut by_recency: Vec<(u64, K)> = self
+            .entries
+            .iter()
+            .map(|(key, e)| (e.last_used, key.clone()))
+            .collect();
+        by_recency.sort_unstable_by_key(|(last_used, _)| *last_used);
+        for (_, key) in by_recency {
+            if self.bytes + bytes <= max_bytes {
+                break;
+            }
+            self.remove(&key);
+        }
+    }
+}
+
+/// A map of values which expire after a per-entry TTL, optionally kept for a while afterwards,
+/// & optionally limited to a total size by evicting the least recently used values
+struct TtlCache<K, V> {
+    state: Mutex<TtlCacheState<K, V>>,
+    max_bytes: Option<usize>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone + Weigh> TtlCache<K, V> {
+    fn new(max_bytes: Option<usize>) -> Self {
+        Self {
+            state: Mutex::new(TtlCacheState {
+                entries: HashMap::new(),
+                bytes: 0,
+                tick: 0,
+            }),
+            max_bytes,
+        }
+    }
+
+    /// The value cached for `key`, with how long ago it expired if it has
+    fn get(&self, key: &K) -> Option<(V, Option<Duration>)> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        state.tick += 1;
+        let tick = state.tick;
+        let entry = state.entries.get_mut(key)?;
+        if entry.is_evictable(now) {
+            state.remove(key);
+            return None;
+        }
+        entry.last_used = tick;
+        let expired_for = entry.expires_at.filter(|at| *at <= now).map(|at| now - at);
+        Some((entry.value.clone(), expired_for))
+    }
+
+    fn insert(&self, key: K, value: V, ttl: Option<Duration>, stale_for: Duration) {
+        let expires_at = ttl.map(|ttl| Instant::now() + ttl);
+        self.insert_expiring(key, value, expires_at, stale_for);
+    }
+
+    fn insert_expiring(&self, key: K, value: V, expires_at: Option<Instant>, stale_for: Duration) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        // drop evictable entries as we go, so the cache does not grow with stale results
+        state.evict_expired(Instant::now());
+        state.remove(&key);
+        let weight = value.weight();
+        if let Some(max_bytes) = self.max_bytes {
+            if weight > max_bytes {
+                // never fits, so isn't worth evicting everything else for
+                return;
+            }
+            state.evict_lru(weight, max_bytes);
+        }
+        state.tick += 1;
+        let entry = CacheEntry {
+            value,
+            expires_at,
+            stale_for,
+            weight,
+            last_used: state.tick,
+        };
+        state.bytes += weight;
+        state.entries.insert(key, entry);
+    }
+
+    /// The keys of the entries, including expired ones not yet evicted
+    fn keys(&self) -> Vec<K> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.entries.keys().cloned().collect()
+    }
+
+    /// The number & total weight of the entries
+    fn usage(&self) -> (usize, usize) {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        (state.entries.len(), state.bytes)
+    }
+
+    fn clear(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.entries.clear();
+        state.bytes = 0;
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use crate::{proto::cheqd::resource::v2::Metadata, resolution::resolver::Transport};
+
+    fn resource(id: &str) -> ResolvedResource {
+        ResolvedResource {
+            data: id.as_bytes().to_vec(),
+            metadata: Metadata {
+                id: id.into(),
+                ..Default::default()
+            },
+            transport: Transport::Grpc,
+            provenance: Default::default(),
+            warnings: Vec::new(),
+        }
+    }
+
+    fn did_doc() -> ResolvedDidDoc {
+        ResolvedDidDoc {
+            did_doc: Default::default(),
+            metadata: None,
+            transport: Transport::Grpc,
+            stale: false,
+            provenance: Default::default(),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_stale_did_docs() {
+        let key = ("did:cheqd:testnet:abc".to_owned(), None);
+        let cache = ResolutionCache::new(CacheConfiguration {
+            latest_ttl: Duration::ZERO,
+            stale_if_error: Some(Duration::from_secs(60)),
+            ..Default::default()
+        });
+        cache.insert_did_doc(key.clone(), did_doc());
+        assert!(matches!(cache.did_doc(&key), CachedDidDoc::Miss));
+        assert!(cache.did_doc_if_error(&key).unwrap().stale);
+
+        let cache = ResolutionCache::new(CacheConfiguration {
+            latest_ttl: Duration::ZERO,
+            stale_while_revalidate: Some(Duration::from_secs(60)),
+            ..Default::default()
+        });
+        cache.insert_did_doc(key.clone(), did_doc());
+        assert!(matches!(cache.did_doc(&key), CachedDidDoc::Revalidate(doc) if doc.stale));
+        assert!(cache.did_doc_if_error(&key).is_none());
+
+        assert!(cache.start_refresh(&key));
+        assert!(!cache.start_refresh(&key));
+        cache.finish_refresh(&key);
+        assert!(cache.start_refresh(&key));
+    }
+
+    #[test]
+    fn test_immutable_and_latest_ttls() {
+        let cache = ResolutionCache::new(CacheConfiguration {
+            immutable_ttl: None,
+            latest_ttl: Duration::ZERO,
+            ..Default::default()
+        });
+        let by_id = ResourceKey::ById("testnet".into(), "abc".into(), "r1".into());
+        let by_query = ResourceKey::ByQuery(
+            "testnet".into(),
+            "abc".into(),
+            "name".into(),
+            "type".into(),
+            None,
+        );
+        cache.insert_resource(by_id.clone(), resource("r1"));
+        cache.insert_resource(by_query.clone(), resource("r2"));
+
+        assert_eq!(cache.resource(&by_id).unwrap().metadata.id, "r1");
+        assert!(cache.resource(&by_query).is_none());
+
+        cache.clear();
+        assert!(cache.resource(&by_id).is_none());
+    }
+
+    #[test]
+    fn test_resource_byte_budget() {
+        let key = |id: &str| ResourceKey::ById("testnet".into(), "abc".into(), id.into());
+        let budget = resource("r1").weight() * 2;
+        let cache = ResolutionCache::new(CacheConfiguration {
+            resource_max_bytes: Some(budget),
+            ..Default::default()
+        });
+        cache.insert_resource(key("r1"), resource("r1"));
+        cache.insert_resource(key("r2"), resource("r2"));
+        // r1 is more recently used than r2, so r2 is evicted for r3
+        assert!(cache.resource(&key("r1")).is_some());
+        cache.insert_resource(key("r3"), resource("r3"));
+        assert!(cache.resource(&key("r2")).is_none());
+        assert!(cache.resource(&key("r1")).is_some());
+        assert!(cache.resource(&key("r3")).is_some());
+
+        // resources which exceed the whole budget aren't cached
+        let mut large = resource("r4");
+        large.data = vec![0; budget];
+        cache.insert_resource(key("r4"), large);
+        assert!(cache.resource(&key("r4")).is_none());
+        assert!(cache.resource(&key("r3")).is_some());
+    }
+}
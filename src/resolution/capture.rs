@@ -0,0 +1,94 @@
+use std::{collections::VecDeque, sync::Mutex};
+
+use chrono::{DateTime, Utc};
+
+/// A gRPC query made by the resolver & the raw response, as recorded when debug capture is
+/// enabled. Useful for filing reproducible bug reports against ledger data quirks.
+#[derive(Debug, Clone)]
+pub struct CapturedQuery {
+    /// when the response was received
+    pub timestamp: DateTime<Utc>,
+    /// the namespace of the network queried
+    pub namespace: String,
+    /// the gRPC URL of the node queried
+    pub endpoint: String,
+    /// the gRPC method called, e.g. `DidDoc`
+    pub method: &'static str,
+    /// the request parameters, debug formatted
+    pub request: String,
+    /// the raw protobuf encoded response, or `None` if the query failed
+    pub response: Option<Vec<u8>>,
+    /// the response (or error status), debug formatted
+    pub response_debug: String,
+}
+
+/// Bounded buffer of the most recently captured queries
+pub(crate) struct DebugCapture {
+    capacity: usize,
+    queries: Mutex<VecDeque<CapturedQuery>>,
+}
+
+impl DebugCapture {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            queries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    pub(crate) fn record(&self, query: CapturedQuery) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut queries = self.queries.lock().unwrap_or_else(|e| e.into_inner());
+        if queries.len() == self.capacity {
+            queries.pop_front();
+        }
+        queries.push_back(query);
+    }
+
+    /// the captured queries, oldest first
+    pub(crate) fn snapshot(&self) -> Vec<CapturedQuery> {
+        let queries = self.queries.lock().unwrap_or_else(|e| e.into_inner());
+        queries.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    fn query(method: &'static str) -> CapturedQuery {
+        CapturedQuery {
+            timestamp: Utc::now(),
+            namespace: "testnet".into(),
+            endpoint: "https://grpc.cheqd.network:443".into(),
+            method,
+            request: String::new(),
+            response: None,
+            response_debug: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_keeps_last_n_queries() {
+        let capture = DebugCapture::new(2);
+        capture.record(query("DidDoc"));
+        capture.record(query("Resource"));
+        capture.record(query("CollectionResources"));
+
+        let methods: Vec<_> = capture.snapshot().iter().map(|q| q.method).collect();
+        assert_eq!(methods, ["Resource", "CollectionResources"]);
+    }
+
+    #[test]
+    fn test_disabled_records_nothing() {
+        let capture = DebugCapture::new(0);
+        capture.record(query("DidDoc"));
+        assert!(capture.snapshot().is_empty());
+    }
+}
@@ -0,0 +1,31 @@
+//! The source of the current time used by the resolver, see [Clock].
+
+use std::fmt::Debug;
+
+use chrono::{DateTime, Utc};
+
+/// Tells the resolver the current time, e.g. to resolve the latest version of a resource as of
+/// now. Tests & deterministic replay environments can supply a fixed time with [FixedClock].
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock, used by default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock stopped at a given time
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
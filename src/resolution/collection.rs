@@ -0,0 +1,189 @@
+//! Streaming the resources of a DID's collection, see
+//! [DidCheqdResolver::iter_collection_resources].
+
+use futures_util::{Stream, stream};
+
+use crate::{
+    error::DidCheqdResult,
+    proto::cheqd::resource::v2::Metadata as CheqdResourceMetadata,
+    resolution::{
+        parser::ledger_id,
+        resolver::{DidCheqdResolver, did_error_context},
+    },
+};
+
+struct CollectionState {
+    resolver: DidCheqdResolver,
+    network: String,
+    did_id: String,
+    /// the resources of the latest page not yet yielded
+    page: std::vec::IntoIter<CheqdResourceMetadata>,
+    /// the pagination key of the next page, `None` once the last page was fetched (or failed)
+    next_key: Option<Vec<u8>>,
+}
+
+impl DidCheqdResolver {
+    /// Stream the metadata of every resource in the collection of the given DID (e.g.
+    /// "did:cheqd:mainnet:zF7..."), in the order the ledger lists them.
+    ///
+    /// Pages are fetched as the stream is polled, so only one page of metadata is held at a time,
+    /// however large the collection. A DID on a network which isn't configured is an error up
+    /// front; a failed page is yielded as an error, ending the stream. The cache is bypassed.
+    pub fn iter_collection_resources(
+        &self,
+        did: &str,
+    ) -> DidCheqdResult<impl Stream<Item = DidCheqdResult<CheqdResourceMetadata>> + Send + 'static>
+    {
+        let parsed = self.parse(did)?;
+        let context = did_error_context(&parsed, "iter_collection_resources");
+        let network = self
+            .network_config(&parsed.namespace)
+            .map_err(|e| e.with_context(context.clone()))?;
+        let state = CollectionState {
+            resolver: self.clone(),
+            network: network.namespace,
+            did_id: ledger_id(&parsed.id),
+            page: Vec::new().into_iter(),
+            next_key: Some(Vec::new()),
+        };
+
+        Ok(stream::unfold(state, move |mut state| {
+            let context = context.clone();
+            async move {
+                loop {
+                    if let Some(metadata) = state.page.next() {
+                        return Some((Ok(metadata), state));
+                    }
+                    let key = state.next_key.take()?;
+                    let fetched = state
+                        .resolver
+                        .fetch_collection_page(&state.did_id, &state.network, key)
+                        .await;
+                    let (page, next_key) = match fetched {
+                        Ok(fetched) => fetched,
+                        Err(e) => return Some((Err(e.with_context(context)), state)),
+                    };
+                    state.page = page.into_iter();
+                    state.next_key = match next_key.is_empty() {
+                        true => None,
+                        false => Some(next_key),
+                    };
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::{collections::HashMap, net::Ipv4Addr};
+
+    use futures_util::StreamExt;
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::{
+        error::DidCheqdError,
+        resolution::resolver::{DidCheqdResolverConfiguration, NetworkConfiguration},
+        test_utils::{LedgerFixtures, MockCheqdNode},
+    };
+
+    const DID: &str = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+
+    #[tokio::test]
+    async fn test_iter_collection_resources_pages() {
+        let mut fixtures = LedgerFixtures::new();
+        // more than two pages
+        for i in 0..250 {
+            let metadata = CheqdResourceMetadata {
+                collection_id: "Ps1ysXP2Ae6GBfxNhNQNKN".into(),
+                id: format!("r{i}"),
+                name: "schema".into(),
+                resource_type: "JsonSchema".into(),
+                created: Some(prost_types::Timestamp {
+                    seconds: i,
+                    nanos: 0,
+                }),
+                ..Default::default()
+            };
+            fixtures = fixtures.with_resource(b"{}".to_vec(), metadata);
+        }
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            ..Default::default()
+        });
+
+        let resources = resolver.iter_collection_resources(DID).unwrap();
+        let ids: Vec<_> = resources.map(|r| r.unwrap().id).collect().await;
+        assert_eq!(ids.len(), 250);
+        assert_eq!((ids[0].as_str(), ids[249].as_str()), ("r0", "r249"));
+        assert_eq!(resolver.stats().await.queries, 3);
+
+        // pages are fetched as the stream is polled
+        let mut resources = Box::pin(resolver.iter_collection_resources(DID).unwrap());
+        assert_eq!(resources.next().await.unwrap().unwrap().id, "r0");
+        assert_eq!(resolver.stats().await.queries, 4);
+
+        // networks which aren't configured fail up front
+        let did = "did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+        let e = resolver.iter_collection_resources(did).err().unwrap();
+        assert!(
+            matches!(e.root(), DidCheqdError::NetworkNotSupported(_)),
+            "{e:?}"
+        );
+
+        // a failed page ends the stream
+        let closed_port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![NetworkConfiguration {
+                grpc_url: format!("http://127.0.0.1:{closed_port}"),
+                namespace: "testnet".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        let results: Vec<_> = resolver
+            .iter_collection_resources(DID)
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_iter_collection_resources_of_legacy_uuid_did() {
+        let collection_id = "de0a1f9c-9b7b-4c1f-9e1b-2f3b5c6d7e8f";
+        let metadata = CheqdResourceMetadata {
+            collection_id: collection_id.into(),
+            id: "r1".into(),
+            name: "schema".into(),
+            resource_type: "JsonSchema".into(),
+            created: Some(Default::default()),
+            ..Default::default()
+        };
+        let fixtures = LedgerFixtures::new().with_resource(b"{}".to_vec(), metadata);
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            namespace_aliases: HashMap::from([("staging".to_string(), "testnet".to_string())]),
+            ..Default::default()
+        });
+
+        // upper case legacy UUIDs are lowercased as on the ledger, & aliases followed
+        for did in [
+            "did:cheqd:testnet:DE0A1F9C-9B7B-4C1F-9E1B-2F3B5C6D7E8F",
+            "did:cheqd:staging:de0a1f9c-9b7b-4c1f-9e1b-2f3b5c6d7e8f",
+        ] {
+            let resources = resolver.iter_collection_resources(did).unwrap();
+            let ids: Vec<_> = resources.map(|r| r.unwrap().id).collect().await;
+            assert_eq!(ids, ["r1"], "{did}");
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Limits on the number of queries a resolver sends to nodes at once, so bursts of resolutions
+//! (e.g. [DidCheqdResolver::prefetch](crate::resolution::resolver::DidCheqdResolver::prefetch),
+//! or many cache misses at once) can't exhaust sockets or trip nodes' rate limits.
+//!
+//! Queries over a limit wait for a query in flight to finish, in the order they were made.
+
+use std::{collections::HashMap, num::NonZeroUsize, sync::Arc};
+
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
+
+/// The maximum numbers of queries in flight at once, see
+/// [crate::resolution::resolver::DidCheqdResolverConfiguration::concurrency_limits]. Unlimited
+/// by default.
+///
+/// A query counts once however many endpoints it is sent to (e.g. quorum nodes), and recorded
+/// fixtures are replayed without counting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConcurrencyLimits {
+    /// the limit across all networks
+    pub global: Option<NonZeroUsize>,
+    /// the limit of each network, so one network's queries can't hold up another's
+    pub per_network: Option<NonZeroUsize>,
+}
+
+/// The permits of queries in flight, limited by [ConcurrencyLimits]
+pub(crate) struct QueryPermits {
+    limits: ConcurrencyLimits,
+    global: Option<Arc<Semaphore>>,
+    networks: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+/// Permission for a query to be in flight, until dropped
+pub(crate) struct QueryPermit {
+    _global: Option<OwnedSemaphorePermit>,
+    _network: Option<OwnedSemaphorePermit>,
+}
+
+impl QueryPermits {
+    pub(crate) fn new(limits: ConcurrencyLimits) -> Self {
+        Self {
+            limits,
+            global: limits
+                .global
+                .map(|limit| Arc::new(Semaphore::new(limit.get()))),
+            networks: Mutex::default(),
+        }
+    }
+
+    /// Wait until a query to the network is within the limits. The network's limit is acquired
+    /// first, so queries waiting on a busy network don't hold global permits.
+    pub(crate) async fn acquire(&self, network: &str) -> QueryPermit {
+        let network = match self.limits.per_network {
+            Some(limit) => {
+                let semaphore = self
+                    .networks
+                    .lock()
+                    .await
+                    .entry(network.to_owned())
+                    .or_insert_with(|| Arc::new(Semaphore::new(limit.get())))
+                    .clone();
+                Some(acquire(semaphore).await)
+            }
+            None => None,
+        };
+        let global = match &self.global {
+            Some(semaphore) => Some(acquire(semaphore.clone()).await),
+            None => None,
+        };
+        QueryPermit {
+            _global: global,
+            _network: network,
+        }
+    }
+}
+
+async fn acquire(semaphore: Arc<Semaphore>) -> OwnedSemaphorePermit {
+    semaphore
+        .acquire_owned()
+        .await
+        .expect("query semaphores are never closed")
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use futures_util::FutureExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_permits_limit_queries() {
+        let limits = ConcurrencyLimits {
+            global: NonZeroUsize::new(2),
+            per_network: NonZeroUsize::new(1),
+        };
+        let permits = QueryPermits::new(limits);
+
+        let mainnet = permits.acquire("mainnet").await;
+        // the network's limit is reached
+        assert!(permits.acquire("mainnet").now_or_never().is_none());
+        let testnet = permits.acquire("testnet").await;
+        // the global limit is reached
+        assert!(permits.acquire("devnet").now_or_never().is_none());
+
+        drop(mainnet);
+        let _mainnet = permits.acquire("mainnet").now_or_never().unwrap();
+        drop(testnet);
+        assert!(permits.acquire("devnet").now_or_never().is_some());
+
+        let unlimited = QueryPermits::new(ConcurrencyLimits::default());
+        let _held: Vec<_> = (0..100)
+            .map(|_| unlimited.acquire("mainnet").now_or_never().unwrap())
+            .collect();
+    }
+
+    #[tokio::test]
+    async fn test_limited_resolver_resolves_bursts() {
+        use crate::{
+            proto::cheqd::did::v2::{DidDoc, Metadata as DidDocMetadata},
+            resolution::resolver::{DidCheqdResolver, DidCheqdResolverConfiguration},
+            test_utils::{LedgerFixtures, MockCheqdNode},
+        };
+
+        let dids: Vec<_> = (0..8).map(|i| format!("did:cheqd:testnet:{i}")).collect();
+        let fixtures = dids.iter().fold(LedgerFixtures::new(), |fixtures, did| {
+            let did_doc = DidDoc {
+                id: did.clone(),
+                ..Default::default()
+            };
+            let metadata = DidDocMetadata {
+                version_id: "v1".into(),
+                ..Default::default()
+            };
+            fixtures.with_did_doc(did_doc, metadata)
+        });
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            concurrency_limits: ConcurrencyLimits {
+                global: NonZeroUsize::new(2),
+                per_network: NonZeroUsize::new(1),
+            },
+            ..Default::default()
+        });
+
+        let results = resolver.prefetch(&dids).await;
+        assert!(results.iter().all(Result::is_ok), "{results:?}");
+        assert_eq!(resolver.stats().await.in_flight, 0);
+    }
+}
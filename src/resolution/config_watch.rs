@@ -0,0 +1,331 @@
+//! Reloading a resolver's networks when a configuration file changes, so long-running resolver
+//! gateways can move to other node endpoints without restarting.
+//!
+//! The file is JSON, listing the networks as [NetworkConfiguration]s (with camelCase fields,
+//! defaulting as [NetworkConfiguration::default]):
+//!
+//! ```json
+//! {
+//!   "networks": [
+//!     {"namespace": "mainnet", "grpcUrl": "https://grpc.cheqd.net:443"},
+//!     {
+//!       "namespace": "testnet",
+//!       "grpcUrl": "https://grpc.cheqd.network:443",
+//!       "failoverGrpcUrls": ["https://grpc-testnet.example.com:443"],
+//!       "restUrl": "https://api.cheqd.network",
+//!       "transport": "grpc",
+//!       "restFallback": false,
+//!       "loadBalancing": {
+//!         "primaryWeight": 80,
+//!         "endpoints": [{"grpcUrl": "https://grpc-backup.example.com:443", "weight": 20}],
+//!         "selection": "weighted"
+//!       }
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Whether a node is connected to with TLS follows the scheme of its URL (`https` or `http`).
+
+use std::path::{Path, PathBuf};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Deserialize;
+use tokio::{sync::mpsc, task::JoinHandle};
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    resolution::{
+        load_balancing::{EndpointSelection, LoadBalancing, WeightedEndpoint},
+        resolver::{DidCheqdResolver, NetworkConfiguration, Transport},
+    },
+};
+
+/// How long to wait for a burst of file events (e.g. an editor's write & rename) to end before
+/// reading the file
+const SETTLE_DELAY: std::time::Duration = std::time::Duration::from_millis(50);
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ConfigFile {
+    networks: Vec<NetworkEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct NetworkEntry {
+    namespace: String,
+    grpc_url: String,
+    #[serde(default)]
+    quorum_grpc_urls: Vec<String>,
+    #[serde(default)]
+    failover_grpc_urls: Vec<String>,
+    #[serde(default)]
+    rest_url: Option<String>,
+    #[serde(default)]
+    transport: TransportEntry,
+    #[serde(default)]
+    rest_fallback: bool,
+    #[serde(default)]
+    load_balancing: Option<LoadBalancingEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct LoadBalancingEntry {
+    primary_weight: u32,
+    endpoints: Vec<WeightedEndpointEntry>,
+    #[serde(default)]
+    selection: SelectionEntry,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct WeightedEndpointEntry {
+    grpc_url: String,
+    weight: u32,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+enum SelectionEntry {
+    #[default]
+    Weighted,
+    Fastest,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+enum TransportEntry {
+    #[default]
+    Grpc,
+    Rest,
+}
+
+impl From<NetworkEntry> for NetworkConfiguration {
+    fn from(entry: NetworkEntry) -> Self {
+        NetworkConfiguration {
+            grpc_url: entry.grpc_url,
+            namespace: entry.namespace,
+            quorum_grpc_urls: entry.quorum_grpc_urls,
+            failover_grpc_urls: entry.failover_grpc_urls,
+            rest_url: entry.rest_url,
+            transport: match entry.transport {
+                TransportEntry::Grpc => Transport::Grpc,
+                TransportEntry::Rest => Transport::Rest,
+            },
+            rest_fallback: entry.rest_fallback,
+            load_balancing: entry.load_balancing.map(|entry| LoadBalancing {
+                primary_weight: entry.primary_weight,
+                endpoints: entry
+                    .endpoints
+                    .into_iter()
+                    .map(|endpoint| WeightedEndpoint {
+                        grpc_url: endpoint.grpc_url,
+                        weight: endpoint.weight,
+                    })
+                    .collect(),
+                selection: match entry.selection {
+                    SelectionEntry::Weighted => EndpointSelection::Weighted,
+                    SelectionEntry::Fastest => EndpointSelection::Fastest,
+                },
+            }),
+        }
+    }
+}
+
+/// Parse the networks of a configuration file's content, see the [module docs](self)
+pub fn parse_networks(content: &[u8]) -> DidCheqdResult<Vec<NetworkConfiguration>> {
+    let file: ConfigFile = serde_json::from_slice(content)
+        .map_err(|e| DidCheqdError::BadConfiguration(format!("invalid configuration file: {e}")))?;
+    Ok(file.networks.into_iter().map(Into::into).collect())
+}
+
+fn read(path: &Path) -> DidCheqdResult<Vec<u8>> {
+    std::fs::read(path).map_err(|e| {
+        DidCheqdError::BadConfiguration(format!("failed to read {}: {e}", path.display()))
+    })
+}
+
+/// Watches a configuration file for [DidCheqdResolver::watch_config], until dropped
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+    reloads: JoinHandle<()>,
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        self.reloads.abort();
+    }
+}
+
+impl DidCheqdResolver {
+    /// Configure the resolver's networks from a configuration file (see the
+    /// [module docs](self)), and reload them (see [DidCheqdResolver::reload_networks]) whenever
+    /// the file changes, until the returned [ConfigWatcher] is dropped.
+    ///
+    /// The file's directory is watched, so files replaced rather than written in place (e.g. by
+    /// editors, or Kubernetes ConfigMap updates) are reloaded too. An invalid file is a
+    /// [DidCheqdError::BadConfiguration] error; once watching, invalid changes are reported to
+    /// [crate::resolution::events::ResolverEventHandler::on_config_reload], and the networks
+    /// configured before are kept.
+    pub async fn watch_config(&self, path: impl AsRef<Path>) -> DidCheqdResult<ConfigWatcher> {
+        let path = path.as_ref().to_path_buf();
+        let content = read(&path)?;
+        self.reload_networks(parse_networks(&content)?).await?;
+
+        let (events, mut changes) = mpsc::unbounded_channel();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                if event.is_ok_and(|event| !event.kind.is_access()) {
+                    let _ = events.send(());
+                }
+            })
+            .map_err(watch_error)?;
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(watch_error)?;
+
+        let resolver = self.clone();
+        let reloads = tokio::spawn(async move {
+            let mut applied = content;
+            while changes.recv().await.is_some() {
+                tokio::time::sleep(SETTLE_DELAY).await;
+                while changes.try_recv().is_ok() {}
+                // e.g. a rename of another file in the directory, or the file being replaced
+                let Ok(content) = std::fs::read(&path) else {
+                    continue;
+                };
+                if content == applied {
+                    continue;
+                }
+                let reloaded = match parse_networks(&content) {
+                    Ok(networks) => resolver.reload_networks(networks).await,
+                    Err(e) => Err(e),
+                };
+                resolver.emit(|h| h.on_config_reload(&path, reloaded.as_ref().err()));
+                applied = content;
+            }
+        });
+        Ok(ConfigWatcher {
+            _watcher: watcher,
+            reloads,
+        })
+    }
+}
+
+fn watch_error(e: notify::Error) -> DidCheqdError {
+    DidCheqdError::BadConfiguration(format!("failed to watch the configuration file: {e}"))
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use super::*;
+    use crate::{
+        proto::cheqd::did::v2::DidDoc,
+        resolution::{events::ResolverEventHandler, resolver::DidCheqdResolverConfiguration},
+        test_utils::{LedgerFixtures, MockCheqdNode},
+    };
+
+    const DID: &str = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+
+    #[derive(Default)]
+    struct Reloads(Mutex<Vec<Option<String>>>);
+
+    impl ResolverEventHandler for Reloads {
+        fn on_config_reload(&self, _: &Path, error: Option<&DidCheqdError>) {
+            self.0.lock().unwrap().push(error.map(|e| e.to_string()));
+        }
+    }
+
+    fn config(grpc_url: &str) -> String {
+        format!(r#"{{"networks": [{{"namespace": "testnet", "grpcUrl": "{grpc_url}"}}]}}"#)
+    }
+
+    /// Wait for the watcher to reload the file, returning the reload's outcome
+    async fn next_reload(reloads: &Reloads) -> Option<String> {
+        for _ in 0..250 {
+            if let Some(reload) = reloads.0.lock().unwrap().pop() {
+                return reload;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("the configuration file was not reloaded");
+    }
+
+    #[test]
+    fn test_parse_networks() {
+        let networks = parse_networks(
+            br#"{"networks": [{
+                "namespace": "testnet",
+                "grpcUrl": "https://grpc.cheqd.network:443",
+                "restUrl": "https://api.cheqd.network",
+                "transport": "rest",
+                "loadBalancing": {
+                    "primaryWeight": 4,
+                    "endpoints": [{"grpcUrl": "https://grpc.example.com:443", "weight": 1}],
+                    "selection": "fastest"
+                }
+            }]}"#,
+        )
+        .unwrap();
+        assert_eq!(networks[0].namespace, "testnet");
+        assert_eq!(networks[0].transport, Transport::Rest);
+        assert!(networks[0].quorum_grpc_urls.is_empty());
+        let load_balancing = networks[0].load_balancing.as_ref().unwrap();
+        assert_eq!(load_balancing.endpoints[0].weight, 1);
+        assert_eq!(load_balancing.selection, EndpointSelection::Fastest);
+        let invalid = parse_networks(br#"{"networks": [{"namespace": "testnet"}]}"#);
+        assert!(matches!(invalid, Err(DidCheqdError::BadConfiguration(_))));
+    }
+
+    #[tokio::test]
+    async fn test_watch_config_reloads_networks() {
+        let did_doc = DidDoc {
+            id: DID.into(),
+            ..Default::default()
+        };
+        let empty = MockCheqdNode::start(LedgerFixtures::new()).await.unwrap();
+        let node =
+            MockCheqdNode::start(LedgerFixtures::new().with_did_doc(did_doc, Default::default()))
+                .await
+                .unwrap();
+        let dir = std::env::temp_dir().join(format!("did-cheqd-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("networks.json");
+        std::fs::write(&path, config(&empty.url())).unwrap();
+
+        let reloads = Arc::new(Reloads::default());
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            event_handler: Some(reloads.clone()),
+            ..Default::default()
+        });
+        let resolve = || resolver.query_did_doc_by_str(DID, resolver.parse(DID).unwrap());
+        let _watcher = resolver.watch_config(&path).await.unwrap();
+        let e = resolve().await.unwrap_err();
+        assert_eq!(e.code(), "notFound");
+
+        // replaced, as editors do
+        let replacement = dir.join("networks.json.tmp");
+        std::fs::write(&replacement, config(&node.url())).unwrap();
+        std::fs::rename(&replacement, &path).unwrap();
+        assert_eq!(next_reload(&reloads).await, None);
+        resolve().await.unwrap();
+
+        // invalid changes keep the networks
+        std::fs::write(&path, config("grpc.cheqd.net")).unwrap();
+        assert!(next_reload(&reloads).await.is_some());
+        resolve().await.unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
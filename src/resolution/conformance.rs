@@ -0,0 +1,339 @@
+//! Conformance of DID resolution & DID URL dereferencing with the did:cheqd method spec, checked
+//! against the test vectors in `tests/vectors/`.
+//!
+//! Each vector file holds the ledger state to serve from a [MockCheqdNode] (`ledger`: DID
+//! document versions & resources) & the vectors to check against it (`vectors`): a DID URL with
+//! its expected result, either `error` (a DID Resolution error code, e.g. `notFound`) or a
+//! `contentType` & `content` (JSON for JSON content types, otherwise text), with optional
+//! `documentMetadata`. Adding a file adds its vectors to the suite.
+//!
+//! The fixtures in `tests/interop/` check interoperability with cheqd's TypeScript resolver
+//! (resolver.cheqd.net): each holds a DID URL, the ledger state it was resolved from & the
+//! TypeScript resolver's `response`, whose `didDocument` & `didDocumentMetadata` must serialize
+//! to the same bytes as this crate's [ResolutionResult], modulo the differences of
+//! [interop_normalize].
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{Map, Value};
+use ssi_dids_core::resolution::{ErrorKind, Output};
+
+use crate::{
+    DIDCheqd,
+    proto::cheqd::{
+        did::v2::{DidDoc, Metadata as DidDocMetadata, Service, VerificationMethod},
+        resource::v2::{AlternativeUri, Metadata as ResourceMetadata},
+    },
+    resolution::{
+        resolver::{DidCheqdResolver, DidCheqdResolverConfiguration},
+        result::ResolutionResult,
+        transformer::to_canonical_json_vec,
+    },
+    test_utils::{LedgerFixtures, MockCheqdNode},
+};
+
+const VECTORS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/vectors");
+const INTEROP_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/interop");
+
+fn string(value: &Value, name: &str) -> String {
+    value[name].as_str().unwrap_or_default().to_owned()
+}
+
+fn strings(value: &Value, name: &str) -> Vec<String> {
+    value[name]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|s| s.as_str().map(str::to_owned))
+        .collect()
+}
+
+fn timestamp(value: &Value, name: &str) -> Option<prost_types::Timestamp> {
+    let time = DateTime::parse_from_rfc3339(value[name].as_str()?)
+        .unwrap_or_else(|e| panic!("invalid `{name}` timestamp: {e}"));
+    Some(prost_types::Timestamp {
+        seconds: time.timestamp(),
+        nanos: time.timestamp_subsec_nanos() as i32,
+    })
+}
+
+/// A DID document as stored on the ledger, with the proto's fields in camelCase
+fn did_doc(value: &Value) -> DidDoc {
+    let objects = |name: &str| value[name].as_array().cloned().unwrap_or_default();
+    DidDoc {
+        context: strings(value, "context"),
+        id: string(value, "id"),
+        controller: strings(value, "controller"),
+        verification_method: objects("verificationMethod")
+            .iter()
+            .map(|method| VerificationMethod {
+                id: string(method, "id"),
+                verification_method_type: string(method, "verificationMethodType"),
+                controller: string(method, "controller"),
+                verification_material: string(method, "verificationMaterial"),
+            })
+            .collect(),
+        authentication: strings(value, "authentication"),
+        assertion_method: strings(value, "assertionMethod"),
+        capability_invocation: strings(value, "capabilityInvocation"),
+        capability_delegation: strings(value, "capabilityDelegation"),
+        key_agreement: strings(value, "keyAgreement"),
+        service: objects("service")
+            .iter()
+            .map(|service| Service {
+                id: string(service, "id"),
+                service_type: string(service, "serviceType"),
+                service_endpoint: strings(service, "serviceEndpoint"),
+                ..Default::default()
+            })
+            .collect(),
+        also_known_as: strings(value, "alsoKnownAs"),
+    }
+}
+
+fn did_doc_metadata(value: &Value) -> DidDocMetadata {
+    DidDocMetadata {
+        created: timestamp(value, "created"),
+        updated: timestamp(value, "updated"),
+        deactivated: value["deactivated"].as_bool().unwrap_or_default(),
+        version_id: string(value, "versionId"),
+        next_version_id: string(value, "nextVersionId"),
+        previous_version_id: string(value, "previousVersionId"),
+    }
+}
+
+fn resource_metadata(value: &Value) -> ResourceMetadata {
+    ResourceMetadata {
+        collection_id: string(value, "collectionId"),
+        id: string(value, "id"),
+        name: string(value, "name"),
+        version: string(value, "version"),
+        resource_type: string(value, "resourceType"),
+        also_known_as: value["alsoKnownAs"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|uri| AlternativeUri {
+                uri: string(uri, "uri"),
+                description: string(uri, "description"),
+            })
+            .collect(),
+        media_type: string(value, "mediaType"),
+        created: timestamp(value, "created"),
+        checksum: string(value, "checksum"),
+        previous_version_id: string(value, "previousVersionId"),
+        next_version_id: string(value, "nextVersionId"),
+    }
+}
+
+fn ledger(value: &Value) -> LedgerFixtures {
+    let entries = |name: &str| value[name].as_array().cloned().unwrap_or_default();
+    let mut fixtures = LedgerFixtures::new();
+    for entry in entries("didDocs") {
+        fixtures = fixtures.with_did_doc(
+            did_doc(&entry["didDoc"]),
+            did_doc_metadata(&entry["metadata"]),
+        );
+    }
+    for entry in entries("resources") {
+        let data = match &entry["data"] {
+            Value::String(text) => text.as_bytes().to_vec(),
+            json => json.to_string().into_bytes(),
+        };
+        fixtures = fixtures.with_resource(data, resource_metadata(&entry["metadata"]));
+    }
+    fixtures
+}
+
+/// The DID Resolution spec's code of an error
+fn error_code(kind: ErrorKind) -> &'static str {
+    match kind {
+        ErrorKind::NotFound => "notFound",
+        ErrorKind::InvalidMethodSpecificId => "invalidDid",
+        ErrorKind::MethodNotSupported => "methodNotSupported",
+        ErrorKind::RepresentationNotSupported => "representationNotSupported",
+        _ => "internalError",
+    }
+}
+
+/// The differences between an output & a vector's expected result
+fn check(output: Output<Vec<u8>>, expected: &Value) -> Result<(), String> {
+    let content_type = output.metadata.content_type.unwrap_or_default();
+    match expected["contentType"].as_str() {
+        Some(expected) if content_type != expected => {
+            return Err(format!("content type {content_type}, expected {expected}"));
+        }
+        _ => {}
+    }
+    let content = match content_type.contains("json") {
+        true => serde_json::from_slice(&output.document)
+            .map_err(|e| format!("content is not JSON: {e}"))?,
+        false => Value::String(String::from_utf8_lossy(&output.document).into_owned()),
+    };
+    match expected.get("content") {
+        Some(expected) if content != *expected => {
+            return Err(format!("content {content}, expected {expected}"));
+        }
+        _ => {}
+    }
+    if let Some(expected) = expected.get("documentMetadata") {
+        let actual = serde_json::to_value(output.document_metadata).unwrap();
+        let matches = expected
+            .as_object()
+            .into_iter()
+            .flat_map(Map::iter)
+            .all(|(name, value)| actual[name] == *value);
+        if !matches {
+            return Err(format!("document metadata {actual}, expected {expected}"));
+        }
+    }
+    Ok(())
+}
+
+/// The JSON files of a directory, in name order
+fn json_files(dir: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "no JSON files in {dir}");
+    paths
+}
+
+fn read_json(path: &Path) -> Value {
+    serde_json::from_slice(&std::fs::read(path).unwrap())
+        .unwrap_or_else(|e| panic!("invalid JSON file {}: {e}", path.display()))
+}
+
+/// Run the vectors of a file, returning a description of each which failed
+async fn run_vectors(path: &Path) -> Vec<String> {
+    let file = read_json(path);
+    let node = MockCheqdNode::start(ledger(&file["ledger"])).await.unwrap();
+    let resolver = DIDCheqd::new(Some(DidCheqdResolverConfiguration {
+        networks: vec![node.network("mainnet"), node.network("testnet")],
+        ..Default::default()
+    }));
+
+    let mut failures = Vec::new();
+    for vector in file["vectors"].as_array().into_iter().flatten() {
+        let did_url = string(vector, "didUrl");
+        let expected = &vector["expected"];
+        let result = match resolver.dereference_did_url(&did_url, None).await {
+            Ok(_) if expected.get("error").is_some() => {
+                Err(format!("resolved, expected a {} error", expected["error"]))
+            }
+            Ok(output) => check(output, expected),
+            Err(e) if expected["error"] == error_code(e.kind()) => Ok(()),
+            Err(e) => Err(format!("{} error: {e}", error_code(e.kind()))),
+        };
+        if let Err(failure) = result {
+            let name = string(vector, "name");
+            failures.push(format!(
+                "{} `{name}` ({did_url}): {failure}",
+                path.display()
+            ));
+        }
+    }
+    failures
+}
+
+/// Remove the differences between this crate's & the TypeScript resolver's output which don't
+/// change its meaning:
+/// - timestamps are serialized in UTC with a `Z` suffix & as many fractional digits as needed,
+///   rather than with a `+00:00` offset or JavaScript's milliseconds
+/// - a single service endpoint may be a string or a one element array
+/// - `linkedResourceMetadata` is left out of DID document metadata, as this crate lists resources
+///   by their collection (see [DidCheqdResolver::search_resources])
+fn interop_normalize(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            object.remove("linkedResourceMetadata");
+            if let Some(Value::Array(endpoints)) = object.get_mut("serviceEndpoint") {
+                if endpoints.len() == 1 {
+                    let endpoint = endpoints.remove(0);
+                    object.insert("serviceEndpoint".to_string(), endpoint);
+                }
+            }
+            object.values_mut().for_each(interop_normalize);
+        }
+        Value::Array(values) => values.iter_mut().for_each(interop_normalize),
+        Value::String(string) => {
+            if let Ok(time) = DateTime::parse_from_rfc3339(string) {
+                *string = time
+                    .with_timezone(&Utc)
+                    .to_rfc3339_opts(SecondsFormat::AutoSi, true);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The canonical serialization of a DID document & its metadata, see [interop_normalize]
+fn interop_bytes(did_document: &Value, did_document_metadata: &Value) -> String {
+    let mut value = serde_json::json!({
+        "didDocument": did_document,
+        "didDocumentMetadata": did_document_metadata,
+    });
+    interop_normalize(&mut value);
+    String::from_utf8(to_canonical_json_vec(&value).unwrap()).unwrap()
+}
+
+/// Compare this crate's resolution of a fixture's DID URL with the TypeScript resolver's
+async fn check_interop(path: &Path) -> Result<(), String> {
+    let fixture = read_json(path);
+    let node = MockCheqdNode::start(ledger(&fixture["ledger"]))
+        .await
+        .unwrap();
+    let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+        networks: vec![node.network("mainnet"), node.network("testnet")],
+        ..Default::default()
+    });
+    let did_url = string(&fixture, "didUrl");
+    let ResolutionResult {
+        did_document,
+        did_document_metadata,
+        ..
+    } = resolver
+        .resolve(&did_url)
+        .await
+        .map_err(|e| format!("{did_url}: {e}"))?;
+
+    let actual = interop_bytes(
+        &did_document.unwrap_or_default(),
+        &Value::Object(did_document_metadata),
+    );
+    let response = &fixture["response"];
+    let expected = interop_bytes(&response["didDocument"], &response["didDocumentMetadata"]);
+    match actual == expected {
+        true => Ok(()),
+        false => Err(format!(
+            "{did_url}:\n  ours   {actual}\n  theirs {expected}"
+        )),
+    }
+}
+
+#[tokio::test]
+async fn test_conformance_vectors() {
+    let mut failures = Vec::new();
+    for path in json_files(VECTORS_DIR) {
+        failures.extend(run_vectors(&path).await);
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[tokio::test]
+async fn test_interop_fixtures() {
+    let mut failures = Vec::new();
+    for path in json_files(INTEROP_DIR) {
+        if let Err(failure) = check_interop(&path).await {
+            failures.push(format!("{}: {failure}", path.display()));
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
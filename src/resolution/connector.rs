@@ -0,0 +1,350 @@
+use std::{
+    collections::HashMap,
+    io,
+    net::{IpAddr, SocketAddr},
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_util::{
+    StreamExt,
+    future::{self, BoxFuture, Either},
+    stream::FuturesUnordered,
+};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpStream;
+use tonic::transport::Uri;
+
+/// A custom DNS resolver, used to resolve the hostnames of cheqd node endpoints.
+pub trait DnsResolve: Send + Sync {
+    /// Resolve a hostname into the IP addresses to attempt connecting to (in order).
+    fn resolve<'a>(&'a self, host: &'a str) -> BoxFuture<'a, io::Result<Vec<IpAddr>>>;
+}
+
+/// The default [DnsConfiguration::happy_eyeballs_delay], as recommended by RFC 8305
+pub const DEFAULT_HAPPY_EYEBALLS_DELAY: Duration = Duration::from_millis(250);
+
+/// Configuration for how cheqd node endpoint hostnames are resolved & connected to.
+///
+/// By default the system resolver is used.
+#[derive(Clone)]
+pub struct DnsConfiguration {
+    /// hostnames pinned to static IP addresses, bypassing DNS resolution entirely
+    pub pinned_hosts: HashMap<String, Vec<IpAddr>>,
+    /// custom resolver used for any hostnames which are not pinned
+    pub resolver: Option<Arc<dyn DnsResolve>>,
+    /// when a gRPC node's host resolves to several addresses, how long a connection attempt to
+    /// one is given before racing an attempt to the next against it ("happy eyeballs", RFC
+    /// 8305), alternating between IPv6 & IPv4 addresses. The first to connect is used, so one
+    /// unreachable address doesn't stall connecting. `None` tries the addresses one after
+    /// another, each once the previous fails.
+    pub happy_eyeballs_delay: Option<Duration>,
+}
+
+impl Default for DnsConfiguration {
+    fn default() -> Self {
+        Self {
+            pinned_hosts: HashMap::new(),
+            resolver: None,
+            happy_eyeballs_delay: Some(DEFAULT_HAPPY_EYEBALLS_DELAY),
+        }
+    }
+}
+
+impl DnsConfiguration {
+    /// Resolve a hostname according to the configuration
+    async fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<IpAddr>> {
+        if let Ok(ip) = host.parse::<IpAddr>() {
+            return Ok(vec![ip]);
+        }
+        if let Some(pinned) = self.pinned_hosts.get(host) {
+            return Ok(pinned.clone());
+        }
+        match &self.resolver {
+            Some(resolver) => resolver.resolve(host).await,
+            None => Ok(tokio::net::lookup_host((host, port))
+                .await?
+                .map(|addr| addr.ip())
+                .collect()),
+        }
+    }
+}
+
+/// A [DnsResolve] implementation using the hickory DNS resolver, which looks up hosts' A & AAAA
+/// records concurrently & caches them by their TTLs, without blocking a thread on the system
+/// resolver. Requires the `hickory-dns` feature.
+#[cfg(feature = "hickory-dns")]
+pub struct HickoryDnsResolver(hickory_resolver::TokioAsyncResolver);
+
+#[cfg(feature = "hickory-dns")]
+impl HickoryDnsResolver {
+    /// A resolver configured by the system's configuration (e.g. `/etc/resolv.conf`), looking up
+    /// both IPv4 & IPv6 addresses
+    pub fn from_system_conf() -> io::Result<Self> {
+        let (config, mut options) = hickory_resolver::system_conf::read_system_conf()?;
+        options.ip_strategy = hickory_resolver::config::LookupIpStrategy::Ipv4AndIpv6;
+        Ok(Self::new(hickory_resolver::TokioAsyncResolver::tokio(
+            config, options,
+        )))
+    }
+
+    /// Use a configured hickory resolver
+    pub fn new(resolver: hickory_resolver::TokioAsyncResolver) -> Self {
+        Self(resolver)
+    }
+}
+
+#[cfg(feature = "hickory-dns")]
+impl DnsResolve for HickoryDnsResolver {
+    fn resolve<'a>(&'a self, host: &'a str) -> BoxFuture<'a, io::Result<Vec<IpAddr>>> {
+        Box::pin(async move { Ok(self.0.lookup_ip(host).await?.iter().collect()) })
+    }
+}
+
+/// Connector for tonic endpoints which resolves hostnames via a [DnsConfiguration], & races
+/// connections to their addresses per [DnsConfiguration::happy_eyeballs_delay].
+///
+/// TLS is layered on top by tonic according to the endpoint's TLS config.
+#[derive(Clone)]
+pub(crate) struct CheqdConnector {
+    dns: DnsConfiguration,
+}
+
+impl CheqdConnector {
+    pub(crate) fn new(dns: DnsConfiguration) -> Self {
+        Self { dns }
+    }
+}
+
+impl tower::Service<Uri> for CheqdConnector {
+    type Response = TokioIo<TcpStream>;
+    type Error = io::Error;
+    type Future = BoxFuture<'static, io::Result<Self::Response>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let dns = self.dns.clone();
+        Box::pin(async move {
+            let host = uri
+                .host()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "URI has no host"))?
+                // IPv6 literals are bracketed in URIs
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_owned();
+            let port = uri.port_u16().unwrap_or(match uri.scheme_str() {
+                Some("http") => 80,
+                _ => 443,
+            });
+
+            let addresses = dns.resolve(&host, port).await?;
+            let addresses = addresses.into_iter().map(|ip| SocketAddr::new(ip, port));
+            let stream = connect_any(&host, addresses.collect(), dns.happy_eyeballs_delay).await?;
+            stream.set_nodelay(true)?;
+            Ok(TokioIo::new(stream))
+        })
+    }
+}
+
+/// Connect to the first of a host's addresses to accept a connection, starting an attempt to the
+/// next address once the previous attempt fails or, if given, the delay passes
+async fn connect_any(
+    host: &str,
+    addresses: Vec<SocketAddr>,
+    delay: Option<Duration>,
+) -> io::Result<TcpStream> {
+    let mut pending = interleave_families(addresses).into_iter();
+    let mut attempts = FuturesUnordered::new();
+    let mut last_error = io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("no addresses resolved for {host}"),
+    );
+    loop {
+        if let Some(address) = pending.next() {
+            attempts.push(TcpStream::connect(address));
+        }
+        if attempts.is_empty() {
+            return Err(last_error);
+        }
+        let mut next_attempt = pin!(match (delay, pending.len()) {
+            (Some(delay), 1..) => Either::Left(tokio::time::sleep(delay)),
+            _ => Either::Right(future::pending()),
+        });
+        match future::select(attempts.next(), next_attempt.as_mut()).await {
+            Either::Left((Some(Ok(stream)), _)) => return Ok(stream),
+            Either::Left((Some(Err(e)), _)) => last_error = e,
+            Either::Left((None, _)) | Either::Right(_) => {}
+        }
+    }
+}
+
+/// Order addresses alternately by family, starting with the family of the first, so attempts to
+/// both families are made early on
+fn interleave_families(addresses: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    let first_ipv6 = addresses.first().is_some_and(SocketAddr::is_ipv6);
+    let (first, second): (Vec<_>, Vec<_>) = addresses
+        .into_iter()
+        .partition(|address| address.is_ipv6() == first_ipv6);
+    let mut interleaved = Vec::with_capacity(first.len() + second.len());
+    let (mut first, mut second) = (first.into_iter(), second.into_iter());
+    loop {
+        match (first.next(), second.next()) {
+            (None, None) => return interleaved,
+            (a, b) => interleaved.extend(a.into_iter().chain(b)),
+        }
+    }
+}
+
+/// The scheme prefix of cheqd node gRPC URLs served over a Unix domain socket, e.g.
+/// `unix:///run/cheqd/grpc.sock`
+pub(crate) const UNIX_SOCKET_SCHEME: &str = "unix://";
+
+/// The socket path of a Unix domain socket node URL, see [UNIX_SOCKET_SCHEME]
+pub(crate) fn unix_socket_path(url: &str) -> Option<&str> {
+    url.strip_prefix(UNIX_SOCKET_SCHEME)
+}
+
+/// Connector for tonic endpoints which connects to a Unix domain socket, whatever the URI, so
+/// co-located nodes are queried without TCP or TLS
+#[cfg(unix)]
+#[derive(Clone)]
+pub(crate) struct UnixSocketConnector {
+    path: Arc<str>,
+}
+
+#[cfg(unix)]
+impl UnixSocketConnector {
+    pub(crate) fn new(path: &str) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[cfg(unix)]
+impl tower::Service<Uri> for UnixSocketConnector {
+    type Response = TokioIo<tokio::net::UnixStream>;
+    type Error = io::Error;
+    type Future = BoxFuture<'static, io::Result<Self::Response>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let path = self.path.clone();
+        Box::pin(async move {
+            let stream = tokio::net::UnixStream::connect(&*path).await?;
+            Ok(TokioIo::new(stream))
+        })
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::net::Ipv4Addr;
+
+    use tokio::net::TcpListener;
+    use tower::Service;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_connects_to_pinned_host() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let mut connector = CheqdConnector::new(DnsConfiguration {
+            pinned_hosts: HashMap::from([(
+                "grpc.cheqd.invalid".to_string(),
+                vec![IpAddr::V4(Ipv4Addr::LOCALHOST)],
+            )]),
+            ..Default::default()
+        });
+        let uri: Uri = format!("http://grpc.cheqd.invalid:{port}").parse().unwrap();
+
+        let (connected, accepted) = tokio::join!(connector.call(uri), listener.accept());
+        assert!(connected.is_ok());
+        assert!(accepted.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_uses_custom_resolver() {
+        struct Unresolvable;
+        impl DnsResolve for Unresolvable {
+            fn resolve<'a>(&'a self, _host: &'a str) -> BoxFuture<'a, io::Result<Vec<IpAddr>>> {
+                Box::pin(async { Ok(vec![]) })
+            }
+        }
+
+        let mut connector = CheqdConnector::new(DnsConfiguration {
+            resolver: Some(Arc::new(Unresolvable)),
+            ..Default::default()
+        });
+        let e = connector
+            .call(Uri::from_static("https://grpc.cheqd.net:443"))
+            .await
+            .unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_interleave_families() {
+        let v4 = |i| SocketAddr::new(IpAddr::V4(Ipv4Addr::new(192, 0, 2, i)), 443);
+        let v6 = |i| {
+            SocketAddr::new(
+                IpAddr::V6(std::net::Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, i)),
+                443,
+            )
+        };
+        assert_eq!(
+            interleave_families(vec![v6(1), v6(2), v6(3), v4(1)]),
+            [v6(1), v4(1), v6(2), v6(3)]
+        );
+        assert_eq!(
+            interleave_families(vec![v4(1), v4(2), v6(1), v6(2)]),
+            [v4(1), v6(1), v4(2), v6(2)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_races_unresponsive_addresses() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let reachable = listener.local_addr().unwrap();
+        // a documentation address, which is either unreachable or never answers
+        let unresponsive = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)), 443);
+
+        let connect = connect_any(
+            "grpc.cheqd.invalid",
+            vec![unresponsive, reachable],
+            Some(Duration::from_millis(50)),
+        );
+        let (connected, accepted) = tokio::join!(connect, listener.accept());
+        assert_eq!(connected.unwrap().peer_addr().unwrap(), reachable);
+        assert!(accepted.is_ok());
+
+        let e = connect_any("grpc.cheqd.invalid", vec![], None)
+            .await
+            .unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_connects_to_unix_socket() {
+        let path = std::env::temp_dir().join(format!("did-cheqd-uds-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+
+        let url = format!("{UNIX_SOCKET_SCHEME}{}", path.display());
+        let mut connector = UnixSocketConnector::new(unix_socket_path(&url).unwrap());
+        let uri = Uri::from_static("http://localhost");
+        let (connected, accepted) = tokio::join!(connector.call(uri), listener.accept());
+        assert!(connected.is_ok());
+        assert!(accepted.is_ok());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,134 @@
+//! A JSON-LD document loader for contexts hosted on cheqd as DID-linked resources, see
+//! [CheqdContextLoader].
+
+use std::{collections::HashMap, sync::Mutex};
+
+use ssi_dids_core::ssi_json_ld::{
+    ContextLoader, Iri, IriBuf, LoadError, Loader, RemoteDocument,
+    syntax::{Parse, Value},
+};
+
+use crate::resolution::{parser::DID_CHEQD_PREFIX, resolver::DidCheqdResolver};
+
+/// Loads JSON-LD contexts, e.g. for verifying credentials with ssi: `did:cheqd` context URLs
+/// (such as `did:cheqd:mainnet:<id>/resources/<resource id>`) are resolved as resources, while
+/// other URLs are loaded by a fallback loader, by default ssi's [ContextLoader] of well-known
+/// contexts.
+///
+/// cheqd contexts are cached for the lifetime of the loader, so a context referenced by name &
+/// type (rather than resource id) keeps the version first loaded.
+pub struct CheqdContextLoader<L = ContextLoader> {
+    resolver: DidCheqdResolver,
+    fallback: L,
+    loaded: Mutex<HashMap<IriBuf, RemoteDocument>>,
+}
+
+impl CheqdContextLoader {
+    /// A loader resolving cheqd contexts with `resolver`, & other contexts with ssi's
+    /// [ContextLoader] of well-known contexts
+    pub fn new(resolver: DidCheqdResolver) -> Self {
+        Self {
+            resolver,
+            fallback: ContextLoader::default(),
+            loaded: Default::default(),
+        }
+    }
+}
+
+impl<L> CheqdContextLoader<L> {
+    /// Load contexts which aren't hosted on cheqd with `fallback` instead
+    pub fn with_fallback<F>(self, fallback: F) -> CheqdContextLoader<F> {
+        CheqdContextLoader {
+            resolver: self.resolver,
+            fallback,
+            loaded: self.loaded,
+        }
+    }
+
+    async fn load_cheqd_context(&self, url: &Iri) -> Result<RemoteDocument, LoadError> {
+        let cached = {
+            let loaded = self.loaded.lock().unwrap_or_else(|e| e.into_inner());
+            loaded.get(url).cloned()
+        };
+        if let Some(document) = cached {
+            return Ok(document);
+        }
+
+        let did_url = url.as_str();
+        let resolved = async {
+            let parsed = self.resolver.parse(did_url)?;
+            self.resolver.resolve_resource(did_url, parsed).await
+        };
+        let resolved = resolved
+            .await
+            .map_err(|e| LoadError::new(url.to_owned(), e))?;
+        let (context, _) =
+            Value::parse_slice(&resolved.data).map_err(|e| LoadError::new(url.to_owned(), e))?;
+        let content_type = "application/ld+json".parse().ok();
+        let document = RemoteDocument::new(Some(url.to_owned()), content_type, context);
+
+        let mut loaded = self.loaded.lock().unwrap_or_else(|e| e.into_inner());
+        loaded.insert(url.to_owned(), document.clone());
+        Ok(document)
+    }
+}
+
+impl<L: Loader> Loader for CheqdContextLoader<L> {
+    async fn load(&self, url: &Iri) -> Result<RemoteDocument, LoadError> {
+        match url.as_str().starts_with(DID_CHEQD_PREFIX) {
+            true => self.load_cheqd_context(url).await,
+            false => self.fallback.load(url).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use crate::{
+        proto::cheqd::resource::v2::Metadata,
+        resolution::resolver::DidCheqdResolverConfiguration,
+        test_utils::{LedgerFixtures, MockCheqdNode},
+    };
+
+    #[tokio::test]
+    async fn test_loads_cheqd_contexts() {
+        let context = br#"{"@context": {"name": "https://schema.org/name"}}"#;
+        let metadata = Metadata {
+            collection_id: "abc".into(),
+            id: "r1".into(),
+            name: "context".into(),
+            resource_type: "JSON-LD Context".into(),
+            media_type: "application/ld+json".into(),
+            ..Default::default()
+        };
+        let fixtures = LedgerFixtures::new().with_resource(context.to_vec(), metadata);
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            ..Default::default()
+        });
+        let loader = CheqdContextLoader::new(resolver.clone());
+
+        let url = Iri::new("did:cheqd:testnet:abc/resources/r1").unwrap();
+        let document = loader.load(url).await.unwrap();
+        assert!(
+            document
+                .document()
+                .as_object()
+                .unwrap()
+                .get("@context")
+                .next()
+                .is_some()
+        );
+        // served from the loader's cache the second time
+        let queries = resolver.stats().await.queries;
+        loader.load(url).await.unwrap();
+        assert_eq!(resolver.stats().await.queries, queries);
+
+        let missing = Iri::new("did:cheqd:testnet:abc/resources/missing").unwrap();
+        assert!(loader.load(missing).await.is_err());
+        let well_known = Iri::new("https://www.w3.org/2018/credentials/v1").unwrap();
+        assert!(loader.load(well_known).await.is_ok());
+    }
+}
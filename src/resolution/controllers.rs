@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+
+use futures_util::future::try_join_all;
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    resolution::resolver::{DidCheqdResolver, ResolvedDidDoc, did_error_context},
+};
+
+/// A controller of a DID, resolved by [DidCheqdResolver::resolve_controllers]
+#[derive(Debug, Clone)]
+pub struct ResolvedController {
+    /// the controller's DID
+    pub did: String,
+    /// the DID which lists it as a controller
+    pub controller_of: String,
+    /// how many controller links separate it from the resolved DID: 1 for its direct controllers
+    pub depth: usize,
+    /// the controller's DID document
+    pub resolved: ResolvedDidDoc,
+}
+
+impl DidCheqdResolver {
+    /// Resolve a DID, then the DIDs controlling it, the DIDs controlling those, and so on, up to
+    /// `max_depth` controller links away, for verifying capability delegation & controller-based
+    /// authorization.
+    ///
+    /// Returns the DID's document with its controllers, ordered by depth. Each controller is
+    /// resolved once, so a DID listed as its own controller (as most are), or any other cycle,
+    /// does not recurse. Controllers are resolved concurrently per depth, and fail the whole
+    /// resolution if any can't be resolved, including controllers of other DID methods.
+    pub async fn resolve_controllers(
+        &self,
+        did: &str,
+        max_depth: usize,
+    ) -> DidCheqdResult<(ResolvedDidDoc, Vec<ResolvedController>)> {
+        let parsed = self.parse(did)?;
+        let context = did_error_context(&parsed, "resolve_controllers");
+        let resolved = self.resolve_did_doc(parsed).await?;
+
+        let mut visited = HashSet::from([resolved.did_doc.id.clone()]);
+        let mut controllers: Vec<ResolvedController> = Vec::new();
+        let mut pending = controller_links(&resolved, &mut visited);
+        for depth in 1..=max_depth {
+            if pending.is_empty() {
+                break;
+            }
+            let level = try_join_all(pending.into_iter().map(|(controller_of, did)| {
+                let context = context.clone();
+                async move {
+                    let parsed = self
+                        .parse(&did)
+                        .map_err(|e| e.with_context(context.clone()))?;
+                    let resolved = self
+                        .resolve_did_doc(parsed)
+                        .await
+                        .map_err(|e| e.with_context(context))?;
+                    Ok::<_, DidCheqdError>(ResolvedController {
+                        did,
+                        controller_of,
+                        depth,
+                        resolved,
+                    })
+                }
+            }))
+            .await?;
+            pending = level
+                .iter()
+                .flat_map(|controller| controller_links(&controller.resolved, &mut visited))
+                .collect();
+            controllers.extend(level);
+        }
+        Ok((resolved, controllers))
+    }
+}
+
+/// The `(controlled DID, controller DID)` links of a DID document to controllers not yet visited
+fn controller_links(
+    resolved: &ResolvedDidDoc,
+    visited: &mut HashSet<String>,
+) -> Vec<(String, String)> {
+    let did = &resolved.did_doc.id;
+    resolved
+        .did_doc
+        .controller
+        .iter()
+        .filter(|controller| visited.insert(controller.to_string()))
+        .map(|controller| (did.clone(), controller.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use crate::{
+        proto::cheqd::did::v2::{DidDoc, Metadata as DidDocMetadata},
+        resolution::resolver::DidCheqdResolverConfiguration,
+        test_utils::{LedgerFixtures, MockCheqdNode},
+    };
+
+    fn did(id: &str) -> String {
+        format!("did:cheqd:testnet:{id}")
+    }
+
+    fn did_doc(id: &str, controllers: &[&str]) -> DidDoc {
+        DidDoc {
+            id: did(id),
+            controller: controllers.iter().map(|c| did(c)).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_controllers() {
+        let metadata = DidDocMetadata {
+            version_id: "v1".into(),
+            ..Default::default()
+        };
+        let fixtures = [
+            did_doc("a", &["a", "b"]),
+            did_doc("b", &["a", "c"]),
+            did_doc("c", &["d"]),
+            did_doc("d", &[]),
+        ]
+        .into_iter()
+        .fold(LedgerFixtures::new(), |fixtures, doc| {
+            fixtures.with_did_doc(doc, metadata.clone())
+        });
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            ..Default::default()
+        });
+
+        let (resolved, controllers) = resolver.resolve_controllers(&did("a"), 2).await.unwrap();
+        assert_eq!(resolved.did_doc.id, did("a"));
+        let chain: Vec<_> = controllers
+            .iter()
+            .map(|c| (c.controller_of.as_str(), c.did.as_str(), c.depth))
+            .collect();
+        assert_eq!(
+            chain,
+            [
+                (did("a").as_str(), did("b").as_str(), 1),
+                (did("b").as_str(), did("c").as_str(), 2)
+            ]
+        );
+
+        let (_, controllers) = resolver.resolve_controllers(&did("a"), 0).await.unwrap();
+        assert!(controllers.is_empty());
+    }
+}
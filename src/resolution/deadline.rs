@@ -0,0 +1,97 @@
+//! Per-call deadlines for resolutions.
+//!
+//! Every resolution is a future which can be cancelled by dropping it, e.g. with
+//! [tokio::time::timeout] or [tokio::select!], & which leaves the resolver in a consistent state
+//! when it is. [with_deadline] additionally tells the nodes queried how long remains (as the
+//! `grpc-timeout` header), so they can abandon queries the caller will no longer wait for.
+
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use crate::error::{DidCheqdError, DidCheqdResult};
+
+tokio::task_local! {
+    static DEADLINE: Instant;
+}
+
+/// Run a resolution (or any future using the resolver), failing with
+/// [DidCheqdError::DeadlineExceeded] if it hasn't completed by `deadline`.
+///
+/// Deadlines nest: within another [with_deadline] call, the earlier deadline applies.
+pub async fn with_deadline<T>(
+    deadline: Instant,
+    resolution: impl Future<Output = DidCheqdResult<T>>,
+) -> DidCheqdResult<T> {
+    let deadline = DEADLINE
+        .try_with(|outer| deadline.min(*outer))
+        .unwrap_or(deadline);
+    let resolution = tokio::time::timeout_at(deadline.into(), resolution);
+    match DEADLINE.scope(deadline, resolution).await {
+        Ok(Ok(resolved)) => Ok(resolved),
+        // queries fail by themselves once the time remaining for them is up
+        Ok(Err(_)) if Instant::now() >= deadline => Err(DidCheqdError::DeadlineExceeded),
+        Ok(Err(e)) => Err(e),
+        Err(_) => Err(DidCheqdError::DeadlineExceeded),
+    }
+}
+
+/// The time remaining until the deadline of the current call, if it has one
+pub(crate) fn remaining() -> Option<Duration> {
+    DEADLINE
+        .try_with(|deadline| deadline.saturating_duration_since(Instant::now()))
+        .ok()
+}
+
+/// A gRPC request carrying the time remaining until the current call's deadline, if any
+pub(crate) fn grpc_request<T>(message: T) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    if let Some(remaining) = remaining() {
+        request.set_timeout(remaining);
+    }
+    request
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::net::Ipv4Addr;
+
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::resolution::resolver::{
+        DidCheqdResolver, DidCheqdResolverConfiguration, NetworkConfiguration,
+    };
+
+    #[tokio::test]
+    async fn test_with_deadline() {
+        // accepts connections, but never responds
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![NetworkConfiguration {
+                grpc_url: format!("http://{}", listener.local_addr().unwrap()),
+                namespace: "testnet".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        let did = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+        let parsed = resolver.parse(did).unwrap();
+
+        let deadline = Instant::now() + Duration::from_millis(50);
+        let e = with_deadline(deadline, resolver.resolve_did_doc(parsed))
+            .await
+            .unwrap_err();
+        assert!(matches!(e, DidCheqdError::DeadlineExceeded), "{e:?}");
+
+        let outer = Instant::now() + Duration::from_secs(1);
+        let nested = with_deadline(outer, async {
+            with_deadline(outer + Duration::from_secs(60), async { Ok(remaining()) }).await
+        })
+        .await
+        .unwrap();
+        assert!(nested.unwrap() <= Duration::from_secs(1));
+        assert!(remaining().is_none());
+    }
+}
@@ -0,0 +1,254 @@
+//! Dereferencing DID URLs into the services of DID documents, as the Universal Resolver does: a
+//! `#<fragment>` DID URL to the service with that id, & a `?service=<id>` DID URL (optionally
+//! with a `relativeRef`) to the URL of the service's endpoint.
+
+use serde_json::Value;
+use ssi_dids_core::document::representation::MediaType;
+use url::Url;
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    proto::cheqd::did::v2::Service,
+    resolution::{
+        resolver::{DidCheqdResolver, did_error_context},
+        transformer::cheqd_service_to_json,
+    },
+};
+
+/// The media type of [ServiceDereference::Url]'s content stream
+pub const URI_LIST_MEDIA_TYPE: &str = "text/uri-list";
+
+/// The service a DID URL dereferences to, see [DidCheqdResolver::dereference_service]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServiceDereference {
+    /// the service identified by a `#<fragment>` DID URL, as in the resolved DID document
+    Service(Value),
+    /// the URL of the endpoint of the service selected by a `?service=<id>` DID URL, with the
+    /// `relativeRef` parameter resolved against it & the DID URL's fragment appended
+    Url(String),
+}
+
+impl ServiceDereference {
+    /// The media type of the content stream: a DID document fragment, or a URI list
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ServiceDereference::Service(_) => MediaType::JsonLd.name(),
+            ServiceDereference::Url(_) => URI_LIST_MEDIA_TYPE,
+        }
+    }
+
+    /// The dereferenced content in string form: the service as JSON, or the endpoint URL
+    pub fn content_stream(&self) -> String {
+        match self {
+            ServiceDereference::Service(service) => service.to_string(),
+            ServiceDereference::Url(url) => url.clone(),
+        }
+    }
+}
+
+/// Whether a (possibly relative) service id has the given fragment
+fn has_fragment(service: &Service, fragment: &str) -> bool {
+    service
+        .id
+        .rsplit_once('#')
+        .is_some_and(|(_, id)| id == fragment)
+}
+
+/// Decode the `%XX` escapes of a DID URL parameter value, which must be URL encoded
+fn percent_decode(value: &str) -> DidCheqdResult<String> {
+    let invalid = || DidCheqdError::InvalidDidUrl(format!("invalid URL encoding: {value}"));
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        if byte != b'%' {
+            bytes.push(byte);
+            continue;
+        }
+        let hex = rest.get(..2).ok_or_else(invalid)?;
+        let hex = std::str::from_utf8(hex).map_err(|_| invalid())?;
+        bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+        rest = &rest[2..];
+    }
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+/// The URL of a service's (first) endpoint, with a relative reference resolved against it (per
+/// RFC 3986) & a fragment appended
+fn endpoint_url(
+    service: &Service,
+    relative_ref: Option<&str>,
+    fragment: Option<&str>,
+) -> DidCheqdResult<String> {
+    let endpoint = service.service_endpoint.first().ok_or_else(|| {
+        DidCheqdError::InvalidDidDocument(format!("service {} has no endpoint", service.id))
+    })?;
+    let invalid = |e: url::ParseError| {
+        DidCheqdError::InvalidDidDocument(format!(
+            "service {} endpoint `{endpoint}` is not a URL: {e}",
+            service.id
+        ))
+    };
+    let mut url = Url::parse(endpoint).map_err(invalid)?;
+    if let Some(relative_ref) = relative_ref {
+        url = url.join(&percent_decode(relative_ref)?).map_err(invalid)?;
+    }
+    if let Some(fragment) = fragment {
+        if url.fragment().is_none() {
+            url.set_fragment(Some(fragment));
+        }
+    }
+    Ok(url.into())
+}
+
+impl DidCheqdResolver {
+    /// Dereference a DID URL into a service of its DID document (after any
+    /// [crate::resolution::document_transform::DocumentTransform]s):
+    /// - `did:cheqd:<namespace>:<id>#<fragment>` to the service with that id, as JSON
+    /// - `did:cheqd:<namespace>:<id>?service=<fragment>` to the URL of that service's (first)
+    ///   endpoint. A `relativeRef` parameter (URL encoded, e.g. `%2Fpath%3Fq%3D1`) is resolved
+    ///   against the endpoint URL, and the DID URL's fragment, if any, is appended to it.
+    ///
+    /// A DID document without the service is a [DidCheqdError::ResourceNotFound] error, i.e.
+    /// `notFound`.
+    pub async fn dereference_service(&self, did_url: &str) -> DidCheqdResult<ServiceDereference> {
+        let parsed = self.parse(did_url)?;
+        let context = did_error_context(&parsed, "dereference_service");
+        let param = |name: &str| parsed.query.as_ref().and_then(|q| q.get(name)).cloned();
+        let (service_id, relative_ref) = (param("service"), param("relativeRef"));
+        let fragment = parsed.fragment.clone();
+        let selector = match (&service_id, &fragment) {
+            _ if parsed.is_resource() => None,
+            (Some(service_id), _) => Some(service_id.clone()),
+            (None, Some(fragment)) if relative_ref.is_none() => Some(fragment.clone()),
+            _ => None,
+        };
+        let Some(selector) = selector else {
+            return Err(DidCheqdError::InvalidDidUrl(format!(
+                "{did_url} doesn't reference a service"
+            ))
+            .with_context(context));
+        };
+
+        let did_doc = self.resolve_did_doc(parsed).await?.did_doc;
+        let service = did_doc
+            .service
+            .into_iter()
+            .find(|service| has_fragment(service, &selector))
+            .ok_or_else(|| {
+                DidCheqdError::ResourceNotFound(format!(
+                    "no service `{selector}` in {}",
+                    did_doc.id
+                ))
+                .with_context(context.clone())
+            })?;
+        match service_id {
+            Some(_) => endpoint_url(&service, relative_ref.as_deref(), fragment.as_deref())
+                .map(ServiceDereference::Url)
+                .map_err(|e| e.with_context(context)),
+            None => Ok(ServiceDereference::Service(cheqd_service_to_json(service))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        proto::cheqd::did::v2::DidDoc,
+        resolution::resolver::DidCheqdResolverConfiguration,
+        test_utils::{LedgerFixtures, MockCheqdNode},
+    };
+
+    const DID: &str = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+
+    #[test]
+    fn test_endpoint_url() {
+        let service = Service {
+            id: format!("{DID}#api"),
+            service_endpoint: vec!["https://example.com/api/v1".into()],
+            ..Default::default()
+        };
+        let url = |relative_ref, fragment| endpoint_url(&service, relative_ref, fragment).unwrap();
+        assert_eq!(url(None, None), "https://example.com/api/v1");
+        assert_eq!(
+            url(Some("%2Fdocs%3Fq%3D1"), Some("top")),
+            "https://example.com/docs?q=1#top"
+        );
+        assert_eq!(
+            url(Some("users/1"), None),
+            "https://example.com/api/users/1"
+        );
+        assert!(endpoint_url(&service, Some("%zz"), None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dereference_service() {
+        let did_doc = DidDoc {
+            id: DID.into(),
+            service: vec![
+                Service {
+                    id: format!("{DID}#website"),
+                    service_type: "LinkedDomains".into(),
+                    service_endpoint: vec!["https://www.cheqd.io/".into()],
+                    ..Default::default()
+                },
+                Service {
+                    id: "#files".into(),
+                    service_type: "FileStore".into(),
+                    service_endpoint: vec!["https://files.example.com/dids/".into()],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let node =
+            MockCheqdNode::start(LedgerFixtures::new().with_did_doc(did_doc, Default::default()))
+                .await
+                .unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            ..Default::default()
+        });
+        let dereference = |did_url: String| {
+            let resolver = resolver.clone();
+            async move { resolver.dereference_service(&did_url).await }
+        };
+
+        let service = dereference(format!("{DID}#website")).await.unwrap();
+        assert_eq!(
+            service,
+            ServiceDereference::Service(json!({
+                "id": format!("{DID}#website"),
+                "type": "LinkedDomains",
+                "serviceEndpoint": "https://www.cheqd.io/",
+            }))
+        );
+        assert_eq!(service.content_type(), "application/did+ld+json");
+
+        // relative service ids are matched by their fragment too
+        let url = dereference(format!(
+            "{DID}?service=files&relativeRef=%2Fdids%2Fschema.json#v1"
+        ))
+        .await
+        .unwrap();
+        assert_eq!(
+            url,
+            ServiceDereference::Url("https://files.example.com/dids/schema.json#v1".into())
+        );
+        assert_eq!(url.content_type(), URI_LIST_MEDIA_TYPE);
+        assert_eq!(
+            url.content_stream(),
+            "https://files.example.com/dids/schema.json#v1"
+        );
+
+        let e = dereference(format!("{DID}?service=missing"))
+            .await
+            .unwrap_err();
+        assert_eq!(e.to_envelope().error, "notFound");
+        let e = dereference(DID.to_owned()).await.unwrap_err();
+        assert!(matches!(e.root(), DidCheqdError::InvalidDidUrl(_)), "{e:?}");
+    }
+}
@@ -0,0 +1,319 @@
+//! Re-expressing the keys of did:cheqd verification methods as `did:key` (or `did:peer:0`, which
+//! embeds a key the same way), for protocols wanting a self-contained key identifier, e.g. the
+//! `skid` of DIDComm v2 anoncrypt & ephemeral peer connections.
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL};
+use serde_json::{Map, Value};
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    proto::cheqd::did::v2::VerificationMethod,
+    resolution::resolver::{DidCheqdResolver, did_error_context},
+};
+
+/// The key types which can be expressed as a `did:key`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyType {
+    Ed25519,
+    X25519,
+    Secp256k1,
+    /// NIST P-256 (secp256r1)
+    P256,
+}
+
+impl KeyType {
+    /// The varint encoded multicodec of the key type's public keys
+    pub fn multicodec(self) -> &'static [u8] {
+        match self {
+            KeyType::Ed25519 => &[0xed, 0x01],
+            KeyType::X25519 => &[0xec, 0x01],
+            KeyType::Secp256k1 => &[0xe7, 0x01],
+            KeyType::P256 => &[0x80, 0x24],
+        }
+    }
+
+    /// The length of the key type's (compressed, for elliptic curve keys) public keys
+    fn key_len(self) -> usize {
+        match self {
+            KeyType::Ed25519 | KeyType::X25519 => 32,
+            KeyType::Secp256k1 | KeyType::P256 => 33,
+        }
+    }
+
+    fn from_multicodec(key: &[u8]) -> Option<(Self, &[u8])> {
+        [Self::Ed25519, Self::X25519, Self::Secp256k1, Self::P256]
+            .into_iter()
+            .find_map(|key_type| Some((key_type, key.strip_prefix(key_type.multicodec())?)))
+    }
+
+    fn from_jwk_curve(crv: &str) -> Option<Self> {
+        match crv {
+            "Ed25519" => Some(KeyType::Ed25519),
+            "X25519" => Some(KeyType::X25519),
+            "secp256k1" => Some(KeyType::Secp256k1),
+            "P-256" => Some(KeyType::P256),
+            _ => None,
+        }
+    }
+}
+
+/// A public key expressed as a `did:key`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DidKey {
+    pub key_type: KeyType,
+    /// the raw public key, compressed for elliptic curve keys
+    pub public_key: Vec<u8>,
+}
+
+impl DidKey {
+    /// A key of a type, checking its length
+    pub fn new(key_type: KeyType, public_key: Vec<u8>) -> DidCheqdResult<Self> {
+        if public_key.len() != key_type.key_len() {
+            return Err(DidCheqdError::InvalidDidDocument(format!(
+                "{key_type:?} public keys are {} bytes, got {}",
+                key_type.key_len(),
+                public_key.len()
+            )));
+        }
+        Ok(Self {
+            key_type,
+            public_key,
+        })
+    }
+
+    /// The key of a verification method.
+    ///
+    /// Supports `Ed25519VerificationKey2018`/`2020`, `X25519KeyAgreementKey2019`/`2020`,
+    /// `Multikey` & `JsonWebKey2020` methods holding ed25519, x25519, secp256k1 or P-256 keys;
+    /// other methods are an [DidCheqdError::InvalidDidDocument] error.
+    pub fn from_verification_method(method: &VerificationMethod) -> DidCheqdResult<Self> {
+        let material = method.verification_material.trim();
+        let invalid = |reason: &str| {
+            DidCheqdError::InvalidDidDocument(format!(
+                "verification method {} can't be expressed as a did:key: {reason}",
+                method.id
+            ))
+        };
+        match method.verification_method_type.as_str() {
+            "Ed25519VerificationKey2018" => Self::new(KeyType::Ed25519, base58(material)?),
+            "X25519KeyAgreementKey2019" => Self::new(KeyType::X25519, base58(material)?),
+            "Ed25519VerificationKey2020" | "X25519KeyAgreementKey2020" | "Multikey" => {
+                Self::from_multibase(material)
+            }
+            "JsonWebKey2020" | "JsonWebKey" => {
+                let jwk: Map<String, Value> = serde_json::from_str(material)?;
+                Self::from_jwk(&jwk)
+            }
+            other => Err(invalid(&format!("unsupported type {other}"))),
+        }
+        .map_err(|e| match e {
+            DidCheqdError::InvalidDidDocument(reason) => invalid(&reason),
+            e => e,
+        })
+    }
+
+    /// A key from its multibase (base58btc, `z`) encoded multicodec form, e.g. a `did:key`'s
+    /// method specific id or a `publicKeyMultibase`
+    pub fn from_multibase(multibase: &str) -> DidCheqdResult<Self> {
+        let encoded = multibase.strip_prefix('z').ok_or_else(|| {
+            DidCheqdError::InvalidDidDocument(format!("`{multibase}` is not base58btc encoded"))
+        })?;
+        let key = base58(encoded)?;
+        let (key_type, public_key) = KeyType::from_multicodec(&key).ok_or_else(|| {
+            DidCheqdError::InvalidDidDocument(format!("`{multibase}` is not a supported key type"))
+        })?;
+        Self::new(key_type, public_key.to_vec())
+    }
+
+    /// A key from a public JWK: an `OKP` key of the `Ed25519` or `X25519` curves, or an `EC` key
+    /// of the `secp256k1` or `P-256` curves
+    pub fn from_jwk(jwk: &Map<String, Value>) -> DidCheqdResult<Self> {
+        let member = |name: &str| {
+            jwk.get(name).and_then(Value::as_str).ok_or_else(|| {
+                DidCheqdError::InvalidDidDocument(format!("JWK has no `{name}` member"))
+            })
+        };
+        let coordinate = |name: &str| {
+            BASE64_URL.decode(member(name)?).map_err(|e| {
+                DidCheqdError::InvalidDidDocument(format!("JWK `{name}` is not base64url: {e}"))
+            })
+        };
+        let crv = member("crv")?;
+        let key_type = KeyType::from_jwk_curve(crv).ok_or_else(|| {
+            DidCheqdError::InvalidDidDocument(format!("unsupported JWK curve {crv}"))
+        })?;
+        match (member("kty")?, key_type) {
+            ("OKP", KeyType::Ed25519 | KeyType::X25519) => Self::new(key_type, coordinate("x")?),
+            ("EC", KeyType::Secp256k1 | KeyType::P256) => {
+                // compress the point: the parity of y, then x
+                let (x, y) = (coordinate("x")?, coordinate("y")?);
+                let parity = y.last().map_or(0, |last| last & 1);
+                Self::new(key_type, [&[0x02 | parity], &x[..]].concat())
+            }
+            (kty, _) => Err(DidCheqdError::InvalidDidDocument(format!(
+                "JWK key type {kty} doesn't match curve {crv}"
+            ))),
+        }
+    }
+
+    /// The multibase (base58btc) encoded multicodec key, e.g. `z6Mk...` for ed25519 keys
+    pub fn multibase(&self) -> String {
+        let key = [self.key_type.multicodec(), &self.public_key].concat();
+        format!("z{}", bs58::encode(key).into_string())
+    }
+
+    /// The `did:key` of the key
+    pub fn did(&self) -> String {
+        format!("did:key:{}", self.multibase())
+    }
+
+    /// The id of the `did:key`'s verification method, e.g. for a DIDComm `skid`
+    pub fn key_id(&self) -> String {
+        format!("{}#{}", self.did(), self.multibase())
+    }
+
+    /// The key as a `did:peer` of numeric algorithm 0, which is an inception key without a DID
+    /// document
+    pub fn peer_did(&self) -> String {
+        format!("did:peer:0{}", self.multibase())
+    }
+}
+
+fn base58(encoded: &str) -> DidCheqdResult<Vec<u8>> {
+    bs58::decode(encoded)
+        .into_vec()
+        .map_err(|e| DidCheqdError::InvalidDidDocument(format!("key is not base58 encoded: {e}")))
+}
+
+impl DidCheqdResolver {
+    /// Resolve a verification method DID URL (e.g. `did:cheqd:mainnet:<id>#key-1`) and express
+    /// its key as a [DidKey]. Relative method ids in the DID document are matched against the
+    /// DID URL's fragment.
+    pub async fn resolve_did_key(&self, did_url: &str) -> DidCheqdResult<DidKey> {
+        let (did, fragment) = did_url.split_once('#').ok_or_else(|| {
+            DidCheqdError::InvalidDidUrl(format!(
+                "{did_url} doesn't reference a verification method"
+            ))
+        })?;
+        let parsed = self.parse(did)?;
+        let context = did_error_context(&parsed, "resolve_did_key");
+        let resolved = self.resolve_did_doc(parsed).await?;
+        let did_doc = resolved.did_doc;
+        did_doc
+            .verification_method
+            .iter()
+            .find(|method| method.id == did_url || method.id.strip_prefix('#') == Some(fragment))
+            .ok_or_else(|| {
+                DidCheqdError::InvalidDidDocument(format!(
+                    "{did_url} is not a verification method of {}",
+                    did_doc.id
+                ))
+            })
+            .and_then(DidKey::from_verification_method)
+            .map_err(|e| e.with_context(context))
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    /// the ed25519 key of the did:key spec's test vectors
+    const DID_KEY: &str = "did:key:z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp";
+    const JWK_X: &str = "O2onvM62pC1io6jQKm8Nc2UyFXcd4kOmOsBIoYtZ2ik";
+
+    fn method(method_type: &str, material: &str) -> VerificationMethod {
+        VerificationMethod {
+            id: "did:cheqd:testnet:abc#key-1".into(),
+            verification_method_type: method_type.into(),
+            controller: "did:cheqd:testnet:abc".into(),
+            verification_material: material.into(),
+        }
+    }
+
+    #[test]
+    fn test_did_key_from_verification_methods() {
+        let multibase = DID_KEY.strip_prefix("did:key:").unwrap();
+        let key = DidKey::from_multibase(multibase).unwrap();
+        assert_eq!(key.key_type, KeyType::Ed25519);
+        assert_eq!(key.did(), DID_KEY);
+        assert_eq!(key.key_id(), format!("{DID_KEY}#{multibase}"));
+        assert_eq!(key.peer_did(), format!("did:peer:0{multibase}"));
+
+        let base58 = bs58::encode(&key.public_key).into_string();
+        let jwk = format!(r#"{{"kty": "OKP", "crv": "Ed25519", "x": "{JWK_X}"}}"#);
+        for (method_type, material) in [
+            ("Ed25519VerificationKey2020", multibase),
+            ("Ed25519VerificationKey2018", &base58),
+            ("JsonWebKey2020", &jwk),
+        ] {
+            let from_method = DidKey::from_verification_method(&method(method_type, material));
+            assert_eq!(from_method.unwrap(), key, "{method_type}");
+        }
+
+        // EC keys are compressed, by the parity of y
+        let x = BASE64_URL.encode([1; 32]);
+        let y = BASE64_URL.encode([[2; 31].as_slice(), &[3]].concat());
+        let jwk = format!(r#"{{"kty": "EC", "crv": "secp256k1", "x": "{x}", "y": "{y}"}}"#);
+        let key = DidKey::from_verification_method(&method("JsonWebKey2020", &jwk)).unwrap();
+        assert_eq!(key.public_key, [[0x03].as_slice(), &[1; 32]].concat());
+        assert!(key.did().starts_with("did:key:zQ3s"));
+        assert_eq!(DidKey::from_multibase(&key.multibase()).unwrap(), key);
+
+        let e = DidKey::from_verification_method(&method("EcdsaSecp256k1RecoveryMethod2020", ""));
+        assert!(matches!(e, Err(DidCheqdError::InvalidDidDocument(_))));
+        assert!(
+            DidKey::from_verification_method(&method("Ed25519VerificationKey2018", "abc")).is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_did_key() {
+        use crate::{
+            proto::cheqd::did::v2::{DidDoc, Metadata as DidDocMetadata},
+            resolution::resolver::DidCheqdResolverConfiguration,
+            test_utils::{LedgerFixtures, MockCheqdNode},
+        };
+
+        let multibase = DID_KEY.strip_prefix("did:key:").unwrap();
+        let did_doc = DidDoc {
+            id: "did:cheqd:testnet:abc".into(),
+            verification_method: vec![VerificationMethod {
+                id: "#key-1".into(),
+                ..method("Ed25519VerificationKey2020", multibase)
+            }],
+            ..Default::default()
+        };
+        let metadata = DidDocMetadata {
+            version_id: "v1".into(),
+            ..Default::default()
+        };
+        let node = MockCheqdNode::start(LedgerFixtures::new().with_did_doc(did_doc, metadata))
+            .await
+            .unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            ..Default::default()
+        });
+
+        let key = resolver
+            .resolve_did_key("did:cheqd:testnet:abc#key-1")
+            .await
+            .unwrap();
+        assert_eq!(key.did(), DID_KEY);
+        let e = resolver
+            .resolve_did_key("did:cheqd:testnet:abc#key-2")
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(e.root(), DidCheqdError::InvalidDidDocument(_)),
+            "{e:?}"
+        );
+        assert!(
+            resolver
+                .resolve_did_key("did:cheqd:testnet:abc")
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+
+use futures_util::future::try_join_all;
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    proto::cheqd::did::v2::{DidDoc, Service, VerificationMethod},
+    resolution::resolver::{DidCheqdResolver, did_error_context},
+};
+
+/// Service types agents use to advertise DIDComm endpoints
+pub const DIDCOMM_SERVICE_TYPES: [&str; 3] = ["did-communication", "DIDCommMessaging", "DIDComm"];
+
+/// A key referenced by a DIDComm service's `recipientKeys` or `routingKeys`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServiceKey {
+    /// a did:cheqd key reference, resolved to its verification method
+    Method(VerificationMethod),
+    /// a reference to a key of another DID method (e.g. `did:key`), left as-is
+    External(String),
+}
+
+/// A DIDComm service of a DID document, with its keys resolved
+#[derive(Debug, Clone, PartialEq)]
+pub struct DidCommService {
+    /// the service's id, made absolute
+    pub id: String,
+    /// the service's type, one of [DIDCOMM_SERVICE_TYPES]
+    pub service_type: String,
+    /// the service's endpoints, in the order of the DID document
+    pub endpoints: Vec<String>,
+    /// the keys messages for the service are encrypted to
+    pub recipient_keys: Vec<ServiceKey>,
+    /// the keys of the mediators messages are forwarded through, outermost last
+    pub routing_keys: Vec<ServiceKey>,
+    /// the DIDComm profiles the service accepts
+    pub accept: Vec<String>,
+    /// the service's priority, lower values are preferred
+    pub priority: u32,
+}
+
+/// The DIDComm services of a DID, resolved by [DidCheqdResolver::resolve_didcomm_services]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DidDocumentServices {
+    /// the resolved DID
+    pub did: String,
+    /// the DID's DIDComm services, most preferred first
+    pub services: Vec<DidCommService>,
+}
+
+impl DidCheqdResolver {
+    /// Resolve a DID referenced by a DIDComm out-of-band invitation to the DIDComm services an
+    /// Aries agent can connect to.
+    ///
+    /// Services not of one of the [DIDCOMM_SERVICE_TYPES] are skipped, and the rest are sorted by
+    /// `priority` (keeping document order for equal priorities). Relative key references are
+    /// resolved against the DID itself, and references to keys of other did:cheqd DIDs (e.g. a
+    /// mediator's) by resolving those DIDs; a reference to a verification method a DID document
+    /// doesn't have is an [DidCheqdError::InvalidDidDocument] error.
+    pub async fn resolve_didcomm_services(&self, did: &str) -> DidCheqdResult<DidDocumentServices> {
+        let parsed = self.parse(did)?;
+        let context = did_error_context(&parsed, "resolve_didcomm_services");
+        let resolved = self.resolve_did_doc(parsed).await?;
+        let did_doc = resolved.did_doc;
+
+        let mut services: Vec<_> = did_doc
+            .service
+            .iter()
+            .filter(|service| DIDCOMM_SERVICE_TYPES.contains(&service.service_type.as_str()))
+            .collect();
+        services.sort_by_key(|service| service.priority);
+
+        // resolve the other did:cheqd DIDs whose keys are referenced, once each
+        let mut others: Vec<String> = services
+            .iter()
+            .flat_map(|service| service.recipient_keys.iter().chain(&service.routing_keys))
+            .map(|key| absolute(&did_doc.id, key))
+            .filter(|key| key.starts_with("did:cheqd:"))
+            .map(|key| key.split('#').next().unwrap_or_default().to_string())
+            .filter(|key_did| *key_did != did_doc.id)
+            .collect();
+        others.sort();
+        others.dedup();
+        let others = try_join_all(others.into_iter().map(|other| {
+            let context = context.clone();
+            async move {
+                let parsed = self
+                    .parse(&other)
+                    .map_err(|e| e.with_context(context.clone()))?;
+                let resolved = self
+                    .resolve_did_doc(parsed)
+                    .await
+                    .map_err(|e| e.with_context(context))?;
+                Ok::<_, DidCheqdError>((other, resolved.did_doc))
+            }
+        }))
+        .await?;
+        let mut docs: HashMap<&str, &DidDoc> = others
+            .iter()
+            .map(|(other, doc)| (other.as_str(), doc))
+            .collect();
+        docs.insert(&did_doc.id, &did_doc);
+
+        let services = services
+            .into_iter()
+            .map(|service| didcomm_service(&did_doc.id, service, &docs))
+            .collect::<DidCheqdResult<_>>()
+            .map_err(|e| e.with_context(context))?;
+        Ok(DidDocumentServices {
+            did: did_doc.id.clone(),
+            services,
+        })
+    }
+}
+
+/// Make a DID URL relative to `did` (e.g. `#key-1`) absolute
+fn absolute(did: &str, reference: &str) -> String {
+    if reference.starts_with('#') {
+        format!("{did}{reference}")
+    } else {
+        reference.to_string()
+    }
+}
+
+fn didcomm_service(
+    did: &str,
+    service: &Service,
+    docs: &HashMap<&str, &DidDoc>,
+) -> DidCheqdResult<DidCommService> {
+    let resolve_keys = |keys: &[String]| {
+        keys.iter()
+            .map(|key| resolve_key(&absolute(did, key), docs))
+            .collect::<DidCheqdResult<Vec<_>>>()
+    };
+    Ok(DidCommService {
+        id: absolute(did, &service.id),
+        service_type: service.service_type.clone(),
+        endpoints: service.service_endpoint.clone(),
+        recipient_keys: resolve_keys(&service.recipient_keys)?,
+        routing_keys: resolve_keys(&service.routing_keys)?,
+        accept: service.accept.clone(),
+        priority: service.priority,
+    })
+}
+
+fn resolve_key(key: &str, docs: &HashMap<&str, &DidDoc>) -> DidCheqdResult<ServiceKey> {
+    let Some(doc) = key.split_once('#').and_then(|(did, _)| docs.get(did)) else {
+        return Ok(ServiceKey::External(key.to_string()));
+    };
+    doc.verification_method
+        .iter()
+        .find(|method| absolute(&doc.id, &method.id) == key)
+        .map(|method| ServiceKey::Method(method.clone()))
+        .ok_or_else(|| {
+            DidCheqdError::InvalidDidDocument(format!(
+                "service key {key} is not a verification method of {}",
+                doc.id
+            ))
+        })
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use crate::{
+        proto::cheqd::did::v2::Metadata as DidDocMetadata,
+        resolution::resolver::DidCheqdResolverConfiguration,
+        test_utils::{LedgerFixtures, MockCheqdNode},
+    };
+
+    fn did(id: &str) -> String {
+        format!("did:cheqd:testnet:{id}")
+    }
+
+    fn method(did: &str) -> VerificationMethod {
+        VerificationMethod {
+            id: format!("{did}#key-1"),
+            verification_method_type: "Ed25519VerificationKey2020".into(),
+            controller: did.into(),
+            verification_material: "z6Mk".into(),
+        }
+    }
+
+    fn service(id: &str, service_type: &str, priority: u32, routing_keys: &[String]) -> Service {
+        Service {
+            id: id.into(),
+            service_type: service_type.into(),
+            service_endpoint: vec![format!("https://agent.example.com/{id}")],
+            recipient_keys: vec!["#key-1".into()],
+            routing_keys: routing_keys.to_vec(),
+            priority,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_didcomm_services() {
+        let metadata = DidDocMetadata {
+            version_id: "v1".into(),
+            ..Default::default()
+        };
+        let mediator = DidDoc {
+            id: did("mediator"),
+            verification_method: vec![method(&did("mediator"))],
+            ..Default::default()
+        };
+        let routing = [
+            format!("{}#key-1", did("mediator")),
+            "did:key:z6MkRouting".to_string(),
+        ];
+        let agent = DidDoc {
+            id: did("agent"),
+            verification_method: vec![method(&did("agent"))],
+            service: vec![
+                service("#fallback", "did-communication", 2, &[]),
+                service("#site", "LinkedDomains", 0, &[]),
+                service("#mediated", "did-communication", 1, &routing),
+            ],
+            ..Default::default()
+        };
+        let broken = DidDoc {
+            id: did("broken"),
+            service: vec![service("#didcomm", "DIDCommMessaging", 0, &[])],
+            ..Default::default()
+        };
+        let fixtures = [mediator, agent, broken]
+            .into_iter()
+            .fold(LedgerFixtures::new(), |fixtures, doc| {
+                fixtures.with_did_doc(doc, metadata.clone())
+            });
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            ..Default::default()
+        });
+
+        let services = resolver
+            .resolve_didcomm_services(&did("agent"))
+            .await
+            .unwrap();
+        assert_eq!(services.did, did("agent"));
+        let ids: Vec<_> = services.services.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                format!("{}#mediated", did("agent")),
+                format!("{}#fallback", did("agent"))
+            ]
+        );
+        let mediated = &services.services[0];
+        assert_eq!(
+            mediated.recipient_keys,
+            [ServiceKey::Method(method(&did("agent")))]
+        );
+        assert_eq!(
+            mediated.routing_keys,
+            [
+                ServiceKey::Method(method(&did("mediator"))),
+                ServiceKey::External("did:key:z6MkRouting".into())
+            ]
+        );
+
+        let err = resolver
+            .resolve_didcomm_services(&did("broken"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("is not a verification method"));
+    }
+}
@@ -0,0 +1,348 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use prost::Message;
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::{
+    proto::cheqd::{
+        did::v2::DidDocWithMetadata,
+        resource::v2::{Resource, ResourceWithMetadata},
+    },
+    resolution::{
+        cache::{DidDocKey, ResourceKey},
+        resolver::{ResolvedDidDoc, ResolvedResource, Transport},
+    },
+};
+
+/// The default [DiskCacheConfiguration::max_bytes]: 64 MiB
+pub const DEFAULT_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Configuration for persisting the resolution cache to an SQLite database, so resolution results
+/// outlive the process (e.g. CLI tools & serverless functions). Requires the `disk-cache`
+/// feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskCacheConfiguration {
+    /// the database file, created if missing. A corrupt database is moved aside (to `<path>.corrupt`)
+    /// & replaced with an empty one.
+    pub path: PathBuf,
+    /// the maximum total size of cached values, beyond which the oldest are evicted
+    pub max_bytes: u64,
+}
+
+impl DiskCacheConfiguration {
+    /// Persist the cache to the database at `path`, limited to [DEFAULT_MAX_BYTES]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_bytes: DEFAULT_MAX_BYTES,
+        }
+    }
+}
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS entries (
+    key TEXT PRIMARY KEY NOT NULL,
+    value BLOB NOT NULL,
+    transport TEXT NOT NULL,
+    expires_at INTEGER,
+    stale_for INTEGER NOT NULL,
+    stored_at INTEGER NOT NULL
+)";
+
+/// A key of the in-memory cache, as stored on disk
+pub(crate) trait DiskKey {
+    fn disk_key(&self) -> String;
+}
+
+impl DiskKey for DidDocKey {
+    fn disk_key(&self) -> String {
+        match &self.1 {
+            Some(version) => format!("{}?versionId={version}", self.0),
+            None => self.0.clone(),
+        }
+    }
+}
+
+impl DiskKey for ResourceKey {
+    fn disk_key(&self) -> String {
+        self.did_url()
+    }
+}
+
+/// A value of the in-memory cache, stored on disk as its protobuf encoding
+pub(crate) trait DiskValue: Sized {
+    fn encode(&self) -> Vec<u8>;
+    fn decode(bytes: &[u8], transport: Transport) -> Option<Self>;
+    fn transport(&self) -> Transport;
+}
+
+impl DiskValue for ResolvedDidDoc {
+    fn encode(&self) -> Vec<u8> {
+        DidDocWithMetadata {
+            did_doc: Some(self.did_doc.clone()),
+            metadata: self.metadata.clone(),
+        }
+        .encode_to_vec()
+    }
+
+    fn decode(bytes: &[u8], transport: Transport) -> Option<Self> {
+        let value = DidDocWithMetadata::decode(bytes).ok()?;
+        Some(ResolvedDidDoc {
+            did_doc: value.did_doc?,
+            metadata: value.metadata,
+            transport,
+            stale: false,
+            provenance: Default::default(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn transport(&self) -> Transport {
+        self.transport
+    }
+}
+
+impl DiskValue for ResolvedResource {
+    fn encode(&self) -> Vec<u8> {
+        ResourceWithMetadata {
+            resource: Some(Resource {
+                data: self.data.clone(),
+            }),
+            metadata: Some(self.metadata.clone()),
+        }
+        .encode_to_vec()
+    }
+
+    fn decode(bytes: &[u8], transport: Transport) -> Option<Self> {
+        let value = ResourceWithMetadata::decode(bytes).ok()?;
+        Some(ResolvedResource {
+            data: value.resource?.data,
+            metadata: value.metadata?,
+            transport,
+            provenance: Default::default(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn transport(&self) -> Transport {
+        self.transport
+    }
+}
+
+/// The resolution cache's on-disk store, see [DiskCacheConfiguration].
+///
+/// The store is best effort: failing reads are treated as misses, failing writes are ignored, and
+/// entries which can't be decoded are dropped.
+pub(crate) struct DiskCache {
+    connection: Mutex<Connection>,
+    max_bytes: u64,
+}
+
+impl DiskCache {
+    /// Open the database, replacing it if it is corrupt. `None` if it can't be opened at all.
+    pub(crate) fn open(config: &DiskCacheConfiguration) -> Option<Self> {
+        let connection = open_connection(&config.path).or_else(|| {
+            let mut corrupt = config.path.clone().into_os_string();
+            corrupt.push(".corrupt");
+            std::fs::rename(&config.path, corrupt).ok()?;
+            open_connection(&config.path)
+        })?;
+        Some(Self {
+            connection: Mutex::new(connection),
+            max_bytes: config.max_bytes,
+        })
+    }
+
+    /// The value stored for `key`, with when it expires & how long it is kept afterwards
+    pub(crate) fn load<K: DiskKey, V: DiskValue>(
+        &self,
+        key: &K,
+    ) -> Option<(V, Option<Instant>, Duration)> {
+        let key = key.disk_key();
+        let connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        let (value, transport, expires_at, stale_for) = connection
+            .query_row(
+                "SELECT value, transport, expires_at, stale_for FROM entries WHERE key = ?1",
+                [&key],
+                |row| {
+                    Ok((
+                        row.get::<_, Vec<u8>>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<i64>>(2)?,
+                        row.get::<_, i64>(3)?,
+                    ))
+                },
+            )
+            .optional()
+            .ok()??;
+        let stale_for = Duration::from_millis(stale_for.try_into().unwrap_or_default());
+        let expires_at = expires_at.map(from_unix_millis);
+        let evictable = expires_at.is_some_and(|at| at + stale_for <= SystemTime::now());
+        let value = match transport.as_str() {
+            _ if evictable => None,
+            "grpc" => V::decode(&value, Transport::Grpc),
+            "rest" => V::decode(&value, Transport::Rest),
+            _ => None,
+        };
+        if value.is_none() {
+            let _ = connection.execute("DELETE FROM entries WHERE key = ?1", [&key]);
+        }
+        Some((value?, expires_at.map(to_instant), stale_for))
+    }
+
+    /// Store a value for `key`, expiring after `ttl` (if any) & kept for `stale_for` afterwards,
+    /// then evict expired entries & the oldest entries beyond the size limit
+    pub(crate) fn store<K: DiskKey, V: DiskValue>(
+        &self,
+        key: &K,
+        value: &V,
+        ttl: Option<Duration>,
+        stale_for: Duration,
+    ) {
+        let now = SystemTime::now();
+        let transport = match value.transport() {
+            Transport::Grpc => "grpc",
+            Transport::Rest => "rest",
+        };
+        let connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = connection.execute(
+            "INSERT OR REPLACE INTO entries \
+             (key, value, transport, expires_at, stale_for, stored_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                key.disk_key(),
+                value.encode(),
+                transport,
+                ttl.map(|ttl| unix_millis(now + ttl)),
+                i64::try_from(stale_for.as_millis()).unwrap_or(i64::MAX),
+                unix_millis(now),
+            ],
+        );
+        let _ = connection.execute(
+            "DELETE FROM entries WHERE expires_at + stale_for <= ?1",
+            [unix_millis(now)],
+        );
+        let _ = connection.execute(
+            "DELETE FROM entries WHERE key IN (
+                SELECT key FROM (
+                    SELECT key, SUM(LENGTH(value)) OVER (ORDER BY stored_at DESC, rowid DESC) AS total
+                    FROM entries
+                ) WHERE total > ?1
+            )",
+            [i64::try_from(self.max_bytes).unwrap_or(i64::MAX)],
+        );
+    }
+
+    pub(crate) fn clear(&self) {
+        let connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = connection.execute("DELETE FROM entries", []);
+    }
+}
+
+/// Open the database at `path`, creating it if missing. `None` if it can't be opened, or fails
+/// its integrity check.
+fn open_connection(path: &Path) -> Option<Connection> {
+    let connection = Connection::open(path).ok()?;
+    // other processes may be using the same database
+    connection.busy_timeout(Duration::from_secs(1)).ok()?;
+    let check: String = connection
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .ok()?;
+    if check != "ok" {
+        return None;
+    }
+    connection.execute(SCHEMA, []).ok()?;
+    Some(connection)
+}
+
+fn unix_millis(time: SystemTime) -> i64 {
+    let millis = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    i64::try_from(millis).unwrap_or(i64::MAX)
+}
+
+fn from_unix_millis(millis: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(millis.try_into().unwrap_or_default())
+}
+
+/// The instant corresponding to a system time, as near as can be represented
+fn to_instant(time: SystemTime) -> Instant {
+    let now = Instant::now();
+    match time.duration_since(SystemTime::now()) {
+        Ok(until) => now + until,
+        Err(e) => now.checked_sub(e.duration()).unwrap_or(now),
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use crate::proto::cheqd::resource::v2::Metadata;
+
+    fn resource(id: &str, size: usize) -> ResolvedResource {
+        ResolvedResource {
+            data: vec![0; size],
+            metadata: Metadata {
+                id: id.into(),
+                ..Default::default()
+            },
+            transport: Transport::Grpc,
+            provenance: Default::default(),
+            warnings: Vec::new(),
+        }
+    }
+
+    fn key(id: &str) -> ResourceKey {
+        ResourceKey::ById("testnet".into(), "abc".into(), id.into())
+    }
+
+    #[test]
+    fn test_disk_cache_persists_and_evicts() {
+        let dir = std::env::temp_dir().join(format!("did-cheqd-disk-cache-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = DiskCacheConfiguration {
+            path: dir.join("cache.sqlite"),
+            max_bytes: 1500,
+        };
+
+        let cache = DiskCache::open(&config).unwrap();
+        cache.store(&key("r1"), &resource("r1", 1000), None, Duration::ZERO);
+        cache.store(
+            &key("r2"),
+            &resource("r2", 10),
+            Some(Duration::ZERO),
+            Duration::ZERO,
+        );
+        drop(cache);
+
+        // entries outlive the connection, except expired ones
+        let cache = DiskCache::open(&config).unwrap();
+        let (r1, expires_at, _) = cache.load::<_, ResolvedResource>(&key("r1")).unwrap();
+        assert_eq!(r1.metadata.id, "r1");
+        assert!(expires_at.is_none());
+        assert!(cache.load::<_, ResolvedResource>(&key("r2")).is_none());
+
+        // the oldest entries are evicted beyond the size limit
+        cache.store(&key("r3"), &resource("r3", 1000), None, Duration::ZERO);
+        assert!(cache.load::<_, ResolvedResource>(&key("r1")).is_none());
+        assert!(cache.load::<_, ResolvedResource>(&key("r3")).is_some());
+        drop(cache);
+
+        // a corrupt database is replaced
+        std::fs::write(
+            &config.path,
+            b"not a database, but long enough to look like one....",
+        )
+        .unwrap();
+        let cache = DiskCache::open(&config).unwrap();
+        assert!(cache.load::<_, ResolvedResource>(&key("r3")).is_none());
+        drop(cache);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
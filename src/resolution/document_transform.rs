@@ -0,0 +1,167 @@
+use crate::{
+    error::DidCheqdResult,
+    proto::cheqd::did::v2::{DidDoc, Service},
+};
+
+/// Post-processing of resolved DID documents, configured with
+/// [crate::resolution::resolver::DidCheqdResolverConfiguration::document_transforms], so
+/// consumers needn't re-parse & mutate documents downstream.
+///
+/// Transforms run in order on every DID document the resolver returns, including those served
+/// from the cache (which holds documents as resolved from the ledger). A transform failing fails
+/// the resolution. Closures taking a `&mut DidDoc` are transforms.
+pub trait DocumentTransform: Send + Sync {
+    /// Transform a resolved DID document in place
+    fn transform(&self, did_doc: &mut DidDoc) -> DidCheqdResult<()>;
+}
+
+impl<F> DocumentTransform for F
+where
+    F: Fn(&mut DidDoc) -> DidCheqdResult<()> + Send + Sync,
+{
+    fn transform(&self, did_doc: &mut DidDoc) -> DidCheqdResult<()> {
+        self(did_doc)
+    }
+}
+
+/// Removes verification methods of the given types (e.g. deprecated key types such as
+/// `Ed25519VerificationKey2018`), along with references to them from verification relationships
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoveVerificationMethodTypes(pub Vec<String>);
+
+impl DocumentTransform for RemoveVerificationMethodTypes {
+    fn transform(&self, did_doc: &mut DidDoc) -> DidCheqdResult<()> {
+        let absolute = |id: &str| match id.starts_with('#') {
+            true => format!("{}{id}", did_doc.id),
+            false => id.to_owned(),
+        };
+        let (removed, kept) = std::mem::take(&mut did_doc.verification_method)
+            .into_iter()
+            .partition::<Vec<_>, _>(|method| self.0.contains(&method.verification_method_type));
+        let removed: Vec<_> = removed.iter().map(|method| absolute(&method.id)).collect();
+        for relationship in [
+            &mut did_doc.authentication,
+            &mut did_doc.assertion_method,
+            &mut did_doc.capability_invocation,
+            &mut did_doc.capability_delegation,
+            &mut did_doc.key_agreement,
+        ] {
+            relationship.retain(|reference| !removed.contains(&absolute(reference)));
+        }
+        did_doc.verification_method = kept;
+        Ok(())
+    }
+}
+
+/// Adds services to DID documents (e.g. a gateway's own endpoints), skipping those with the id of
+/// a service the document already has
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddServices(pub Vec<Service>);
+
+impl DocumentTransform for AddServices {
+    fn transform(&self, did_doc: &mut DidDoc) -> DidCheqdResult<()> {
+        for service in &self.0 {
+            if !did_doc.service.iter().any(|s| s.id == service.id) {
+                did_doc.service.push(service.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        error::DidCheqdError,
+        proto::cheqd::did::v2::{Metadata as DidDocMetadata, VerificationMethod},
+        resolution::resolver::{DidCheqdResolver, DidCheqdResolverConfiguration},
+        test_utils::{LedgerFixtures, MockCheqdNode},
+    };
+
+    const DID: &str = "did:cheqd:testnet:abc";
+
+    fn method(id: &str, method_type: &str) -> VerificationMethod {
+        VerificationMethod {
+            id: format!("{DID}{id}"),
+            verification_method_type: method_type.into(),
+            controller: DID.into(),
+            verification_material: "z6Mk".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transforms_apply_in_order() {
+        let did_doc = DidDoc {
+            id: DID.into(),
+            verification_method: vec![
+                method("#key-1", "Ed25519VerificationKey2018"),
+                method("#key-2", "Ed25519VerificationKey2020"),
+            ],
+            authentication: vec!["#key-1".into(), format!("{DID}#key-2")],
+            assertion_method: vec![format!("{DID}#key-1")],
+            ..Default::default()
+        };
+        let metadata = DidDocMetadata {
+            version_id: "v1".into(),
+            ..Default::default()
+        };
+        let node = MockCheqdNode::start(LedgerFixtures::new().with_did_doc(did_doc, metadata))
+            .await
+            .unwrap();
+        let gateway = Service {
+            id: "#gateway".into(),
+            service_type: "LinkedDomains".into(),
+            service_endpoint: vec!["https://gateway.example.com".into()],
+            ..Default::default()
+        };
+        let resolver = |transforms| {
+            DidCheqdResolver::new(DidCheqdResolverConfiguration {
+                networks: vec![node.network("testnet")],
+                document_transforms: transforms,
+                ..Default::default()
+            })
+        };
+        let resolver = resolver(vec![
+            Arc::new(RemoveVerificationMethodTypes(vec![
+                "Ed25519VerificationKey2018".into(),
+            ])),
+            Arc::new(AddServices(vec![gateway.clone()])),
+            // sees the services added before it
+            Arc::new(|did_doc: &mut DidDoc| {
+                let services = did_doc.service.len();
+                did_doc.also_known_as.push(format!("services:{services}"));
+                Ok(())
+            }),
+        ]);
+
+        let resolved = resolver
+            .resolve_did_doc(resolver.parse(DID).unwrap())
+            .await
+            .unwrap();
+        let did_doc = resolved.did_doc;
+        assert_eq!(
+            did_doc.verification_method,
+            [method("#key-2", "Ed25519VerificationKey2020")]
+        );
+        assert_eq!(did_doc.authentication, [format!("{DID}#key-2")]);
+        assert!(did_doc.assertion_method.is_empty());
+        assert_eq!(did_doc.service, [gateway]);
+        assert_eq!(did_doc.also_known_as, ["services:1"]);
+
+        let failing = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            document_transforms: vec![Arc::new(|_: &mut DidDoc| {
+                Err(DidCheqdError::InvalidDidDocument("rejected".into()))
+            })],
+            ..Default::default()
+        });
+        let e = failing
+            .resolve_did_doc(failing.parse(DID).unwrap())
+            .await
+            .unwrap_err();
+        assert!(matches!(e.root(), DidCheqdError::InvalidDidDocument(_)));
+    }
+}
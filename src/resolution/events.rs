@@ -0,0 +1,94 @@
+use std::{path::Path, time::Duration};
+
+use crate::{error::DidCheqdError, resolution::resolver::Transport};
+
+/// Hooks into the lifecycle of resolutions by a [crate::resolution::resolver::DidCheqdResolver],
+/// configured with [crate::resolution::resolver::DidCheqdResolverConfiguration::event_handler],
+/// e.g. for audit logging or custom metrics.
+///
+/// Every method defaults to doing nothing. Handlers are called synchronously from the resolution,
+/// so should not block.
+pub trait ResolverEventHandler: Send + Sync {
+    /// A DID or DID URL is about to be resolved
+    fn on_resolution_start(&self, did_url: &str) {
+        let _ = did_url;
+    }
+
+    /// A DID or DID URL was resolved, taking `elapsed`
+    fn on_resolution_success(&self, did_url: &str, elapsed: Duration) {
+        let _ = (did_url, elapsed);
+    }
+
+    /// A DID or DID URL failed to resolve after `elapsed`
+    fn on_resolution_failure(&self, did_url: &str, error: &DidCheqdError, elapsed: Duration) {
+        let _ = (did_url, error, elapsed);
+    }
+
+    /// A DID document or resource was served from the cache. `did_url` identifies the cached
+    /// result, with any namespace alias resolved. `stale` is true if it had expired.
+    fn on_cache_hit(&self, did_url: &str, stale: bool) {
+        let _ = (did_url, stale);
+    }
+
+    /// A client for a node of the network with the given namespace was connected
+    fn on_network_connect(&self, namespace: &str, url: &str, transport: Transport) {
+        let _ = (namespace, url, transport);
+    }
+
+    /// A configuration file watched by `DidCheqdResolver::watch_config` (with the `config-watch`
+    /// feature) changed, & its networks were reloaded, or failed to be with `error` (keeping the
+    /// networks configured before)
+    fn on_config_reload(&self, path: &Path, error: Option<&DidCheqdError>) {
+        let _ = (path, error);
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::resolution::resolver::{DidCheqdResolver, DidCheqdResolverConfiguration};
+
+    #[derive(Default)]
+    struct RecordingHandler {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl ResolverEventHandler for RecordingHandler {
+        fn on_resolution_start(&self, did_url: &str) {
+            self.events.lock().unwrap().push(format!("start {did_url}"));
+        }
+
+        fn on_resolution_failure(&self, did_url: &str, error: &DidCheqdError, _: Duration) {
+            let event = format!("failure {did_url}: {}", error.root());
+            self.events.lock().unwrap().push(event);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolution_events() {
+        let handler = Arc::new(RecordingHandler::default());
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            event_handler: Some(handler.clone()),
+            ..Default::default()
+        });
+
+        let did_url = "did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN?resourceName=a&resourceType=b";
+        resolver.resolve_did_url(did_url).await.unwrap_err();
+        let did = "did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN/versions/1";
+        resolver.resolve_did_url(did).await.unwrap_err();
+
+        assert_eq!(
+            *handler.events.lock().unwrap(),
+            [
+                format!("start {did_url}"),
+                format!("failure {did_url}: Cheqd network not supported: devnet"),
+                "start did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN?versionId=1".to_owned(),
+                "failure did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN?versionId=1: Cheqd network not \
+                 supported: devnet"
+                    .to_owned(),
+            ]
+        );
+    }
+}
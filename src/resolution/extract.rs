@@ -0,0 +1,178 @@
+//! [axum] extractors which resolve the DID URL in a request path with a shared
+//! [DidCheqdResolver] from the app state, for building resolver gateways & DID auth services.
+//!
+//! The DID URL is taken from the [DID_PATH_PARAM] route parameter, with the request's query
+//! string appended. Use a wildcard parameter (e.g. `/1.0/identifiers/*did`) so DID URLs with
+//! paths, such as `did:cheqd:mainnet:<id>/resources/<resource id>`, are matched.
+
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts, RawPathParams},
+    http::{StatusCode, header, request::Parts},
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    error::DidCheqdError,
+    resolution::{
+        resolver::{DidCheqdResolver, ResolvedDidDoc},
+        service::Resolved,
+    },
+};
+
+/// The name of the route parameter holding the DID URL
+pub const DID_PATH_PARAM: &str = "did";
+
+/// Extracts & resolves the request's DID URL to whatever it identifies, see
+/// [DidCheqdResolver::resolve_did_url]
+#[derive(Debug, Clone)]
+pub struct ResolvedDidUrl(pub Resolved);
+
+/// Extracts & resolves the request's DID URL to a DID document, rejecting resource DID URLs
+#[derive(Debug, Clone)]
+pub struct ResolvedDidDocument(pub ResolvedDidDoc);
+
+/// Why a DID URL could not be extracted & resolved
+#[derive(Debug)]
+pub enum DidRejection {
+    /// the route has no [DID_PATH_PARAM] parameter
+    MissingDidUrl,
+    /// the DID URL identifies a resource, where a DID document was required
+    NotADocument,
+    /// the DID URL could not be resolved
+    Resolution(DidCheqdError),
+}
+
+impl DidRejection {
+    /// The HTTP status of the rejection, following the DID resolution HTTP(S) binding
+    pub fn status(&self) -> StatusCode {
+        let DidRejection::Resolution(e) = self else {
+            return StatusCode::BAD_REQUEST;
+        };
+        match e.root() {
+            DidCheqdError::InvalidDid(_)
+            | DidCheqdError::InvalidDidUrl(_)
+            | DidCheqdError::ParsingError(_) => StatusCode::BAD_REQUEST,
+            DidCheqdError::MethodNotSupported(_) | DidCheqdError::NetworkNotSupported(_) => {
+                StatusCode::NOT_IMPLEMENTED
+            }
+            DidCheqdError::ResourceNotFound(_) => StatusCode::NOT_FOUND,
+            DidCheqdError::ChecksumMismatch(_) => StatusCode::CONFLICT,
+            DidCheqdError::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+            DidCheqdError::ShutDown => StatusCode::SERVICE_UNAVAILABLE,
+            DidCheqdError::NonSuccessResponse(_)
+                if e.status_code() == Some(tonic::Code::NotFound) =>
+            {
+                StatusCode::NOT_FOUND
+            }
+            DidCheqdError::NonSuccessResponse(_)
+            | DidCheqdError::TransportError(_)
+            | DidCheqdError::InvalidResponse(_)
+            | DidCheqdError::ResponseTooLarge(_)
+            | DidCheqdError::InconsistentResponses(_) => StatusCode::BAD_GATEWAY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for DidRejection {
+    fn into_response(self) -> Response {
+        let message = match &self {
+            DidRejection::MissingDidUrl => format!("route has no `{DID_PATH_PARAM}` parameter"),
+            DidRejection::NotADocument => "DID URL does not identify a DID document".to_owned(),
+            DidRejection::Resolution(e) => {
+                // a DID resolution error, see [DidCheqdError::to_envelope]
+                let body = serde_json::to_string(&e.to_envelope()).unwrap_or_default();
+                let content_type = [(header::CONTENT_TYPE, "application/json")];
+                return (self.status(), content_type, body).into_response();
+            }
+        };
+        (self.status(), message).into_response()
+    }
+}
+
+/// The DID URL of a request: the [DID_PATH_PARAM] route parameter & the query string
+async fn did_url<S: Send + Sync>(parts: &mut Parts, state: &S) -> Result<String, DidRejection> {
+    let params = RawPathParams::from_request_parts(parts, state)
+        .await
+        .map_err(|_| DidRejection::MissingDidUrl)?;
+    let did = params
+        .iter()
+        .find(|(name, _)| *name == DID_PATH_PARAM)
+        .map(|(_, value)| value.trim_start_matches('/').to_owned())
+        .ok_or(DidRejection::MissingDidUrl)?;
+    Ok(match parts.uri.query() {
+        Some(query) => format!("{did}?{query}"),
+        None => did,
+    })
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ResolvedDidUrl
+where
+    DidCheqdResolver: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = DidRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let did_url = did_url(parts, state).await?;
+        let resolver = DidCheqdResolver::from_ref(state);
+        resolver
+            .resolve_did_url(&did_url)
+            .await
+            .map(ResolvedDidUrl)
+            .map_err(DidRejection::Resolution)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ResolvedDidDocument
+where
+    DidCheqdResolver: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = DidRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match ResolvedDidUrl::from_request_parts(parts, state).await?.0 {
+            Resolved::Document(document) => Ok(ResolvedDidDocument(document)),
+            Resolved::Resource(_) => Err(DidRejection::NotADocument),
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use axum::{Router, body::Body, http::Request, routing::get};
+    use tower::Service;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_extractor_rejections() {
+        async fn handler(ResolvedDidDocument(document): ResolvedDidDocument) -> String {
+            document.did_doc.id
+        }
+        let resolver = DidCheqdResolver::new(Default::default());
+        let mut app = Router::new()
+            .route("/1.0/identifiers/*did", get(handler))
+            .route("/no-did", get(handler))
+            .with_state(resolver);
+        let mut status = |uri: &str| {
+            let request = Request::get(uri).body(Body::empty()).unwrap();
+            let response = app.call(request);
+            async move { response.await.unwrap().status() }
+        };
+
+        assert_eq!(
+            status("/1.0/identifiers/did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN").await,
+            StatusCode::NOT_IMPLEMENTED
+        );
+        assert_eq!(
+            status("/1.0/identifiers/did:key:z6Mk").await,
+            StatusCode::NOT_IMPLEMENTED
+        );
+        assert_eq!(status("/no-did").await, StatusCode::BAD_REQUEST);
+    }
+}
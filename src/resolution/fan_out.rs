@@ -0,0 +1,126 @@
+//! Resolution of DIDs against every configured network concurrently, for DIDs whose namespace is
+//! omitted, see [crate::resolution::resolver::DidCheqdResolverConfiguration::namespace_fan_out].
+
+use std::future::Future;
+
+use futures_util::future::{join_all, select_ok};
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    resolution::{
+        parser::DidCheqdParsed,
+        resolver::{DidCheqdResolver, ResolvedDidDoc},
+    },
+};
+
+impl DidCheqdResolver {
+    /// Resolve a DID (its namespace, if any, is ignored) on every configured network
+    /// concurrently, returning the DID documents found, tagged by the namespace of the network
+    /// each was found on, in the order the networks are configured.
+    ///
+    /// Fails with the error of the first network only if the DID was found on none of them.
+    pub async fn resolve_did_doc_in_all_namespaces(
+        &self,
+        did: &str,
+    ) -> DidCheqdResult<Vec<(String, ResolvedDidDoc)>> {
+        let parsed = self.parse(did)?;
+        let namespaces = self.namespaces();
+        let resolutions = namespaces.iter().map(|namespace| {
+            let parsed = parsed.clone().in_namespace(namespace);
+            async move { self.resolve_did_doc(parsed).await }
+        });
+
+        let mut found = Vec::new();
+        let mut first_error = None;
+        for (namespace, resolved) in namespaces.iter().zip(join_all(resolutions).await) {
+            match resolved {
+                Ok(resolved) => found.push((namespace.clone(), resolved)),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) if found.is_empty() => Err(e),
+            _ => Ok(found),
+        }
+    }
+
+    /// Resolve a DID URL with an omitted namespace on every configured network concurrently,
+    /// returning the first result to succeed, or the error of the last network to fail if none
+    /// do
+    pub(crate) async fn fan_out_first<T, F>(
+        &self,
+        parsed: DidCheqdParsed,
+        resolve: impl Fn(DidCheqdParsed) -> F,
+    ) -> DidCheqdResult<T>
+    where
+        F: Future<Output = DidCheqdResult<T>>,
+    {
+        let resolutions: Vec<_> = self
+            .namespaces()
+            .iter()
+            .map(|namespace| Box::pin(resolve(parsed.clone().in_namespace(namespace))))
+            .collect();
+        if resolutions.is_empty() {
+            return Err(DidCheqdError::NetworkNotSupported(parsed.namespace));
+        }
+        select_ok(resolutions).await.map(|(resolved, _)| resolved)
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use crate::{
+        proto::cheqd::did::v2::DidDoc,
+        resolution::resolver::DidCheqdResolverConfiguration,
+        test_utils::{LedgerFixtures, MockCheqdNode},
+    };
+
+    const ID: &str = "Ps1ysXP2Ae6GBfxNhNQNKN";
+
+    #[tokio::test]
+    async fn test_namespace_fan_out() {
+        let did_doc = DidDoc {
+            id: format!("did:cheqd:testnet:{ID}"),
+            ..Default::default()
+        };
+        let mainnet = MockCheqdNode::start(LedgerFixtures::new()).await.unwrap();
+        let testnet = LedgerFixtures::new().with_did_doc(did_doc, Default::default());
+        let testnet = MockCheqdNode::start(testnet).await.unwrap();
+        let resolver = |namespace_fan_out| {
+            DidCheqdResolver::new(DidCheqdResolverConfiguration {
+                networks: vec![mainnet.network("mainnet"), testnet.network("testnet")],
+                namespace_fan_out,
+                ..Default::default()
+            })
+        };
+        let did = format!("did:cheqd:{ID}");
+        let resolve = |resolver: DidCheqdResolver| {
+            let parsed = resolver.parse(&did).unwrap();
+            async move { resolver.resolve_did_doc(parsed).await }
+        };
+
+        // the namespace defaults to mainnet, unless fanning out
+        let e = resolve(resolver(false)).await.unwrap_err();
+        assert_eq!(e.status_code(), Some(tonic::Code::NotFound), "{e:?}");
+        let resolved = resolve(resolver(true)).await.unwrap();
+        assert_eq!(resolved.did_doc.id, format!("did:cheqd:testnet:{ID}"));
+
+        let found = resolver(false)
+            .resolve_did_doc_in_all_namespaces(&did)
+            .await
+            .unwrap();
+        let namespaces: Vec<_> = found
+            .iter()
+            .map(|(namespace, _)| namespace.as_str())
+            .collect();
+        assert_eq!(namespaces, ["testnet"]);
+        let e = resolver(false)
+            .resolve_did_doc_in_all_namespaces("did:cheqd:missing")
+            .await
+            .unwrap_err();
+        assert_eq!(e.status_code(), Some(tonic::Code::NotFound), "{e:?}");
+    }
+}
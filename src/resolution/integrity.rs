@@ -0,0 +1,286 @@
+//! Integrity of DID-Linked Resources referenced from credentials.
+//!
+//! Credentials reference resources (e.g. schemas & status lists) as `relatedResource` entries:
+//! the resource's DID URL with a digest of its content, as a `digestSRI` (a Subresource Integrity
+//! string, e.g. `sha384-...`) or a `digestMultibase` (a multibase encoded multihash). cheqd DID
+//! URLs may also carry the ledger's SHA-256 `checksum` of the content as a parameter.
+
+use base64::{
+    Engine,
+    engine::general_purpose::{STANDARD as BASE64, URL_SAFE_NO_PAD as BASE64_URL},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    resolution::resolver::{DidCheqdResolver, ResolvedResource},
+};
+
+/// The hash algorithms of integrity digests, in increasing strength
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DigestAlgorithm {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    /// The algorithm's name in Subresource Integrity strings
+    pub fn sri_name(self) -> &'static str {
+        match self {
+            DigestAlgorithm::Sha256 => "sha256",
+            DigestAlgorithm::Sha384 => "sha384",
+            DigestAlgorithm::Sha512 => "sha512",
+        }
+    }
+
+    /// The algorithm's multihash code
+    pub fn multihash_code(self) -> u8 {
+        match self {
+            DigestAlgorithm::Sha256 => 0x12,
+            DigestAlgorithm::Sha384 => 0x20,
+            DigestAlgorithm::Sha512 => 0x13,
+        }
+    }
+
+    fn from_sri_name(name: &str) -> Option<Self> {
+        [Self::Sha256, Self::Sha384, Self::Sha512]
+            .into_iter()
+            .find(|algorithm| algorithm.sri_name() == name)
+    }
+
+    fn from_multihash_code(code: u8) -> Option<Self> {
+        [Self::Sha256, Self::Sha384, Self::Sha512]
+            .into_iter()
+            .find(|algorithm| algorithm.multihash_code() == code)
+    }
+
+    /// The digest of some content
+    pub fn digest(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            DigestAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+            DigestAlgorithm::Sha384 => Sha384::digest(data).to_vec(),
+            DigestAlgorithm::Sha512 => Sha512::digest(data).to_vec(),
+        }
+    }
+}
+
+/// A Subresource Integrity string of some content, e.g. `sha384-<base64 digest>`
+pub fn digest_sri(data: &[u8], algorithm: DigestAlgorithm) -> String {
+    format!(
+        "{}-{}",
+        algorithm.sri_name(),
+        BASE64.encode(algorithm.digest(data))
+    )
+}
+
+/// A multibase (base64url, `u`) encoded multihash of some content
+pub fn digest_multibase(data: &[u8], algorithm: DigestAlgorithm) -> String {
+    let digest = algorithm.digest(data);
+    let multihash = [
+        &[algorithm.multihash_code(), digest.len() as u8],
+        &digest[..],
+    ]
+    .concat();
+    format!("u{}", BASE64_URL.encode(multihash))
+}
+
+/// Check content against a Subresource Integrity string.
+///
+/// Per the SRI spec, a string may list several space separated digests: only those of the
+/// strongest listed algorithm are considered, and the content must match one of them.
+pub fn verify_digest_sri(data: &[u8], sri: &str) -> DidCheqdResult<()> {
+    let digests: Vec<_> = sri
+        .split_whitespace()
+        .filter_map(|digest| {
+            let (name, value) = digest.split_once('-')?;
+            // options (`?...`) are reserved, and ignored
+            let value = value.split('?').next().unwrap_or_default();
+            Some((DigestAlgorithm::from_sri_name(name)?, value))
+        })
+        .collect();
+    let Some(strongest) = digests.iter().map(|(algorithm, _)| *algorithm).max() else {
+        return Err(DidCheqdError::ChecksumMismatch(format!(
+            "`{sri}` has no supported SRI digest"
+        )));
+    };
+    let actual = BASE64.encode(strongest.digest(data));
+    if digests
+        .iter()
+        .any(|(algorithm, value)| *algorithm == strongest && *value == actual)
+    {
+        return Ok(());
+    }
+    Err(DidCheqdError::ChecksumMismatch(format!(
+        "expected {sri}, found {}-{actual}",
+        strongest.sri_name()
+    )))
+}
+
+/// Check content against a multibase encoded multihash. Base64url (`u`) & base64 (`m`) encodings
+/// are supported.
+pub fn verify_digest_multibase(data: &[u8], digest: &str) -> DidCheqdResult<()> {
+    let unsupported = || {
+        DidCheqdError::ChecksumMismatch(format!("`{digest}` is not a supported multibase digest"))
+    };
+    let multihash = match digest.split_at_checked(1) {
+        Some(("u", encoded)) => BASE64_URL.decode(encoded),
+        Some(("m", encoded)) => {
+            base64::engine::general_purpose::STANDARD_NO_PAD.decode(encoded.trim_end_matches('='))
+        }
+        _ => return Err(unsupported()),
+    }
+    .map_err(|_| unsupported())?;
+    let (algorithm, expected) = match multihash.as_slice() {
+        [code, len, expected @ ..] if *len as usize == expected.len() => (
+            DigestAlgorithm::from_multihash_code(*code).ok_or_else(unsupported)?,
+            expected,
+        ),
+        _ => return Err(unsupported()),
+    };
+    if algorithm.digest(data) != expected {
+        return Err(DidCheqdError::ChecksumMismatch(format!(
+            "expected {digest}, found {}",
+            digest_multibase(data, algorithm)
+        )));
+    }
+    Ok(())
+}
+
+/// A `relatedResource` entry of a credential, referencing a resource by its DID URL
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelatedResource {
+    /// the resource's DID URL, optionally with a `checksum` parameter
+    pub id: String,
+    #[serde(rename = "digestSRI", default, skip_serializing_if = "Option::is_none")]
+    pub digest_sri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest_multibase: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+}
+
+impl RelatedResource {
+    /// An entry for a resource issuers reference, with a `digestSRI` of its content
+    pub fn new(did_url: &str, data: &[u8], algorithm: DigestAlgorithm) -> Self {
+        Self {
+            id: did_url.to_owned(),
+            digest_sri: Some(digest_sri(data, algorithm)),
+            digest_multibase: None,
+            media_type: None,
+        }
+    }
+
+    /// Check content against the entry's digests, all of which must match. An entry without
+    /// digests fails, as it can't protect the content's integrity.
+    pub fn verify(&self, data: &[u8]) -> DidCheqdResult<()> {
+        if self.digest_sri.is_none() && self.digest_multibase.is_none() {
+            return Err(DidCheqdError::ChecksumMismatch(format!(
+                "related resource {} has no digestSRI or digestMultibase",
+                self.id
+            )));
+        }
+        if let Some(sri) = &self.digest_sri {
+            verify_digest_sri(data, sri)?;
+        }
+        if let Some(digest) = &self.digest_multibase {
+            verify_digest_multibase(data, digest)?;
+        }
+        Ok(())
+    }
+}
+
+impl DidCheqdResolver {
+    /// Resolve the resource of a credential's `relatedResource` entry, verifying its content
+    /// against the entry's digests (and the DID URL's `checksum` parameter, if present).
+    ///
+    /// A mismatch is a [DidCheqdError::ChecksumMismatch] error.
+    pub async fn resolve_related_resource(
+        &self,
+        related: &RelatedResource,
+    ) -> DidCheqdResult<ResolvedResource> {
+        let parsed = self.parse(&related.id)?;
+        let resolved = self.resolve_resource(&related.id, parsed).await?;
+        related.verify(&resolved.data)?;
+        Ok(resolved)
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    const DATA: &[u8] = b"alert('Hello, world.');";
+
+    #[test]
+    fn test_digest_sri() {
+        // the example of the Subresource Integrity spec
+        let sri = digest_sri(DATA, DigestAlgorithm::Sha384);
+        assert_eq!(
+            sri,
+            "sha384-H8BRh8j48O9oYatfu5AZzq6A9RINhZO5H16dQZngK7T62em8MUt1FLm52t+eX6xO"
+        );
+        verify_digest_sri(DATA, &sri).unwrap();
+        // only the strongest algorithm counts
+        let weaker = digest_sri(DATA, DigestAlgorithm::Sha256);
+        let stronger = digest_sri(b"other", DigestAlgorithm::Sha512);
+        verify_digest_sri(DATA, &format!("{weaker} {sri}?opt")).unwrap();
+        assert!(verify_digest_sri(DATA, &format!("{weaker} {stronger}")).is_err());
+        assert!(verify_digest_sri(DATA, "md5-abc").is_err());
+    }
+
+    #[test]
+    fn test_related_resource_digests() {
+        let multibase = digest_multibase(DATA, DigestAlgorithm::Sha256);
+        assert!(multibase.starts_with("uEi"));
+        verify_digest_multibase(DATA, &multibase).unwrap();
+        assert!(verify_digest_multibase(b"other", &multibase).is_err());
+
+        let mut related = RelatedResource::new(
+            "did:cheqd:testnet:abc/resources/r1",
+            DATA,
+            DigestAlgorithm::Sha384,
+        );
+        related.digest_multibase = Some(multibase);
+        related.verify(DATA).unwrap();
+        let e = related.verify(b"other").unwrap_err();
+        assert!(matches!(e, DidCheqdError::ChecksumMismatch(_)));
+        let json = serde_json::to_value(&related).unwrap();
+        assert!(json["digestSRI"].as_str().unwrap().starts_with("sha384-"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_related_resource() {
+        use crate::{
+            proto::cheqd::resource::v2::Metadata as ResourceMetadata,
+            resolution::resolver::DidCheqdResolverConfiguration,
+            test_utils::{LedgerFixtures, MockCheqdNode},
+        };
+
+        let metadata = ResourceMetadata {
+            collection_id: "abc".into(),
+            id: "r1".into(),
+            ..Default::default()
+        };
+        let node = MockCheqdNode::start(LedgerFixtures::new().with_resource(DATA.into(), metadata))
+            .await
+            .unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            ..Default::default()
+        });
+        let did_url = "did:cheqd:testnet:abc/resources/r1";
+
+        let related = RelatedResource::new(did_url, DATA, DigestAlgorithm::Sha384);
+        let resolved = resolver.resolve_related_resource(&related).await.unwrap();
+        assert_eq!(resolved.data, DATA);
+        let tampered = RelatedResource::new(did_url, b"other", DigestAlgorithm::Sha384);
+        let e = resolver
+            .resolve_related_resource(&tampered)
+            .await
+            .unwrap_err();
+        assert!(matches!(e, DidCheqdError::ChecksumMismatch(_)), "{e:?}");
+    }
+}
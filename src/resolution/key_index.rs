@@ -0,0 +1,241 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::RwLock,
+};
+
+use futures_util::future::{BoxFuture, join_all};
+use serde_json::{Map, Value};
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    proto::cheqd::did::v2::{DidDoc, VerificationMethod},
+    resolution::resolver::DidCheqdResolver,
+};
+
+/// The JWK members identifying a public key, compared when matching JWKs
+const JWK_PUBLIC_MEMBERS: &[&str] = &["kty", "crv", "x", "y", "n", "e"];
+
+/// A public key to look up DIDs by, see [DidCheqdResolver::find_did_by_key]
+#[derive(Debug, Clone, PartialEq)]
+pub enum PublicKey {
+    /// a multibase (or other string) encoded key, matched against verification material exactly,
+    /// e.g. the `publicKeyMultibase` of an `Ed25519VerificationKey2020`
+    Multibase(String),
+    /// a JWK, matched against JWK verification material by its public members (`kty`, `crv`, `x`,
+    /// `y`, `n` & `e`)
+    Jwk(Map<String, Value>),
+}
+
+impl PublicKey {
+    /// Parse a public key: a JSON object is a JWK, anything else a multibase encoded key
+    pub fn parse(key: &str) -> DidCheqdResult<Self> {
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(DidCheqdError::Other("public key is empty".into()));
+        }
+        if key.starts_with('{') {
+            let jwk = serde_json::from_str(key)?;
+            return Ok(PublicKey::Jwk(jwk));
+        }
+        Ok(PublicKey::Multibase(key.to_owned()))
+    }
+
+    /// Whether the verification method holds this key
+    pub fn matches(&self, method: &VerificationMethod) -> bool {
+        let material = &method.verification_material;
+        match self {
+            PublicKey::Multibase(key) => material == key,
+            PublicKey::Jwk(jwk) => {
+                let Ok(Value::Object(material)) = serde_json::from_str(material) else {
+                    return false;
+                };
+                let mut members = JWK_PUBLIC_MEMBERS
+                    .iter()
+                    .filter(|member| jwk.contains_key(**member))
+                    .peekable();
+                members.peek().is_some()
+                    && members.all(|member| jwk.get(*member) == material.get(*member))
+            }
+        }
+    }
+}
+
+/// An index of which DIDs control which keys, searched by [DidCheqdResolver::find_did_by_key]
+/// as the ledger has no such index. Configured with
+/// [crate::resolution::resolver::DidCheqdResolverConfiguration::key_indexes], e.g. backed by an
+/// external indexing service.
+pub trait KeyIndex: Send + Sync {
+    /// The DIDs which may control the key. Candidates are checked against their resolved DID
+    /// documents, so the index may be stale or return false positives.
+    fn candidates<'a>(&'a self, key: &'a PublicKey) -> BoxFuture<'a, DidCheqdResult<Vec<String>>>;
+}
+
+/// A [KeyIndex] of DID documents held in memory, e.g. those of the DIDs an application deals
+/// with
+#[derive(Debug, Default)]
+pub struct InMemoryKeyIndex {
+    did_docs: RwLock<HashMap<String, DidDoc>>,
+}
+
+impl InMemoryKeyIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index the keys of a DID document, replacing any earlier version of it
+    pub fn insert(&self, did_doc: DidDoc) {
+        let mut did_docs = self.did_docs.write().unwrap_or_else(|e| e.into_inner());
+        did_docs.insert(did_doc.id.clone(), did_doc);
+    }
+}
+
+impl KeyIndex for InMemoryKeyIndex {
+    fn candidates<'a>(&'a self, key: &'a PublicKey) -> BoxFuture<'a, DidCheqdResult<Vec<String>>> {
+        let did_docs = self.did_docs.read().unwrap_or_else(|e| e.into_inner());
+        let candidates = did_docs
+            .values()
+            .filter(|doc| doc.verification_method.iter().any(|m| key.matches(m)))
+            .map(|doc| doc.id.clone())
+            .collect();
+        Box::pin(std::future::ready(Ok(candidates)))
+    }
+}
+
+/// A verification method holding a key, found by [DidCheqdResolver::find_did_by_key]
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyMatch {
+    /// the DID whose current DID document holds the key
+    pub did: String,
+    pub verification_method: VerificationMethod,
+}
+
+impl DidCheqdResolver {
+    /// Find the DIDs controlling a public key (a JWK or multibase encoded key, see
+    /// [PublicKey::parse]), e.g. for wallets presented with a bare key.
+    ///
+    /// Candidate DIDs are taken from the configured [KeyIndex]es, then resolved, returning the
+    /// verification methods of their current, non-deactivated DID documents which hold the key.
+    /// Candidates which no longer exist are skipped. Fails with
+    /// [DidCheqdError::BadConfiguration] if no key index is configured.
+    pub async fn find_did_by_key(&self, public_key: &str) -> DidCheqdResult<Vec<KeyMatch>> {
+        let key = PublicKey::parse(public_key)?;
+        let indexes = self.key_indexes();
+        if indexes.is_empty() {
+            return Err(DidCheqdError::BadConfiguration(
+                "no key indexes are configured".to_string(),
+            ));
+        }
+
+        let mut candidates = Vec::new();
+        for index in indexes {
+            candidates.extend(index.candidates(&key).await?);
+        }
+        let mut seen = HashSet::new();
+        candidates.retain(|did| seen.insert(did.clone()));
+
+        let resolutions = candidates.iter().map(|did| async move {
+            let parsed = self.parse(did)?;
+            match self.resolve_did_doc(parsed).await {
+                Err(e) if e.status_code() == Some(tonic::Code::NotFound) => Ok(None),
+                resolved => resolved.map(Some),
+            }
+        });
+        let mut matches = Vec::new();
+        for (did, resolved) in candidates.iter().zip(join_all(resolutions).await) {
+            let Some(resolved) = resolved? else {
+                continue;
+            };
+            if resolved.metadata.is_some_and(|m| m.deactivated) {
+                continue;
+            }
+            matches.extend(
+                resolved
+                    .did_doc
+                    .verification_method
+                    .into_iter()
+                    .filter(|method| key.matches(method))
+                    .map(|verification_method| KeyMatch {
+                        did: did.clone(),
+                        verification_method,
+                    }),
+            );
+        }
+        Ok(matches)
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        proto::cheqd::did::v2::Metadata as DidDocMetadata,
+        resolution::resolver::DidCheqdResolverConfiguration,
+        test_utils::{LedgerFixtures, MockCheqdNode},
+    };
+
+    const DID: &str = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+
+    fn did_doc(id: &str, material: &str) -> DidDoc {
+        DidDoc {
+            id: id.into(),
+            verification_method: vec![VerificationMethod {
+                id: format!("{id}#key-1"),
+                verification_method_type: "JsonWebKey2020".into(),
+                controller: id.into(),
+                verification_material: material.into(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_public_key_matches() {
+        let jwk = r#"{"kty":"OKP","crv":"Ed25519","x":"abc"}"#;
+        let method = &did_doc(DID, r#"{"crv":"Ed25519","kty":"OKP","x":"abc","kid":"1"}"#)
+            .verification_method[0];
+        assert!(PublicKey::parse(jwk).unwrap().matches(method));
+        let other = r#"{"kty":"OKP","crv":"Ed25519","x":"abd"}"#;
+        assert!(!PublicKey::parse(other).unwrap().matches(method));
+        assert!(!PublicKey::parse("z6Mk").unwrap().matches(method));
+        assert!(!PublicKey::parse("{}").unwrap().matches(method));
+        assert!(PublicKey::parse(" ").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_find_did_by_key() {
+        // the DID's key has been rotated since it was indexed
+        let stale = "did:cheqd:testnet:b1ad2b1a-5b38-4b7e-bd53-9e6f4c1c0f6f";
+        let fixtures = LedgerFixtures::new()
+            .with_did_doc(did_doc(DID, "z6MkOld"), DidDocMetadata::default())
+            .with_did_doc(did_doc(DID, "z6MkNew"), DidDocMetadata::default())
+            .with_did_doc(did_doc(stale, "z6MkRotated"), DidDocMetadata::default());
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let index = Arc::new(InMemoryKeyIndex::new());
+        index.insert(did_doc(DID, "z6MkNew"));
+        index.insert(did_doc(stale, "z6MkNew"));
+        index.insert(did_doc("did:cheqd:testnet:missing", "z6MkNew"));
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            key_indexes: vec![index],
+            ..Default::default()
+        });
+
+        let matches = resolver.find_did_by_key("z6MkNew").await.unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].did, DID);
+        assert_eq!(matches[0].verification_method.id, format!("{DID}#key-1"));
+        assert!(
+            resolver
+                .find_did_by_key("z6MkOld")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let unindexed = DidCheqdResolver::new(Default::default());
+        let e = unindexed.find_did_by_key("z6MkNew").await.unwrap_err();
+        assert!(matches!(e, DidCheqdError::BadConfiguration(_)), "{e:?}");
+    }
+}
@@ -0,0 +1,112 @@
+use futures_util::future::try_join;
+
+use crate::{
+    error::DidCheqdResult,
+    proto::cheqd::did::v2::{DidDoc, VerificationMethod},
+    resolution::resolver::DidCheqdResolver,
+};
+
+/// How the verification methods of a DID changed between two versions of its DID document, see
+/// [DidCheqdResolver::compare_keys]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeyChanges {
+    /// verification methods only in the later version
+    pub added: Vec<VerificationMethod>,
+    /// verification methods only in the earlier version
+    pub removed: Vec<VerificationMethod>,
+    /// verification methods in both versions whose key material or type changed, as
+    /// `(earlier, later)` pairs
+    pub rekeyed: Vec<(VerificationMethod, VerificationMethod)>,
+}
+
+impl KeyChanges {
+    /// Compare the verification methods of two DID documents, matching them by id
+    pub fn between(from: &DidDoc, to: &DidDoc) -> Self {
+        let find = |doc: &DidDoc, id: &str| {
+            doc.verification_method
+                .iter()
+                .find(|method| method.id == id)
+                .cloned()
+        };
+        let mut changes = KeyChanges::default();
+        for method in &from.verification_method {
+            match find(to, &method.id) {
+                None => changes.removed.push(method.clone()),
+                Some(later)
+                    if later.verification_material != method.verification_material
+                        || later.verification_method_type != method.verification_method_type =>
+                {
+                    changes.rekeyed.push((method.clone(), later))
+                }
+                Some(_) => {}
+            }
+        }
+        changes.added = to
+            .verification_method
+            .iter()
+            .filter(|method| find(from, &method.id).is_none())
+            .cloned()
+            .collect();
+        changes
+    }
+
+    /// Whether no verification method was added, removed or re-keyed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.rekeyed.is_empty()
+    }
+}
+
+impl DidCheqdResolver {
+    /// Report which verification methods of a DID were added, removed or re-keyed between two
+    /// versions of its DID document, e.g. so wallets can warn when an issuer rotates its keys.
+    ///
+    /// Versions are identified by their `versionId`; changes to a verification method's
+    /// controller alone are not reported.
+    pub async fn compare_keys(
+        &self,
+        did: &str,
+        from_version: &str,
+        to_version: &str,
+    ) -> DidCheqdResult<KeyChanges> {
+        let resolve = |version: &str| {
+            let parsed = self.parse(&format!("{did}?versionId={version}"));
+            async move { self.resolve_did_doc(parsed?).await }
+        };
+        let (from, to) = try_join(resolve(from_version), resolve(to_version)).await?;
+        Ok(KeyChanges::between(&from.did_doc, &to.did_doc))
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    fn method(id: &str, material: &str) -> VerificationMethod {
+        VerificationMethod {
+            id: id.into(),
+            verification_method_type: "Ed25519VerificationKey2020".into(),
+            controller: "did:cheqd:testnet:abc".into(),
+            verification_material: material.into(),
+        }
+    }
+
+    #[test]
+    fn test_key_changes_between_versions() {
+        let from = DidDoc {
+            verification_method: vec![method("#key-1", "z1"), method("#key-2", "z2")],
+            ..Default::default()
+        };
+        let to = DidDoc {
+            verification_method: vec![method("#key-1", "z1b"), method("#key-3", "z3")],
+            ..Default::default()
+        };
+        let changes = KeyChanges::between(&from, &to);
+        assert_eq!(changes.added, [method("#key-3", "z3")]);
+        assert_eq!(changes.removed, [method("#key-2", "z2")]);
+        assert_eq!(
+            changes.rekeyed,
+            [(method("#key-1", "z1"), method("#key-1", "z1b"))]
+        );
+        assert!(KeyChanges::between(&to, &to).is_empty());
+    }
+}
@@ -0,0 +1,164 @@
+//! Rolling latencies of the nodes a resolver queries, for operators' dashboards (see
+//! [ResolverStats::endpoint_latencies](crate::resolution::stats::ResolverStats::endpoint_latencies))
+//! & for sending queries to the fastest node (see
+//! [EndpointSelection::Fastest](crate::resolution::load_balancing::EndpointSelection::Fastest)).
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// The weight (in percent) of each new sample in an endpoint's moving average latency
+const EWMA_WEIGHT: u32 = 30;
+/// The latency recorded for a query failing with a transport error (e.g. a refused
+/// connection), which is often quicker than any answer, so mustn't make the node look fast
+pub const FAILURE_LATENCY: Duration = Duration::from_secs(5);
+/// How long a node can go without being queried before the next query is sent to it regardless
+/// of its latency, so nodes which recovered are noticed
+pub const PROBE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The rolling latency of queries to a node endpoint
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EndpointLatency {
+    /// the exponentially weighted moving average of the latencies
+    pub ewma: Duration,
+    /// the latency of the latest query
+    pub last: Duration,
+    /// the number of queries answered (successfully or not) by the node
+    pub samples: u64,
+    /// the number of queries which failed with a transport error, counting as
+    /// [FAILURE_LATENCY]
+    pub failures: u64,
+}
+
+impl EndpointLatency {
+    fn record(&mut self, latency: Duration, failed: bool) {
+        let latency = match failed {
+            true => latency.max(FAILURE_LATENCY),
+            false => latency,
+        };
+        self.ewma = match self.samples + self.failures {
+            0 => latency,
+            _ => (self.ewma * (100 - EWMA_WEIGHT) + latency * EWMA_WEIGHT) / 100,
+        };
+        self.last = latency;
+        match failed {
+            true => self.failures += 1,
+            false => self.samples += 1,
+        }
+    }
+}
+
+/// The [EndpointLatency] of each endpoint queried, by URL, with when it was last queried or
+/// chosen to be probed
+#[derive(Default)]
+pub(crate) struct LatencyTracker {
+    endpoints: Mutex<HashMap<String, (EndpointLatency, Instant)>>,
+}
+
+impl LatencyTracker {
+    /// Record the latency of a query to the endpoint, which `failed` with a transport error or
+    /// was answered
+    pub(crate) fn record(&self, url: &str, latency: Duration, failed: bool) {
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        let (endpoint, recorded) = endpoints
+            .entry(url.to_owned())
+            .or_insert_with(|| (EndpointLatency::default(), Instant::now()));
+        endpoint.record(latency, failed);
+        *recorded = Instant::now();
+    }
+
+    /// The latencies of the endpoints queried so far
+    pub(crate) fn snapshot(&self) -> HashMap<String, EndpointLatency> {
+        let endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        endpoints
+            .iter()
+            // endpoints being probed for the first time have no latency yet
+            .filter(|(_, (latency, _))| latency.samples + latency.failures > 0)
+            .map(|(url, (latency, _))| (url.clone(), *latency))
+            .collect()
+    }
+
+    /// The index of the fastest of the weighted endpoints, skipping those of zero weight: the
+    /// first not queried within [PROBE_INTERVAL] of `now`, or else the one with the lowest
+    /// moving average latency.
+    ///
+    /// An endpoint chosen to be probed counts as queried from then, so only one query at a time
+    /// probes it, rather than every query until the probe is answered; endpoints being probed
+    /// for the first time aren't chosen until then.
+    pub(crate) fn fastest(&self, weighted: &[(&str, u32)], now: Instant) -> Option<usize> {
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        let mut fastest: Option<(usize, Duration)> = None;
+        for (i, (url, weight)) in weighted.iter().enumerate() {
+            if *weight == 0 {
+                continue;
+            }
+            let ewma = match endpoints.get_mut(*url) {
+                Some((latency, recorded)) if now.duration_since(*recorded) < PROBE_INTERVAL => {
+                    match latency.samples + latency.failures {
+                        0 => continue,
+                        _ => latency.ewma,
+                    }
+                }
+                Some((_, recorded)) => {
+                    *recorded = now;
+                    return Some(i);
+                }
+                None => {
+                    endpoints.insert(url.to_string(), (EndpointLatency::default(), now));
+                    return Some(i);
+                }
+            };
+            match fastest {
+                Some((_, lowest)) if lowest <= ewma => {}
+                _ => fastest = Some((i, ewma)),
+            }
+        }
+        fastest.map(|(i, _)| i)
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn test_endpoint_latency_moving_average() {
+        let mut latency = EndpointLatency::default();
+        latency.record(100 * MS, false);
+        assert_eq!(latency.ewma, 100 * MS);
+        latency.record(200 * MS, false);
+        assert_eq!(latency.ewma, 130 * MS);
+        assert_eq!(latency.last, 200 * MS);
+        // a refused connection doesn't make the node look fast
+        latency.record(MS, true);
+        assert!(latency.ewma > 130 * MS);
+        assert_eq!((latency.samples, latency.failures), (2, 1));
+    }
+
+    #[test]
+    fn test_fastest_endpoint() {
+        let tracker = LatencyTracker::default();
+        let weighted = [("a", 1), ("b", 1), ("c", 0)];
+        // endpoints without samples are probed first, one query at a time
+        assert_eq!(tracker.fastest(&weighted, Instant::now()), Some(0));
+        assert_eq!(tracker.fastest(&weighted, Instant::now()), Some(1));
+        assert_eq!(tracker.fastest(&weighted, Instant::now()), None);
+        assert!(tracker.snapshot().is_empty());
+        tracker.record("a", 80 * MS, false);
+        assert_eq!(tracker.fastest(&weighted, Instant::now()), Some(0));
+        tracker.record("b", 20 * MS, false);
+        tracker.record("c", MS, false);
+        // zero weight endpoints only serve failed over queries
+        assert_eq!(tracker.fastest(&weighted, Instant::now()), Some(1));
+        // endpoints not queried for a while are probed again
+        let later = Instant::now() + PROBE_INTERVAL;
+        assert_eq!(tracker.fastest(&weighted, later), Some(0));
+        assert_eq!(tracker.fastest(&weighted, later), Some(1));
+        assert_eq!(tracker.fastest(&weighted, later), Some(1));
+        assert!(tracker.fastest(&[("a", 0)], Instant::now()).is_none());
+    }
+}
@@ -0,0 +1,121 @@
+//! Weighted load balancing of a network's queries across gRPC nodes, e.g. sending 80% of queries
+//! to a primary node provider & 20% to a backup, to spread load across providers with different
+//! SLAs.
+//!
+//! Queries are spread by smooth weighted round-robin, so each node's share of any run of queries
+//! is close to its weight, without bursts to one node. The balanced nodes also fail over to each
+//! other: a query whose node fails with a transport error is retried by the other balanced nodes,
+//! before the network's
+//! [failover_grpc_urls](crate::resolution::resolver::NetworkConfiguration::failover_grpc_urls).
+
+use std::{collections::HashMap, sync::Mutex};
+
+/// The weights of a network's gRPC nodes, see
+/// [crate::resolution::resolver::NetworkConfiguration::load_balancing]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadBalancing {
+    /// the weight of the network's `grpc_url`. `0` only sends it queries failed over from the
+    /// other nodes.
+    pub primary_weight: u32,
+    /// the further gRPC nodes sharing the network's queries
+    pub endpoints: Vec<WeightedEndpoint>,
+    /// how the node each query is sent to is selected
+    pub selection: EndpointSelection,
+}
+
+/// How the node of each of a network's queries is selected from its balanced nodes, see
+/// [LoadBalancing::selection]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EndpointSelection {
+    /// spread queries across the nodes by weight
+    #[default]
+    Weighted,
+    /// send queries to the node with the lowest moving average latency (see
+    /// [crate::resolution::latency]), among those with a non-zero weight, so one degraded node
+    /// doesn't hold up resolutions. Nodes not queried within
+    /// [PROBE_INTERVAL](crate::resolution::latency::PROBE_INTERVAL) are sent the next query, so
+    /// nodes which recovered are noticed.
+    Fastest,
+}
+
+/// A gRPC node sharing a network's queries, see [LoadBalancing]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WeightedEndpoint {
+    pub grpc_url: String,
+    /// the node's share of queries, relative to the weights of the network's other nodes
+    pub weight: u32,
+}
+
+impl LoadBalancing {
+    /// The balanced nodes with their weights: the primary `grpc_url` first, then the
+    /// [LoadBalancing::endpoints] in order
+    pub(crate) fn weighted_urls<'a>(&'a self, grpc_url: &'a str) -> Vec<(&'a str, u32)> {
+        std::iter::once((grpc_url, self.primary_weight))
+            .chain(
+                self.endpoints
+                    .iter()
+                    .map(|endpoint| (endpoint.grpc_url.as_str(), endpoint.weight)),
+            )
+            .collect()
+    }
+}
+
+/// The state of each network's smooth weighted round-robin: the current weight of each node
+#[derive(Default)]
+pub(crate) struct Balancer {
+    current: Mutex<HashMap<String, Vec<i64>>>,
+}
+
+impl Balancer {
+    /// The index of the node (of those with the given weights) the next query to the network is
+    /// sent to
+    pub(crate) fn next(&self, namespace: &str, weights: &[u32]) -> usize {
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        let current = current.entry(namespace.to_owned()).or_default();
+        // the network's nodes were reconfigured
+        if current.len() != weights.len() {
+            *current = vec![0; weights.len()];
+        }
+        let total: i64 = weights.iter().map(|&weight| i64::from(weight)).sum();
+        for (current, &weight) in current.iter_mut().zip(weights) {
+            *current += i64::from(weight);
+        }
+        // the first node with the highest current weight
+        let mut next = 0;
+        for (i, weight) in current.iter().enumerate() {
+            if *weight > current[next] {
+                next = i;
+            }
+        }
+        current[next] -= total;
+        next
+    }
+
+    /// Forget the round-robin state, e.g. when the networks are reloaded
+    pub(crate) fn reset(&self) {
+        self.current
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_smooth_weighted_round_robin() {
+        let balancer = Balancer::default();
+        let picks: Vec<_> = (0..10).map(|_| balancer.next("mainnet", &[4, 1])).collect();
+        // 80/20, interleaved rather than in bursts
+        assert_eq!(picks, [0, 0, 1, 0, 0, 0, 0, 1, 0, 0]);
+
+        let picks: Vec<_> = (0..6)
+            .map(|_| balancer.next("testnet", &[0, 1, 2]))
+            .collect();
+        assert_eq!(picks, [2, 1, 2, 2, 1, 2]);
+        // the weights of other networks are independent
+        assert_eq!(balancer.next("mainnet", &[4, 1]), 0);
+    }
+}
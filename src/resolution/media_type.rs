@@ -0,0 +1,252 @@
+use serde::de::IgnoredAny;
+#[cfg(feature = "cbor")]
+use serde_json::Value;
+
+const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+const PDF_MAGIC: &[u8] = b"%PDF-";
+
+/// Infer a best-effort media type from the content of a resource, for resources created without
+/// a media type.
+///
+/// Recognizes JSON, PNG, PDF & UTF-8 text, returning `None` for anything else.
+pub fn sniff_media_type(content: &[u8]) -> Option<&'static str> {
+    if content.starts_with(PNG_MAGIC) {
+        return Some("image/png");
+    }
+    if content.starts_with(PDF_MAGIC) {
+        return Some("application/pdf");
+    }
+    let text = std::str::from_utf8(content).ok()?;
+    let trimmed = text.trim_start();
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<IgnoredAny>(text).is_ok()
+    {
+        return Some("application/json");
+    }
+    (!text.is_empty()).then_some("text/plain")
+}
+
+/// whether a media type is JSON, i.e. `application/json` or a structured `+json` type
+pub(crate) fn is_json_media_type(media_type: &str) -> bool {
+    let essence = media_type.split(';').next().unwrap_or_default().trim();
+    essence.eq_ignore_ascii_case("application/json")
+        || essence.to_ascii_lowercase().ends_with("+json")
+}
+
+/// whether a media type is within a media range, i.e. equal to it, or matched by a `type/*` or
+/// `*/*` wildcard. Parameters (e.g. `; charset=utf-8`) are ignored.
+pub(crate) fn media_type_matches(range: &str, media_type: &str) -> bool {
+    let essence = |s: &str| {
+        s.split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase()
+    };
+    let (range, media_type) = (essence(range), essence(media_type));
+    match range.strip_suffix("/*") {
+        Some("*") => true,
+        Some(top_level) => media_type.split('/').next() == Some(top_level),
+        None => range == media_type,
+    }
+}
+
+/// whether a media type is CBOR, i.e. `application/cbor` or a structured `+cbor` type
+#[cfg(feature = "cbor")]
+fn is_cbor_media_type(media_type: &str) -> bool {
+    let essence = media_type.split(';').next().unwrap_or_default().trim();
+    essence.eq_ignore_ascii_case("application/cbor")
+        || essence.to_ascii_lowercase().ends_with("+cbor")
+}
+
+/// The media ranges of an `Accept` value, e.g. `application/cbor, application/json;q=0.5`, most
+/// preferred (by `q` weight, then order) first. Ranges with a weight of 0 are left out.
+pub(crate) fn accepted_media_ranges(accept: &str) -> Vec<&str> {
+    let mut ranges: Vec<_> = accept
+        .split(',')
+        .filter_map(|range| {
+            let mut params = range.split(';');
+            let media_range = params.next()?.trim();
+            let weight = params
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            (!media_range.is_empty() && weight > 0.0).then_some((media_range, weight))
+        })
+        .collect();
+    ranges.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    ranges.into_iter().map(|(range, _)| range).collect()
+}
+
+/// The representation of a resource's content for an `Accept` value (see
+/// [accepted_media_ranges]): its media type & content, for the most preferred media range either
+/// matching the stored media type or naming a media type the content converts to. Content only
+/// converts between JSON & CBOR, with the `cbor` feature.
+///
+/// `None` if no accepted representation is possible.
+pub(crate) fn negotiate(
+    accept: &str,
+    media_type: &str,
+    content: &[u8],
+) -> Option<(String, Vec<u8>)> {
+    accepted_media_ranges(accept).into_iter().find_map(|range| {
+        match media_type_matches(range, media_type) {
+            true => Some((media_type.to_owned(), content.to_vec())),
+            false => convert(content, media_type, range).map(|c| (range.to_owned(), c)),
+        }
+    })
+}
+
+/// Convert content to a media type, if it is a JSON or CBOR type & the content is of the other
+#[cfg(feature = "cbor")]
+fn convert(content: &[u8], from: &str, to: &str) -> Option<Vec<u8>> {
+    if to.contains('*') {
+        return None;
+    }
+    if is_json_media_type(from) && is_cbor_media_type(to) {
+        let json: Value = serde_json::from_slice(content).ok()?;
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&json_to_cbor(json), &mut cbor).ok()?;
+        return Some(cbor);
+    }
+    if is_cbor_media_type(from) && is_json_media_type(to) {
+        let cbor: ciborium::Value = ciborium::from_reader(content).ok()?;
+        return serde_json::to_vec(&cbor_to_json(cbor)?).ok();
+    }
+    None
+}
+
+#[cfg(not(feature = "cbor"))]
+fn convert(_content: &[u8], _from: &str, _to: &str) -> Option<Vec<u8>> {
+    None
+}
+
+// JSON numbers are converted by value, rather than through serde, as `arbitrary_precision`
+// serializes them as maps
+#[cfg(feature = "cbor")]
+fn json_to_cbor(json: Value) -> ciborium::Value {
+    match json {
+        Value::Null => ciborium::Value::Null,
+        Value::Bool(b) => ciborium::Value::Bool(b),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => ciborium::Value::Integer(i.into()),
+            (_, Some(u)) => ciborium::Value::Integer(u.into()),
+            _ => ciborium::Value::Float(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        Value::String(s) => ciborium::Value::Text(s),
+        Value::Array(values) => {
+            ciborium::Value::Array(values.into_iter().map(json_to_cbor).collect())
+        }
+        Value::Object(object) => ciborium::Value::Map(
+            object
+                .into_iter()
+                .map(|(key, value)| (ciborium::Value::Text(key), json_to_cbor(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// The JSON of a CBOR value, if it has one: byte strings, tags & non-text map keys don't
+#[cfg(feature = "cbor")]
+fn cbor_to_json(cbor: ciborium::Value) -> Option<Value> {
+    Some(match cbor {
+        ciborium::Value::Null => Value::Null,
+        ciborium::Value::Bool(b) => Value::Bool(b),
+        ciborium::Value::Integer(i) => match i64::try_from(i) {
+            Ok(i) => i.into(),
+            Err(_) => u64::try_from(i).ok()?.into(),
+        },
+        ciborium::Value::Float(f) => serde_json::Number::from_f64(f)?.into(),
+        ciborium::Value::Text(s) => Value::String(s),
+        ciborium::Value::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .map(cbor_to_json)
+                .collect::<Option<_>>()?,
+        ),
+        ciborium::Value::Map(entries) => Value::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| Some((key.into_text().ok()?, cbor_to_json(value)?)))
+                .collect::<Option<_>>()?,
+        ),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_media_type() {
+        assert_eq!(sniff_media_type(b" {\"a\": 1}"), Some("application/json"));
+        assert_eq!(sniff_media_type(b"[1, 2"), Some("text/plain"));
+        assert_eq!(
+            sniff_media_type(b"\x89PNG\r\n\x1a\n\0\0"),
+            Some("image/png")
+        );
+        assert_eq!(sniff_media_type(b"%PDF-1.7\n"), Some("application/pdf"));
+        assert_eq!(sniff_media_type("hello 👋".as_bytes()), Some("text/plain"));
+        assert_eq!(sniff_media_type(&[0xff, 0xfe, 0x00]), None);
+        assert_eq!(sniff_media_type(b""), None);
+    }
+
+    #[test]
+    fn test_is_json_media_type() {
+        assert!(is_json_media_type("application/json"));
+        assert!(is_json_media_type("application/json; charset=utf-8"));
+        assert!(is_json_media_type("application/did+json"));
+        assert!(!is_json_media_type("text/plain"));
+        assert!(!is_json_media_type(""));
+    }
+
+    #[test]
+    fn test_media_type_matches() {
+        assert!(media_type_matches(
+            "application/json",
+            "Application/JSON; charset=utf-8"
+        ));
+        assert!(media_type_matches("image/*", "image/png"));
+        assert!(media_type_matches("*/*", "text/plain"));
+        assert!(!media_type_matches("image/*", "application/json"));
+        assert!(!media_type_matches(
+            "application/json",
+            "application/ld+json"
+        ));
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(
+            accepted_media_ranges("text/plain;q=0.5, application/json , image/*;q=0"),
+            ["application/json", "text/plain"]
+        );
+        let json = br#"{"a": [1, -2, 1.5, "b", null]}"#;
+        assert_eq!(
+            negotiate("image/png, */*;q=0.1", "application/json", json),
+            Some(("application/json".to_owned(), json.to_vec()))
+        );
+        assert_eq!(negotiate("image/*", "application/json", json), None);
+        assert_eq!(negotiate("", "application/json", json), None);
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_converts_json_and_cbor() {
+        let json = br#"{"a": [1, -2, 1.5, "b", null]}"#;
+        let (media_type, cbor) = negotiate("application/cbor", "application/json", json).unwrap();
+        assert_eq!(media_type, "application/cbor");
+        let (media_type, converted) = negotiate("application/json", &media_type, &cbor).unwrap();
+        assert_eq!(media_type, "application/json");
+        assert_eq!(
+            serde_json::from_slice::<Value>(&converted).unwrap(),
+            serde_json::from_slice::<Value>(json).unwrap()
+        );
+        // content which isn't valid in its media type doesn't convert
+        assert_eq!(
+            negotiate("application/cbor", "application/json", b"{"),
+            None
+        );
+    }
+}
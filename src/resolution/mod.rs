@@ -1,3 +1,44 @@
+pub mod cache;
+pub mod capture;
+pub mod clock;
+pub mod collection;
+pub mod concurrency;
+#[cfg(feature = "config-watch")]
+pub mod config_watch;
+#[cfg(test)]
+mod conformance;
+pub mod connector;
+pub mod context_loader;
+pub mod controllers;
+pub mod deadline;
+pub mod dereference;
+pub mod did_key;
+pub mod didcomm;
+#[cfg(feature = "disk-cache")]
+pub mod disk_cache;
+pub mod document_transform;
+pub mod events;
+#[cfg(feature = "axum")]
+pub mod extract;
+pub mod fan_out;
+pub mod integrity;
+pub mod key_index;
+pub mod keys;
+pub mod latency;
+pub mod load_balancing;
+pub mod media_type;
+pub mod options;
 pub mod parser;
+pub mod recording;
 pub mod resolver;
+pub mod response_validation;
+#[cfg(feature = "rest")]
+mod rest;
+pub mod result;
+pub mod service;
+pub mod stats;
 pub mod transformer;
+pub mod typed_resource;
+#[cfg(feature = "validate")]
+pub mod validation;
+pub mod watch;
@@ -0,0 +1,371 @@
+//! Per-call resolution options, see [ResolutionOptions] &
+//! [DidCheqdResolver::resolve_with_options].
+
+use std::future::Future;
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    resolution::{
+        media_type::negotiate,
+        parser::{DidCheqdParser, DuplicateParams, ParserOptions},
+        resolver::DidCheqdResolver,
+        service::Resolved,
+    },
+};
+
+tokio::task_local! {
+    static CACHE_POLICY: CachePolicy;
+    static VERSION_POLICY: VersionPolicy;
+}
+
+/// How a resolution uses the resolver's cache (see
+/// [crate::resolution::resolver::DidCheqdResolverConfiguration::cache])
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// serve cached results, caching the results of queries
+    #[default]
+    Use,
+    /// query the ledger rather than serving cached results, but cache the results
+    Refresh,
+    /// neither serve nor cache results
+    Bypass,
+}
+
+/// How a DID document resolution weighs the DID documents in the resolver's cache against the
+/// ledger, for lookups of a version (by `versionId`) & of the latest document.
+///
+/// Cached versions never change, whereas the latest document may have been updated on the
+/// ledger since it was cached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VersionPolicy {
+    /// serve the latest document from the cache, even while it is being revalidated or the
+    /// ledger is unreachable (per the cache's configuration), and a version from the cache entry
+    /// of the version or, failing that, the cached latest document if it is that version
+    #[default]
+    PreferCache,
+    /// serve each lookup only from its own, unexpired cache entry, otherwise query the ledger
+    Exact,
+    /// always query the ledger for the latest document, so it is the freshest, while versions are
+    /// still served from the cache
+    LatestOnly,
+}
+
+/// Options for a single resolution, see [DidCheqdResolver::resolve_with_options].
+///
+/// Options can be set directly, or with [ResolutionOptions::builder]:
+///
+/// ```
+/// use did_resolver_cheqd::resolution::options::{CachePolicy, ResolutionOptions};
+///
+/// let options = ResolutionOptions::builder()
+///     .accept("application/json")
+///     .cache(CachePolicy::Refresh)
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolutionOptions {
+    /// the media ranges accepted for resources, as an `Accept` header value, e.g. `image/*` or
+    /// `application/cbor, application/json;q=0.5`. A resource of a type which isn't accepted is
+    /// converted to the most preferred accepted type where that's trivial (JSON to CBOR & back,
+    /// with the `cbor` feature), its metadata's `media_type` then being the converted type (while
+    /// its `checksum` remains that of the stored content); otherwise it fails with
+    /// [DidCheqdError::UnexpectedMediaType], i.e. `representationNotSupported`.
+    pub accept: Option<String>,
+    /// resolve this version of the DID document. A DID URL identifying another version is
+    /// rejected as invalid.
+    pub version_id: Option<String>,
+    /// how the resolver's cache is used
+    pub cache: CachePolicy,
+    /// how cached versions & latest DID documents are served
+    pub version_policy: VersionPolicy,
+    /// resolve against the network with this namespace, rather than the DID's namespace
+    pub network: Option<String>,
+    /// parse the DID URL strictly: requiring a namespace & rejecting duplicated query parameters,
+    /// whatever the resolver's [ParserOptions]
+    pub strict: bool,
+}
+
+impl ResolutionOptions {
+    /// A builder for options, starting from the defaults
+    pub fn builder() -> ResolutionOptionsBuilder {
+        ResolutionOptionsBuilder::default()
+    }
+}
+
+/// A builder for [ResolutionOptions], see [ResolutionOptions::builder]
+#[derive(Debug, Clone, Default)]
+pub struct ResolutionOptionsBuilder {
+    options: ResolutionOptions,
+}
+
+impl ResolutionOptionsBuilder {
+    /// see [ResolutionOptions::accept]
+    pub fn accept(mut self, media_range: impl Into<String>) -> Self {
+        self.options.accept = Some(media_range.into());
+        self
+    }
+
+    /// see [ResolutionOptions::version_id]
+    pub fn version_id(mut self, version_id: impl Into<String>) -> Self {
+        self.options.version_id = Some(version_id.into());
+        self
+    }
+
+    /// see [ResolutionOptions::cache]
+    pub fn cache(mut self, cache: CachePolicy) -> Self {
+        self.options.cache = cache;
+        self
+    }
+
+    /// see [ResolutionOptions::version_policy]
+    pub fn version_policy(mut self, version_policy: VersionPolicy) -> Self {
+        self.options.version_policy = version_policy;
+        self
+    }
+
+    /// see [ResolutionOptions::network]
+    pub fn network(mut self, namespace: impl Into<String>) -> Self {
+        self.options.network = Some(namespace.into());
+        self
+    }
+
+    /// see [ResolutionOptions::strict]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.options.strict = strict;
+        self
+    }
+
+    pub fn build(self) -> ResolutionOptions {
+        self.options
+    }
+}
+
+/// The cache policy of the current call
+pub(crate) fn cache_policy() -> CachePolicy {
+    CACHE_POLICY.try_with(|policy| *policy).unwrap_or_default()
+}
+
+/// Run a resolution with the given cache policy
+async fn with_cache_policy<T>(policy: CachePolicy, resolution: impl Future<Output = T>) -> T {
+    CACHE_POLICY.scope(policy, resolution).await
+}
+
+/// The version policy of the current call
+pub(crate) fn version_policy() -> VersionPolicy {
+    VERSION_POLICY
+        .try_with(|policy| *policy)
+        .unwrap_or_default()
+}
+
+impl DidCheqdResolver {
+    /// Resolve a DID URL to whatever it identifies, as [DidCheqdResolver::resolve_did_url], with
+    /// the given options.
+    pub async fn resolve_with_options(
+        &self,
+        did_url: &str,
+        options: &ResolutionOptions,
+    ) -> DidCheqdResult<Resolved> {
+        let mut parsed = match options.strict {
+            true => DidCheqdParser::parse_with_options(
+                did_url,
+                ParserOptions {
+                    require_namespace: true,
+                    duplicate_params: DuplicateParams::Reject,
+                },
+            )?,
+            false => self.parse(did_url)?,
+        };
+        if let Some(version_id) = &options.version_id {
+            if parsed.version.as_ref().is_some_and(|v| v != version_id) {
+                return Err(DidCheqdError::InvalidDidUrl(format!(
+                    "`{did_url}` contradicts the requested version `{version_id}`"
+                )));
+            }
+            parsed.version = Some(version_id.clone());
+        }
+        if let Some(namespace) = &options.network {
+            parsed = parsed.in_namespace(namespace);
+        }
+
+        let resolution = async {
+            if !parsed.is_resource() {
+                return self.resolve_did_doc(parsed).await.map(Resolved::Document);
+            }
+            let mut resolved = self.resolve_resource(did_url, parsed).await?;
+            if let Some(accept) = &options.accept {
+                let media_type = self
+                    .effective_media_type(&resolved.metadata, &resolved.data)
+                    .map(|(media_type, _)| media_type)
+                    .unwrap_or_default();
+                let Some((media_type, data)) = negotiate(accept, &media_type, &resolved.data)
+                else {
+                    return Err(DidCheqdError::UnexpectedMediaType(format!(
+                        "expected `{accept}`, found `{media_type}`"
+                    )));
+                };
+                resolved.metadata.media_type = media_type;
+                resolved.data = data;
+            }
+            Ok(Resolved::Resource(resolved))
+        };
+        let resolution = VERSION_POLICY.scope(options.version_policy, resolution);
+        with_cache_policy(options.cache, resolution).await
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use crate::{
+        proto::cheqd::{did::v2::DidDoc, resource::v2::Metadata as ResourceMetadata},
+        resolution::resolver::DidCheqdResolverConfiguration,
+        test_utils::{LedgerFixtures, MockCheqdNode},
+    };
+
+    const DID: &str = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+
+    #[tokio::test]
+    async fn test_resolve_with_options() {
+        let version = |version_id: &str| crate::proto::cheqd::did::v2::Metadata {
+            version_id: version_id.into(),
+            ..Default::default()
+        };
+        let did_doc = DidDoc {
+            id: DID.into(),
+            ..Default::default()
+        };
+        let fixtures = LedgerFixtures::new()
+            .with_did_doc(did_doc.clone(), version("v1"))
+            .with_did_doc(did_doc, version("v2"))
+            .with_resource(
+                b"{}".to_vec(),
+                ResourceMetadata {
+                    collection_id: "Ps1ysXP2Ae6GBfxNhNQNKN".into(),
+                    id: "r1".into(),
+                    media_type: "application/json".into(),
+                    ..Default::default()
+                },
+            );
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            cache: Some(Default::default()),
+            ..Default::default()
+        });
+        let resolve = |did_url: &'static str, options: ResolutionOptions| {
+            let resolver = resolver.clone();
+            async move { resolver.resolve_with_options(did_url, &options).await }
+        };
+        let document = |resolved| match resolved {
+            Ok(Resolved::Document(document)) => document,
+            other => panic!("expected a DID document, got {other:?}"),
+        };
+
+        let options = ResolutionOptions::builder().version_id("v1").build();
+        let resolved = document(resolve(DID, options.clone()).await);
+        assert_eq!(resolved.metadata.unwrap().version_id, "v1");
+        let e = resolve(
+            "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN?versionId=v2",
+            options,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(e, DidCheqdError::InvalidDidUrl(_)), "{e:?}");
+
+        // the namespace-less DID resolves against the requested network, unless strict
+        let options = ResolutionOptions::builder().network("testnet").build();
+        let resolved = document(resolve("did:cheqd:Ps1ysXP2Ae6GBfxNhNQNKN", options).await);
+        assert!(!resolved.provenance.cached);
+        let options = ResolutionOptions::builder()
+            .network("testnet")
+            .strict(true)
+            .build();
+        assert!(
+            resolve("did:cheqd:Ps1ysXP2Ae6GBfxNhNQNKN", options)
+                .await
+                .is_err()
+        );
+
+        let cached = document(resolve(DID, Default::default()).await);
+        assert!(cached.provenance.cached);
+        let options = ResolutionOptions::builder()
+            .cache(CachePolicy::Refresh)
+            .build();
+        assert!(!document(resolve(DID, options).await).provenance.cached);
+
+        let resource = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN/resources/r1";
+        let options = ResolutionOptions::builder().accept("application/*").build();
+        assert!(matches!(
+            resolve(resource, options).await,
+            Ok(Resolved::Resource(_))
+        ));
+        let options = ResolutionOptions::builder().accept("image/*").build();
+        let e = resolve(resource, options).await.unwrap_err();
+        assert!(matches!(e, DidCheqdError::UnexpectedMediaType(_)), "{e:?}");
+        assert_eq!(e.to_envelope().error, "representationNotSupported");
+        #[cfg(feature = "cbor")]
+        {
+            let options = ResolutionOptions::builder()
+                .accept("application/cbor, application/json;q=0.5")
+                .build();
+            let Ok(Resolved::Resource(resolved)) = resolve(resource, options).await else {
+                panic!("expected a resource");
+            };
+            assert_eq!(resolved.metadata.media_type, "application/cbor");
+            // an empty CBOR map
+            assert_eq!(resolved.data, [0xa0]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_version_policy() {
+        let version = |version_id: &str| crate::proto::cheqd::did::v2::Metadata {
+            version_id: version_id.into(),
+            ..Default::default()
+        };
+        let did_doc = DidDoc {
+            id: DID.into(),
+            ..Default::default()
+        };
+        let fixtures = LedgerFixtures::new()
+            .with_did_doc(did_doc.clone(), version("v1"))
+            .with_did_doc(did_doc, version("v2"));
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            cache: Some(Default::default()),
+            ..Default::default()
+        });
+        let resolve = |did_url: &'static str, version_policy| {
+            let resolver = resolver.clone();
+            async move {
+                let options = ResolutionOptions::builder()
+                    .version_policy(version_policy)
+                    .build();
+                let Resolved::Document(resolved) = resolver
+                    .resolve_with_options(did_url, &options)
+                    .await
+                    .unwrap()
+                else {
+                    panic!("expected a DID document");
+                };
+                let queries = resolver.stats().await.queries;
+                (resolved.provenance.cached, queries)
+            }
+        };
+        let latest_version = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN?versionId=v2";
+
+        assert_eq!(resolve(DID, VersionPolicy::PreferCache).await, (false, 1));
+        // the cached latest document is the version
+        let resolved = resolve(latest_version, VersionPolicy::PreferCache).await;
+        assert_eq!(resolved, (true, 1));
+        let resolved = resolve(latest_version, VersionPolicy::Exact).await;
+        assert_eq!(resolved, (false, 2));
+        assert_eq!(
+            resolve(latest_version, VersionPolicy::LatestOnly).await,
+            (true, 2)
+        );
+        assert_eq!(resolve(DID, VersionPolicy::Exact).await, (true, 2));
+        assert_eq!(resolve(DID, VersionPolicy::LatestOnly).await, (false, 3));
+    }
+}
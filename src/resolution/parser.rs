@@ -1,23 +1,134 @@
 use crate::error::{DidCheqdError, DidCheqdResult};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 
 /// Parsed representation of a did:cheqd DID or DID URL
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DidCheqdParsed {
     /// The canonical DID string (e.g. `did:cheqd:mainnet:abcd123`)
     pub did: String,
     /// Namespace (e.g. `mainnet`, `testnet`), or default `mainnet` when omitted
     pub namespace: String,
+    /// Whether the namespace was omitted from the input, and the default namespace assumed
+    pub namespace_defaulted: bool,
     /// Identifier part (collection / DID id)
     pub id: String,
-    /// Optional parsed query parameters
+    /// Optional parsed query parameters. Names of [RECOGNIZED_PARAMETERS] are canonicalized, see
+    /// [DidCheqdParser::parse].
     pub query: Option<HashMap<String, String>>,
     /// Optional version identifier (from `versionId` query param or `/versions/<id>` path)
     pub version: Option<String>,
+    /// Optional fragment (everything after the first `#`), e.g. a verification method id
+    pub fragment: Option<String>,
+    /// Whether the path ends in `/metadata`, i.e. the DID URL identifies the metadata of the DID
+    /// document or resource, rather than the document or resource itself
+    pub metadata: bool,
 }
 
 pub const DEFAULT_NAMESPACE: &str = "mainnet";
 
+/// The prefix of every did:cheqd DID & DID URL
+pub const DID_CHEQD_PREFIX: &str = "did:cheqd:";
+
+/// DID URL parameters which are recognized by the resolver
+pub const RECOGNIZED_PARAMETERS: &[&str] = &[
+    "checksum",
+    "relativeRef",
+    "resourceId",
+    "resourceName",
+    "resourceType",
+    "resourceVersionTime",
+    "service",
+    "versionId",
+];
+
+/// DID URL parameters which identify a resource, rather than the DID document
+pub const RESOURCE_PARAMETERS: &[&str] = &[
+    "resourceId",
+    "resourceName",
+    "resourceType",
+    "resourceVersionTime",
+];
+
+/// Alternative spellings of resource query parameters, used by other cheqd tooling, with the
+/// recognized parameter each is canonicalized to
+pub const RESOURCE_PARAMETER_ALIASES: &[(&str, &str)] = &[("versionTime", "resourceVersionTime")];
+
+impl DidCheqdParsed {
+    /// Whether the DID URL identifies a resource (by a `/resources/<id>` path or a
+    /// [RESOURCE_PARAMETERS] query), rather than a DID document, possibly a version of it
+    pub fn is_resource(&self) -> bool {
+        self.query
+            .as_ref()
+            .is_some_and(|query| RESOURCE_PARAMETERS.iter().any(|p| query.contains_key(*p)))
+    }
+
+    /// The same DID URL on the network with the given namespace
+    pub fn in_namespace(mut self, namespace: &str) -> Self {
+        self.did = format!("{DID_CHEQD_PREFIX}{namespace}:{}", self.id);
+        self.namespace = namespace.to_owned();
+        self.namespace_defaulted = false;
+        self
+    }
+
+    /// A JSON object describing how the DID URL was interpreted, for inclusion in resolution
+    /// metadata (as the `didUrl` property). Includes the canonical DID, namespace (and whether it
+    /// was defaulted), identifier, version, the recognized parameters and fragment.
+    pub fn did_url_metadata(&self) -> Value {
+        let mut obj = Map::new();
+        obj.insert("did".to_string(), Value::String(self.did.clone()));
+        obj.insert(
+            "namespace".to_string(),
+            Value::String(self.namespace.clone()),
+        );
+        obj.insert(
+            "namespaceDefaulted".to_string(),
+            Value::Bool(self.namespace_defaulted),
+        );
+        obj.insert("id".to_string(), Value::String(self.id.clone()));
+        if let Some(version) = &self.version {
+            obj.insert("versionId".to_string(), Value::String(version.clone()));
+        }
+        let parameters: Map<String, Value> = self
+            .query
+            .iter()
+            .flatten()
+            .filter(|(k, _)| RECOGNIZED_PARAMETERS.contains(&k.as_str()))
+            .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+            .collect();
+        if !parameters.is_empty() {
+            obj.insert("parameters".to_string(), Value::Object(parameters));
+        }
+        if let Some(fragment) = &self.fragment {
+            obj.insert("fragment".to_string(), Value::String(fragment.clone()));
+        }
+        if self.metadata {
+            obj.insert("metadata".to_string(), Value::Bool(true));
+        }
+        Value::Object(obj)
+    }
+}
+
+/// Options controlling how DIDs & DID URLs are parsed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParserOptions {
+    /// Require an explicit namespace (as the cheqd DID method spec does), rejecting
+    /// namespace-less DIDs such as `did:cheqd:abcd` rather than assuming [DEFAULT_NAMESPACE].
+    pub require_namespace: bool,
+    /// How query parameters given more than once, such as `?versionId=a&versionId=b`, are treated
+    pub duplicate_params: DuplicateParams,
+}
+
+/// How query parameters given more than once in a DID URL are treated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateParams {
+    /// reject the DID URL as invalid
+    #[default]
+    Reject,
+    /// use the value of the last occurrence
+    LastWins,
+}
+
 pub struct DidCheqdParser;
 
 impl DidCheqdParser {
@@ -27,14 +138,43 @@ impl DidCheqdParser {
     /// - `did:cheqd:<namespace>:<identifier>`
     /// - `did:cheqd:<namespace>:<identifier>?resourceName=...&resourceType=...`
     /// - `did:cheqd:<namespace>:<identifier>/resources/<resource_id>`
+    /// - `did:cheqd:<namespace>:<identifier>/versions/<version_id>`, optionally followed by
+    ///   `/resources/<resource_id>` as produced by some ledger tooling
+    /// - any of the above paths (or none) followed by `/metadata`, e.g.
+    ///   `did:cheqd:<namespace>:<identifier>/resources/<resource_id>/metadata`
+    /// - any of the above with a `#<fragment>`, e.g. `did:cheqd:<namespace>:<identifier>#key-1`
     /// - namespace may be omitted (defaults to `mainnet`)
+    ///
+    /// Query parameter names are canonicalized: [RECOGNIZED_PARAMETERS] are matched
+    /// case-insensitively (e.g. `resourcename` becomes `resourceName`), and in resource queries
+    /// (those with a `resourceId`, `resourceName` or `resourceType`) the
+    /// [RESOURCE_PARAMETER_ALIASES] are replaced by the parameters they alias (e.g. `versionTime`
+    /// becomes `resourceVersionTime`). A parameter given under more than one spelling counts as
+    /// duplicated, see [ParserOptions::duplicate_params].
+    ///
+    /// A version or resource id given by the path may be repeated as the `versionId` or
+    /// `resourceId` query parameter, but a DID URL where the two differ is rejected.
     pub fn parse(input: &str) -> DidCheqdResult<DidCheqdParsed> {
-        if !input.starts_with("did:cheqd:") {
+        Self::parse_with_options(input, ParserOptions::default())
+    }
+
+    /// Parse a DID or DID URL as [DidCheqdParser::parse], with the given options.
+    pub fn parse_with_options(
+        input: &str,
+        options: ParserOptions,
+    ) -> DidCheqdResult<DidCheqdParsed> {
+        if !input.starts_with(DID_CHEQD_PREFIX) {
             return Err(DidCheqdError::MethodNotSupported(format!(
                 "not a did:cheqd string: {input}"
             )));
         }
 
+        // split off the fragment first, as it may itself contain `?` or `/`
+        let (input, fragment) = match input.split_once('#') {
+            Some((before, fragment)) => (before, Some(fragment.to_string())),
+            None => (input, None),
+        };
+
         // split off query
         let (base, query_opt) = match input.split_once('?') {
             Some((b, q)) => (b, Some(q)),
@@ -42,7 +182,7 @@ impl DidCheqdParser {
         };
 
         // strip prefix
-        let rest = &base["did:cheqd:".len()..];
+        let rest = &base[DID_CHEQD_PREFIX.len()..];
 
         // look for a path after the id (preserves leading slash)
         let (id_part, path_opt) = match rest.split_once('/') {
@@ -51,82 +191,206 @@ impl DidCheqdParser {
         };
 
         // id_part may contain an optional namespace separated by ':'
-        let (namespace, id) = if let Some((ns, id)) = id_part.split_once(':') {
-            (ns.to_string(), id.to_string())
+        let (namespace, id, namespace_defaulted) = if let Some((ns, id)) = id_part.split_once(':') {
+            (ns.to_string(), id.to_string(), false)
         } else {
-            (DEFAULT_NAMESPACE.to_string(), id_part.to_string())
+            (DEFAULT_NAMESPACE.to_string(), id_part.to_string(), true)
         };
+        if namespace_defaulted && options.require_namespace {
+            return Err(DidCheqdError::InvalidDid(format!(
+                "namespace is required but missing: {input}"
+            )));
+        }
 
         // parse query string into an owned map so we can inject `resourceId` from the path
-        let mut query = query_opt.map(parse_query_string);
+        let mut query = query_opt
+            .map(|q| parse_query_string(q, options.duplicate_params))
+            .transpose()?;
 
-        // version may come from the path or the query param `versionId` (query takes precedence)
+        // version & resource id may come from the path or the query params `versionId` &
+        // `resourceId`, which must then agree with it
         let mut version: Option<String> = None;
+        let mut metadata = false;
         if let Some(p) = path_opt {
-            let parts: Vec<&str> = p.trim_start_matches('/').split('/').collect();
-            if parts.len() != 2 {
-                return Err(DidCheqdError::InvalidDidUrl(
-                    "unsupported path format; expected /resources/<id> or /versions/<id>"
-                        .to_string(),
-                ));
+            let mut parts: Vec<&str> = p.trim_start_matches('/').split('/').collect();
+            if parts.last() == Some(&"metadata") {
+                parts.pop();
+                metadata = true;
             }
-
-            match parts[0] {
-                "resources" => {
-                    let resource_id = parts[1];
-                    match &mut query {
-                        Some(map) => {
-                            map.insert("resourceId".to_string(), resource_id.to_string());
-                        }
-                        None => {
-                            let mut m = HashMap::new();
-                            m.insert("resourceId".to_string(), resource_id.to_string());
-                            query = Some(m);
-                        }
-                    }
+            let (path_version, path_resource) = match parts.as_slice() {
+                [] => (None, None),
+                ["resources", resource_id] => (None, Some(*resource_id)),
+                ["versions", version_id] => (Some(*version_id), None),
+                ["versions", version_id, "resources", resource_id] => {
+                    (Some(*version_id), Some(*resource_id))
                 }
-                "versions" => {
-                    version = Some(parts[1].to_string());
+                [_, _] | [_, _, _, _] => {
+                    return Err(DidCheqdError::InvalidDidUrl(
+                        "unsupported path segment; only `resources` and `versions` are accepted"
+                            .to_string(),
+                    ));
                 }
                 _ => {
                     return Err(DidCheqdError::InvalidDidUrl(
-                        "unsupported path segment; only `resources` and `versions` are accepted"
+                        "unsupported path format; expected /resources/<id>, /versions/<id> or \
+                         /versions/<id>/resources/<id>, optionally followed by /metadata"
                             .to_string(),
                     ));
                 }
+            };
+            let query_param = |name: &str| query.as_ref().and_then(|q| q.get(name));
+            if let (Some(path), Some(param)) = (path_version, query_param("versionId")) {
+                if path != param {
+                    return Err(DidCheqdError::InvalidDidUrl(format!(
+                        "path version `{path}` contradicts `versionId={param}`"
+                    )));
+                }
+            }
+            if let (Some(path), Some(param)) = (path_resource, query_param("resourceId")) {
+                if path != param {
+                    return Err(DidCheqdError::InvalidDidUrl(format!(
+                        "path resource `{path}` contradicts `resourceId={param}`"
+                    )));
+                }
+            }
+            version = path_version.map(str::to_string);
+            if let Some(resource_id) = path_resource {
+                query
+                    .get_or_insert_with(HashMap::new)
+                    .insert("resourceId".to_string(), resource_id.to_string());
             }
         }
 
-        // If the query contains an explicit `versionId`, it takes precedence.
         if let Some(ref qmap) = query {
             if let Some(v) = qmap.get("versionId") {
                 version = Some(v.clone());
             }
         }
 
-        let did = format!("did:cheqd:{}:{}", namespace, id);
+        let did = format!("{DID_CHEQD_PREFIX}{namespace}:{id}");
 
         Ok(DidCheqdParsed {
             did,
             namespace,
+            namespace_defaulted,
             id,
             query,
             version,
+            fragment,
+            metadata,
         })
     }
 }
 
-fn parse_query_string(q: &str) -> HashMap<String, String> {
-    q.split('&')
-        .filter_map(|kv| kv.split_once('='))
-        .map(|(k, v)| (k.to_string(), v.to_string()))
-        .collect()
+/// The form the ledger stores an identifier (a DID's unique id, a version or resource id) in.
+///
+/// DIDs & resources created under the v1 ledger state could use upper case UUIDs, which the
+/// v2 state migration lowercased, so those DIDs are still referenced by their original spelling.
+/// Other identifiers (e.g. base58 unique ids, which are case-sensitive) are returned as given.
+pub(crate) fn ledger_id(id: &str) -> String {
+    let is_uuid = id.len() == 36
+        && id.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        });
+    if is_uuid {
+        id.to_ascii_lowercase()
+    } else {
+        id.to_string()
+    }
+}
+
+/// The canonical spelling of a query parameter name
+fn canonical_parameter(name: &str, resource_query: bool) -> &str {
+    let aliases = RESOURCE_PARAMETER_ALIASES
+        .iter()
+        .filter(|_| resource_query)
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+        .map(|(_, canonical)| canonical);
+    let recognized = || {
+        RECOGNIZED_PARAMETERS
+            .iter()
+            .find(|param| param.eq_ignore_ascii_case(name))
+    };
+    aliases.or_else(recognized).copied().unwrap_or(name)
+}
+
+/// Parse a query string into a map of parameters with canonical names, rejecting parameters with
+/// an empty name or value, and duplicated parameters unless [DuplicateParams::LastWins] is given.
+fn parse_query_string(
+    q: &str,
+    duplicates: DuplicateParams,
+) -> DidCheqdResult<HashMap<String, String>> {
+    let pairs: Vec<(&str, &str)> = q
+        .split('&')
+        .filter(|kv| !kv.is_empty())
+        .map(|kv| kv.split_once('=').unwrap_or((kv, "")))
+        .collect();
+    let resource_query = pairs.iter().any(|(k, _)| {
+        RESOURCE_PARAMETERS
+            .iter()
+            .any(|param| param.eq_ignore_ascii_case(k))
+    });
+
+    let mut params = HashMap::new();
+    for (k, v) in pairs {
+        if k.is_empty() || v.is_empty() {
+            return Err(DidCheqdError::InvalidDidUrl(format!(
+                "query parameter has an empty name or value: {k}={v}"
+            )));
+        }
+        let k = canonical_parameter(k, resource_query);
+        let previous = params.insert(k.to_string(), v.to_string());
+        if previous.is_some() && duplicates == DuplicateParams::Reject {
+            return Err(DidCheqdError::InvalidDidUrl(format!(
+                "query parameter given more than once: {k}"
+            )));
+        }
+    }
+    Ok(params)
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
+    proptest! {
+        #[test]
+        fn parse_never_panics(input in "did:cheqd:\\PC*") {
+            let _ = DidCheqdParser::parse(&input).map(|p| p.did_url_metadata());
+        }
+
+        #[test]
+        fn parse_round_trips_dids(
+            namespace in "[a-z]{1,10}",
+            id in "[1-9A-HJ-NP-Za-km-z]{16,32}",
+        ) {
+            let did = format!("did:cheqd:{namespace}:{id}");
+            let parsed = DidCheqdParser::parse(&did).unwrap();
+            prop_assert_eq!(parsed.did, did);
+            prop_assert_eq!(parsed.namespace, namespace);
+            prop_assert_eq!(parsed.id, id);
+        }
+    }
+
+    #[test]
+    fn ledger_id_lowercases_legacy_uuids() {
+        assert_eq!(
+            ledger_id("DE0A1F9C-9B7B-4C1F-9E1B-2F3B5C6D7E8F"),
+            "de0a1f9c-9b7b-4c1f-9e1b-2f3b5c6d7e8f"
+        );
+        assert_eq!(
+            ledger_id("Ps8DqBGxvS2gRpLYKj3d7H"),
+            "Ps8DqBGxvS2gRpLYKj3d7H"
+        );
+        assert_eq!(
+            ledger_id("DE0A1F9C9B7B4C1F9E1B2F3B5C6D7E8F"),
+            "DE0A1F9C9B7B4C1F9E1B2F3B5C6D7E8F"
+        );
+    }
+
     #[test]
     fn parse_basic_did_with_namespace() {
         let s = "did:cheqd:mainnet:abcd123";
@@ -147,6 +411,28 @@ mod tests {
         assert_eq!(p.id, "abcd123".to_string());
     }
 
+    #[test]
+    fn did_url_metadata_describes_interpretation() {
+        let p = DidCheqdParser::parse("did:cheqd:abcd123?resourceName=foo&foo=bar").unwrap();
+        let meta = p.did_url_metadata();
+        assert_eq!(meta["did"], "did:cheqd:mainnet:abcd123");
+        assert_eq!(meta["namespaceDefaulted"], true);
+        assert_eq!(meta["parameters"]["resourceName"], "foo");
+        // unrecognized parameters are omitted
+        assert!(meta["parameters"].get("foo").is_none());
+    }
+
+    #[test]
+    fn parse_without_namespace_when_required() {
+        let options = ParserOptions {
+            require_namespace: true,
+            ..Default::default()
+        };
+        let e = DidCheqdParser::parse_with_options("did:cheqd:abcd123", options).unwrap_err();
+        assert!(matches!(e, DidCheqdError::InvalidDid(_)));
+        assert!(DidCheqdParser::parse_with_options("did:cheqd:testnet:abcd123", options).is_ok());
+    }
+
     #[test]
     fn parse_resource_path() {
         let s = "did:cheqd:mainnet:abcd123/resources/r1";
@@ -210,6 +496,139 @@ mod tests {
         assert_eq!(q.get("versionId").map(String::as_str), Some("v42"));
     }
 
+    #[test]
+    fn parse_fragment_with_path_and_query() {
+        let s = "did:cheqd:testnet:abcd123?resourceName=foo&resourceType=bar#key-1";
+        let p = DidCheqdParser::parse(s).unwrap();
+        assert_eq!(p.id, "abcd123");
+        assert_eq!(p.fragment.as_deref(), Some("key-1"));
+        let q = p.query.unwrap();
+        assert_eq!(q.get("resourceType").map(String::as_str), Some("bar"));
+
+        let s = "did:cheqd:testnet:abcd123/resources/r1#frag?not=query/path";
+        let p = DidCheqdParser::parse(s).unwrap();
+        assert_eq!(p.fragment.as_deref(), Some("frag?not=query/path"));
+        let q = p.query.unwrap();
+        assert_eq!(q.len(), 1);
+        assert_eq!(q.get("resourceId").map(String::as_str), Some("r1"));
+
+        let p = DidCheqdParser::parse("did:cheqd:testnet:abcd123#key-1").unwrap();
+        assert_eq!(p.did, "did:cheqd:testnet:abcd123");
+        assert!(p.query.is_none());
+        assert_eq!(p.did_url_metadata()["fragment"], "key-1");
+    }
+
+    #[test]
+    fn parse_rejects_empty_query_params() {
+        for s in [
+            "did:cheqd:testnet:abcd123?resourceName=&resourceType=x",
+            "did:cheqd:testnet:abcd123?resourceName&resourceType=x",
+            "did:cheqd:testnet:abcd123?=foo",
+        ] {
+            let err = DidCheqdParser::parse(s).unwrap_err();
+            assert!(matches!(err, DidCheqdError::InvalidDidUrl(_)), "{s}: {err}");
+        }
+    }
+
+    #[test]
+    fn parse_duplicate_query_params() {
+        let s = "did:cheqd:testnet:abcd123?versionId=a&versionId=b";
+        let err = DidCheqdParser::parse(s).unwrap_err();
+        assert!(matches!(err, DidCheqdError::InvalidDidUrl(_)));
+
+        let options = ParserOptions {
+            duplicate_params: DuplicateParams::LastWins,
+            ..Default::default()
+        };
+        let p = DidCheqdParser::parse_with_options(s, options).unwrap();
+        assert_eq!(p.version.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn parse_canonicalizes_parameter_names() {
+        let s = "did:cheqd:testnet:abcd123?ResourceName=foo&resourcetype=bar&versionTime=2024-01-01T00:00:00Z";
+        let q = DidCheqdParser::parse(s).unwrap().query.unwrap();
+        assert_eq!(q.get("resourceName").map(String::as_str), Some("foo"));
+        assert_eq!(q.get("resourceType").map(String::as_str), Some("bar"));
+        assert_eq!(
+            q.get("resourceVersionTime").map(String::as_str),
+            Some("2024-01-01T00:00:00Z")
+        );
+
+        // `versionTime` only aliases `resourceVersionTime` in resource queries
+        let s = "did:cheqd:testnet:abcd123?versionTime=2024-01-01T00:00:00Z";
+        let q = DidCheqdParser::parse(s).unwrap().query.unwrap();
+        assert!(q.contains_key("versionTime"));
+
+        let s = "did:cheqd:testnet:abcd123?resourceid=r1";
+        let q = DidCheqdParser::parse(s).unwrap().query.unwrap();
+        assert_eq!(q.get("resourceId").map(String::as_str), Some("r1"));
+
+        let s = "did:cheqd:testnet:abcd123?resourceName=a&resourceType=b&versionTime=x&resourceVersionTime=y";
+        assert!(DidCheqdParser::parse(s).is_err());
+    }
+
+    #[test]
+    fn parse_distinguishes_resources_from_did_documents() {
+        let is_resource = |s: &str| DidCheqdParser::parse(s).unwrap().is_resource();
+        assert!(is_resource("did:cheqd:testnet:abcd123/resources/r1"));
+        assert!(is_resource(
+            "did:cheqd:testnet:abcd123?resourceName=a&resourceType=b#frag"
+        ));
+        assert!(!is_resource("did:cheqd:testnet:abcd123"));
+        assert!(!is_resource("did:cheqd:testnet:abcd123?versionId=v1"));
+        assert!(!is_resource(
+            "did:cheqd:testnet:abcd123?versionTime=2024-01-01T00:00:00Z"
+        ));
+        assert!(!is_resource("did:cheqd:testnet:abcd123/versions/v1#key-1"));
+    }
+
+    #[test]
+    fn parse_path_and_query_conflicts() {
+        let parsed =
+            DidCheqdParser::parse("did:cheqd:testnet:abcd123/versions/v1?versionId=v1").unwrap();
+        assert_eq!(parsed.version.as_deref(), Some("v1"));
+
+        let e = DidCheqdParser::parse("did:cheqd:testnet:abcd123/versions/v1?versionId=v2")
+            .unwrap_err();
+        assert!(e.to_string().contains("contradicts `versionId=v2`"), "{e}");
+        let e = DidCheqdParser::parse("did:cheqd:testnet:abcd123/resources/r1?resourceId=r2")
+            .unwrap_err();
+        assert!(e.to_string().contains("contradicts `resourceId=r2`"), "{e}");
+
+        let parsed =
+            DidCheqdParser::parse("did:cheqd:testnet:abcd123/versions/v1/resources/r1").unwrap();
+        assert_eq!(parsed.version.as_deref(), Some("v1"));
+        assert_eq!(parsed.query.unwrap()["resourceId"], "r1");
+        assert!(
+            DidCheqdParser::parse("did:cheqd:testnet:abcd123/resources/r1/versions/v1").is_err()
+        );
+    }
+
+    #[test]
+    fn parse_metadata_paths() {
+        let parsed = DidCheqdParser::parse("did:cheqd:testnet:abcd123/metadata").unwrap();
+        assert!(parsed.metadata && !parsed.is_resource());
+        assert_eq!(parsed.did_url_metadata()["metadata"], true);
+
+        let parsed =
+            DidCheqdParser::parse("did:cheqd:testnet:abcd123/resources/r1/metadata").unwrap();
+        assert!(parsed.metadata && parsed.is_resource());
+        assert_eq!(parsed.query.unwrap()["resourceId"], "r1");
+
+        let parsed =
+            DidCheqdParser::parse("did:cheqd:testnet:abcd123/versions/v1/metadata").unwrap();
+        assert!(parsed.metadata);
+        assert_eq!(parsed.version.as_deref(), Some("v1"));
+
+        assert!(
+            !DidCheqdParser::parse("did:cheqd:testnet:abcd123")
+                .unwrap()
+                .metadata
+        );
+        assert!(DidCheqdParser::parse("did:cheqd:testnet:abcd123/metadata/metadata").is_err());
+    }
+
     #[test]
     fn parse_invalid_path_param() {
         let s = "did:cheqd:mainnet:f5a28137-5cfa-486f-bf88-3fbe6507eac5/invalid/r1";
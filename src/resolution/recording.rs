@@ -0,0 +1,206 @@
+//! VCR-style recording of node query responses to fixture files, & deterministic replay of them,
+//! so tests against live networks can run reliably & offline.
+//!
+//! Each query is stored as a JSON file named after the network namespace, the query method & a
+//! hash of the (protobuf encoded) request, holding the encoded response or the error status.
+
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use tonic::{Code, Status};
+
+use crate::error::{DidCheqdError, DidCheqdResult};
+
+/// Whether node query responses are recorded to, or replayed from, fixture files
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FixtureMode {
+    /// queries are sent to the network's nodes
+    #[default]
+    Off,
+    /// queries are sent to the network's nodes, & the primary node's responses are written to
+    /// fixture files in the given directory
+    Record(PathBuf),
+    /// queries are answered from the fixture files in the given directory, without contacting any
+    /// node. Queries without a fixture fail.
+    Replay(PathBuf),
+}
+
+impl FromStr for FixtureMode {
+    type Err = DidCheqdError;
+
+    /// Parse `off`, `record:<dir>` or `replay:<dir>`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            _ if s == "off" => Ok(FixtureMode::Off),
+            Some(("record", dir)) if !dir.is_empty() => Ok(FixtureMode::Record(dir.into())),
+            Some(("replay", dir)) if !dir.is_empty() => Ok(FixtureMode::Replay(dir.into())),
+            _ => Err(DidCheqdError::BadConfiguration(format!(
+                "invalid fixture mode `{s}`; expected `off`, `record:<dir>` or `replay:<dir>`"
+            ))),
+        }
+    }
+}
+
+/// The fixture file of a query
+fn fixture_path(dir: &Path, namespace: &str, method: &str, request: &[u8]) -> PathBuf {
+    let hash = format!("{:x}", Sha256::digest(request));
+    dir.join(format!("{namespace}-{method}-{}.json", &hash[..16]))
+}
+
+/// Write the response to a query to its fixture file
+pub(crate) fn record(
+    dir: &Path,
+    namespace: &str,
+    method: &str,
+    request: &impl prost::Message,
+    response: Result<Vec<u8>, &Status>,
+) -> DidCheqdResult<()> {
+    let mut fixture = json!({
+        "namespace": namespace,
+        "method": method,
+        "request": format!("{request:?}"),
+    });
+    match response {
+        Ok(response) => fixture["response"] = to_hex(&response).into(),
+        Err(status) => {
+            fixture["status"] = json!({
+                "code": status.code() as i32,
+                "message": status.message(),
+            })
+        }
+    }
+    let path = fixture_path(dir, namespace, method, &request.encode_to_vec());
+    std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(&path, serde_json::to_vec_pretty(&fixture)?))
+        .map_err(|e| {
+            DidCheqdError::Other(format!("failed to write {}: {e}", path.display()).into())
+        })
+}
+
+/// Read the response to a query from its fixture file
+pub(crate) fn replay(
+    dir: &Path,
+    namespace: &str,
+    method: &str,
+    request: &impl prost::Message,
+) -> DidCheqdResult<Result<Vec<u8>, Status>> {
+    let path = fixture_path(dir, namespace, method, &request.encode_to_vec());
+    let fixture = std::fs::read(&path).map_err(|e| {
+        DidCheqdError::Other(format!("no fixture for {method} at {}: {e}", path.display()).into())
+    })?;
+    let fixture: Value = serde_json::from_slice(&fixture)?;
+    let invalid = || DidCheqdError::Other(format!("invalid fixture {}", path.display()).into());
+
+    if let Some(status) = fixture.get("status") {
+        let code = status["code"].as_i64().ok_or_else(invalid)?;
+        let message = status["message"].as_str().ok_or_else(invalid)?;
+        return Ok(Err(Status::new(Code::from(code as i32), message)));
+    }
+    let response = fixture["response"].as_str().ok_or_else(invalid)?;
+    from_hex(response).map(Ok).ok_or_else(invalid)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use crate::{
+        proto::cheqd::did::v2::{DidDoc, QueryDidDocRequest, QueryDidDocResponse},
+        resolution::{
+            resolver::{DidCheqdResolver, DidCheqdResolverConfiguration},
+            service::Resolved,
+        },
+        test_utils::{LedgerFixtures, MockCheqdNode},
+    };
+
+    #[test]
+    fn test_record_then_replay() {
+        let dir = std::env::temp_dir().join(format!("did-cheqd-fixtures-{}", std::process::id()));
+        let request = QueryDidDocRequest {
+            id: "did:cheqd:testnet:abc".into(),
+        };
+        let response = prost::Message::encode_to_vec(&QueryDidDocResponse::default());
+        record(&dir, "testnet", "DidDoc", &request, Ok(response.clone())).unwrap();
+        assert_eq!(
+            replay(&dir, "testnet", "DidDoc", &request)
+                .unwrap()
+                .unwrap(),
+            response
+        );
+
+        let not_found = Status::not_found("DID Doc not found");
+        record(&dir, "testnet", "DidDoc", &request, Err(&not_found)).unwrap();
+        let status = replay(&dir, "testnet", "DidDoc", &request)
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+
+        let unrecorded = QueryDidDocRequest::default();
+        assert!(replay(&dir, "testnet", "DidDoc", &unrecorded).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resolver_replays_recorded_queries() {
+        let dir = std::env::temp_dir().join(format!("did-cheqd-replay-{}", std::process::id()));
+        let did = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+        let fixtures = LedgerFixtures::new().with_did_doc(
+            DidDoc {
+                id: did.into(),
+                ..Default::default()
+            },
+            Default::default(),
+        );
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = |fixture_mode| {
+            DidCheqdResolver::new(DidCheqdResolverConfiguration {
+                networks: vec![node.network("testnet")],
+                fixture_mode,
+                ..Default::default()
+            })
+        };
+
+        let recorder = resolver(FixtureMode::Record(dir.clone()));
+        recorder.resolve_did_url(did).await.unwrap();
+        let unknown = "did:cheqd:testnet:zF7rhDBfUt9d1gJPjx7s1J";
+        recorder.resolve_did_url(unknown).await.unwrap_err();
+
+        // the node is not contacted when replaying
+        let replayer = resolver(FixtureMode::Replay(dir.clone()));
+        drop(node);
+        let Resolved::Document(resolved) = replayer.resolve_did_url(did).await.unwrap() else {
+            panic!("expected a DID document");
+        };
+        assert_eq!(resolved.did_doc.id, did);
+        let e = replayer.resolve_did_url(unknown).await.unwrap_err();
+        assert_eq!(e.status_code(), Some(Code::NotFound));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_fixture_mode() {
+        assert_eq!("off".parse::<FixtureMode>().unwrap(), FixtureMode::Off);
+        assert_eq!(
+            "replay:fixtures".parse::<FixtureMode>().unwrap(),
+            FixtureMode::Replay("fixtures".into())
+        );
+        assert!("record:".parse::<FixtureMode>().is_err());
+    }
+}
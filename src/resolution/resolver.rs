@@ -1,25 +1,75 @@
-use std::{cmp::Ordering, collections::HashMap};
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    net::Ipv6Addr,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering},
+    },
+    time::{Duration, Instant},
+};
 
 use chrono::{DateTime, Utc};
-use tokio::sync::Mutex;
-use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use futures_util::future::join_all;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio::sync::{Mutex, Notify};
+use tonic::{
+    Code, Status,
+    transport::{Channel, ClientTlsConfig, Endpoint, Uri},
+};
 
 // transformer helpers produce JSON values; no direct types imported here.
 use crate::{
-    error::{DidCheqdError, DidCheqdResult},
+    error::{DidCheqdError, DidCheqdResult, context::ErrorContext},
     proto::cheqd::{
         did::v2::{
-            QueryDidDocRequest, QueryDidDocVersionRequest,
+            DidDoc, Metadata as DidDocMetadata, QueryDidDocRequest, QueryDidDocResponse,
+            QueryDidDocVersionRequest, QueryDidDocVersionResponse,
             query_client::QueryClient as DidQueryClient,
         },
         resource::v2::{
             Metadata as CheqdResourceMetadata, QueryCollectionResourcesRequest,
-            QueryResourceRequest, query_client::QueryClient as ResourceQueryClient,
+            QueryCollectionResourcesResponse, QueryResourceRequest, QueryResourceResponse,
+            query_client::QueryClient as ResourceQueryClient,
+        },
+    },
+    proto::cosmos::base::query::v1beta1::PageRequest,
+    resolution::{
+        cache::{
+            CacheConfiguration, CachedCollection, CachedDidDoc, DidDocKey, ResolutionCache,
+            ResourceKey,
         },
+        capture::{CapturedQuery, DebugCapture},
+        clock::{Clock, SystemClock},
+        concurrency::{ConcurrencyLimits, QueryPermits},
+        connector::{CheqdConnector, DnsConfiguration, unix_socket_path},
+        deadline::grpc_request,
+        document_transform::DocumentTransform,
+        events::ResolverEventHandler,
+        key_index::KeyIndex,
+        latency::LatencyTracker,
+        load_balancing::{Balancer, EndpointSelection, LoadBalancing},
+        media_type::{is_json_media_type, sniff_media_type},
+        options::{CachePolicy, VersionPolicy, cache_policy, version_policy},
+        parser::{DidCheqdParsed, DidCheqdParser, ParserOptions, ledger_id},
+        recording::{self, FixtureMode},
+        response_validation::{ResponseValidation, validate_did_doc, validate_resource_metadata},
+        stats::{Counters, ResolverStats},
+        typed_resource::ResourceTypeRegistry,
     },
-    resolution::parser::DidCheqdParsed,
 };
 
+#[cfg(unix)]
+use crate::resolution::connector::UnixSocketConnector;
+#[cfg(feature = "rest")]
+use crate::resolution::rest::RestClient;
+#[cfg(feature = "validate")]
+use crate::resolution::validation::ValidationMode;
+
 /// default namespace for the cheqd "mainnet". as it would appear in a DID.
 pub const MAINNET_NAMESPACE: &str = "mainnet";
 /// default gRPC URL for the cheqd "mainnet".
@@ -28,11 +78,133 @@ pub const MAINNET_DEFAULT_GRPC: &str = "https://grpc.cheqd.net:443";
 pub const TESTNET_NAMESPACE: &str = "testnet";
 /// default gRPC URL for the cheqd "testnet".
 pub const TESTNET_DEFAULT_GRPC: &str = "https://grpc.cheqd.network:443";
+/// default REST gRPC-gateway URL for the cheqd "mainnet".
+pub const MAINNET_DEFAULT_REST: &str = "https://api.cheqd.net";
+/// default REST gRPC-gateway URL for the cheqd "testnet".
+pub const TESTNET_DEFAULT_REST: &str = "https://api.cheqd.network";
+/// number of resource metadata entries requested per page when listing a collection.
+const COLLECTION_PAGE_SIZE: u64 = 100;
 
-/// Configuration for the [DidCheqdResolver] resolver
+/// env var listing the networks to resolve, as comma separated `namespace=grpc_url` entries
+pub const ENV_NETWORKS: &str = "DID_CHEQD_NETWORKS";
+/// env var for [DidCheqdResolverConfiguration::fallback_namespace]
+pub const ENV_FALLBACK_NAMESPACE: &str = "DID_CHEQD_FALLBACK_NAMESPACE";
+/// env var for [ParserOptions::require_namespace] (`true`/`false`)
+pub const ENV_REQUIRE_NAMESPACE: &str = "DID_CHEQD_REQUIRE_NAMESPACE";
+/// env var for [DidCheqdResolverConfiguration::debug_capture]
+pub const ENV_DEBUG_CAPTURE: &str = "DID_CHEQD_DEBUG_CAPTURE";
+/// env var for [DidCheqdResolverConfiguration::fixture_mode] (`off`, `record:<dir>` or
+/// `replay:<dir>`)
+pub const ENV_FIXTURE_MODE: &str = "DID_CHEQD_FIXTURE_MODE";
+/// env var for [DidCheqdResolverConfiguration::user_agent]
+pub const ENV_USER_AGENT: &str = "DID_CHEQD_USER_AGENT";
+/// env var for [DidCheqdResolverConfiguration::deployment_id]
+pub const ENV_DEPLOYMENT_ID: &str = "DID_CHEQD_DEPLOYMENT_ID";
+/// The default [DidCheqdResolverConfiguration::user_agent]: the crate name & version
+pub const DEFAULT_USER_AGENT: &str =
+    concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+/// env var for [DidCheqdResolverConfiguration::max_resource_size]
+pub const ENV_MAX_RESOURCE_SIZE: &str = "DID_CHEQD_MAX_RESOURCE_SIZE";
+/// env var for [DidCheqdResolverConfiguration::max_response_size]
+pub const ENV_MAX_RESPONSE_SIZE: &str = "DID_CHEQD_MAX_RESPONSE_SIZE";
+/// How much larger than [DidCheqdResolverConfiguration::max_resource_size] the response to a
+/// resource query may be, for the resource's metadata
+const RESOURCE_METADATA_ALLOWANCE: usize = 64 * 1024;
+/// The default [DidCheqdResolverConfiguration::max_response_size]: 4 MiB
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Configuration for the [DidCheqdResolver] resolver.
+///
+/// Start from [Default::default] and set fields, or use [DidCheqdResolverConfiguration::builder]:
+///
+/// ```
+/// use did_resolver_cheqd::resolution::resolver::{
+///     DidCheqdResolverConfiguration, NetworkConfiguration,
+/// };
+///
+/// let config = DidCheqdResolverConfiguration::builder()
+///     .networks(vec![NetworkConfiguration::testnet()])
+///     .namespace_fan_out(true)
+///     .build();
+/// ```
+#[non_exhaustive]
 pub struct DidCheqdResolverConfiguration {
     /// Configuration for which networks are resolvable
     pub networks: Vec<NetworkConfiguration>,
+    /// Configuration for how node endpoint hostnames are resolved
+    pub dns: DnsConfiguration,
+    /// Options for parsing DIDs & DID URLs, e.g. whether an explicit namespace is required
+    pub parser: ParserOptions,
+    /// Aliases for configured networks, mapping an alias namespace (as it would appear in a DID)
+    /// to the namespace of a network in `networks`. DIDs using the alias are resolved against
+    /// that network, as if they used its namespace.
+    pub namespace_aliases: HashMap<String, String>,
+    /// The namespace of a network in `networks` used to resolve DIDs with an otherwise
+    /// unrecognized namespace. The network is queried for such DIDs unchanged, i.e. with their
+    /// own namespace. If `None`, such DIDs fail with [DidCheqdError::NetworkNotSupported].
+    pub fallback_namespace: Option<String>,
+    /// Whether DIDs with an omitted namespace (e.g. `did:cheqd:<id>`) are resolved against every
+    /// network in `networks` concurrently, returning the first DID document or resource found,
+    /// rather than against the `mainnet` network only. See also
+    /// [DidCheqdResolver::resolve_did_doc_in_all_namespaces].
+    pub namespace_fan_out: bool,
+    /// The number of most recent gRPC queries (requests & raw responses) to record for
+    /// debugging, retrievable via [DidCheqdResolver::captured_queries]. `0` disables capture.
+    pub debug_capture: usize,
+    /// Whether to infer a best-effort media type from the content of resources created without
+    /// one (see [crate::resolution::media_type::sniff_media_type]), instead of leaving it unset.
+    pub sniff_media_types: bool,
+    /// Configuration for caching resolution results in memory. If `None`, nothing is cached.
+    pub cache: Option<CacheConfiguration>,
+    /// Hooks notified of resolutions, cache hits & network connections, e.g. for audit logging
+    pub event_handler: Option<Arc<dyn ResolverEventHandler>>,
+    /// The source of the current time, e.g. which the latest version of a resource is resolved
+    /// as of. Defaults to the [SystemClock].
+    pub clock: Arc<dyn Clock>,
+    /// Whether node query responses are recorded to, or replayed from, fixture files, see
+    /// [crate::resolution::recording]
+    pub fixture_mode: FixtureMode,
+    /// The maximum size in bytes of resource content, beyond which resolving the resource fails
+    /// with [DidCheqdError::ResourceTooLarge]. Fetches of oversized resources are aborted as
+    /// the response exceeds the limit (allowing for the resource's metadata), rather than after
+    /// buffering it. If `None`, resources of any size are resolved.
+    pub max_resource_size: Option<usize>,
+    /// The maximum size in bytes of a node's response, beyond which the query fails with
+    /// [DidCheqdError::ResponseTooLarge] without the response being decoded, protecting against
+    /// endpoints returning pathological payloads. Applies to the encoded gRPC message, or the
+    /// JSON body of [Transport::Rest] responses. Defaults to [DEFAULT_MAX_RESPONSE_SIZE].
+    pub max_response_size: usize,
+    /// The maximum numbers of queries sent to nodes at once, across all networks & per network.
+    /// Unlimited by default.
+    pub concurrency_limits: ConcurrencyLimits,
+    /// Whether a `resourceVersionTime` selects the resource version created strictly before the
+    /// time (the default), or at or before it, see [VersionTimeBoundary]
+    pub version_time_boundary: VersionTimeBoundary,
+    /// How node responses are checked for the fields the cheqd DID method requires, see
+    /// [ResponseValidation]. Off by default, using responses as received.
+    pub response_validation: ResponseValidation,
+    /// The `user-agent` sent to nodes, identifying the client to node operators
+    pub user_agent: String,
+    /// An identifier of the deployment using the resolver (e.g. an organization or service
+    /// name), sent to nodes as a comment in the `user-agent`, so node operators can attribute
+    /// traffic. If `None`, only [Self::user_agent] is sent.
+    pub deployment_id: Option<String>,
+    /// Indexes of which DIDs control which keys, searched by [DidCheqdResolver::find_did_by_key]
+    pub key_indexes: Vec<Arc<dyn KeyIndex>>,
+    /// Named configuration profiles, each resolved by a resolver of its own (with its own
+    /// networks, DNS configuration, cache, ...) selected per call, e.g. so multi-tenant platforms
+    /// can isolate each tenant's node endpoints. See [DidCheqdResolver::resolve_with_profile].
+    pub profiles: HashMap<String, DidCheqdResolverConfiguration>,
+    /// Transforms applied in order to every resolved DID document before it is returned, e.g.
+    /// removing deprecated key types, see [DocumentTransform]
+    pub document_transforms: Vec<Arc<dyn DocumentTransform>>,
+    /// The decoders of resources by `resourceType`, used by
+    /// [DidCheqdResolver::resolve_resource_typed]
+    pub resource_types: ResourceTypeRegistry,
+    /// Whether resolved DID documents are checked against the DID Core invariants, see
+    /// [crate::resolution::validation::validate_did_document]
+    #[cfg(feature = "validate")]
+    pub validation: ValidationMode,
 }
 
 impl Default for DidCheqdResolverConfiguration {
@@ -42,16 +214,109 @@ impl Default for DidCheqdResolverConfiguration {
                 NetworkConfiguration::mainnet(),
                 NetworkConfiguration::testnet(),
             ],
+            dns: DnsConfiguration::default(),
+            parser: ParserOptions::default(),
+            namespace_aliases: HashMap::new(),
+            fallback_namespace: None,
+            namespace_fan_out: false,
+            debug_capture: 0,
+            sniff_media_types: false,
+            cache: None,
+            event_handler: None,
+            clock: Arc::new(SystemClock),
+            fixture_mode: FixtureMode::Off,
+            max_resource_size: None,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            concurrency_limits: ConcurrencyLimits::default(),
+            version_time_boundary: VersionTimeBoundary::Before,
+            response_validation: ResponseValidation::Off,
+            user_agent: DEFAULT_USER_AGENT.to_owned(),
+            deployment_id: None,
+            key_indexes: Vec::new(),
+            profiles: HashMap::new(),
+            document_transforms: Vec::new(),
+            resource_types: ResourceTypeRegistry::default(),
+            #[cfg(feature = "validate")]
+            validation: ValidationMode::default(),
         }
     }
 }
 
 /// Configuration for a cheqd network. Defining details such as where to resolve DIDs from.
+///
+/// Start from [NetworkConfiguration::mainnet], [NetworkConfiguration::testnet] or
+/// [Default::default] and set fields, or use [NetworkConfiguration::builder]:
+///
+/// ```
+/// use did_resolver_cheqd::resolution::resolver::NetworkConfiguration;
+///
+/// let network = NetworkConfiguration::builder()
+///     .namespace("mainnet")
+///     .grpc_url("https://grpc.cheqd.net:443")
+///     .build();
+/// ```
+#[derive(Default)]
+#[non_exhaustive]
 pub struct NetworkConfiguration {
-    /// the cheqd nodes gRPC URL
+    /// the cheqd nodes gRPC URL. Nodes co-located with the resolver may be queried over a Unix
+    /// domain socket (without TLS) with a `unix://` URL of the socket's absolute path, e.g.
+    /// `unix:///run/cheqd/grpc.sock`.
     pub grpc_url: String,
     /// the namespace of the network - as it would appear in a DID (did:cheqd:namespace:123)
     pub namespace: String,
+    /// additional cheqd node gRPC URLs for "quorum" resolution. When non-empty, every query is
+    /// also sent to these nodes, and their responses must be identical to the response from
+    /// `grpc_url`, otherwise [DidCheqdError::InconsistentResponses] is returned.
+    pub quorum_grpc_urls: Vec<String>,
+    /// further cheqd node gRPC URLs of the network, tried in order when the primary gRPC node
+    /// fails with a transport error, before falling back to `rest_url` (see `rest_fallback`).
+    /// The primary node is retried by the next query.
+    pub failover_grpc_urls: Vec<String>,
+    /// the cheqd nodes REST gRPC-gateway URL, used by [Transport::Rest]
+    pub rest_url: Option<String>,
+    /// how the primary node (`grpc_url`, or `rest_url` for [Transport::Rest]) is queried.
+    /// Quorum nodes are always queried over gRPC.
+    pub transport: Transport,
+    /// whether queries to the primary gRPC node which fail with a transport error (e.g. the
+    /// connection is refused or reset) are retried via `rest_url`. Requires the `rest` feature.
+    pub rest_fallback: bool,
+    /// weights spreading the network's queries across `grpc_url` & further gRPC nodes, rather
+    /// than sending them all to `grpc_url`, see [crate::resolution::load_balancing]. Applies to
+    /// [Transport::Grpc] only; quorum nodes are still sent every query.
+    pub load_balancing: Option<LoadBalancing>,
+}
+
+/// Which resource versions a `resourceVersionTime` (or `versionTime`) selects from: the newest
+/// version created strictly before the time, or at or before it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VersionTimeBoundary {
+    /// versions created strictly before the time, so a version created at exactly the time is
+    /// not selected
+    #[default]
+    Before,
+    /// versions created at or before the time, matching the cheqd TypeScript resolver
+    AtOrBefore,
+}
+
+impl VersionTimeBoundary {
+    /// Whether a version created at `created` is a candidate for the version as of `time`
+    pub fn includes(self, created: DateTime<Utc>, time: DateTime<Utc>) -> bool {
+        match self {
+            VersionTimeBoundary::Before => created < time,
+            VersionTimeBoundary::AtOrBefore => created <= time,
+        }
+    }
+}
+
+/// How a cheqd node is queried
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Transport {
+    /// gRPC over HTTP/2
+    #[default]
+    Grpc,
+    /// the Cosmos REST gRPC-gateway over HTTP/1.1, for environments where gRPC egress is blocked
+    /// (e.g. by corporate proxies). Requires the `rest` feature.
+    Rest,
 }
 
 impl Clone for NetworkConfiguration {
@@ -59,6 +324,12 @@ impl Clone for NetworkConfiguration {
         Self {
             grpc_url: self.grpc_url.clone(),
             namespace: self.namespace.clone(),
+            quorum_grpc_urls: self.quorum_grpc_urls.clone(),
+            failover_grpc_urls: self.failover_grpc_urls.clone(),
+            rest_url: self.rest_url.clone(),
+            transport: self.transport,
+            rest_fallback: self.rest_fallback,
+            load_balancing: self.load_balancing.clone(),
         }
     }
 }
@@ -67,8 +338,486 @@ impl Clone for DidCheqdResolverConfiguration {
     fn clone(&self) -> Self {
         Self {
             networks: self.networks.clone(),
+            dns: self.dns.clone(),
+            parser: self.parser,
+            namespace_aliases: self.namespace_aliases.clone(),
+            fallback_namespace: self.fallback_namespace.clone(),
+            namespace_fan_out: self.namespace_fan_out,
+            debug_capture: self.debug_capture,
+            sniff_media_types: self.sniff_media_types,
+            cache: self.cache.clone(),
+            event_handler: self.event_handler.clone(),
+            clock: self.clock.clone(),
+            fixture_mode: self.fixture_mode.clone(),
+            max_resource_size: self.max_resource_size,
+            max_response_size: self.max_response_size,
+            concurrency_limits: self.concurrency_limits,
+            version_time_boundary: self.version_time_boundary,
+            response_validation: self.response_validation,
+            user_agent: self.user_agent.clone(),
+            deployment_id: self.deployment_id.clone(),
+            key_indexes: self.key_indexes.clone(),
+            profiles: self.profiles.clone(),
+            document_transforms: self.document_transforms.clone(),
+            resource_types: self.resource_types.clone(),
+            #[cfg(feature = "validate")]
+            validation: self.validation,
+        }
+    }
+}
+
+impl DidCheqdResolverConfiguration {
+    /// A builder for a configuration, starting from the default
+    pub fn builder() -> DidCheqdResolverConfigurationBuilder {
+        DidCheqdResolverConfigurationBuilder::default()
+    }
+}
+
+/// A builder for [DidCheqdResolverConfiguration], see [DidCheqdResolverConfiguration::builder]
+#[derive(Clone, Default)]
+pub struct DidCheqdResolverConfigurationBuilder {
+    config: DidCheqdResolverConfiguration,
+}
+
+impl DidCheqdResolverConfigurationBuilder {
+    /// see [DidCheqdResolverConfiguration::networks]
+    pub fn networks(mut self, networks: Vec<NetworkConfiguration>) -> Self {
+        self.config.networks = networks;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::dns]
+    pub fn dns(mut self, dns: DnsConfiguration) -> Self {
+        self.config.dns = dns;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::parser]
+    pub fn parser(mut self, parser: ParserOptions) -> Self {
+        self.config.parser = parser;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::namespace_aliases]
+    pub fn namespace_aliases(mut self, namespace_aliases: HashMap<String, String>) -> Self {
+        self.config.namespace_aliases = namespace_aliases;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::fallback_namespace]
+    pub fn fallback_namespace(mut self, fallback_namespace: impl Into<String>) -> Self {
+        self.config.fallback_namespace = Some(fallback_namespace.into());
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::namespace_fan_out]
+    pub fn namespace_fan_out(mut self, namespace_fan_out: bool) -> Self {
+        self.config.namespace_fan_out = namespace_fan_out;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::debug_capture]
+    pub fn debug_capture(mut self, debug_capture: usize) -> Self {
+        self.config.debug_capture = debug_capture;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::sniff_media_types]
+    pub fn sniff_media_types(mut self, sniff_media_types: bool) -> Self {
+        self.config.sniff_media_types = sniff_media_types;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::cache]
+    pub fn cache(mut self, cache: CacheConfiguration) -> Self {
+        self.config.cache = Some(cache);
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::event_handler]
+    pub fn event_handler(mut self, event_handler: Arc<dyn ResolverEventHandler>) -> Self {
+        self.config.event_handler = Some(event_handler);
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::clock]
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.config.clock = clock;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::fixture_mode]
+    pub fn fixture_mode(mut self, fixture_mode: FixtureMode) -> Self {
+        self.config.fixture_mode = fixture_mode;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::max_resource_size]
+    pub fn max_resource_size(mut self, max_resource_size: usize) -> Self {
+        self.config.max_resource_size = Some(max_resource_size);
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::max_response_size]
+    pub fn max_response_size(mut self, max_response_size: usize) -> Self {
+        self.config.max_response_size = max_response_size;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::concurrency_limits]
+    pub fn concurrency_limits(mut self, concurrency_limits: ConcurrencyLimits) -> Self {
+        self.config.concurrency_limits = concurrency_limits;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::version_time_boundary]
+    pub fn version_time_boundary(mut self, version_time_boundary: VersionTimeBoundary) -> Self {
+        self.config.version_time_boundary = version_time_boundary;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::response_validation]
+    pub fn response_validation(mut self, response_validation: ResponseValidation) -> Self {
+        self.config.response_validation = response_validation;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::user_agent]
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.config.user_agent = user_agent.into();
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::deployment_id]
+    pub fn deployment_id(mut self, deployment_id: impl Into<String>) -> Self {
+        self.config.deployment_id = Some(deployment_id.into());
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::key_indexes]
+    pub fn key_indexes(mut self, key_indexes: Vec<Arc<dyn KeyIndex>>) -> Self {
+        self.config.key_indexes = key_indexes;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::profiles]
+    pub fn profiles(mut self, profiles: HashMap<String, DidCheqdResolverConfiguration>) -> Self {
+        self.config.profiles = profiles;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::document_transforms]
+    pub fn document_transforms(
+        mut self,
+        document_transforms: Vec<Arc<dyn DocumentTransform>>,
+    ) -> Self {
+        self.config.document_transforms = document_transforms;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::resource_types]
+    pub fn resource_types(mut self, resource_types: ResourceTypeRegistry) -> Self {
+        self.config.resource_types = resource_types;
+        self
+    }
+
+    /// see [DidCheqdResolverConfiguration::validation]
+    #[cfg(feature = "validate")]
+    pub fn validation(mut self, validation: ValidationMode) -> Self {
+        self.config.validation = validation;
+        self
+    }
+
+    pub fn build(self) -> DidCheqdResolverConfiguration {
+        self.config
+    }
+}
+
+impl NetworkConfiguration {
+    /// A builder for a network's configuration, starting from the default (with neither a
+    /// namespace nor a gRPC URL)
+    pub fn builder() -> NetworkConfigurationBuilder {
+        NetworkConfigurationBuilder::default()
+    }
+}
+
+/// A builder for [NetworkConfiguration], see [NetworkConfiguration::builder]
+#[derive(Clone, Default)]
+pub struct NetworkConfigurationBuilder {
+    config: NetworkConfiguration,
+}
+
+impl NetworkConfigurationBuilder {
+    /// see [NetworkConfiguration::grpc_url]
+    pub fn grpc_url(mut self, grpc_url: impl Into<String>) -> Self {
+        self.config.grpc_url = grpc_url.into();
+        self
+    }
+
+    /// see [NetworkConfiguration::namespace]
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.config.namespace = namespace.into();
+        self
+    }
+
+    /// see [NetworkConfiguration::quorum_grpc_urls]
+    pub fn quorum_grpc_urls(mut self, quorum_grpc_urls: Vec<String>) -> Self {
+        self.config.quorum_grpc_urls = quorum_grpc_urls;
+        self
+    }
+
+    /// see [NetworkConfiguration::failover_grpc_urls]
+    pub fn failover_grpc_urls(mut self, failover_grpc_urls: Vec<String>) -> Self {
+        self.config.failover_grpc_urls = failover_grpc_urls;
+        self
+    }
+
+    /// see [NetworkConfiguration::rest_url]
+    pub fn rest_url(mut self, rest_url: impl Into<String>) -> Self {
+        self.config.rest_url = Some(rest_url.into());
+        self
+    }
+
+    /// see [NetworkConfiguration::transport]
+    pub fn transport(mut self, transport: Transport) -> Self {
+        self.config.transport = transport;
+        self
+    }
+
+    /// see [NetworkConfiguration::rest_fallback]
+    pub fn rest_fallback(mut self, rest_fallback: bool) -> Self {
+        self.config.rest_fallback = rest_fallback;
+        self
+    }
+
+    /// see [NetworkConfiguration::load_balancing]
+    pub fn load_balancing(mut self, load_balancing: LoadBalancing) -> Self {
+        self.config.load_balancing = Some(load_balancing);
+        self
+    }
+
+    pub fn build(self) -> NetworkConfiguration {
+        self.config
+    }
+}
+
+impl DidCheqdResolverConfiguration {
+    /// Build a configuration from environment variables, starting from the default.
+    ///
+    /// - `DID_CHEQD_NETWORKS`: comma separated `namespace=grpc_url` entries, replacing the
+    ///   default networks, e.g. `mainnet=https://grpc.cheqd.net:443,testnet=http://localhost:9090`
+    /// - `DID_CHEQD_FALLBACK_NAMESPACE`: see [Self::fallback_namespace]
+    /// - `DID_CHEQD_REQUIRE_NAMESPACE`: `true` or `false`, see [ParserOptions::require_namespace]
+    /// - `DID_CHEQD_DEBUG_CAPTURE`: see [Self::debug_capture]
+    /// - `DID_CHEQD_FIXTURE_MODE`: `off`, `record:<dir>` or `replay:<dir>`, see
+    ///   [Self::fixture_mode]
+    /// - `DID_CHEQD_MAX_RESOURCE_SIZE`: a number of bytes, see [Self::max_resource_size]
+    /// - `DID_CHEQD_MAX_RESPONSE_SIZE`: a number of bytes, see [Self::max_response_size]
+    /// - `DID_CHEQD_USER_AGENT`: see [Self::user_agent]
+    /// - `DID_CHEQD_DEPLOYMENT_ID`: see [Self::deployment_id]
+    ///
+    /// Returns [DidCheqdError::BadConfiguration] if a variable is set to an invalid value.
+    pub fn from_env() -> DidCheqdResult<Self> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> DidCheqdResult<Self> {
+        let mut config = Self::default();
+        let bad = |name: &str, value: &str, expected: &str| {
+            DidCheqdError::BadConfiguration(format!(
+                "invalid {name} `{value}`; expected {expected}"
+            ))
+        };
+
+        if let Some(networks) = var(ENV_NETWORKS) {
+            config.networks = networks
+                .split(',')
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty())
+                .map(|entry| {
+                    let (namespace, grpc_url) = entry
+                        .split_once('=')
+                        .ok_or_else(|| bad(ENV_NETWORKS, entry, "`namespace=grpc_url`"))?;
+                    let network = NetworkConfiguration {
+                        grpc_url: grpc_url.trim().to_owned(),
+                        namespace: namespace.trim().to_owned(),
+                        ..Default::default()
+                    };
+                    network.validate()?;
+                    Ok(network)
+                })
+                .collect::<DidCheqdResult<_>>()?;
+        }
+        if let Some(namespace) = var(ENV_FALLBACK_NAMESPACE) {
+            config.fallback_namespace = Some(namespace);
+        }
+        if let Some(require) = var(ENV_REQUIRE_NAMESPACE) {
+            config.parser.require_namespace = require
+                .parse()
+                .map_err(|_| bad(ENV_REQUIRE_NAMESPACE, &require, "`true` or `false`"))?;
+        }
+        if let Some(capture) = var(ENV_DEBUG_CAPTURE) {
+            config.debug_capture = capture
+                .parse()
+                .map_err(|_| bad(ENV_DEBUG_CAPTURE, &capture, "a number of queries"))?;
+        }
+        if let Some(mode) = var(ENV_FIXTURE_MODE) {
+            config.fixture_mode = mode.parse()?;
+        }
+        if let Some(user_agent) = var(ENV_USER_AGENT) {
+            if !is_header_value(&user_agent) {
+                return Err(bad(ENV_USER_AGENT, &user_agent, "a valid header value"));
+            }
+            config.user_agent = user_agent;
+        }
+        if let Some(deployment_id) = var(ENV_DEPLOYMENT_ID) {
+            if !is_header_value(&deployment_id) {
+                return Err(bad(
+                    ENV_DEPLOYMENT_ID,
+                    &deployment_id,
+                    "a valid header value",
+                ));
+            }
+            config.deployment_id = Some(deployment_id);
+        }
+        if let Some(size) = var(ENV_MAX_RESOURCE_SIZE) {
+            config.max_resource_size = Some(
+                size.parse()
+                    .map_err(|_| bad(ENV_MAX_RESOURCE_SIZE, &size, "a number of bytes"))?,
+            );
+        }
+        if let Some(size) = var(ENV_MAX_RESPONSE_SIZE) {
+            config.max_response_size = size
+                .parse()
+                .map_err(|_| bad(ENV_MAX_RESPONSE_SIZE, &size, "a number of bytes"))?;
+        }
+        Ok(config)
+    }
+}
+
+impl DidCheqdResolverConfiguration {
+    /// Validate the whole configuration, returning a [DidCheqdError::BadConfiguration] listing
+    /// every problem found, see [DidCheqdResolver::try_new]:
+    /// - there must be at least one network, each valid (see [NetworkConfiguration::validate])
+    ///   and with a namespace no other network has
+    /// - [Self::namespace_aliases] & [Self::fallback_namespace] must refer to configured
+    ///   networks, and aliases mustn't shadow them
+    /// - sizes must be non-zero, and TTLs & delays small enough to add to the current time
+    /// - the `user-agent` (with [Self::deployment_id]) must be a valid header value
+    /// - each of the [Self::profiles] must be valid too
+    pub fn validate(&self) -> DidCheqdResult<()> {
+        let problems = self.problems();
+        if problems.is_empty() {
+            return Ok(());
+        }
+        Err(DidCheqdError::BadConfiguration(problems.join("; ")))
+    }
+
+    fn problems(&self) -> Vec<String> {
+        let mut problems = network_problems(
+            &self.networks,
+            &self.namespace_aliases,
+            self.fallback_namespace.as_deref(),
+        );
+        if self.max_response_size == 0 {
+            problems.push("max_response_size must be non-zero".to_owned());
+        }
+        if self.max_resource_size == Some(0) {
+            problems.push("max_resource_size must be non-zero".to_owned());
+        }
+        let mut durations = vec![("dns.happy_eyeballs_delay", self.dns.happy_eyeballs_delay)];
+        if let Some(cache) = &self.cache {
+            durations.extend([
+                ("cache.immutable_ttl", cache.immutable_ttl),
+                ("cache.latest_ttl", Some(cache.latest_ttl)),
+                ("cache.stale_while_revalidate", cache.stale_while_revalidate),
+                ("cache.stale_if_error", cache.stale_if_error),
+                ("cache.collection_ttl", cache.collection_ttl),
+            ]);
+        }
+        let now = Instant::now();
+        for (name, duration) in durations {
+            match duration {
+                // deadlines are computed by adding durations to the current time
+                Some(duration) if now.checked_add(duration).is_none() => {
+                    problems.push(format!("{name} of {duration:?} is too long"));
+                }
+                _ => {}
+            }
+        }
+
+        let user_agent = match &self.deployment_id {
+            Some(id) => format!("{} ({id})", self.user_agent),
+            None => self.user_agent.clone(),
+        };
+        if !is_header_value(&user_agent) {
+            problems.push(format!(
+                "user agent `{user_agent}` is not a valid header value"
+            ));
+        }
+
+        let mut profiles: Vec<_> = self.profiles.iter().collect();
+        profiles.sort_by_key(|(name, _)| *name);
+        for (name, profile) in profiles {
+            problems.extend(
+                profile
+                    .problems()
+                    .into_iter()
+                    .map(|problem| format!("profile `{name}`: {problem}")),
+            );
+        }
+        problems
+    }
+}
+
+/// The problems of a set of networks, with the namespace aliases & fallback namespace referring
+/// to them, see [DidCheqdResolverConfiguration::validate]
+fn network_problems(
+    networks: &[NetworkConfiguration],
+    namespace_aliases: &HashMap<String, String>,
+    fallback_namespace: Option<&str>,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    if networks.is_empty() {
+        problems.push("no networks are configured".to_owned());
+    }
+    let mut namespaces = HashSet::new();
+    for network in networks {
+        if let Err(e) = network.validate() {
+            problems.push(format!("network `{}`: {}", network.namespace, e));
+        }
+        if !namespaces.insert(network.namespace.as_str()) {
+            problems.push(format!(
+                "network `{}` is configured more than once",
+                network.namespace
+            ));
         }
     }
+
+    let mut aliases: Vec<_> = namespace_aliases.iter().collect();
+    aliases.sort();
+    for (alias, target) in aliases {
+        if namespaces.contains(alias.as_str()) {
+            problems.push(format!(
+                "namespace alias `{alias}` shadows the configured network `{alias}`"
+            ));
+        }
+        if !namespaces.contains(target.as_str()) {
+            problems.push(format!(
+                "namespace alias `{alias}` refers to `{target}`, which isn't a configured \
+                 network"
+            ));
+        }
+    }
+    if let Some(fallback) = fallback_namespace {
+        if !namespaces.contains(fallback) {
+            problems.push(format!(
+                "fallback namespace `{fallback}` isn't a configured network"
+            ));
+        }
+    }
+
+    problems
 }
 
 impl NetworkConfiguration {
@@ -77,6 +826,12 @@ impl NetworkConfiguration {
         Self {
             grpc_url: String::from(MAINNET_DEFAULT_GRPC),
             namespace: String::from(MAINNET_NAMESPACE),
+            quorum_grpc_urls: Vec::new(),
+            failover_grpc_urls: Vec::new(),
+            rest_url: Some(String::from(MAINNET_DEFAULT_REST)),
+            transport: Transport::Grpc,
+            rest_fallback: false,
+            load_balancing: None,
         }
     }
 
@@ -85,372 +840,3695 @@ impl NetworkConfiguration {
         Self {
             grpc_url: String::from(TESTNET_DEFAULT_GRPC),
             namespace: String::from(TESTNET_NAMESPACE),
+            quorum_grpc_urls: Vec::new(),
+            failover_grpc_urls: Vec::new(),
+            rest_url: Some(String::from(TESTNET_DEFAULT_REST)),
+            transport: Transport::Grpc,
+            rest_fallback: false,
+            load_balancing: None,
+        }
+    }
+
+    /// Validate the configuration, returning a [DidCheqdError::BadConfiguration] describing the
+    /// first problem found.
+    ///
+    /// The namespace must be alphanumeric, and each gRPC & REST URL must be an absolute `http` or
+    /// `https` URL with a valid host (IPv6 literals in brackets, e.g. `https://[::1]:9090`) and
+    /// port. [Transport::Rest] & `rest_fallback` require a `rest_url`, and the `rest` feature.
+    pub fn validate(&self) -> DidCheqdResult<()> {
+        if self.namespace.is_empty() || !self.namespace.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(DidCheqdError::BadConfiguration(format!(
+                "invalid namespace `{}`; expected a non-empty alphanumeric string",
+                self.namespace
+            )));
+        }
+        std::iter::once(&self.grpc_url)
+            .chain(&self.quorum_grpc_urls)
+            .chain(&self.failover_grpc_urls)
+            .try_for_each(|url| validate_grpc_url(url))?;
+        if let Some(rest_url) = &self.rest_url {
+            validate_node_url(rest_url, "REST")?;
+        }
+        if let Some(load_balancing) = &self.load_balancing {
+            let weighted = load_balancing.weighted_urls(&self.grpc_url);
+            weighted
+                .iter()
+                .skip(1)
+                .try_for_each(|(url, _)| validate_grpc_url(url))?;
+            if weighted.iter().all(|(_, weight)| *weight == 0) {
+                return Err(DidCheqdError::BadConfiguration(format!(
+                    "load balancing of network `{}` needs a node with a non-zero weight",
+                    self.namespace
+                )));
+            }
+            if self.transport != Transport::Grpc {
+                return Err(DidCheqdError::BadConfiguration(format!(
+                    "load balancing of network `{}` requires the gRPC transport",
+                    self.namespace
+                )));
+            }
+        }
+        if self.transport == Transport::Rest || self.rest_fallback {
+            if !cfg!(feature = "rest") {
+                return Err(DidCheqdError::BadConfiguration(
+                    "the REST transport requires the `rest` feature".into(),
+                ));
+            }
+            if self.rest_url.is_none() {
+                return Err(DidCheqdError::BadConfiguration(format!(
+                    "the REST transport & fallback require a `rest_url` for network `{}`",
+                    self.namespace
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// the endpoints the network is queried through: the primary node's first, then the quorum
+    /// nodes'
+    fn node_endpoints(&self) -> impl Iterator<Item = (&str, Transport)> {
+        std::iter::once((self.primary_url(), self.transport)).chain(
+            self.quorum_grpc_urls
+                .iter()
+                .map(|url| (url.as_str(), Transport::Grpc)),
+        )
+    }
+
+    /// the URL of the primary node, according to the transport
+    fn primary_url(&self) -> &str {
+        match (self.transport, &self.rest_url) {
+            (Transport::Rest, Some(rest_url)) => rest_url,
+            _ => &self.grpc_url,
+        }
+    }
+
+    /// the REST gateway URL to fall back to, if the primary node is queried over gRPC
+    fn fallback_url(&self) -> Option<&str> {
+        match self.transport {
+            Transport::Grpc if self.rest_fallback => self.rest_url.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// the endpoints to try in order when the primary gRPC node fails with a transport error:
+    /// the load balanced nodes, the failover gRPC nodes, then the REST gateway
+    fn failover_endpoints(&self) -> Vec<(&str, Transport)> {
+        if self.transport != Transport::Grpc {
+            return Vec::new();
+        }
+        let balanced = self.load_balancing.iter().flat_map(|load_balancing| {
+            let weighted = load_balancing.weighted_urls(&self.grpc_url);
+            weighted.into_iter().map(|(url, _)| url)
+        });
+        let failover = balanced.chain(self.failover_grpc_urls.iter().map(String::as_str));
+        failover
+            .map(|url| (url, Transport::Grpc))
+            .chain(self.fallback_url().map(|url| (url, Transport::Rest)))
+            .collect()
+    }
+}
+
+/// Validate a cheqd node gRPC URL, which may be a Unix domain socket URL, see
+/// [NetworkConfiguration::validate]
+/// Whether a value can be sent to nodes as a header, e.g. the `user-agent`
+fn is_header_value(value: &str) -> bool {
+    tonic::metadata::AsciiMetadataValue::try_from(value).is_ok()
+}
+
+fn validate_grpc_url(url: &str) -> DidCheqdResult<()> {
+    let Some(path) = unix_socket_path(url) else {
+        return validate_node_url(url, "gRPC");
+    };
+    let bad = |reason: &str| {
+        Err(DidCheqdError::BadConfiguration(format!(
+            "invalid gRPC URL `{url}`: {reason}"
+        )))
+    };
+    if !cfg!(unix) {
+        return bad("Unix domain sockets are only supported on Unix platforms");
+    }
+    if !path.starts_with('/') {
+        return bad("expected an absolute socket path, e.g. `unix:///run/cheqd/grpc.sock`");
+    }
+    Ok(())
+}
+
+/// Validate a cheqd node gRPC or REST URL, see [NetworkConfiguration::validate]
+fn validate_node_url(url: &str, kind: &str) -> DidCheqdResult<()> {
+    let bad = |reason: String| {
+        Err(DidCheqdError::BadConfiguration(format!(
+            "invalid {kind} URL `{url}`: {reason}"
+        )))
+    };
+
+    let uri = match url.parse::<Uri>() {
+        Ok(uri) => uri,
+        Err(e) => return bad(e.to_string()),
+    };
+    match uri.scheme_str() {
+        Some("http" | "https") => {}
+        Some(scheme) => {
+            return bad(format!(
+                "unsupported scheme `{scheme}`; expected `http` or `https`"
+            ));
+        }
+        None => return bad("missing scheme; expected `http://` or `https://`".into()),
+    }
+    let Some(authority) = uri.authority() else {
+        return bad("missing host".into());
+    };
+    let host = authority.host();
+    if host.is_empty() {
+        return bad("missing host".into());
+    }
+    if let Some(ipv6) = host.strip_prefix('[').and_then(|h| h.strip_suffix(']')) {
+        if ipv6.parse::<Ipv6Addr>().is_err() {
+            return bad(format!("invalid IPv6 address `{ipv6}`"));
+        }
+    } else if host.contains(':') {
+        return bad("IPv6 addresses must be enclosed in brackets, e.g. `[::1]`".into());
+    }
+    let port = authority
+        .as_str()
+        .rsplit_once(host)
+        .and_then(|(_, rest)| rest.strip_prefix(':'));
+    if let Some(port) = port {
+        if port.parse::<u16>().is_err() {
+            return bad(format!("invalid port `{port}`"));
+        }
+    }
+    Ok(())
+}
+
+/// A DID document resolved from the ledger
+#[derive(Debug, Clone)]
+pub struct ResolvedDidDoc {
+    pub did_doc: DidDoc,
+    pub metadata: Option<DidDocMetadata>,
+    /// the transport which served the DID document
+    pub transport: Transport,
+    /// whether the DID document was served from the cache after expiring, see
+    /// [CacheConfiguration::stale_while_revalidate] & [CacheConfiguration::stale_if_error]
+    pub stale: bool,
+    /// where & how the DID document was obtained
+    pub provenance: Provenance,
+    /// the fields found missing from the node's response & filled with defaults, see
+    /// [DidCheqdResolverConfiguration::response_validation]
+    pub warnings: Vec<String>,
+}
+
+/// A resource resolved from the ledger
+#[derive(Debug, Clone)]
+pub struct ResolvedResource {
+    pub data: Vec<u8>,
+    pub metadata: CheqdResourceMetadata,
+    /// the transport which served the resource
+    pub transport: Transport,
+    /// where & how the resource was obtained
+    pub provenance: Provenance,
+    /// the fields found missing from the node's response & filled with defaults, see
+    /// [DidCheqdResolverConfiguration::response_validation]
+    pub warnings: Vec<String>,
+}
+
+/// Where & how a resolution result was obtained, for diagnosing resolutions in production
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Provenance {
+    /// the URL of the node endpoint which served the result, if it was queried from a node
+    /// (rather than replayed from fixtures or loaded from the disk cache). For cached results,
+    /// this is the endpoint which originally served it.
+    pub endpoint: Option<String>,
+    /// whether the result was served from the cache
+    pub cached: bool,
+    /// how many times the query was retried, e.g. via the REST fallback
+    pub retries: u32,
+    /// how long the resolution took
+    pub elapsed: Duration,
+}
+
+/// A client for a single cheqd node endpoint
+#[derive(Clone)]
+struct CheqdNodeClient {
+    url: String,
+    transport: NodeTransport,
+}
+
+// the gRPC clients are cloned for every query, so are kept inline rather than boxed
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone)]
+enum NodeTransport {
+    Grpc {
+        did: DidQueryClient<Channel>,
+        resources: ResourceQueryClient<Channel>,
+    },
+    #[cfg(feature = "rest")]
+    Rest(RestClient),
+}
+
+impl CheqdNodeClient {
+    fn transport(&self) -> Transport {
+        match self.transport {
+            NodeTransport::Grpc { .. } => Transport::Grpc,
+            #[cfg(feature = "rest")]
+            NodeTransport::Rest(_) => Transport::Rest,
+        }
+    }
+
+    async fn did_doc(self, request: QueryDidDocRequest) -> Result<QueryDidDocResponse, Status> {
+        match self.transport {
+            NodeTransport::Grpc { mut did, .. } => did
+                .did_doc(grpc_request(request))
+                .await
+                .map(tonic::Response::into_inner),
+            #[cfg(feature = "rest")]
+            NodeTransport::Rest(rest) => rest.did_doc(request).await,
+        }
+    }
+
+    async fn did_doc_version(
+        self,
+        request: QueryDidDocVersionRequest,
+    ) -> Result<QueryDidDocVersionResponse, Status> {
+        match self.transport {
+            NodeTransport::Grpc { mut did, .. } => did
+                .did_doc_version(grpc_request(request))
+                .await
+                .map(tonic::Response::into_inner),
+            #[cfg(feature = "rest")]
+            NodeTransport::Rest(rest) => rest.did_doc_version(request).await,
+        }
+    }
+
+    /// Query a resource, failing as soon as the response exceeds `max_size` bytes, before it's
+    /// buffered whole
+    async fn resource(
+        self,
+        request: QueryResourceRequest,
+        max_size: usize,
+    ) -> Result<QueryResourceResponse, Status> {
+        match self.transport {
+            NodeTransport::Grpc { resources, .. } => resources
+                .max_decoding_message_size(max_size)
+                .resource(grpc_request(request))
+                .await
+                .map(tonic::Response::into_inner),
+            #[cfg(feature = "rest")]
+            NodeTransport::Rest(rest) => rest.resource(request, max_size).await,
         }
     }
+
+    async fn collection_resources(
+        self,
+        request: QueryCollectionResourcesRequest,
+    ) -> Result<QueryCollectionResourcesResponse, Status> {
+        match self.transport {
+            NodeTransport::Grpc { mut resources, .. } => resources
+                .collection_resources(grpc_request(request))
+                .await
+                .map(tonic::Response::into_inner),
+            #[cfg(feature = "rest")]
+            NodeTransport::Rest(rest) => rest.collection_resources(request).await,
+        }
+    }
+}
+
+/// A resolver for did:cheqd DIDs & DID URLs, querying the configured cheqd networks.
+///
+/// The resolver is a cheap handle to shared state (its connections, cache & configuration), so
+/// it can be cloned into each task, request handler or service using it, rather than wrapped in
+/// an `Arc`. Clones share their state, e.g. [DidCheqdResolver::shutdown] shuts them all down.
+///
+/// Errors of resolutions are returned as [DidCheqdError::WithContext], naming the DID, network,
+/// node & operation which failed; the underlying error is [DidCheqdError::root].
+#[derive(Clone)]
+pub struct DidCheqdResolver {
+    inner: Arc<ResolverInner>,
+}
+
+// handles are shared between tasks & threads
+const _: fn() = || {
+    fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+    assert_shareable::<DidCheqdResolver>();
+};
+
+/// The state of a [DidCheqdResolver], shared by its clones & background tasks
+struct ResolverInner {
+    /// the configured networks, replaced by [DidCheqdResolver::reload_networks]
+    networks: RwLock<Arc<Vec<NetworkConfiguration>>>,
+    dns: DnsConfiguration,
+    parser: ParserOptions,
+    namespace_aliases: HashMap<String, String>,
+    fallback_namespace: Option<String>,
+    namespace_fan_out: bool,
+    debug_capture: DebugCapture,
+    sniff_media_types: bool,
+    cache: Option<ResolutionCache>,
+    event_handler: Option<Arc<dyn ResolverEventHandler>>,
+    clock: Arc<dyn Clock>,
+    fixture_mode: FixtureMode,
+    max_resource_size: Option<usize>,
+    max_response_size: usize,
+    query_permits: QueryPermits,
+    balancer: Balancer,
+    latencies: LatencyTracker,
+    version_time_boundary: VersionTimeBoundary,
+    response_validation: ResponseValidation,
+    /// the `user-agent` sent to nodes, including the deployment id
+    user_agent: String,
+    key_indexes: Vec<Arc<dyn KeyIndex>>,
+    profiles: HashMap<String, DidCheqdResolver>,
+    document_transforms: Vec<Arc<dyn DocumentTransform>>,
+    resource_types: ResourceTypeRegistry,
+    node_clients: Mutex<NodeClients>,
+    in_flight: InFlight,
+    counters: Counters,
+}
+
+/// The connected node clients, keyed by endpoint URL (& transport) so networks configured with
+/// the same node share its connection, with the endpoints each network is queried through
+#[derive(Default)]
+struct NodeClients {
+    by_endpoint: HashMap<(String, Transport), CheqdNodeClient>,
+    /// each network's endpoints, by namespace: the primary node's first, then the quorum nodes'
+    network_endpoints: HashMap<String, Vec<(String, Transport)>>,
 }
 
-#[derive(Clone)]
-struct CheqdGrpcClient {
-    did: DidQueryClient<Channel>,
-    resources: ResourceQueryClient<Channel>,
-}
+impl NodeClients {
+    /// The clients of a network's endpoints, if all are connected
+    fn for_network(&self, namespace: &str) -> Option<Vec<CheqdNodeClient>> {
+        let endpoints = self.network_endpoints.get(namespace)?;
+        let clients = endpoints
+            .iter()
+            .map(|endpoint| self.by_endpoint.get(endpoint).cloned());
+        clients.collect()
+    }
+
+    fn clear(&mut self) {
+        self.by_endpoint.clear();
+        self.network_endpoints.clear();
+    }
+}
+
+/// The queries in flight, so [DidCheqdResolver::shutdown] can wait for them
+#[derive(Default)]
+struct InFlight {
+    closed: AtomicBool,
+    count: AtomicUsize,
+    drained: Notify,
+}
+
+/// A query in flight, until dropped
+struct InFlightGuard<'a>(&'a InFlight);
+
+impl InFlight {
+    fn is_closed(&self) -> bool {
+        self.closed.load(AtomicOrdering::SeqCst)
+    }
+
+    /// Start a query, unless the resolver has been shut down
+    fn start(&self) -> DidCheqdResult<InFlightGuard<'_>> {
+        self.count.fetch_add(1, AtomicOrdering::SeqCst);
+        let guard = InFlightGuard(self);
+        match self.is_closed() {
+            true => Err(DidCheqdError::ShutDown),
+            false => Ok(guard),
+        }
+    }
+
+    /// Stop new queries from starting, then wait for those in flight to finish
+    async fn drain(&self) {
+        self.closed.store(true, AtomicOrdering::SeqCst);
+        loop {
+            let drained = self.drained.notified();
+            tokio::pin!(drained);
+            drained.as_mut().enable();
+            if self.count.load(AtomicOrdering::SeqCst) == 0 {
+                return;
+            }
+            drained.await;
+        }
+    }
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        if self.0.count.fetch_sub(1, AtomicOrdering::SeqCst) == 1 {
+            self.0.drained.notify_waiters();
+        }
+    }
+}
+
+// Note: we intentionally avoid depending on external `did_resolver` types here.
+// This module exposes string-based resolution helpers that return proto results
+// or raw bytes + media type so callers can transform them into the desired
+// in-repo types without importing the external did_resolver crate.
+
+impl DidCheqdResolver {
+    /// Assemble a new resolver with the given config, after validating it (see
+    /// [DidCheqdResolverConfiguration::validate]), so misconfigurations fail up front with a
+    /// [DidCheqdError::BadConfiguration] rather than with the first queries.
+    pub fn try_new(configuration: DidCheqdResolverConfiguration) -> DidCheqdResult<Self> {
+        configuration.validate()?;
+        Ok(Self::new(configuration))
+    }
+
+    /// Assemble a new resolver with the given config.
+    ///
+    /// [DidCheqdResolverConfiguration::default] can be used if default mainnet & testnet
+    /// configurations are suitable. The configuration is not validated, so problems
+    /// surface as errors of the queries they affect; see [DidCheqdResolver::try_new].
+    pub fn new(configuration: DidCheqdResolverConfiguration) -> Self {
+        let inner = ResolverInner {
+            networks: RwLock::new(Arc::new(configuration.networks)),
+            dns: configuration.dns,
+            parser: configuration.parser,
+            namespace_aliases: configuration.namespace_aliases,
+            fallback_namespace: configuration.fallback_namespace,
+            namespace_fan_out: configuration.namespace_fan_out,
+            debug_capture: DebugCapture::new(configuration.debug_capture),
+            sniff_media_types: configuration.sniff_media_types,
+            cache: configuration.cache.map(ResolutionCache::new),
+            event_handler: configuration.event_handler,
+            clock: configuration.clock,
+            fixture_mode: configuration.fixture_mode,
+            max_resource_size: configuration.max_resource_size,
+            max_response_size: configuration.max_response_size,
+            query_permits: QueryPermits::new(configuration.concurrency_limits),
+            balancer: Balancer::default(),
+            latencies: LatencyTracker::default(),
+            version_time_boundary: configuration.version_time_boundary,
+            response_validation: configuration.response_validation,
+            user_agent: match configuration.deployment_id {
+                Some(id) => format!("{} ({id})", configuration.user_agent),
+                None => configuration.user_agent,
+            },
+            key_indexes: configuration.key_indexes,
+            profiles: configuration
+                .profiles
+                .into_iter()
+                .map(|(name, profile)| (name, DidCheqdResolver::new(profile)))
+                .collect(),
+            document_transforms: configuration.document_transforms,
+            resource_types: configuration.resource_types,
+            node_clients: Default::default(),
+            in_flight: Default::default(),
+            counters: Default::default(),
+        };
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// A process wide resolver, lazily assembled on first use with
+    /// [DidCheqdResolverConfiguration::from_env].
+    ///
+    /// Intended for small applications & FFI layers which would rather not thread a resolver
+    /// through their code. Explicitly assembled resolvers are unaffected by it. If the
+    /// environment is invalid the error is returned, and initialization is retried on the next
+    /// call.
+    #[cfg(feature = "global")]
+    pub fn global() -> DidCheqdResult<&'static DidCheqdResolver> {
+        static GLOBAL: std::sync::OnceLock<DidCheqdResolver> = std::sync::OnceLock::new();
+
+        if let Some(resolver) = GLOBAL.get() {
+            return Ok(resolver);
+        }
+        let configuration = DidCheqdResolverConfiguration::from_env()?;
+        Ok(GLOBAL.get_or_init(|| DidCheqdResolver::new(configuration)))
+    }
+
+    /// Parse a DID or DID URL according to the resolver's configured [ParserOptions]
+    pub fn parse(&self, input: &str) -> DidCheqdResult<DidCheqdParsed> {
+        DidCheqdParser::parse_with_options(input, self.inner.parser)
+    }
+
+    /// The most recent gRPC queries made by this resolver, oldest first. Empty unless
+    /// [DidCheqdResolverConfiguration::debug_capture] is enabled.
+    pub fn captured_queries(&self) -> Vec<CapturedQuery> {
+        self.inner.debug_capture.snapshot()
+    }
+
+    /// The media type of a resolved resource: its declared media type, or if it has none &
+    /// [DidCheqdResolverConfiguration::sniff_media_types] is enabled, one inferred from its
+    /// content. The flag is `true` if the media type was inferred.
+    pub fn effective_media_type(
+        &self,
+        metadata: &CheqdResourceMetadata,
+        content: &[u8],
+    ) -> Option<(String, bool)> {
+        if let Some(media_type) = resource_media_type(metadata) {
+            return Some((media_type, false));
+        }
+        if !self.inner.sniff_media_types {
+            return None;
+        }
+        sniff_media_type(content).map(|media_type| (media_type.to_owned(), true))
+    }
+
+    /// Shut the resolver down, e.g. when the embedding service is stopping: new resolutions &
+    /// queries fail with [DidCheqdError::ShutDown], queries already sent to nodes are waited for,
+    /// then the connections to nodes are closed. Every handle sharing this resolver's state is
+    /// shut down, as are its profiles, and background cache refreshes end with their queries.
+    pub async fn shutdown(&self) {
+        let mut resolvers = vec![self];
+        let mut i = 0;
+        while let Some(resolver) = resolvers.get(i) {
+            resolvers.extend(resolver.inner.profiles.values());
+            i += 1;
+        }
+        join_all(resolvers.into_iter().map(|resolver| async move {
+            resolver.inner.in_flight.drain().await;
+            resolver.inner.node_clients.lock().await.clear();
+        }))
+        .await;
+    }
+
+    /// Whether [DidCheqdResolver::shutdown] has been called
+    pub fn is_shut_down(&self) -> bool {
+        self.inner.in_flight.is_closed()
+    }
+
+    /// The resolver for a configured profile, see [DidCheqdResolverConfiguration::profiles]
+    pub fn profile(&self, name: &str) -> DidCheqdResult<&DidCheqdResolver> {
+        self.inner.profiles.get(name).ok_or_else(|| {
+            DidCheqdError::BadConfiguration(format!("no profile named `{name}` is configured"))
+        })
+    }
+
+    /// The configured networks, as of the last [DidCheqdResolver::reload_networks]
+    fn networks(&self) -> Arc<Vec<NetworkConfiguration>> {
+        let networks = self
+            .inner
+            .networks
+            .read()
+            .unwrap_or_else(|e| e.into_inner());
+        networks.clone()
+    }
+
+    /// The namespaces of the configured networks, in order
+    pub(crate) fn namespaces(&self) -> Vec<String> {
+        let networks = self.networks();
+        networks
+            .iter()
+            .map(|network| network.namespace.clone())
+            .collect()
+    }
+
+    /// The configured key indexes, see [DidCheqdResolverConfiguration::key_indexes]
+    pub(crate) fn key_indexes(&self) -> &[Arc<dyn KeyIndex>] {
+        &self.inner.key_indexes
+    }
+
+    /// A snapshot of the resolver's cache, connections & activity, see [ResolverStats]. Profiles
+    /// (see [DidCheqdResolverConfiguration::profiles]) have stats of their own.
+    pub async fn stats(&self) -> ResolverStats {
+        let mut stats = ResolverStats::default();
+        if let Some(cache) = &self.inner.cache {
+            (stats.cached_did_docs, stats.cached_did_doc_bytes) = cache.did_doc_usage();
+            (stats.cached_resources, stats.cached_resource_bytes) = cache.resource_usage();
+        }
+        let node_clients = self.inner.node_clients.lock().await;
+        stats.channels = node_clients
+            .network_endpoints
+            .iter()
+            .map(|(network, endpoints)| (network.clone(), endpoints.len()))
+            .collect();
+        stats.in_flight = self.inner.in_flight.count.load(AtomicOrdering::SeqCst);
+        self.inner.counters.snapshot(&mut stats);
+        stats.endpoint_latencies = self.inner.latencies.snapshot();
+        stats
+    }
+
+    /// Drop all cached resolution results, see [DidCheqdResolverConfiguration::cache]
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.inner.cache {
+            cache.clear();
+        }
+        self.inner.profiles.values().for_each(Self::clear_cache);
+    }
+
+    /// Replace the configured networks, e.g. to move to other node endpoints, without
+    /// restarting: queries from then on use the new networks, while queries in flight finish
+    /// with the clients they started with. Connections to endpoints a network is still queried
+    /// through are kept; those of networks whose primary or quorum endpoints changed are
+    /// reconnected by their next query.
+    ///
+    /// The networks are validated as by [DidCheqdResolverConfiguration::validate] (including
+    /// that the namespace aliases & fallback namespace still refer to them); if invalid, a
+    /// [DidCheqdError::BadConfiguration] is returned & the current networks are kept. Profiles
+    /// (see [DidCheqdResolverConfiguration::profiles]) have networks of their own.
+    pub async fn reload_networks(&self, networks: Vec<NetworkConfiguration>) -> DidCheqdResult<()> {
+        let problems = network_problems(
+            &networks,
+            &self.inner.namespace_aliases,
+            self.inner.fallback_namespace.as_deref(),
+        );
+        if !problems.is_empty() {
+            return Err(DidCheqdError::BadConfiguration(problems.join("; ")));
+        }
+
+        let mut node_clients = self.inner.node_clients.lock().await;
+        let unchanged = |namespace: &String, endpoints: &Vec<(String, Transport)>| {
+            networks.iter().any(|network| {
+                network.namespace == *namespace
+                    && network.node_endpoints().eq(endpoints
+                        .iter()
+                        .map(|(url, transport)| (url.as_str(), *transport)))
+            })
+        };
+        node_clients
+            .network_endpoints
+            .retain(|namespace, endpoints| unchanged(namespace, endpoints));
+        let in_use: HashSet<_> = node_clients
+            .network_endpoints
+            .values()
+            .flatten()
+            .cloned()
+            .collect();
+        node_clients
+            .by_endpoint
+            .retain(|endpoint, _| in_use.contains(endpoint));
+        let mut configured = self
+            .inner
+            .networks
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *configured = Arc::new(networks);
+        self.inner.balancer.reset();
+        Ok(())
+    }
+
+    /// Resolve DIDs & DID URLs (see [DidCheqdResolver::resolve_did_url]) concurrently, so that
+    /// their results are cached (see [DidCheqdResolverConfiguration::cache]) & connections to
+    /// their networks are established before latency sensitive requests are served, e.g. at
+    /// startup.
+    ///
+    /// Returns the result of resolving each DID URL, in the given order.
+    pub async fn prefetch<I>(&self, did_urls: I) -> Vec<DidCheqdResult<()>>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let resolutions = did_urls
+            .into_iter()
+            .map(|did_url| async move { self.resolve_did_url(did_url.as_ref()).await.map(|_| ()) });
+        join_all(resolutions).await
+    }
+
+    /// Connect to every configured network's nodes (including quorum nodes) concurrently, so the
+    /// first resolutions don't pay for connection setup, e.g. at the startup of latency
+    /// sensitive services. Networks already connected are left as they are; in
+    /// [FixtureMode::Replay], nothing is connected.
+    ///
+    /// Returns the result of connecting to each network, by namespace, in the configured order.
+    /// Networks which failed to connect are retried by the next query to them.
+    pub async fn connect_all(&self) -> Vec<(String, DidCheqdResult<()>)> {
+        let replaying = matches!(self.inner.fixture_mode, FixtureMode::Replay(_));
+        let networks = self.networks();
+        let connections = networks.iter().map(|network| async move {
+            let namespace = network.namespace.clone();
+            if replaying {
+                return (namespace, Ok(()));
+            }
+            let connected = self.clients_for_network(&namespace).await.map(|_| ());
+            (namespace, connected)
+        });
+        join_all(connections).await
+    }
+
+    /// A gRPC client of the DID query service of the network which DIDs in the given namespace
+    /// are resolved against, for advanced users issuing queries the resolver doesn't wrap. The
+    /// client shares the resolver's connection to the network's primary node.
+    ///
+    /// Queries made with the client bypass the resolver's cache, fixtures, concurrency limits,
+    /// quorum & fallbacks, and aren't waited for by [DidCheqdResolver::shutdown]. Networks
+    /// queried over [Transport::Rest] have no gRPC client, so are a
+    /// [DidCheqdError::BadConfiguration] error.
+    pub async fn raw_did_client(&self, namespace: &str) -> DidCheqdResult<DidQueryClient<Channel>> {
+        let (did, _) = self.raw_clients(namespace).await?;
+        Ok(did)
+    }
+
+    /// A gRPC client of the resource query service of the network which DIDs in the given
+    /// namespace are resolved against, see [DidCheqdResolver::raw_did_client]
+    pub async fn raw_resource_client(
+        &self,
+        namespace: &str,
+    ) -> DidCheqdResult<ResourceQueryClient<Channel>> {
+        let (_, resources) = self.raw_clients(namespace).await?;
+        Ok(resources)
+    }
+
+    /// The gRPC clients of the network's primary node, connecting them if not already
+    async fn raw_clients(
+        &self,
+        namespace: &str,
+    ) -> DidCheqdResult<(DidQueryClient<Channel>, ResourceQueryClient<Channel>)> {
+        if self.is_shut_down() {
+            return Err(DidCheqdError::ShutDown);
+        }
+        let mut clients = self.clients_for_network(namespace).await?;
+        match clients.swap_remove(0).transport {
+            NodeTransport::Grpc { did, resources } => Ok((did, resources)),
+            #[cfg(feature = "rest")]
+            NodeTransport::Rest(_) => Err(DidCheqdError::BadConfiguration(format!(
+                "network `{namespace}` is queried over REST, so has no gRPC client"
+            ))),
+        }
+    }
+
+    /// Find the configuration of the network which DIDs in the given namespace are resolved
+    /// against, following namespace aliases & the fallback namespace.
+    pub(crate) fn network_config(&self, namespace: &str) -> DidCheqdResult<NetworkConfiguration> {
+        let networks = self.networks();
+        let find = |ns: &str| networks.iter().find(|n| n.namespace == ns).cloned();
+
+        find(namespace)
+            .or_else(|| {
+                self.inner
+                    .namespace_aliases
+                    .get(namespace)
+                    .and_then(|target| find(target))
+            })
+            .or_else(|| self.inner.fallback_namespace.as_deref().and_then(find))
+            .ok_or(DidCheqdError::NetworkNotSupported(namespace.to_owned()))
+    }
+
+    /// lazily get the clients for each of the network's endpoints, initializing if not already.
+    /// The first client is always for the network's primary `grpc_url`, unless it failed to
+    /// connect & a failover endpoint is used in its place.
+    async fn clients_for_network(&self, network: &str) -> DidCheqdResult<Vec<CheqdNodeClient>> {
+        let network_config = {
+            let node_clients = self.inner.node_clients.lock().await;
+            let network_config = self.network_config(network)?;
+            if let Some(clients) = node_clients.for_network(&network_config.namespace) {
+                return Ok(clients);
+            }
+            network_config
+        };
+        let network = network_config.namespace.as_str();
+
+        network_config.validate()?;
+
+        // connected without holding the lock, so an unreachable node doesn't hold up the
+        // connections & queries of other networks
+        let mut clients = Vec::with_capacity(1 + network_config.quorum_grpc_urls.len());
+        let mut fell_back = false;
+        for (i, (url, transport)) in network_config.node_endpoints().enumerate() {
+            let mut client = self.endpoint_client(network, url, transport).await;
+            if i == 0 {
+                for (failover_url, transport) in network_config.failover_endpoints() {
+                    if !matches!(client, Err(DidCheqdError::TransportError(_))) {
+                        break;
+                    }
+                    fell_back = true;
+                    client = self.endpoint_client(network, failover_url, transport).await;
+                }
+            }
+            let client = client.map_err(|e| {
+                e.with_context(ErrorContext {
+                    namespace: Some(network.to_owned()),
+                    endpoint: Some(url.to_owned()),
+                    ..Default::default()
+                })
+            })?;
+            clients.push(client);
+        }
+
+        // the primary node is retried by the next query, rather than failing over indefinitely
+        if fell_back {
+            return Ok(clients);
+        }
+        let endpoints: Vec<_> = clients
+            .iter()
+            .map(|client| (client.url.clone(), client.transport()))
+            .collect();
+        // the networks may have been reloaded while connecting, in which case the clients only
+        // serve this query
+        let mut node_clients = self.inner.node_clients.lock().await;
+        let reloaded = !self.network_config(network).is_ok_and(|current| {
+            current.node_endpoints().eq(endpoints
+                .iter()
+                .map(|(url, transport)| (url.as_str(), *transport)))
+        });
+        if reloaded || self.inner.in_flight.is_closed() {
+            return Ok(clients);
+        }
+        for (endpoint, client) in endpoints.iter().zip(&clients) {
+            node_clients
+                .by_endpoint
+                .entry(endpoint.clone())
+                .or_insert_with(|| client.clone());
+        }
+        node_clients
+            .network_endpoints
+            .insert(network.to_owned(), endpoints);
+
+        Ok(clients)
+    }
+
+    /// The client of a node endpoint, connecting it (without holding the lock of the connected
+    /// clients) if no network has already
+    async fn endpoint_client(
+        &self,
+        network: &str,
+        url: &str,
+        transport: Transport,
+    ) -> DidCheqdResult<CheqdNodeClient> {
+        let endpoint = (url.to_owned(), transport);
+        let connected = self.inner.node_clients.lock().await;
+        if let Some(client) = connected.by_endpoint.get(&endpoint) {
+            return Ok(client.clone());
+        }
+        drop(connected);
+        let client = match transport {
+            Transport::Grpc => connect_client(url, &self.inner).await,
+            Transport::Rest => connect_rest_client(url, &self.inner),
+        }?;
+        self.emit(|h| h.on_network_connect(network, url, transport));
+        // the connections of a resolver which was shut down while connecting are closed
+        if self.inner.in_flight.is_closed() {
+            return Ok(client);
+        }
+        // another query may have connected the endpoint meanwhile, in which case its client is
+        // shared
+        let mut connected = self.inner.node_clients.lock().await;
+        Ok(connected
+            .by_endpoint
+            .entry(endpoint)
+            .or_insert(client)
+            .clone())
+    }
+
+    /// Await a node's response to a query, recording its latency, see [LatencyTracker]
+    async fn timed<T>(
+        &self,
+        url: &str,
+        response: impl Future<Output = Result<T, Status>>,
+    ) -> Result<T, Status> {
+        let started = Instant::now();
+        let response = response.await;
+        let failed = matches!(&response, Err(status) if is_transport_failure(status));
+        self.inner.latencies.record(url, started.elapsed(), failed);
+        response
+    }
+
+    /// Run a query against the given network, returning the response from the primary endpoint,
+    /// with the transport which served it.
+    ///
+    /// In quorum mode, the query is run concurrently against all of the network's endpoints, and
+    /// the responses are compared by their canonical (protobuf encoded) hash. If the primary
+    /// endpoint fails with a transport error & the network has `rest_fallback` enabled, the query
+    /// is retried via the network's REST gateway.
+    async fn query<R, T, F, Fut>(
+        &self,
+        network: &str,
+        method: &'static str,
+        request: R,
+        query: F,
+    ) -> DidCheqdResult<(T, Transport, Provenance)>
+    where
+        R: prost::Message + Clone + Debug,
+        T: prost::Message + Default,
+        F: Fn(CheqdNodeClient, R) -> Fut,
+        Fut: Future<Output = Result<T, Status>>,
+    {
+        let _in_flight = self.inner.in_flight.start()?;
+        self.inner.counters.record_query();
+        if let FixtureMode::Replay(dir) = &self.inner.fixture_mode {
+            return self.replay_query(dir, network, method, &request);
+        }
+
+        let namespace = &self.network_config(network)?.namespace;
+        let _permit = self.inner.query_permits.acquire(namespace).await;
+        let mut clients = self.clients_for_network(network).await?;
+        let network_config = self.network_config(network)?;
+        if let Some(load_balancing) = &network_config.load_balancing {
+            let weighted = load_balancing.weighted_urls(&network_config.grpc_url);
+            let weights: Vec<_> = weighted.iter().map(|(_, weight)| *weight).collect();
+            let fastest = match load_balancing.selection {
+                EndpointSelection::Weighted => None,
+                EndpointSelection::Fastest => {
+                    self.inner.latencies.fastest(&weighted, Instant::now())
+                }
+            };
+            let next = fastest.unwrap_or_else(|| self.inner.balancer.next(namespace, &weights));
+            let (url, _) = weighted[next];
+            if clients[0].url != url {
+                match self.endpoint_client(network, url, Transport::Grpc).await {
+                    Ok(client) => clients[0] = client,
+                    // the query fails over to the other nodes below
+                    Err(DidCheqdError::TransportError(_)) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        let mut responses = join_all(
+            clients
+                .iter()
+                .map(|client| self.timed(&client.url, query(client.clone(), request.clone()))),
+        )
+        .await;
+
+        let mut retries = 0;
+        let mut tried = HashSet::from([clients[0].url.clone()]);
+        for (url, transport) in network_config.failover_endpoints() {
+            let failed = match &responses[0] {
+                Err(status) => {
+                    clients[0].transport() == Transport::Grpc && is_transport_failure(status)
+                }
+                Ok(_) => false,
+            };
+            if !failed {
+                break;
+            }
+            if !tried.insert(url.to_owned()) {
+                continue;
+            }
+            let client = match self.endpoint_client(network, url, transport).await {
+                Err(DidCheqdError::TransportError(_)) => continue,
+                client => client?,
+            };
+            responses[0] = self
+                .timed(&client.url, query(client.clone(), request.clone()))
+                .await;
+            clients[0] = client;
+            retries += 1;
+        }
+
+        if let FixtureMode::Record(dir) = &self.inner.fixture_mode {
+            let namespace = &self.network_config(network)?.namespace;
+            let response = responses[0].as_ref().map(|r| r.encode_to_vec());
+            recording::record(dir, namespace, method, &request, response)?;
+        }
+
+        if self.inner.debug_capture.is_enabled() {
+            for (client, response) in clients.iter().zip(&responses) {
+                self.inner.debug_capture.record(CapturedQuery {
+                    timestamp: self.inner.clock.now(),
+                    namespace: network.to_owned(),
+                    endpoint: client.url.clone(),
+                    method,
+                    request: format!("{request:?}"),
+                    response: response.as_ref().ok().map(|r| r.encode_to_vec()),
+                    response_debug: match response {
+                        Ok(r) => format!("{r:?}"),
+                        Err(status) => format!("{status:?}"),
+                    },
+                });
+            }
+        }
+
+        let mut responses = clients
+            .iter()
+            .zip(responses)
+            .map(|(client, response)| {
+                response.map(|r| (client.url.as_str(), r)).map_err(|e| {
+                    status_error(e).with_context(ErrorContext {
+                        namespace: Some(network.to_owned()),
+                        endpoint: Some(client.url.clone()),
+                        operation: Some(method.to_owned()),
+                        ..Default::default()
+                    })
+                })
+            })
+            .collect::<DidCheqdResult<Vec<_>>>()?;
+
+        let transport = clients[0].transport();
+        let (primary_url, primary) = responses.remove(0);
+        let provenance = Provenance {
+            endpoint: Some(primary_url.to_owned()),
+            retries,
+            ..Default::default()
+        };
+        if responses.is_empty() {
+            return Ok((primary, transport, provenance));
+        }
+
+        let primary_hash = canonical_hash(&primary);
+        for (url, response) in responses {
+            let hash = canonical_hash(&response);
+            if hash != primary_hash {
+                return Err(DidCheqdError::InconsistentResponses(format!(
+                    "{primary_url} responded with {primary_hash}, {url} responded with {hash}"
+                )));
+            }
+        }
+
+        Ok((primary, transport, provenance))
+    }
+
+    /// Answer a query from its recorded fixture, see [FixtureMode::Replay]
+    fn replay_query<T: prost::Message + Default>(
+        &self,
+        dir: &std::path::Path,
+        network: &str,
+        method: &'static str,
+        request: &impl prost::Message,
+    ) -> DidCheqdResult<(T, Transport, Provenance)> {
+        let network_config = self.network_config(network)?;
+        let context = ErrorContext {
+            namespace: Some(network_config.namespace.clone()),
+            operation: Some(method.to_owned()),
+            ..Default::default()
+        };
+        let response = recording::replay(dir, &network_config.namespace, method, request)
+            .and_then(|response| {
+                let response =
+                    response.map_err(|e| DidCheqdError::NonSuccessResponse(Box::new(e)))?;
+                T::decode(response.as_slice())
+                    .map_err(|e| DidCheqdError::InvalidResponse(format!("invalid fixture: {e}")))
+            })
+            .map_err(|e| e.with_context(context))?;
+        Ok((response, network_config.transport, Provenance::default()))
+    }
+
+    /// Query a DID Doc by a DID string (e.g. "did:cheqd:mainnet:zF7...").
+    /// Returns the raw proto DIDDoc and an optional proto metadata object. Errors are wrapped in
+    /// [DidCheqdError::WithContext], see [DidCheqdError::root].
+    pub async fn query_did_doc_by_str(
+        &self,
+        _did_str: &str,
+        parsed_did: DidCheqdParsed,
+    ) -> DidCheqdResult<(DidDoc, Option<DidDocMetadata>)> {
+        let resolved = self.resolve_did_doc(parsed_did).await?;
+        Ok((resolved.did_doc, resolved.metadata))
+    }
+
+    /// Resolve a parsed DID (optionally with a version) to its DID document, as
+    /// [DidCheqdResolver::query_did_doc_by_str], also returning the transport which served it.
+    ///
+    /// If the namespace was omitted, the DID may be resolved on every network, see
+    /// [DidCheqdResolverConfiguration::namespace_fan_out]. Errors carry the DID & network as
+    /// [DidCheqdError::WithContext].
+    pub async fn resolve_did_doc(
+        &self,
+        parsed_did: DidCheqdParsed,
+    ) -> DidCheqdResult<ResolvedDidDoc> {
+        if parsed_did.namespace_defaulted && self.inner.namespace_fan_out {
+            let resolve = |parsed| self.resolve_did_doc_on_network(parsed);
+            return self.fan_out_first(parsed_did, resolve).await;
+        }
+        // boxed, as the resolution is a large future to hold on the callers' stack
+        Box::pin(self.resolve_did_doc_on_network(parsed_did)).await
+    }
+
+    /// Resolve a parsed DID on the network of its namespace
+    async fn resolve_did_doc_on_network(
+        &self,
+        parsed_did: DidCheqdParsed,
+    ) -> DidCheqdResult<ResolvedDidDoc> {
+        let context = did_error_context(&parsed_did, "query_did_doc");
+        let did_url = match &parsed_did.version {
+            Some(version) => format!("{}?versionId={version}", parsed_did.did),
+            None => parsed_did.did.clone(),
+        };
+        let started = Instant::now();
+        let resolution = async move {
+            // parsed.namespace is an owned String; borrow as &str for client lookup
+            let network = parsed_did.namespace.as_str();
+            let did = self.ledger_did(&parsed_did)?;
+            let version = parsed_did.version.as_deref().map(ledger_id);
+            let Some(cache) = self.readable_cache() else {
+                return self.fetch_did_doc(network, did, version).await;
+            };
+            let cache_key = (did.clone(), version.clone());
+            let policy = version_policy();
+            let cached = match (&version, policy) {
+                (None, VersionPolicy::LatestOnly) => CachedDidDoc::Miss,
+                (Some(version), VersionPolicy::PreferCache) => match cache.did_doc(&cache_key) {
+                    CachedDidDoc::Miss => cache.latest_did_doc_at_version(&did, version),
+                    cached => cached,
+                },
+                _ => cache.did_doc(&cache_key),
+            };
+            let prefer_cache = policy == VersionPolicy::PreferCache;
+            let cached = match cached {
+                CachedDidDoc::Fresh(cached) => {
+                    self.cache_hit(&did, false);
+                    cached
+                }
+                CachedDidDoc::Revalidate(cached) if prefer_cache => {
+                    self.cache_hit(&did, true);
+                    self.refresh_did_doc_in_background(network, cache_key);
+                    cached
+                }
+                CachedDidDoc::Revalidate(_) | CachedDidDoc::Miss => {
+                    match self.fetch_did_doc(network, did.clone(), version).await {
+                        Err(e) if prefer_cache && is_network_failure(&e) => {
+                            let cached = cache.did_doc_if_error(&cache_key).ok_or(e)?;
+                            self.cache_hit(&did, cached.stale);
+                            cached
+                        }
+                        result => return result,
+                    }
+                }
+            };
+            Ok(ResolvedDidDoc {
+                provenance: Provenance {
+                    cached: true,
+                    ..cached.provenance
+                },
+                ..cached
+            })
+        };
+        self.observe(&did_url, resolution)
+            .await
+            .and_then(|mut resolved| {
+                for transform in &self.inner.document_transforms {
+                    transform.transform(&mut resolved.did_doc)?;
+                }
+                resolved.provenance.elapsed = started.elapsed();
+                Ok(resolved)
+            })
+            .map_err(|e| e.with_context(context))
+    }
+
+    /// The DID as stored on the ledger, with any alias resolved to the network's namespace and
+    /// a legacy upper case UUID lowercased
+    pub(crate) fn ledger_did(&self, parsed_did: &DidCheqdParsed) -> DidCheqdResult<String> {
+        let namespace = self.ledger_namespace(&parsed_did.namespace)?;
+        Ok(format!(
+            "did:cheqd:{namespace}:{}",
+            ledger_id(&parsed_did.id)
+        ))
+    }
+
+    /// The namespace of DIDs in the given namespace as stored on the ledger: the network's
+    /// namespace for an alias, otherwise the namespace itself, including for DIDs resolved
+    /// against the fallback network
+    pub(crate) fn ledger_namespace(&self, namespace: &str) -> DidCheqdResult<String> {
+        let network = self.network_config(namespace)?;
+        let aliased = self.inner.namespace_aliases.get(namespace) == Some(&network.namespace);
+        match aliased {
+            true => Ok(network.namespace),
+            false => Ok(namespace.to_owned()),
+        }
+    }
+
+    /// Fetch a DID (optionally a version of it) from the ledger, bypassing the cache but caching
+    /// the result if enabled
+    pub(crate) async fn fetch_did_doc(
+        &self,
+        network: &str,
+        did: String,
+        version: Option<String>,
+    ) -> DidCheqdResult<ResolvedDidDoc> {
+        let cache_key = (did.clone(), version.clone());
+        let (query_doc, metadata, transport, provenance) = if let Some(version) = version {
+            let request = QueryDidDocVersionRequest {
+                id: did.clone(),
+                version,
+            };
+            let (query_response, transport, provenance) = self
+                .query(
+                    network,
+                    "DidDocVersion",
+                    request,
+                    CheqdNodeClient::did_doc_version,
+                )
+                .await?;
+            let query_doc_res = query_response.value.ok_or(DidCheqdError::InvalidResponse(
+                "DIDDoc query did version not return a value".into(),
+            ))?;
+            let query_doc = query_doc_res.did_doc.ok_or(DidCheqdError::InvalidResponse(
+                "DIDDoc query did version not return a DIDDoc".into(),
+            ))?;
+
+            (query_doc, query_doc_res.metadata, transport, provenance)
+        } else {
+            let request = QueryDidDocRequest { id: did.clone() };
+            let (query_response, transport, provenance) = self
+                .query(network, "DidDoc", request, CheqdNodeClient::did_doc)
+                .await?;
+            let query_doc_res = query_response.value.ok_or(DidCheqdError::InvalidResponse(
+                "DIDDoc query did not return a value".into(),
+            ))?;
+            let query_doc = query_doc_res.did_doc.ok_or(DidCheqdError::InvalidResponse(
+                "DIDDoc query did not return a DIDDoc".into(),
+            ))?;
+
+            (query_doc, query_doc_res.metadata, transport, provenance)
+        };
+        let (mut query_doc, mut metadata) = (query_doc, metadata);
+        let warnings = validate_did_doc(
+            self.inner.response_validation,
+            &did,
+            &mut query_doc,
+            &mut metadata,
+        )?;
+
+        if query_doc.id != did {
+            return Err(DidCheqdError::InvalidResponse(format!(
+                "DIDDoc query for {did} returned a DIDDoc for {}",
+                query_doc.id
+            )));
+        }
+
+        let resolved = ResolvedDidDoc {
+            did_doc: query_doc,
+            metadata,
+            transport,
+            stale: false,
+            provenance,
+            warnings,
+        };
+        if let Some(cache) = self.writable_cache() {
+            cache.insert_did_doc(cache_key, resolved.clone());
+        }
+        Ok(resolved)
+    }
+
+    /// Refresh a cached DID document in a background task, unless it is already being refreshed.
+    /// Failures are ignored, leaving the stale DID document cached.
+    fn refresh_did_doc_in_background(&self, network: &str, cache_key: DidDocKey) {
+        let Some(cache) = &self.inner.cache else {
+            return;
+        };
+        if !cache.start_refresh(&cache_key) {
+            return;
+        }
+        let resolver = self.clone();
+        let network = network.to_owned();
+        tokio::spawn(async move {
+            let (did, version) = cache_key.clone();
+            let _ = resolver.fetch_did_doc(&network, did, version).await;
+            if let Some(cache) = &resolver.inner.cache {
+                cache.finish_refresh(&cache_key);
+            }
+        });
+    }
+
+    /// Query a DID resource by a DID URL string and return raw bytes and optional
+    /// media type. Supported forms mirror the earlier functionality:
+    /// * `did:cheqd:<namespace>:<did>/resources/<resource_id>`
+    /// * `did:cheqd:<namespace>:<did>?resourceName=...&resourceType=...&resourceVersionTime=...`
+    ///
+    /// Errors are wrapped in [DidCheqdError::WithContext], see [DidCheqdError::root].
+    pub async fn query_resource_by_str(
+        &self,
+        did_url: &str,
+        parsed_did: DidCheqdParsed,
+    ) -> DidCheqdResult<(Vec<u8>, Option<String>)> {
+        let (data, metadata) = self
+            .query_resource_with_metadata_by_str(did_url, parsed_did)
+            .await?;
+        let media_type = self
+            .effective_media_type(&metadata, &data)
+            .map(|(media_type, _)| media_type);
+        Ok((data, media_type))
+    }
+
+    /// Query a DID resource by a DID URL string, as [DidCheqdResolver::query_resource_by_str],
+    /// but return the raw bytes with the full proto resource metadata.
+    pub async fn query_resource_with_metadata_by_str(
+        &self,
+        did_url: &str,
+        parsed_did: DidCheqdParsed,
+    ) -> DidCheqdResult<(Vec<u8>, CheqdResourceMetadata)> {
+        let resolved = self.resolve_resource(did_url, parsed_did).await?;
+        Ok((resolved.data, resolved.metadata))
+    }
+
+    /// Resolve a resource DID URL, as [DidCheqdResolver::query_resource_with_metadata_by_str],
+    /// also returning the transport which served it.
+    ///
+    /// As with [DidCheqdResolver::resolve_did_doc], a DID URL with an omitted namespace may be
+    /// resolved on every network, see [DidCheqdResolverConfiguration::namespace_fan_out]. Errors
+    /// carry the DID URL & network as [DidCheqdError::WithContext].
+    pub async fn resolve_resource(
+        &self,
+        did_url: &str,
+        parsed_did: DidCheqdParsed,
+    ) -> DidCheqdResult<ResolvedResource> {
+        if parsed_did.namespace_defaulted && self.inner.namespace_fan_out {
+            let resolve = |parsed| self.resolve_resource_on_network(did_url, parsed);
+            return self.fan_out_first(parsed_did, resolve).await;
+        }
+        Box::pin(self.resolve_resource_on_network(did_url, parsed_did)).await
+    }
+
+    /// Resolve a parsed resource DID URL on the network of its namespace
+    async fn resolve_resource_on_network(
+        &self,
+        did_url: &str,
+        parsed_did: DidCheqdParsed,
+    ) -> DidCheqdResult<ResolvedResource> {
+        let context = did_error_context(&parsed_did, "query_resource");
+        let started = Instant::now();
+        let checksum = parsed_did
+            .query
+            .as_ref()
+            .and_then(|query| query.get("checksum"))
+            .cloned();
+        let fetch = async move {
+            // borrow the owned Strings for local use
+            let network = parsed_did.namespace.as_str();
+            let did_id = &ledger_id(&parsed_did.id);
+
+            // If parser injected a resourceId (from a path like /resources/<id>), resolve by id.
+            if let Some(ref qmap) = parsed_did.query {
+                if let Some(resource_id) = qmap.get("resourceId") {
+                    return self
+                        .resolve_resource_by_id(did_id, &ledger_id(resource_id), network)
+                        .await;
+                }
+            }
+
+            // Otherwise, if query parameters indicate name+type lookup, perform that
+            if let Some(qmap) = parsed_did.query {
+                let resource_name = qmap.get("resourceName");
+                let resource_type = qmap.get("resourceType");
+                let version_time = qmap.get("resourceVersionTime");
+
+                let (Some(resource_name), Some(resource_type)) = (resource_name, resource_type)
+                else {
+                    return Err(DidCheqdError::InvalidDidUrl(format!(
+                        "Resolver can only resolve by exact resource ID or name+type combination \
+                         {did_url}"
+                    )));
+                };
+
+                let Some(version_time) = version_time else {
+                    return self
+                        .resolve_latest_resource_in_collection(
+                            did_id,
+                            resource_name.as_str(),
+                            resource_type.as_str(),
+                            network,
+                        )
+                        .await;
+                };
+                let version_time = DateTime::parse_from_rfc3339(version_time)
+                    .map_err(|e| DidCheqdError::InvalidDidUrl(e.to_string()))?
+                    .to_utc();
+
+                return self
+                    .resolve_resource_by_name_type_and_time(
+                        did_id,
+                        resource_name.as_str(),
+                        resource_type.as_str(),
+                        version_time,
+                        network,
+                    )
+                    .await;
+            }
+
+            Err(DidCheqdError::InvalidDidUrl(format!(
+                "No resource path or query present: {did_url}"
+            )))
+        };
+        let resolution = async {
+            let resolved = fetch.await?;
+            if let Some(checksum) = &checksum {
+                verify_checksum(&resolved.data, checksum)?;
+            }
+            Ok(resolved)
+        };
+        self.observe(did_url, resolution)
+            .await
+            .map(|mut resolved| {
+                resolved.provenance.elapsed = started.elapsed();
+                resolved
+            })
+            .map_err(|e| e.with_context(context))
+    }
+
+    /// Resolve a resource DID URL (see [DidCheqdResolver::query_resource_by_str]) whose media
+    /// type is JSON (`application/json` or a `+json` type), parsing its content.
+    ///
+    /// Returns [DidCheqdError::UnexpectedMediaType] for resources of any other media type.
+    pub async fn resolve_resource_json(&self, did_url: &str) -> DidCheqdResult<Value> {
+        self.resolve_resource_as(did_url).await
+    }
+
+    /// Resolve a JSON resource DID URL, as [DidCheqdResolver::resolve_resource_json], and
+    /// deserialize its content into `T`.
+    pub async fn resolve_resource_as<T: DeserializeOwned>(
+        &self,
+        did_url: &str,
+    ) -> DidCheqdResult<T> {
+        let parsed = self.parse(did_url)?;
+        let context = did_error_context(&parsed, "resolve_resource_as");
+        let (data, metadata) = self
+            .query_resource_with_metadata_by_str(did_url, parsed)
+            .await?;
+        let media_type = self
+            .effective_media_type(&metadata, &data)
+            .map(|(media_type, _)| media_type)
+            .unwrap_or_default();
+        if !is_json_media_type(&media_type) {
+            return Err(DidCheqdError::UnexpectedMediaType(format!(
+                "expected a JSON resource, found `{media_type}`"
+            ))
+            .with_context(context));
+        }
+        serde_json::from_slice(&data).map_err(|e| DidCheqdError::from(e).with_context(context))
+    }
+
+    /// Resolve the latest resource with a given name & type from the collection of the given DID
+    /// (e.g. "did:cheqd:mainnet:zF7..."). The newest resource is selected by its `created` time.
+    ///
+    /// Returns the raw resource bytes and optional media type.
+    pub async fn resolve_latest_resource(
+        &self,
+        did: &str,
+        name: &str,
+        rtyp: &str,
+    ) -> DidCheqdResult<(Vec<u8>, Option<String>)> {
+        let parsed = self.parse(did)?;
+        let did_url = format!("{}?resourceName={name}&resourceType={rtyp}", parsed.did);
+        let did_id = ledger_id(&parsed.id);
+        let resolution =
+            self.resolve_latest_resource_in_collection(&did_id, name, rtyp, &parsed.namespace);
+        let resolved = self
+            .observe(&did_url, resolution)
+            .await
+            .map_err(|e| e.with_context(did_error_context(&parsed, "resolve_latest_resource")))?;
+        Ok((resolved.data, resource_media_type(&resolved.metadata)))
+    }
+
+    /// Fetch the version history of the resource with a given name & type from the collection of
+    /// the given DID (e.g. "did:cheqd:mainnet:zF7...").
+    ///
+    /// Returns the metadata of each version, ordered from oldest to newest.
+    pub async fn resource_version_history(
+        &self,
+        did: &str,
+        name: &str,
+        rtyp: &str,
+    ) -> DidCheqdResult<Vec<CheqdResourceMetadata>> {
+        let parsed = self.parse(did)?;
+        let did_id = ledger_id(&parsed.id);
+        let resources = self
+            .collection_resources(&did_id, &parsed.namespace, |_| false)
+            .await
+            .map_err(|e| e.with_context(did_error_context(&parsed, "resource_version_history")))?;
+        let versions: Vec<_> =
+            filter_resources_by_name_and_type(resources.into_iter(), name, rtyp).collect();
+        if versions.is_empty() {
+            return Err(DidCheqdError::ResourceNotFound(format!(
+                "network: {}, collection: {did_id}, name: {name}, type: {rtyp}",
+                parsed.namespace
+            )));
+        }
+
+        Ok(order_version_chain(versions))
+    }
+
+    /// Search the collections of several DIDs on a network for resources with a given name &
+    /// type, e.g. to find an AnonCreds schema by name without knowing its issuer's DID.
+    ///
+    /// The collections searched are the given collection ids, or if `None`, those of the DIDs
+    /// with DID documents or resources currently in the cache. Collections which don't exist are
+    /// skipped.
+    ///
+    /// Returns the metadata of every matching resource, by collection in the order searched,
+    /// then from newest to oldest.
+    pub async fn search_resources(
+        &self,
+        network: &str,
+        name: &str,
+        rtyp: &str,
+        collections: Option<&[String]>,
+    ) -> DidCheqdResult<Vec<CheqdResourceMetadata>> {
+        let collections = match (collections, &self.inner.cache) {
+            (Some(collections), _) => collections.to_vec(),
+            (None, Some(cache)) => cache.collections(&self.ledger_namespace(network)?),
+            (None, None) => {
+                return Err(DidCheqdError::BadConfiguration(
+                    "no collections to search were given, and caching is disabled".to_string(),
+                ));
+            }
+        };
+
+        let searches = collections.iter().map(|collection| async move {
+            match self
+                .collection_resources(collection, network, |_| false)
+                .await
+            {
+                Err(e) if e.status_code() == Some(tonic::Code::NotFound) => Ok(Vec::new()),
+                resources => resources,
+            }
+        });
+        let mut matches = Vec::new();
+        for resources in join_all(searches).await {
+            let mut found: Vec<_> =
+                filter_resources_by_name_and_type(resources?.into_iter(), name, rtyp).collect();
+            found.sort_by(desc_chronological_sort_resources);
+            matches.extend(found);
+        }
+        Ok(matches)
+    }
+
+    /// Resolve a resource from a collection (did_id) and network by an exact id.
+    async fn resolve_resource_by_id(
+        &self,
+        did_id: &str,
+        resource_id: &str,
+        network: &str,
+    ) -> DidCheqdResult<ResolvedResource> {
+        let key = |namespace| ResourceKey::ById(namespace, did_id.into(), resource_id.into());
+        self.with_resource_cache(
+            network,
+            key,
+            self.fetch_resource_by_id(did_id, resource_id, network),
+        )
+        .await
+    }
+
+    /// Fetch a resource from a collection (did_id) and network by an exact id, bypassing the cache.
+    async fn fetch_resource_by_id(
+        &self,
+        did_id: &str,
+        resource_id: &str,
+        network: &str,
+    ) -> DidCheqdResult<ResolvedResource> {
+        let request = QueryResourceRequest {
+            collection_id: did_id.to_owned(),
+            id: resource_id.to_owned(),
+        };
+        // oversized resources are cut off while they're transferred, the size of the content is
+        // checked below in case the metadata was smaller than allowed for
+        let max_size = match self.inner.max_resource_size {
+            Some(limit) => limit
+                .saturating_add(RESOURCE_METADATA_ALLOWANCE)
+                .min(self.inner.max_response_size),
+            None => self.inner.max_response_size,
+        };
+        let query = move |client: CheqdNodeClient, request| client.resource(request, max_size);
+        let (query_response, transport, provenance) = self
+            .query(network, "Resource", request, query)
+            .await
+            .map_err(|e| match (e.root(), self.inner.max_resource_size) {
+                (DidCheqdError::ResponseTooLarge(message), Some(limit))
+                    if max_size < self.inner.max_response_size =>
+                {
+                    let too_large = DidCheqdError::ResourceTooLarge(format!(
+                        "{did_id}/{resource_id} exceeds the limit of {limit} bytes: {message}"
+                    ));
+                    // keeping the context of the query, e.g. the node's endpoint
+                    match e.context() {
+                        Some(context) => too_large.with_context(context.clone()),
+                        None => too_large,
+                    }
+                }
+                _ => e,
+            })?;
+        let query_response = query_response
+            .resource
+            .ok_or(DidCheqdError::InvalidResponse(
+                "Resource query did not return a value".into(),
+            ))?;
+        let query_resource = query_response
+            .resource
+            .ok_or(DidCheqdError::InvalidResponse(
+                "Resource query did not return a resource".into(),
+            ))?;
+        let mut query_metadata = query_response
+            .metadata
+            .ok_or(DidCheqdError::InvalidResponse(
+                "Resource query did not return metadata".into(),
+            ))?;
+
+        if query_metadata.collection_id != did_id || query_metadata.id != resource_id {
+            return Err(DidCheqdError::InvalidResponse(format!(
+                "Resource query for {did_id}/{resource_id} returned resource {}/{}",
+                query_metadata.collection_id, query_metadata.id
+            )));
+        }
+        let warnings =
+            validate_resource_metadata(self.inner.response_validation, &mut query_metadata)?;
+        if let Some(limit) = self.inner.max_resource_size {
+            let size = query_resource.data.len();
+            if size > limit {
+                return Err(DidCheqdError::ResourceTooLarge(format!(
+                    "{did_id}/{resource_id} is {size} bytes, exceeding the limit of {limit} bytes"
+                )));
+            }
+        }
+
+        Ok(ResolvedResource {
+            data: query_resource.data,
+            metadata: query_metadata,
+            transport,
+            provenance,
+            warnings,
+        })
+    }
+
+    /// Resolve the newest resource from a given collection (did_id) & network, that has a given
+    /// name & type, as of the configured clock's current time.
+    async fn resolve_latest_resource_in_collection(
+        &self,
+        did_id: &str,
+        name: &str,
+        rtyp: &str,
+        network: &str,
+    ) -> DidCheqdResult<ResolvedResource> {
+        let key = |namespace| {
+            ResourceKey::ByQuery(namespace, did_id.into(), name.into(), rtyp.into(), None)
+        };
+        let now = self.inner.clock.now();
+        let resolve = async {
+            let resources = self
+                .collection_resources(did_id, network, |fetched| {
+                    let boundary = VersionTimeBoundary::AtOrBefore;
+                    version_chain_resolved(fetched, name, rtyp, Some(now), boundary)
+                })
+                .await?;
+            let not_after_now = |r: &&CheqdResourceMetadata| {
+                resource_created(r).is_none_or(|created| created <= now)
+            };
+            let resource_meta = find_latest_resource(
+                filter_resources_by_name_and_type(resources.iter(), name, rtyp)
+                    .filter(not_after_now),
+            );
+
+            let Some(meta) = resource_meta else {
+                return Err(DidCheqdError::ResourceNotFound(format!(
+                    "network: {network}, collection: {did_id}, name: {name}, type: {rtyp}"
+                )));
+            };
+
+            self.resolve_resource_by_id(did_id, &meta.id, network).await
+        };
+        self.with_resource_cache(network, key, resolve).await
+    }
+
+    /// The metadata of resources in a given collection (did_id) & network, from the cached
+    /// listing of the collection if there is one (see [CacheConfiguration::collection_ttl]),
+    /// otherwise fetched page by page.
+    ///
+    /// Paging stops early once `is_complete` returns true for the resources fetched so far,
+    /// otherwise all pages are fetched. Only complete listings are cached.
+    async fn collection_resources(
+        &self,
+        did_id: &str,
+        network: &str,
+        is_complete: impl Fn(&[CheqdResourceMetadata]) -> bool,
+    ) -> DidCheqdResult<Vec<CheqdResourceMetadata>> {
+        let Some(cache) = self.writable_cache() else {
+            let (resources, _) = self
+                .fetch_collection_resources(did_id, network, is_complete)
+                .await?;
+            return Ok(resources);
+        };
+        let key = (self.ledger_namespace(network)?, did_id.to_owned());
+        let cached = self
+            .readable_cache()
+            .map_or(CachedCollection::Miss, |cache| {
+                cache.collection_listing(&key)
+            });
+        match cached {
+            CachedCollection::Fresh(resources) => return Ok(resources.to_vec()),
+            CachedCollection::Revalidate(resources) => {
+                if self
+                    .collection_unchanged(did_id, network, &resources)
+                    .await?
+                {
+                    let listed = resources.to_vec();
+                    cache.insert_collection_listing(key, resources);
+                    return Ok(listed);
+                }
+            }
+            CachedCollection::Miss => {}
+        }
+        let (resources, complete) = self
+            .fetch_collection_resources(did_id, network, is_complete)
+            .await?;
+        if complete {
+            cache.insert_collection_listing(key, Arc::new(resources.clone()));
+        }
+        Ok(resources)
+    }
+
+    /// Whether a cached listing of a collection is still current, checked with a single query
+    /// for the collection's resource count & first listed resource. Resources can't be removed
+    /// from a collection, so any new resource changes the count.
+    async fn collection_unchanged(
+        &self,
+        did_id: &str,
+        network: &str,
+        cached: &[CheqdResourceMetadata],
+    ) -> DidCheqdResult<bool> {
+        let request = QueryCollectionResourcesRequest {
+            collection_id: did_id.to_owned(),
+            pagination: Some(PageRequest {
+                limit: 1,
+                count_total: true,
+                ..Default::default()
+            }),
+        };
+        let (query_response, ..) = self
+            .query(
+                network,
+                "CollectionResources",
+                request,
+                CheqdNodeClient::collection_resources,
+            )
+            .await?;
+        let total = query_response.pagination.map(|p| p.total);
+        let first_unchanged = query_response.resources.first().is_none_or(|listed| {
+            cached
+                .iter()
+                .any(|r| r.id == listed.id && r.created == listed.created)
+        });
+        Ok(total == Some(cached.len() as u64) && first_unchanged)
+    }
+
+    /// Fetch the metadata of resources in a given collection (did_id) & network, page by page,
+    /// returning whether all pages were fetched.
+    ///
+    /// Paging stops early once `is_complete` returns true for the resources fetched so far,
+    /// otherwise all pages are fetched.
+    async fn fetch_collection_resources(
+        &self,
+        did_id: &str,
+        network: &str,
+        is_complete: impl Fn(&[CheqdResourceMetadata]) -> bool,
+    ) -> DidCheqdResult<(Vec<CheqdResourceMetadata>, bool)> {
+        let mut resources = Vec::new();
+        let mut next_key = Vec::new();
+
+        loop {
+            let listed;
+            (listed, next_key) = self
+                .fetch_collection_page(did_id, network, next_key)
+                .await?;
+            resources.extend(listed);
+            if next_key.is_empty() {
+                return Ok((resources, true));
+            }
+            if is_complete(&resources) {
+                return Ok((resources, false));
+            }
+        }
+    }
+
+    /// Fetch a page of the metadata of resources in a given collection (did_id) & network, from
+    /// the pagination key of the previous page (empty for the first page), returning the key of
+    /// the next page (empty after the last page).
+    pub(crate) async fn fetch_collection_page(
+        &self,
+        did_id: &str,
+        network: &str,
+        key: Vec<u8>,
+    ) -> DidCheqdResult<(Vec<CheqdResourceMetadata>, Vec<u8>)> {
+        let request = QueryCollectionResourcesRequest {
+            collection_id: did_id.to_owned(),
+            pagination: Some(PageRequest {
+                key,
+                limit: COLLECTION_PAGE_SIZE,
+                ..Default::default()
+            }),
+        };
+        let (query_response, ..) = self
+            .query(
+                network,
+                "CollectionResources",
+                request,
+                CheqdNodeClient::collection_resources,
+            )
+            .await?;
+        if let Some(foreign) = query_response
+            .resources
+            .iter()
+            .find(|r| r.collection_id != did_id)
+        {
+            return Err(DidCheqdError::InvalidResponse(format!(
+                "Collection query for {did_id} returned a resource from collection {}",
+                foreign.collection_id
+            )));
+        }
+        // the resources selected are then fetched by id, recording their own warnings
+        let mut listed = query_response.resources;
+        for metadata in &mut listed {
+            validate_resource_metadata(self.inner.response_validation, metadata)?;
+        }
+        let next_key = query_response
+            .pagination
+            .map(|p| p.next_key)
+            .unwrap_or_default();
+        Ok((listed, next_key))
+    }
+
+    /// Resolve a resource from a given collection (did_id) & network, that has a given name & type,
+    /// as of a given time (see [DidCheqdResolverConfiguration::version_time_boundary]).
+    async fn resolve_resource_by_name_type_and_time(
+        &self,
+        did_id: &str,
+        name: &str,
+        rtyp: &str,
+        time: DateTime<Utc>,
+        network: &str,
+    ) -> DidCheqdResult<ResolvedResource> {
+        let key = |namespace| {
+            let time = Some(time.to_rfc3339());
+            ResourceKey::ByQuery(namespace, did_id.into(), name.into(), rtyp.into(), time)
+        };
+        let boundary = self.inner.version_time_boundary;
+        let resolve = async {
+            let resources = self
+                .collection_resources(did_id, network, |fetched| {
+                    version_chain_resolved(fetched, name, rtyp, Some(time), boundary)
+                })
+                .await?;
+            let mut filtered: Vec<_> =
+                filter_resources_by_name_and_type(resources.iter(), name, rtyp).collect();
+            filtered.sort_by(|a, b| desc_chronological_sort_resources(a, b));
+
+            let resource_meta =
+                find_resource_just_before_time(filtered.into_iter(), time, boundary);
+
+            let Some(meta) = resource_meta else {
+                return Err(DidCheqdError::ResourceNotFound(format!(
+                    "network: {network}, collection: {did_id}, name: {name}, type: {rtyp}, \
+                     time: {time}"
+                )));
+            };
+
+            self.resolve_resource_by_id(did_id, &meta.id, network).await
+        };
+        self.with_resource_cache(network, key, resolve).await
+    }
+
+    /// Return the cached resource for the key built by `key` (from the network's namespace) if
+    /// caching is enabled, otherwise `resolve` it & cache the result.
+    async fn with_resource_cache(
+        &self,
+        network: &str,
+        key: impl FnOnce(String) -> ResourceKey,
+        resolve: impl Future<Output = DidCheqdResult<ResolvedResource>>,
+    ) -> DidCheqdResult<ResolvedResource> {
+        let Some(cache) = self.writable_cache() else {
+            return resolve.await;
+        };
+        let key = key(self.ledger_namespace(network)?);
+        if let Some(mut cached) = self.readable_cache().and_then(|cache| cache.resource(&key)) {
+            self.cache_hit(&key.did_url(), false);
+            cached.provenance.cached = true;
+            return Ok(cached);
+        }
+        let resolved = resolve.await?;
+        cache.insert_resource(key, resolved.clone());
+        Ok(resolved)
+    }
+
+    /// The configured decoders of resources by `resourceType`
+    pub(crate) fn resource_types(&self) -> &ResourceTypeRegistry {
+        &self.inner.resource_types
+    }
+
+    /// The cache to serve results from, unless disabled for the current call (see
+    /// [CachePolicy])
+    fn readable_cache(&self) -> Option<&ResolutionCache> {
+        let cache = self.inner.cache.as_ref();
+        cache.filter(|_| cache_policy() == CachePolicy::Use)
+    }
+
+    /// The cache to store results in, unless disabled for the current call (see [CachePolicy])
+    fn writable_cache(&self) -> Option<&ResolutionCache> {
+        let cache = self.inner.cache.as_ref();
+        cache.filter(|_| cache_policy() != CachePolicy::Bypass)
+    }
+
+    /// Call the configured [ResolverEventHandler], if any
+    pub(crate) fn emit(&self, event: impl FnOnce(&dyn ResolverEventHandler)) {
+        if let Some(handler) = &self.inner.event_handler {
+            event(handler.as_ref());
+        }
+    }
+
+    /// Record a result served from the cache, see [ResolverEventHandler::on_cache_hit]
+    fn cache_hit(&self, did_url: &str, stale: bool) {
+        self.inner.counters.record_cache_hit();
+        self.emit(|h| h.on_cache_hit(did_url, stale));
+    }
+
+    /// Run a resolution of `did_url`, notifying the configured [ResolverEventHandler] of its
+    /// start & outcome
+    async fn observe<T>(
+        &self,
+        did_url: &str,
+        resolution: impl Future<Output = DidCheqdResult<T>>,
+    ) -> DidCheqdResult<T> {
+        if self.is_shut_down() {
+            return Err(DidCheqdError::ShutDown);
+        }
+        let handler = self.inner.event_handler.as_deref();
+        if let Some(handler) = handler {
+            handler.on_resolution_start(did_url);
+        }
+        let started = Instant::now();
+        let result = resolution.await;
+        self.inner.counters.record_resolution(result.is_ok());
+        match (handler, &result) {
+            (None, _) => {}
+            (Some(handler), Ok(_)) => handler.on_resolution_success(did_url, started.elapsed()),
+            (Some(handler), Err(e)) => handler.on_resolution_failure(did_url, e, started.elapsed()),
+        }
+        result
+    }
+}
+
+/// Error context for an operation on a parsed DID or DID URL
+pub(crate) fn did_error_context(parsed: &DidCheqdParsed, operation: &str) -> ErrorContext {
+    ErrorContext {
+        did: Some(parsed.did.clone()),
+        namespace: Some(parsed.namespace.clone()),
+        operation: Some(operation.to_owned()),
+        ..Default::default()
+    }
+}
+
+/// The media type of a resource, if present in its metadata
+fn resource_media_type(metadata: &CheqdResourceMetadata) -> Option<String> {
+    (!metadata.media_type.trim().is_empty()).then(|| metadata.media_type.clone())
+}
+
+/// whether a resolution failed because the network could not be reached
+fn is_network_failure(e: &DidCheqdError) -> bool {
+    match e.root() {
+        DidCheqdError::TransportError(_) => true,
+        DidCheqdError::NonSuccessResponse(status) => is_transport_failure(status),
+        _ => false,
+    }
+}
+
+/// whether a failed gRPC query failed to reach the node, rather than being rejected by it
+fn is_transport_failure(status: &Status) -> bool {
+    status.code() == Code::Unavailable
+        || std::error::Error::source(status).is_some_and(|e| e.is::<tonic::transport::Error>())
+}
+
+/// The error of a failed query, distinguishing responses exceeding
+/// [DidCheqdResolverConfiguration::max_response_size]
+fn status_error(status: Status) -> DidCheqdError {
+    if status.code() == Code::OutOfRange && status.message().contains("message length too large") {
+        return DidCheqdError::ResponseTooLarge(status.message().to_owned());
+    }
+    DidCheqdError::NonSuccessResponse(Box::new(status))
+}
+
+/// Create a REST gRPC-gateway client for the given cheqd node URL
+fn connect_rest_client(url: &str, inner: &ResolverInner) -> DidCheqdResult<CheqdNodeClient> {
+    #[cfg(feature = "rest")]
+    return Ok(CheqdNodeClient {
+        url: url.to_owned(),
+        transport: NodeTransport::Rest(RestClient::new(
+            url,
+            &inner.dns,
+            &inner.user_agent,
+            inner.max_response_size,
+        )?),
+    });
+    #[cfg(not(feature = "rest"))]
+    {
+        let _ = (url, inner);
+        Err(DidCheqdError::BadConfiguration(
+            "the REST transport requires the `rest` feature".into(),
+        ))
+    }
+}
+
+/// Connect a gRPC client to the given cheqd node URL
+async fn connect_client(url: &str, inner: &ResolverInner) -> DidCheqdResult<CheqdNodeClient> {
+    let dns = &inner.dns;
+    let socket_path = unix_socket_path(url);
+    // the URI of a socket endpoint only names the node in requests
+    let endpoint_url = match socket_path {
+        Some(_) => "http://localhost".to_string(),
+        None => url.to_string(),
+    };
+    let endpoint = Endpoint::new(endpoint_url)
+        .map_err(|_e| DidCheqdError::BadConfiguration("Failed to parse GRPC url".to_string()))?
+        .user_agent(inner.user_agent.as_str())
+        .map_err(|_e| {
+            DidCheqdError::BadConfiguration(format!("invalid user agent `{}`", inner.user_agent))
+        })?;
+
+    // Connect to the channel
+    let channel = match socket_path {
+        #[cfg(unix)]
+        Some(path) => {
+            endpoint
+                .connect_with_connector(UnixSocketConnector::new(path))
+                .await
+        }
+        #[cfg(not(unix))]
+        Some(_) => {
+            return Err(DidCheqdError::BadConfiguration(
+                "Unix domain sockets are only supported on Unix platforms".into(),
+            ));
+        }
+        None => {
+            let endpoint = endpoint
+                .tls_config(ClientTlsConfig::new().with_webpki_roots())
+                .map_err(|e| DidCheqdError::TransportError(Box::new(e)))?;
+            endpoint
+                .connect_with_connector(CheqdConnector::new(dns.clone()))
+                .await
+        }
+    }
+    .map_err(|e| DidCheqdError::TransportError(Box::new(e)))?;
+
+    Ok(CheqdNodeClient {
+        url: url.to_owned(),
+        transport: NodeTransport::Grpc {
+            did: DidQueryClient::new(channel.clone())
+                .max_decoding_message_size(inner.max_response_size),
+            resources: ResourceQueryClient::new(channel)
+                .max_decoding_message_size(inner.max_response_size),
+        },
+    })
+}
+
+/// Check resource content against the hex encoded SHA-256 digest given by a DID URL's `checksum`
+/// parameter
+fn verify_checksum(data: &[u8], checksum: &str) -> DidCheqdResult<()> {
+    if checksum.len() != 64 || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(DidCheqdError::InvalidDidUrl(format!(
+            "`checksum` is not a hex encoded SHA-256 digest: {checksum}"
+        )));
+    }
+    let actual = format!("{:x}", Sha256::digest(data));
+    if !actual.eq_ignore_ascii_case(checksum) {
+        return Err(DidCheqdError::ChecksumMismatch(format!(
+            "expected {checksum}, found {actual}"
+        )));
+    }
+    Ok(())
+}
+
+/// Hex encoded SHA-256 hash of the protobuf encoding of a message
+fn canonical_hash(message: &impl prost::Message) -> String {
+    format!("{:x}", Sha256::digest(message.encode_to_vec()))
+}
+
+/// Whether the resource with a given name & type, as of `before_time` (or latest if `None`), can
+/// be determined from `resources` alone, without fetching the rest of the collection.
+///
+/// Resource versions form a chain via `next_version_id`, so a matching resource created before the
+/// target time (within the `boundary`) is the answer if it has no next version, or if its next
+/// version has been fetched and was created after the target time.
+fn version_chain_resolved(
+    resources: &[CheqdResourceMetadata],
+    name: &str,
+    rtyp: &str,
+    before_time: Option<DateTime<Utc>>,
+    boundary: VersionTimeBoundary,
+) -> bool {
+    let is_before = |r: &CheqdResourceMetadata| match (before_time, resource_created(r)) {
+        (None, _) => true,
+        (Some(t), Some(created)) => boundary.includes(created, t),
+        (Some(_), None) => false,
+    };
+
+    filter_resources_by_name_and_type(resources.iter(), name, rtyp)
+        .filter(|r| is_before(r))
+        .any(|r| {
+            if r.next_version_id.is_empty() {
+                return true;
+            }
+            resources
+                .iter()
+                .find(|next| next.id == r.next_version_id)
+                .is_some_and(|next| resource_created(next).is_some() && !is_before(next))
+        })
+}
+
+/// The time a resource was created, to nanosecond precision
+fn resource_created(resource: &CheqdResourceMetadata) -> Option<DateTime<Utc>> {
+    let created = resource.created?.normalized();
+    DateTime::from_timestamp(created.seconds, created.nanos.try_into().ok()?)
+}
+
+/// Filter for resources which have a matching name and type
+fn filter_resources_by_name_and_type<'a, R: Borrow<CheqdResourceMetadata>>(
+    resources: impl Iterator<Item = R> + 'a,
+    name: &'a str,
+    rtyp: &'a str,
+) -> impl Iterator<Item = R> + 'a {
+    resources.filter(move |r| {
+        let r = r.borrow();
+        r.name == name && r.resource_type == rtyp
+    })
+}
+
+/// Order the versions of a resource from oldest to newest.
+///
+/// Versions are ordered chronologically by their created timestamps, then the chain is walked via
+/// `next_version_id` from the first version; any versions not linked into the chain (e.g. from
+/// inconsistent ledger data) are appended in chronological order.
+fn order_version_chain(mut versions: Vec<CheqdResourceMetadata>) -> Vec<CheqdResourceMetadata> {
+    versions.sort_by(|a, b| desc_chronological_sort_resources(b, a));
+
+    let Some(first) = versions
+        .iter()
+        .position(|v| v.previous_version_id.is_empty())
+    else {
+        return versions;
+    };
+
+    let mut ordered = Vec::with_capacity(versions.len());
+    let mut next = Some(versions.remove(first));
+    while let Some(current) = next {
+        next = versions
+            .iter()
+            .position(|v| v.id == current.next_version_id)
+            .map(|i| versions.remove(i));
+        ordered.push(current);
+    }
+    ordered.extend(versions);
+    ordered
+}
+
+/// Sort resources chronologically by their created timestamps
+fn desc_chronological_sort_resources(
+    b: &CheqdResourceMetadata,
+    a: &CheqdResourceMetadata,
+) -> Ordering {
+    let (a_secs, a_ns) = a
+        .created
+        .map(|v| {
+            let v = v.normalized();
+            (v.seconds, v.nanos)
+        })
+        .unwrap_or((0, 0));
+    let (b_secs, b_ns) = b
+        .created
+        .map(|v| {
+            let v = v.normalized();
+            (v.seconds, v.nanos)
+        })
+        .unwrap_or((0, 0));
+
+    match a_secs.cmp(&b_secs) {
+        Ordering::Equal => a_ns.cmp(&b_ns),
+        res => res,
+    }
+}
+
+/// find the resource with the newest `.created` time, without requiring `resources` to be sorted.
+fn find_latest_resource<'a>(
+    resources: impl Iterator<Item = &'a CheqdResourceMetadata>,
+) -> Option<&'a CheqdResourceMetadata> {
+    // the ordering is descending, so the "minimum" is the newest
+    resources.min_by(|a, b| desc_chronological_sort_resources(a, b))
+}
+
+/// assuming `resources` is sorted by `.created` time in descending order, find
+/// the resource which is closest to `before_time`, but NOT after.
+///
+/// Returns a reference to this resource if it exists.
+///
+/// e.g.:
+/// resources: [{created: 20}, {created: 15}, {created: 10}, {created: 5}]
+/// before_time: 14
+/// returns: {created: 10}
+///
+/// resources: [{created: 20}, {created: 15}, {created: 10}, {created: 5}]
+/// before_time: 4
+/// returns: None
+fn find_resource_just_before_time<'a>(
+    mut resources: impl Iterator<Item = &'a CheqdResourceMetadata>,
+    before_time: DateTime<Utc>,
+    boundary: VersionTimeBoundary,
+) -> Option<&'a CheqdResourceMetadata> {
+    resources.find(|r| {
+        resource_created(r).is_some_and(|created| boundary.includes(created, before_time))
+    })
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use crate::{
+        resolution::{clock::FixedClock, service::Resolved},
+        test_utils::{LedgerFixtures, MockCheqdNode},
+    };
+
+    fn resource_meta(id: &str, name: &str, rtyp: &str, created: i64) -> CheqdResourceMetadata {
+        CheqdResourceMetadata {
+            id: id.into(),
+            name: name.into(),
+            resource_type: rtyp.into(),
+            created: Some(prost_types::Timestamp {
+                seconds: created,
+                nanos: 0,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_version_chain_resolved() {
+        let mut v1 = resource_meta("v1", "schema", "anonCredsSchema", 10);
+        v1.next_version_id = "v2".into();
+        let v2 = resource_meta("v2", "schema", "anonCredsSchema", 20);
+        let time = |secs| DateTime::from_timestamp(secs, 0);
+
+        // latest is only known once the resource without a next version is fetched
+        assert!(!version_chain_resolved(
+            &[v1.clone()],
+            "schema",
+            "anonCredsSchema",
+            None,
+            VersionTimeBoundary::Before
+        ));
+        assert!(version_chain_resolved(
+            &[v1.clone(), v2.clone()],
+            "schema",
+            "anonCredsSchema",
+            None,
+            VersionTimeBoundary::Before
+        ));
+        // v1 is the answer for time 15 once its successor is known to be newer
+        assert!(!version_chain_resolved(
+            &[v1.clone()],
+            "schema",
+            "anonCredsSchema",
+            time(15),
+            VersionTimeBoundary::Before
+        ));
+        assert!(version_chain_resolved(
+            &[v2.clone(), v1.clone()],
+            "schema",
+            "anonCredsSchema",
+            time(15),
+            VersionTimeBoundary::Before
+        ));
+        // nothing before time 5
+        assert!(!version_chain_resolved(
+            &[v1, v2],
+            "schema",
+            "anonCredsSchema",
+            time(5),
+            VersionTimeBoundary::Before
+        ));
+    }
+
+    #[test]
+    fn test_version_time_boundary_equality() {
+        let mut v1 = resource_meta("v1", "schema", "anonCredsSchema", 10);
+        v1.next_version_id = "v2".into();
+        let v2 = resource_meta("v2", "schema", "anonCredsSchema", 20);
+        let resources = [v2.clone(), v1.clone()];
+        let at_v2 = DateTime::from_timestamp(20, 0).unwrap();
+
+        let before = VersionTimeBoundary::Before;
+        let found = find_resource_just_before_time(resources.iter(), at_v2, before);
+        assert_eq!(found.unwrap().id, "v1");
+        assert!(version_chain_resolved(
+            &resources,
+            "schema",
+            "anonCredsSchema",
+            Some(at_v2),
+            before
+        ));
+
+        let at_or_before = VersionTimeBoundary::AtOrBefore;
+        let found = find_resource_just_before_time(resources.iter(), at_v2, at_or_before);
+        assert_eq!(found.unwrap().id, "v2");
+        // v1 can't be the answer until its successor is known to be created after the time
+        assert!(!version_chain_resolved(
+            &[v1],
+            "schema",
+            "anonCredsSchema",
+            Some(at_v2),
+            at_or_before
+        ));
+    }
+
+    #[test]
+    fn test_order_version_chain() {
+        let mut v1 = resource_meta("v1", "schema", "anonCredsSchema", 10);
+        v1.next_version_id = "v2".into();
+        let mut v2 = resource_meta("v2", "schema", "anonCredsSchema", 20);
+        v2.previous_version_id = "v1".into();
+        v2.next_version_id = "v3".into();
+        let mut v3 = resource_meta("v3", "schema", "anonCredsSchema", 30);
+        v3.previous_version_id = "v2".into();
+
+        let ordered = order_version_chain(vec![v3, v1, v2]);
+        let ids: Vec<_> = ordered.iter().map(|v| v.id.as_str()).collect();
+        assert_eq!(ids, ["v1", "v2", "v3"]);
+    }
+
+    #[test]
+    fn test_latest_resource_picks_newest_matching() {
+        let resources = [
+            resource_meta("a", "schema", "anonCredsSchema", 10),
+            resource_meta("b", "schema", "anonCredsSchema", 30),
+            resource_meta("c", "other", "anonCredsSchema", 40),
+            resource_meta("d", "schema", "anonCredsSchema", 20),
+        ];
+        let latest = find_latest_resource(filter_resources_by_name_and_type(
+            resources.iter(),
+            "schema",
+            "anonCredsSchema",
+        ))
+        .unwrap();
+        assert_eq!(latest.id, "b");
+    }
+
+    #[test]
+    fn test_resource_just_before_time_sub_second() {
+        let at = |id, nanos| CheqdResourceMetadata {
+            created: Some(prost_types::Timestamp { seconds: 10, nanos }),
+            ..resource_meta(id, "schema", "anonCredsSchema", 10)
+        };
+        // sorted newest first
+        let resources = [at("b", 500_000_000), at("a", 100_000_000)];
+        let time = |s| DateTime::parse_from_rfc3339(s).unwrap().to_utc();
+
+        let found = |t| {
+            let boundary = VersionTimeBoundary::Before;
+            find_resource_just_before_time(resources.iter(), time(t), boundary).map(|r| &r.id)
+        };
+        assert_eq!(found("1970-01-01T00:00:10.3Z").unwrap(), "a");
+        assert_eq!(found("1970-01-01T00:00:10.500000001Z").unwrap(), "b");
+        assert_eq!(found("1970-01-01T00:00:10.1Z"), None);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_fails_if_no_network_config() {
+        let did = "did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+        let resolver = DidCheqdResolver::new(Default::default());
+        let e = resolver
+            .query_did_doc_by_str(did, DidCheqdParser::parse(did).unwrap())
+            .await
+            .unwrap_err();
+        assert!(matches!(e.root(), DidCheqdError::NetworkNotSupported(_)));
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_reports_each_result_in_order() {
+        let resolver = DidCheqdResolver::new(Default::default());
+        let results = resolver
+            .prefetch(["did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN", "did:key:z6Mk"])
+            .await;
+        assert_eq!(results.len(), 2);
+        let errors: Vec<_> = results
+            .iter()
+            .map(|r| r.as_ref().unwrap_err().root())
+            .collect();
+        assert!(matches!(errors[0], DidCheqdError::NetworkNotSupported(_)));
+        assert!(matches!(errors[1], DidCheqdError::MethodNotSupported(_)));
+    }
+
+    #[tokio::test]
+    async fn test_connect_all_reports_each_network() {
+        use std::net::Ipv4Addr;
+
+        use tokio::net::TcpListener;
+
+        let node = MockCheqdNode::start(LedgerFixtures::new()).await.unwrap();
+        // nothing listens on the port once the listener is dropped
+        let closed_port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![
+                node.network("testnet"),
+                NetworkConfiguration {
+                    grpc_url: format!("http://127.0.0.1:{closed_port}"),
+                    namespace: "devnet".into(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        });
+
+        let results = resolver.connect_all().await;
+        let namespaces: Vec<_> = results.iter().map(|(ns, _)| ns.as_str()).collect();
+        assert_eq!(namespaces, ["testnet", "devnet"]);
+        assert!(results[0].1.is_ok());
+        let e = results[1].1.as_ref().unwrap_err();
+        assert!(
+            matches!(e.root(), DidCheqdError::TransportError(_)),
+            "{e:?}"
+        );
+        let channels = resolver.stats().await.channels;
+        assert_eq!(channels.get("testnet"), Some(&1));
+        assert_eq!(channels.get("devnet"), None);
+    }
+
+    #[tokio::test]
+    async fn test_connect_all_connects_networks_concurrently() {
+        use std::{net::Ipv4Addr, time::Duration};
+
+        use tokio::net::TcpListener;
+
+        let node = MockCheqdNode::start(LedgerFixtures::new()).await.unwrap();
+        // the listener never accepts, so the TLS handshake with it never completes
+        let unresponsive = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let port = unresponsive.local_addr().unwrap().port();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![
+                NetworkConfiguration {
+                    grpc_url: format!("https://127.0.0.1:{port}"),
+                    namespace: "devnet".into(),
+                    ..Default::default()
+                },
+                node.network("testnet"),
+            ],
+            ..Default::default()
+        });
+
+        let connecting = tokio::spawn({
+            let resolver = resolver.clone();
+            async move { resolver.connect_all().await }
+        });
+        let connected = async {
+            while resolver.stats().await.channels.get("testnet") != Some(&1) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), connected)
+            .await
+            .expect("testnet is connected while devnet's node is unresponsive");
+        assert!(!connecting.is_finished());
+        connecting.abort();
+    }
+
+    #[tokio::test]
+    async fn test_quorum_responses_must_agree() {
+        let did = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+        let node = |controller: &str| {
+            let did_doc = DidDoc {
+                id: did.into(),
+                controller: vec![controller.into()],
+                ..Default::default()
+            };
+            MockCheqdNode::start(LedgerFixtures::new().with_did_doc(did_doc, Default::default()))
+        };
+        let primary = node(did).await.unwrap();
+        let agreeing = node(did).await.unwrap();
+        let disagreeing = node("did:cheqd:testnet:zF7rhDBfUt9d1gJPjx7s1J")
+            .await
+            .unwrap();
+        let resolver = |quorum: &MockCheqdNode| {
+            DidCheqdResolver::new(DidCheqdResolverConfiguration {
+                networks: vec![NetworkConfiguration {
+                    quorum_grpc_urls: vec![quorum.url()],
+                    ..primary.network("testnet")
+                }],
+                ..Default::default()
+            })
+        };
+
+        let resolved = resolver(&agreeing)
+            .resolve_did_doc(DidCheqdParser::parse(did).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resolved.did_doc.id, did);
+
+        let e = resolver(&disagreeing)
+            .resolve_did_doc(DidCheqdParser::parse(did).unwrap())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(e.root(), DidCheqdError::InconsistentResponses(_)),
+            "{e:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_raw_clients_share_connections() {
+        let did = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+        let fixtures = LedgerFixtures::new()
+            .with_did_doc(
+                DidDoc {
+                    id: did.into(),
+                    ..Default::default()
+                },
+                Default::default(),
+            )
+            .with_resource(
+                b"data".to_vec(),
+                CheqdResourceMetadata {
+                    collection_id: "Ps1ysXP2Ae6GBfxNhNQNKN".into(),
+                    ..resource_meta("r1", "schema", "JsonSchema", 10)
+                },
+            );
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            ..Default::default()
+        });
+
+        let mut did_client = resolver.raw_did_client("testnet").await.unwrap();
+        let request = QueryDidDocRequest { id: did.into() };
+        let response = did_client.did_doc(request).await.unwrap().into_inner();
+        assert_eq!(response.value.unwrap().did_doc.unwrap().id, did);
+        let mut resource_client = resolver.raw_resource_client("testnet").await.unwrap();
+        let request = QueryCollectionResourcesRequest {
+            collection_id: "Ps1ysXP2Ae6GBfxNhNQNKN".into(),
+            pagination: None,
+        };
+        let response = resource_client.collection_resources(request).await;
+        assert_eq!(response.unwrap().into_inner().resources.len(), 1);
+
+        resolver
+            .resolve_did_doc(resolver.parse(did).unwrap())
+            .await
+            .unwrap();
+        let stats = resolver.stats().await;
+        assert_eq!(stats.channels, HashMap::from([("testnet".to_string(), 1)]));
+        // raw queries aren't counted
+        assert_eq!(stats.queries, 1);
+        assert!(matches!(
+            resolver.raw_did_client("unknown").await.unwrap_err(),
+            DidCheqdError::NetworkNotSupported(_)
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_resolves_over_unix_socket() {
+        use tokio::net::{TcpStream, UnixListener};
+
+        let did = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+        let did_doc = DidDoc {
+            id: did.into(),
+            ..Default::default()
+        };
+        let fixtures = LedgerFixtures::new().with_did_doc(did_doc, Default::default());
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        // the mock node serves over TCP, so the socket is proxied to it
+        let path = std::env::temp_dir().join(format!("did-cheqd-node-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        let node_addr = node.url().trim_start_matches("http://").to_owned();
+        let proxy = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut tcp = TcpStream::connect(node_addr).await.unwrap();
+            let _ = tokio::io::copy_bidirectional(&mut socket, &mut tcp).await;
+        });
+
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![NetworkConfiguration {
+                grpc_url: format!("unix://{}", path.display()),
+                ..node.network("testnet")
+            }],
+            ..Default::default()
+        });
+        let resolved = resolver
+            .resolve_did_doc(resolver.parse(did).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resolved.did_doc.id, did);
+        assert_eq!(
+            resolved.provenance.endpoint,
+            Some(format!("unix://{}", path.display()))
+        );
+        proxy.abort();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_clients_shared_by_endpoint_url() {
+        use std::net::Ipv4Addr;
+
+        let did = |namespace| format!("did:cheqd:{namespace}:Ps1ysXP2Ae6GBfxNhNQNKN");
+        let fixtures = ["testnet", "mocknet", "devnet"].into_iter().fold(
+            LedgerFixtures::new(),
+            |fixtures, namespace| {
+                let did_doc = DidDoc {
+                    id: did(namespace),
+                    ..Default::default()
+                };
+                fixtures.with_did_doc(did_doc, Default::default())
+            },
+        );
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let closed_port = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![
+                node.network("testnet"),
+                node.network("mocknet"),
+                NetworkConfiguration {
+                    grpc_url: format!("http://127.0.0.1:{closed_port}"),
+                    failover_grpc_urls: vec![node.url()],
+                    ..node.network("devnet")
+                },
+            ],
+            ..Default::default()
+        });
+        let resolve =
+            |namespace| resolver.resolve_did_doc(DidCheqdParser::parse(&did(namespace)).unwrap());
+
+        resolve("testnet").await.unwrap();
+        resolve("mocknet").await.unwrap();
+        // the primary node is down, so the failover node serves the query
+        let resolved = resolve("devnet").await.unwrap();
+        assert_eq!(resolved.provenance.endpoint, Some(node.url()));
+
+        let channels = resolver.stats().await.channels;
+        assert_eq!(channels.get("testnet"), Some(&1));
+        assert_eq!(channels.get("mocknet"), Some(&1));
+        // the primary node is retried by the next query
+        assert_eq!(channels.get("devnet"), None);
+        let node_clients = resolver.inner.node_clients.lock().await;
+        assert_eq!(node_clients.by_endpoint.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_load_balancing_spreads_queries_by_weight() {
+        use std::net::Ipv4Addr;
+
+        use crate::resolution::load_balancing::WeightedEndpoint;
+
+        let did = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+        let fixtures = || {
+            let did_doc = DidDoc {
+                id: did.into(),
+                ..Default::default()
+            };
+            LedgerFixtures::new().with_did_doc(did_doc, Default::default())
+        };
+        let primary = MockCheqdNode::start(fixtures()).await.unwrap();
+        let backup = MockCheqdNode::start(fixtures()).await.unwrap();
+        let closed_port = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let network = |endpoints: Vec<(String, u32)>| NetworkConfiguration {
+            load_balancing: Some(LoadBalancing {
+                primary_weight: 4,
+                endpoints: endpoints
+                    .into_iter()
+                    .map(|(grpc_url, weight)| WeightedEndpoint { grpc_url, weight })
+                    .collect(),
+                ..Default::default()
+            }),
+            ..primary.network("testnet")
+        };
+        let endpoints = |resolver: DidCheqdResolver| async move {
+            let mut endpoints = Vec::new();
+            for _ in 0..10 {
+                let resolved = resolver
+                    .resolve_did_doc(DidCheqdParser::parse(did).unwrap())
+                    .await
+                    .unwrap();
+                endpoints.push(resolved.provenance.endpoint.unwrap());
+            }
+            endpoints
+        };
+
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![network(vec![(backup.url(), 1)])],
+            ..Default::default()
+        });
+        let served = endpoints(resolver).await;
+        let by_backup = served.iter().filter(|url| **url == backup.url()).count();
+        assert_eq!(by_backup, 2, "{served:?}");
+
+        // queries for a node which is down fail over to the other balanced nodes
+        let down = format!("http://127.0.0.1:{closed_port}");
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![network(vec![(down, 1)])],
+            ..Default::default()
+        });
+        let served = endpoints(resolver).await;
+        assert!(served.iter().all(|url| *url == primary.url()), "{served:?}");
+
+        let unweighted = NetworkConfiguration {
+            load_balancing: Some(LoadBalancing::default()),
+            ..primary.network("testnet")
+        };
+        assert!(unweighted.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fastest_endpoint_selection() {
+        use crate::resolution::load_balancing::{EndpointSelection, WeightedEndpoint};
+
+        let did = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+        let fixtures = || {
+            let did_doc = DidDoc {
+                id: did.into(),
+                ..Default::default()
+            };
+            LedgerFixtures::new().with_did_doc(did_doc, Default::default())
+        };
+        let primary = MockCheqdNode::start(fixtures()).await.unwrap();
+        let backup = MockCheqdNode::start(fixtures()).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![NetworkConfiguration {
+                load_balancing: Some(LoadBalancing {
+                    primary_weight: 1,
+                    endpoints: vec![WeightedEndpoint {
+                        grpc_url: backup.url(),
+                        weight: 1,
+                    }],
+                    selection: EndpointSelection::Fastest,
+                }),
+                ..primary.network("testnet")
+            }],
+            ..Default::default()
+        });
+        // the primary node degraded
+        let latencies = &resolver.inner.latencies;
+        latencies.record(&primary.url(), Duration::from_millis(500), false);
+        latencies.record(&backup.url(), Duration::from_millis(5), false);
+
+        for _ in 0..3 {
+            let resolved = resolver
+                .resolve_did_doc(DidCheqdParser::parse(did).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(resolved.provenance.endpoint.unwrap(), backup.url());
+        }
+        let stats = resolver.stats().await;
+        let (primary, backup) = (
+            stats.endpoint_latencies[&primary.url()],
+            stats.endpoint_latencies[&backup.url()],
+        );
+        assert_eq!((primary.samples, backup.samples), (1, 4));
+        assert!(primary.ewma > backup.ewma);
+    }
+
+    #[tokio::test]
+    async fn test_reload_networks_keeps_unchanged_connections() {
+        let did = |namespace| format!("did:cheqd:{namespace}:Ps1ysXP2Ae6GBfxNhNQNKN");
+        let fixtures = |namespace| {
+            let did_doc = DidDoc {
+                id: did(namespace),
+                ..Default::default()
+            };
+            LedgerFixtures::new().with_did_doc(did_doc, Default::default())
+        };
+        let (mainnet, testnet) = (
+            MockCheqdNode::start(fixtures("mainnet")).await.unwrap(),
+            MockCheqdNode::start(fixtures("testnet")).await.unwrap(),
+        );
+        let moved = MockCheqdNode::start(fixtures("testnet")).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![mainnet.network("mainnet"), testnet.network("testnet")],
+            ..Default::default()
+        });
+        let resolve =
+            |namespace| resolver.resolve_did_doc(DidCheqdParser::parse(&did(namespace)).unwrap());
+        resolve("mainnet").await.unwrap();
+        resolve("testnet").await.unwrap();
+
+        let duplicated = vec![mainnet.network("mainnet"), mainnet.network("mainnet")];
+        let e = resolver.reload_networks(duplicated).await.unwrap_err();
+        assert!(e.to_string().contains("more than once"), "{e}");
+        resolver
+            .reload_networks(vec![mainnet.network("mainnet"), moved.network("testnet")])
+            .await
+            .unwrap();
+        {
+            let node_clients = resolver.inner.node_clients.lock().await;
+            let endpoints: Vec<_> = node_clients
+                .by_endpoint
+                .keys()
+                .map(|(url, _)| url)
+                .collect();
+            assert_eq!(endpoints, [&mainnet.url()]);
+        }
+        let resolved = resolve("testnet").await.unwrap();
+        assert_eq!(resolved.provenance.endpoint, Some(moved.url()));
+    }
+
+    #[cfg(feature = "rest")]
+    #[tokio::test]
+    async fn test_falls_back_to_rest_on_transport_error() {
+        use std::net::Ipv4Addr;
+
+        use tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::TcpListener,
+        };
+
+        // nothing listens on the gRPC port once the listener is dropped
+        let closed_port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let gateway = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let gateway_port = gateway.local_addr().unwrap().port();
+        let server = async {
+            let (mut stream, _) = gateway.accept().await.unwrap();
+            let mut request = vec![0; 4096];
+            let _ = stream.read(&mut request).await.unwrap();
+            let body = r#"{"value":{"did_doc":{"id":"did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN"}}}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        };
+
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![NetworkConfiguration {
+                grpc_url: format!("http://127.0.0.1:{closed_port}"),
+                namespace: "devnet".into(),
+                rest_url: Some(format!("http://127.0.0.1:{gateway_port}")),
+                rest_fallback: true,
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        let did = "did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+        let (resolved, _) = tokio::join!(
+            resolver.resolve_did_doc(DidCheqdParser::parse(did).unwrap()),
+            server
+        );
+
+        let resolved = resolved.unwrap();
+        assert_eq!(resolved.did_doc.id, did);
+        assert_eq!(resolved.transport, Transport::Rest);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_fails_if_bad_network_uri() {
+        let did = "did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+        let config = DidCheqdResolverConfiguration {
+            networks: vec![NetworkConfiguration {
+                grpc_url: "@baduri://.".into(),
+                namespace: "devnet".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let resolver = DidCheqdResolver::new(config);
+        let e = resolver
+            .query_did_doc_by_str(did, DidCheqdParser::parse(did).unwrap())
+            .await
+            .unwrap_err();
+        assert!(matches!(e.root(), DidCheqdError::BadConfiguration(_)));
+    }
+
+    #[test]
+    fn test_network_config_follows_aliases_and_fallback() {
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            namespace_aliases: HashMap::from([("staging".to_string(), TESTNET_NAMESPACE.into())]),
+            ..Default::default()
+        });
+        let ns = |namespace| {
+            resolver
+                .network_config(namespace)
+                .map(|n| n.namespace.clone())
+        };
+        assert_eq!(ns("mainnet").unwrap(), MAINNET_NAMESPACE);
+        assert_eq!(ns("staging").unwrap(), TESTNET_NAMESPACE);
+        assert!(matches!(
+            ns("devnet"),
+            Err(DidCheqdError::NetworkNotSupported(_))
+        ));
+
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            fallback_namespace: Some(TESTNET_NAMESPACE.into()),
+            ..Default::default()
+        });
+        assert_eq!(
+            resolver.network_config("devnet").unwrap().namespace,
+            TESTNET_NAMESPACE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fallback_network_queries_the_did_unchanged() {
+        let did = |namespace| format!("did:cheqd:{namespace}:Ps1ysXP2Ae6GBfxNhNQNKN");
+        let fixtures = ["devnet", TESTNET_NAMESPACE].into_iter().fold(
+            LedgerFixtures::new(),
+            |fixtures, namespace| {
+                let did_doc = DidDoc {
+                    id: did(namespace),
+                    ..Default::default()
+                };
+                fixtures.with_did_doc(did_doc, Default::default())
+            },
+        );
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network(TESTNET_NAMESPACE)],
+            namespace_aliases: HashMap::from([("staging".to_string(), TESTNET_NAMESPACE.into())]),
+            fallback_namespace: Some(TESTNET_NAMESPACE.into()),
+            ..Default::default()
+        });
+        let resolve = |namespace| {
+            let parsed = DidCheqdParser::parse(&did(namespace)).unwrap();
+            resolver.resolve_did_doc(parsed)
+        };
+
+        // the fallback network is asked for the DID as requested
+        assert_eq!(resolve("devnet").await.unwrap().did_doc.id, did("devnet"));
+        // aliases are resolved to the network's namespace
+        assert_eq!(
+            resolve("staging").await.unwrap().did_doc.id,
+            did(TESTNET_NAMESPACE)
+        );
+    }
+
+    #[test]
+    fn test_validate_network_configuration() {
+        let network = |grpc_url: &str| NetworkConfiguration {
+            grpc_url: grpc_url.into(),
+            namespace: "devnet".into(),
+            ..Default::default()
+        };
+
+        assert!(NetworkConfiguration::mainnet().validate().is_ok());
+        assert!(network("http://localhost:9090").validate().is_ok());
+        assert!(network("https://[::1]:9090").validate().is_ok());
+        #[cfg(unix)]
+        assert!(network("unix:///run/cheqd/grpc.sock").validate().is_ok());
+
+        let err = |grpc_url: &str| network(grpc_url).validate().unwrap_err().to_string();
+        assert!(err("grpc.cheqd.net:443").contains("scheme"));
+        assert!(err("ftp://grpc.cheqd.net").contains("unsupported scheme `ftp`"));
+        assert!(err("https://[::g]:9090").contains("invalid IPv6 address"));
+        assert!(err("https://grpc.cheqd.net:99999").contains("invalid port"));
+        #[cfg(unix)]
+        assert!(err("unix://grpc.sock").contains("socket path"));
+
+        let bad_namespace = NetworkConfiguration {
+            namespace: "dev-net".into(),
+            ..NetworkConfiguration::mainnet()
+        };
+        assert!(bad_namespace.validate().is_err());
+
+        let bad_rest_url = NetworkConfiguration {
+            rest_url: Some("api.cheqd.net".into()),
+            ..NetworkConfiguration::mainnet()
+        };
+        assert!(
+            bad_rest_url
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("REST URL")
+        );
+        let rest_without_url = NetworkConfiguration {
+            transport: Transport::Rest,
+            rest_url: None,
+            ..NetworkConfiguration::mainnet()
+        };
+        assert!(rest_without_url.validate().is_err());
+    }
+
+    #[test]
+    fn test_try_new_validates_configuration() {
+        assert!(DidCheqdResolver::try_new(DidCheqdResolverConfiguration::default()).is_ok());
+
+        let config = DidCheqdResolverConfiguration {
+            networks: vec![
+                NetworkConfiguration::mainnet(),
+                NetworkConfiguration {
+                    grpc_url: "grpc.cheqd.net:443".into(),
+                    ..NetworkConfiguration::testnet()
+                },
+                NetworkConfiguration::mainnet(),
+            ],
+            namespace_aliases: HashMap::from([("staging".to_string(), "devnet".to_string())]),
+            fallback_namespace: Some("devnet".into()),
+            max_response_size: 0,
+            cache: Some(CacheConfiguration {
+                latest_ttl: Duration::MAX,
+                ..Default::default()
+            }),
+            user_agent: "cheqd\nresolver".into(),
+            profiles: HashMap::from([(
+                "tenant".to_string(),
+                DidCheqdResolverConfiguration {
+                    networks: Vec::new(),
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+        let Err(DidCheqdError::BadConfiguration(problems)) = DidCheqdResolver::try_new(config)
+        else {
+            panic!("configuration is valid");
+        };
+        for problem in [
+            "network `testnet`: Bad configuration: invalid gRPC URL",
+            "network `mainnet` is configured more than once",
+            "alias `staging` refers to `devnet`",
+            "fallback namespace `devnet`",
+            "max_response_size must be non-zero",
+            "cache.latest_ttl",
+            "user agent",
+            "profile `tenant`: no networks are configured",
+        ] {
+            assert!(problems.contains(problem), "{problem} not in {problems}");
+        }
+    }
+
+    #[test]
+    fn test_configuration_from_env_vars() {
+        let vars = HashMap::from([
+            (
+                ENV_NETWORKS,
+                "devnet=http://localhost:9090, testnet=https://grpc.cheqd.network:443",
+            ),
+            (ENV_FALLBACK_NAMESPACE, "devnet"),
+            (ENV_REQUIRE_NAMESPACE, "true"),
+            (ENV_DEBUG_CAPTURE, "10"),
+            (ENV_MAX_RESOURCE_SIZE, "1048576"),
+            (ENV_MAX_RESPONSE_SIZE, "65536"),
+            (ENV_DEPLOYMENT_ID, "acme-verifier"),
+        ]);
+        let config =
+            DidCheqdResolverConfiguration::from_vars(|name| vars.get(name).map(|v| v.to_string()))
+                .unwrap();
 
-pub struct DidCheqdResolver {
-    networks: Vec<NetworkConfiguration>,
-    network_clients: Mutex<HashMap<String, CheqdGrpcClient>>,
-}
+        let namespaces: Vec<_> = config.networks.iter().map(|n| &n.namespace).collect();
+        assert_eq!(namespaces, ["devnet", "testnet"]);
+        assert_eq!(config.networks[0].grpc_url, "http://localhost:9090");
+        assert_eq!(config.fallback_namespace.as_deref(), Some("devnet"));
+        assert!(config.parser.require_namespace);
+        assert_eq!(config.debug_capture, 10);
+        assert_eq!(config.max_resource_size, Some(1048576));
+        assert_eq!(config.max_response_size, 65536);
+        assert_eq!(config.user_agent, DEFAULT_USER_AGENT);
+        assert_eq!(config.deployment_id.as_deref(), Some("acme-verifier"));
+        let resolver = DidCheqdResolver::new(config);
+        assert_eq!(
+            resolver.inner.user_agent,
+            format!(
+                "did-resolver-cheqd/{} (acme-verifier)",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
 
-// Note: we intentionally avoid depending on external `did_resolver` types here.
-// This module exposes string-based resolution helpers that return proto results
-// or raw bytes + media type so callers can transform them into the desired
-// in-repo types without importing the external did_resolver crate.
+        let unset = DidCheqdResolverConfiguration::from_vars(|_| None).unwrap();
+        assert_eq!(unset.networks.len(), 2);
+        assert_eq!(unset.max_response_size, DEFAULT_MAX_RESPONSE_SIZE);
 
-impl DidCheqdResolver {
-    /// Assemble a new resolver with the given config.
-    ///
-    /// [DidCheqdResolverConfiguration::default] can be used if default mainnet & testnet
-    /// configurations are suitable.
-    pub fn new(configuration: DidCheqdResolverConfiguration) -> Self {
-        Self {
-            networks: configuration.networks,
-            network_clients: Default::default(),
-        }
+        let invalid = |name: &str, value: &str| {
+            DidCheqdResolverConfiguration::from_vars(|n| (n == name).then(|| value.to_string()))
+                .is_err()
+        };
+        assert!(invalid(ENV_NETWORKS, "devnet"));
+        assert!(invalid(ENV_NETWORKS, "devnet=localhost:9090"));
+        assert!(invalid(ENV_REQUIRE_NAMESPACE, "yes"));
+        assert!(invalid(ENV_DEBUG_CAPTURE, "-1"));
+        assert!(invalid(ENV_MAX_RESOURCE_SIZE, "1MB"));
+        assert!(invalid(ENV_USER_AGENT, "cheqd\nresolver"));
+        assert!(invalid(ENV_DEPLOYMENT_ID, "acme\u{7f}"));
     }
 
-    /// lazily get the client, initializing if not already
-    async fn client_for_network(&self, network: &str) -> DidCheqdResult<CheqdGrpcClient> {
-        let mut lock = self.network_clients.lock().await;
-        if let Some(client) = lock.get(network) {
-            return Ok(client.clone());
-        }
+    #[tokio::test]
+    async fn test_nodes_are_sent_the_user_agent() {
+        let did = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+        let did_doc = DidDoc {
+            id: did.into(),
+            ..Default::default()
+        };
+        let fixtures = LedgerFixtures::new().with_did_doc(did_doc, Default::default());
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::try_new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            user_agent: "acme-resolver/2.0".into(),
+            deployment_id: Some("acme-verifier".into()),
+            ..Default::default()
+        })
+        .unwrap();
 
-        let network_config = self
-            .networks
-            .iter()
-            .find(|n| n.namespace == network)
-            .ok_or(DidCheqdError::NetworkNotSupported(network.to_owned()))?;
+        resolver.resolve_did_url(did).await.unwrap();
+        let requests = node.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/cheqd.did.v2.Query/DidDoc");
+        let user_agent = requests[0].metadata.get("user-agent").unwrap();
+        // tonic appends its own product to the user agent
+        assert!(
+            user_agent
+                .to_str()
+                .unwrap()
+                .starts_with("acme-resolver/2.0 (acme-verifier)"),
+            "{user_agent:?}"
+        );
+    }
 
-        let endpoint = Endpoint::new(network_config.grpc_url.to_string())
-            .map_err(|_e| DidCheqdError::BadConfiguration("Failed to parse GRPC url".to_string()))?
-            .tls_config(ClientTlsConfig::new().with_webpki_roots())
-            .map_err(|e| DidCheqdError::TransportError(Box::new(e)))?;
+    #[tokio::test]
+    async fn test_max_resource_size() {
+        let metadata = CheqdResourceMetadata {
+            collection_id: "abc".into(),
+            ..resource_meta("r1", "schema", "JsonSchema", 1)
+        };
+        let fixtures = LedgerFixtures::new().with_resource(vec![0; 16], metadata);
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = |max_resource_size| {
+            DidCheqdResolver::new(DidCheqdResolverConfiguration {
+                networks: vec![node.network("testnet")],
+                max_resource_size,
+                ..Default::default()
+            })
+        };
+        let did_url = "did:cheqd:testnet:abc/resources/r1";
 
-        // Connect to the channel
-        let channel = endpoint
-            .connect()
+        let e = resolver(Some(15))
+            .resolve_resource_json(did_url)
             .await
-            .map_err(|e| DidCheqdError::TransportError(Box::new(e)))?;
-
-        let did_client = DidQueryClient::new(channel.clone());
-        let resource_client = ResourceQueryClient::new(channel);
+            .unwrap_err();
+        assert!(matches!(e.root(), DidCheqdError::ResourceTooLarge(_)));
+        let resolved = resolver(Some(16))
+            .resolve_resource(did_url, DidCheqdParser::parse(did_url).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resolved.data.len(), 16);
 
-        let client = CheqdGrpcClient {
-            did: did_client,
-            resources: resource_client,
+        // oversized resources are cut off while decoding the response
+        let metadata = CheqdResourceMetadata {
+            collection_id: "abc".into(),
+            ..resource_meta("r2", "schema", "JsonSchema", 1)
         };
+        let fixtures = LedgerFixtures::new().with_resource(vec![0; 1 << 20], metadata);
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            max_resource_size: Some(1024),
+            ..Default::default()
+        });
+        let did_url = "did:cheqd:testnet:abc/resources/r2";
+        let e = resolver
+            .resolve_resource(did_url, DidCheqdParser::parse(did_url).unwrap())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(e.root(), DidCheqdError::ResourceTooLarge(message)
+                if message.contains("message length too large")),
+            "{e:?}"
+        );
+        assert_eq!(e.endpoint(), Some(node.url().as_str()));
+    }
 
-        lock.insert(network.to_owned(), client.clone());
+    #[tokio::test]
+    async fn test_max_response_size() {
+        let metadata = CheqdResourceMetadata {
+            collection_id: "abc".into(),
+            ..resource_meta("r1", "schema", "JsonSchema", 1)
+        };
+        let fixtures = LedgerFixtures::new().with_resource(vec![0; 4096], metadata);
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = |max_response_size| {
+            DidCheqdResolver::new(DidCheqdResolverConfiguration {
+                networks: vec![node.network("testnet")],
+                max_response_size,
+                ..Default::default()
+            })
+        };
+        let did_url = "did:cheqd:testnet:abc/resources/r1";
 
-        Ok(client)
+        let e = resolver(1024)
+            .resolve_resource(did_url, DidCheqdParser::parse(did_url).unwrap())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(e.root(), DidCheqdError::ResponseTooLarge(_)),
+            "{e:?}"
+        );
+        let resolved = resolver(DEFAULT_MAX_RESPONSE_SIZE)
+            .resolve_resource(did_url, DidCheqdParser::parse(did_url).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resolved.data.len(), 4096);
     }
 
-    /// Query a DID Doc by a DID string (e.g. "did:cheqd:mainnet:zF7...").
-    /// Returns the raw proto DIDDoc and an optional proto metadata object.
-    pub async fn query_did_doc_by_str(
-        &self,
-        _did_str: &str,
-        parsed_did: DidCheqdParsed,
-    ) -> DidCheqdResult<(
-        crate::proto::cheqd::did::v2::DidDoc,
-        Option<crate::proto::cheqd::did::v2::Metadata>,
-    )> {
-        // parsed.namespace is an owned String; borrow as &str for client lookup
-        let network = parsed_did.namespace.as_str();
-        let mut client = self.client_for_network(network).await?;
-
-        if parsed_did.version.is_some() {
-            let request = tonic::Request::new(QueryDidDocVersionRequest {
-                id: parsed_did.did.to_string(),
-                version: parsed_did.version.unwrap(),
-            });
-            let response = client
-                .did
-                .did_doc_version(request)
-                .await
-                .map_err(|e| DidCheqdError::NonSuccessResponse(Box::new(e)))?;
-            let query_response = response.into_inner();
-            let query_doc_res = query_response.value.ok_or(DidCheqdError::InvalidResponse(
-                "DIDDoc query did version not return a value".into(),
-            ))?;
-            let query_doc = query_doc_res.did_doc.ok_or(DidCheqdError::InvalidResponse(
-                "DIDDoc query did version not return a DIDDoc".into(),
-            ))?;
-
-            Ok((query_doc, query_doc_res.metadata))
-        } else {
-            let request = tonic::Request::new(QueryDidDocRequest {
-                id: parsed_did.did.to_string(),
-            });
-            let response = client
-                .did
-                .did_doc(request)
-                .await
-                .map_err(|e| DidCheqdError::NonSuccessResponse(Box::new(e)))?;
-            let query_response = response.into_inner();
-            let query_doc_res = query_response.value.ok_or(DidCheqdError::InvalidResponse(
-                "DIDDoc query did not return a value".into(),
-            ))?;
-            let query_doc = query_doc_res.did_doc.ok_or(DidCheqdError::InvalidResponse(
-                "DIDDoc query did not return a DIDDoc".into(),
-            ))?;
+    #[tokio::test]
+    async fn test_resource_checksum() {
+        let metadata = CheqdResourceMetadata {
+            collection_id: "abc".into(),
+            ..resource_meta("r1", "schema", "JsonSchema", 1)
+        };
+        let fixtures = LedgerFixtures::new().with_resource(b"{}".to_vec(), metadata);
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            ..Default::default()
+        });
+        let resolve = |checksum: &str| {
+            let did_url = format!("did:cheqd:testnet:abc/resources/r1?checksum={checksum}");
+            let resolver = resolver.clone();
+            async move {
+                let parsed = resolver.parse(&did_url)?;
+                resolver.resolve_resource(&did_url, parsed).await
+            }
+        };
 
-            Ok((query_doc, query_doc_res.metadata))
-        }
+        let sha256 = "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a";
+        assert!(resolve(sha256).await.is_ok());
+        assert!(resolve(&sha256.to_uppercase()).await.is_ok());
+        let e = resolve(&sha256.replace('4', "5")).await.unwrap_err();
+        assert!(
+            matches!(e.root(), DidCheqdError::ChecksumMismatch(_)),
+            "{e:?}"
+        );
+        let e = resolve("not-a-digest").await.unwrap_err();
+        assert!(matches!(e.root(), DidCheqdError::InvalidDidUrl(_)), "{e:?}");
     }
 
-    /// Query a DID resource by a DID URL string and return raw bytes and optional
-    /// media type. Supported forms mirror the earlier functionality:
-    /// * `did:cheqd:<namespace>:<did>/resources/<resource_id>`
-    /// * `did:cheqd:<namespace>:<did>?resourceName=...&resourceType=...&resourceVersionTime=...`
-    pub async fn query_resource_by_str(
-        &self,
-        did_url: &str,
-        parsed_did: DidCheqdParsed,
-    ) -> DidCheqdResult<(Vec<u8>, Option<String>)> {
-        // borrow the owned Strings for local use
-        let network = parsed_did.namespace.as_str();
-        let did_id = parsed_did.id.as_str();
+    #[tokio::test]
+    async fn test_resolve_legacy_uuid_ids() {
+        let did = "did:cheqd:testnet:de0a1f9c-9b7b-4c1f-9e1b-2f3b5c6d7e8f";
+        let doc = DidDoc {
+            id: did.into(),
+            ..Default::default()
+        };
+        let did_metadata = DidDocMetadata {
+            version_id: "0b8a1d3e-5e6f-4a7b-8c9d-0e1f2a3b4c5d".into(),
+            ..Default::default()
+        };
+        let metadata = CheqdResourceMetadata {
+            collection_id: "de0a1f9c-9b7b-4c1f-9e1b-2f3b5c6d7e8f".into(),
+            ..resource_meta(
+                "5e16a3f9-7da7-4916-a5ce-4c4a9d3bc8e8",
+                "schema",
+                "JsonSchema",
+                1,
+            )
+        };
+        let fixtures = LedgerFixtures::new()
+            .with_did_doc(doc, did_metadata)
+            .with_resource(b"{}".to_vec(), metadata);
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            ..Default::default()
+        });
 
-        // If parser injected a resourceId (from a path like /resources/<id>), resolve by id.
-        if let Some(ref qmap) = parsed_did.query {
-            if let Some(resource_id) = qmap.get("resourceId") {
-                return self
-                    .resolve_resource_by_id(did_id, resource_id.as_str(), network)
-                    .await;
-            }
+        let legacy = did
+            .to_uppercase()
+            .replace("DID:CHEQD:TESTNET", "did:cheqd:testnet");
+        for did_url in [
+            legacy.clone(),
+            format!("{legacy}?versionId=0B8A1D3E-5E6F-4A7B-8C9D-0E1F2A3B4C5D"),
+        ] {
+            let resolved = resolver
+                .resolve_did_doc(resolver.parse(&did_url).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(resolved.did_doc.id, did);
         }
+        let did_url = format!("{legacy}/resources/5E16A3F9-7DA7-4916-A5CE-4C4A9D3BC8E8");
+        let resolved = resolver
+            .resolve_resource(&did_url, resolver.parse(&did_url).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resolved.data, b"{}");
 
-        // Otherwise, if query parameters indicate name+type lookup, perform that
-        if let Some(qmap) = parsed_did.query {
-            let resource_name = qmap.get("resourceName");
-            let resource_type = qmap.get("resourceType");
-            let version_time = qmap.get("resourceVersionTime");
-
-            let (Some(resource_name), Some(resource_type)) = (resource_name, resource_type) else {
-                return Err(DidCheqdError::InvalidDidUrl(format!(
-                    "Resolver can only resolve by exact resource ID or name+type combination {did_url}"
-                )));
-            };
-
-            let version_time = match version_time {
-                Some(v) => DateTime::parse_from_rfc3339(v)
-                    .map_err(|e| DidCheqdError::InvalidDidUrl(e.to_string()))?
-                    .to_utc(),
-                None => Utc::now(),
-            };
+        let (data, _) = resolver
+            .resolve_latest_resource(&legacy, "schema", "JsonSchema")
+            .await
+            .unwrap();
+        assert_eq!(data, b"{}");
+        let versions = resolver
+            .resource_version_history(&legacy, "schema", "JsonSchema")
+            .await
+            .unwrap();
+        assert_eq!(versions[0].id, "5e16a3f9-7da7-4916-a5ce-4c4a9d3bc8e8");
+    }
 
-            return self
-                .resolve_resource_by_name_type_and_time(
-                    did_id,
-                    resource_name.as_str(),
-                    resource_type.as_str(),
-                    version_time,
-                    network,
-                )
-                .await;
-        }
+    #[tokio::test]
+    async fn test_latest_resource_as_of_clock() {
+        let mut v1 = CheqdResourceMetadata {
+            collection_id: "abc".into(),
+            ..resource_meta("v1", "schema", "JsonSchema", 10)
+        };
+        v1.next_version_id = "v2".into();
+        let v2 = CheqdResourceMetadata {
+            collection_id: "abc".into(),
+            previous_version_id: "v1".into(),
+            ..resource_meta("v2", "schema", "JsonSchema", 20)
+        };
+        let fixtures = LedgerFixtures::new()
+            .with_resource(b"v1".to_vec(), v1)
+            .with_resource(b"v2".to_vec(), v2);
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = |secs| {
+            DidCheqdResolver::new(DidCheqdResolverConfiguration {
+                networks: vec![node.network("testnet")],
+                clock: Arc::new(FixedClock(DateTime::from_timestamp(secs, 0).unwrap())),
+                ..Default::default()
+            })
+        };
+        let did_url = "did:cheqd:testnet:abc?resourceName=schema&resourceType=JsonSchema";
+        let latest = |secs| {
+            let resolver = resolver(secs);
+            async move {
+                let parsed = DidCheqdParser::parse(did_url).unwrap();
+                resolver.resolve_resource(did_url, parsed).await
+            }
+        };
 
-        Err(DidCheqdError::InvalidDidUrl(format!(
-            "No resource path or query present: {did_url}"
-        )))
+        assert_eq!(latest(15).await.unwrap().data, b"v1");
+        assert_eq!(latest(20).await.unwrap().data, b"v2");
+        let e = latest(5).await.unwrap_err();
+        assert!(
+            matches!(e.root(), DidCheqdError::ResourceNotFound(_)),
+            "{e:?}"
+        );
     }
 
-    /// Resolve a resource from a collection (did_id) and network by an exact id.
-    async fn resolve_resource_by_id(
-        &self,
-        did_id: &str,
-        resource_id: &str,
-        network: &str,
-    ) -> DidCheqdResult<(Vec<u8>, Option<String>)> {
-        let mut client = self.client_for_network(network).await?;
-        let request = QueryResourceRequest {
-            collection_id: did_id.to_owned(),
-            id: resource_id.to_owned(),
+    #[tokio::test]
+    async fn test_collection_listing_cache() {
+        let mut v1 = CheqdResourceMetadata {
+            collection_id: "abc".into(),
+            ..resource_meta("v1", "schema", "JsonSchema", 10)
+        };
+        v1.next_version_id = "v2".into();
+        let v2 = CheqdResourceMetadata {
+            collection_id: "abc".into(),
+            previous_version_id: "v1".into(),
+            ..resource_meta("v2", "schema", "JsonSchema", 20)
+        };
+        let fixtures = LedgerFixtures::new()
+            .with_resource(b"v1".to_vec(), v1.clone())
+            .with_resource(b"v2".to_vec(), v2);
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = |collection_ttl| {
+            DidCheqdResolver::new(DidCheqdResolverConfiguration {
+                networks: vec![node.network("testnet")],
+                cache: Some(CacheConfiguration {
+                    latest_ttl: Duration::ZERO,
+                    collection_ttl: Some(collection_ttl),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        };
+        let did_url = "did:cheqd:testnet:abc?resourceName=schema&resourceType=JsonSchema";
+        let latest = |resolver: &DidCheqdResolver| {
+            let parsed = DidCheqdParser::parse(did_url).unwrap();
+            let resolver = resolver.clone();
+            async move { resolver.resolve_resource(did_url, parsed).await.unwrap() }
         };
-        let response = client
-            .resources
-            .resource(request)
-            .await
-            .map_err(|e| DidCheqdError::NonSuccessResponse(Box::new(e)))?;
 
-        let query_response = response.into_inner();
-        let query_response = query_response
-            .resource
-            .ok_or(DidCheqdError::InvalidResponse(
-                "Resource query did not return a value".into(),
-            ))?;
-        let query_resource = query_response
-            .resource
-            .ok_or(DidCheqdError::InvalidResponse(
-                "Resource query did not return a resource".into(),
-            ))?;
-        let query_metadata = query_response
-            .metadata
-            .ok_or(DidCheqdError::InvalidResponse(
-                "Resource query did not return metadata".into(),
-            ))?;
+        // a fresh listing is used without querying the collection
+        let fresh = resolver(Duration::from_secs(60));
+        assert_eq!(latest(&fresh).await.data, b"v2");
+        assert_eq!(fresh.stats().await.queries, 2);
+        assert_eq!(latest(&fresh).await.data, b"v2");
+        assert_eq!(fresh.stats().await.queries, 2);
 
-        let media_type =
-            (!query_metadata.media_type.trim().is_empty()).then_some(query_metadata.media_type);
+        // an expired listing is revalidated with a single query
+        let expired = resolver(Duration::ZERO);
+        latest(&expired).await;
+        assert_eq!(expired.stats().await.queries, 2);
+        assert_eq!(latest(&expired).await.data, b"v2");
+        assert_eq!(expired.stats().await.queries, 3);
 
-        Ok((query_resource.data, media_type))
+        // & fetched again in full if the collection has changed
+        let cache = expired.inner.cache.as_ref().unwrap();
+        let key = ("testnet".to_string(), "abc".to_string());
+        cache.insert_collection_listing(key, Arc::new(vec![v1]));
+        assert_eq!(latest(&expired).await.data, b"v2");
+        assert_eq!(expired.stats().await.queries, 5);
     }
 
-    /// Resolve a resource from a given collection (did_id) & network, that has a given name & type,
-    /// as of a given time.
-    async fn resolve_resource_by_name_type_and_time(
-        &self,
-        did_id: &str,
-        name: &str,
-        rtyp: &str,
-        time: DateTime<Utc>,
-        network: &str,
-    ) -> DidCheqdResult<(Vec<u8>, Option<String>)> {
-        let mut client = self.client_for_network(network).await?;
+    #[tokio::test]
+    async fn test_search_resources() {
+        let in_collection = |collection: &str, id, rtyp, created| CheqdResourceMetadata {
+            collection_id: collection.into(),
+            ..resource_meta(id, "schema", rtyp, created)
+        };
+        let fixtures = LedgerFixtures::new()
+            .with_resource(vec![], in_collection("abc", "a1", "anonCredsSchema", 10))
+            .with_resource(vec![], in_collection("abc", "a2", "anonCredsSchema", 20))
+            .with_resource(vec![], in_collection("def", "d1", "anonCredsSchema", 15))
+            .with_resource(vec![], in_collection("def", "d2", "JsonSchema", 15));
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            cache: Some(Default::default()),
+            ..Default::default()
+        });
+        let ids = |matches: Vec<CheqdResourceMetadata>| {
+            matches.into_iter().map(|m| m.id).collect::<Vec<_>>()
+        };
 
-        let response = client
-            .resources
-            .collection_resources(QueryCollectionResourcesRequest {
-                collection_id: did_id.to_owned(),
-                // FUTURE - pagination
-                pagination: None,
-            })
+        let collections = ["def".to_owned(), "missing".to_owned(), "abc".to_owned()];
+        let matches = resolver
+            .search_resources("testnet", "schema", "anonCredsSchema", Some(&collections))
             .await
-            .map_err(|e| DidCheqdError::NonSuccessResponse(Box::new(e)))?;
-
-        let query_response = response.into_inner();
-        let resources = query_response.resources;
-        let mut filtered: Vec<_> =
-            filter_resources_by_name_and_type(resources.iter(), name, rtyp).collect();
-        filtered.sort_by(|a, b| desc_chronological_sort_resources(a, b));
+            .unwrap();
+        assert_eq!(ids(matches), ["d1", "a2", "a1"]);
 
-        let resource_meta = find_resource_just_before_time(filtered.into_iter(), time);
+        // without collections given, those in the cache are searched
+        let matches = resolver
+            .search_resources("testnet", "schema", "anonCredsSchema", None)
+            .await
+            .unwrap();
+        assert!(matches.is_empty());
+        let did_url = "did:cheqd:testnet:abc/resources/a1";
+        let parsed = DidCheqdParser::parse(did_url).unwrap();
+        resolver.resolve_resource(did_url, parsed).await.unwrap();
+        let matches = resolver
+            .search_resources("testnet", "schema", "anonCredsSchema", None)
+            .await
+            .unwrap();
+        assert_eq!(ids(matches), ["a2", "a1"]);
+    }
 
-        let Some(meta) = resource_meta else {
-            return Err(DidCheqdError::ResourceNotFound(format!(
-                "network: {network}, collection: {did_id}, name: {name}, type: {rtyp}, time: \
-                 {time}"
-            )));
+    #[tokio::test]
+    async fn test_mismatched_responses_are_invalid() {
+        let (requested, served) = ("Ps1ysXP2Ae6GBfxNhNQNKN", "zF7rhDBfUt9d1gJPjx7s1J");
+        let did = format!("did:cheqd:testnet:{requested}");
+        let served_did = format!("did:cheqd:testnet:{served}");
+        let resource = |collection_id: &str| CheqdResourceMetadata {
+            collection_id: collection_id.into(),
+            ..resource_meta("r1", "schema", "JsonSchema", 10)
+        };
+        let fixtures = LedgerFixtures::new()
+            .with_did_doc(
+                DidDoc {
+                    id: served_did.clone(),
+                    ..Default::default()
+                },
+                Default::default(),
+            )
+            .with_resource(b"{}".to_vec(), resource(served))
+            .with_misdirected(&did, &served_did)
+            .with_misdirected(requested, served);
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            ..Default::default()
+        });
+        let assert_invalid = |result: DidCheqdResult<Resolved>| {
+            let e = result.unwrap_err();
+            assert!(
+                matches!(e.root(), DidCheqdError::InvalidResponse(_)),
+                "{e:?}"
+            );
         };
 
-        let (data, media) = self
-            .resolve_resource_by_id(did_id, &meta.id, network)
-            .await?;
-        Ok((data, media))
+        // the DID document of another DID
+        assert_invalid(resolver.resolve_did_url(&did).await);
+        // a resource from another collection
+        assert_invalid(
+            resolver
+                .resolve_did_url(&format!("{did}/resources/r1"))
+                .await,
+        );
+        // a collection listing resources from another collection
+        let query = format!("{did}?resourceName=schema&resourceType=JsonSchema");
+        assert_invalid(resolver.resolve_did_url(&query).await);
     }
-}
-
-/// Filter for resources which have a matching name and type
-fn filter_resources_by_name_and_type<'a>(
-    resources: impl Iterator<Item = &'a CheqdResourceMetadata> + 'a,
-    name: &'a str,
-    rtyp: &'a str,
-) -> impl Iterator<Item = &'a CheqdResourceMetadata> + 'a {
-    resources.filter(move |r| r.name == name && r.resource_type == rtyp)
-}
 
-/// Sort resources chronologically by their created timestamps
-fn desc_chronological_sort_resources(
-    b: &CheqdResourceMetadata,
-    a: &CheqdResourceMetadata,
-) -> Ordering {
-    let (a_secs, a_ns) = a
-        .created
-        .map(|v| {
-            let v = v.normalized();
-            (v.seconds, v.nanos)
-        })
-        .unwrap_or((0, 0));
-    let (b_secs, b_ns) = b
-        .created
-        .map(|v| {
-            let v = v.normalized();
-            (v.seconds, v.nanos)
-        })
-        .unwrap_or((0, 0));
+    #[tokio::test]
+    async fn test_response_validation() {
+        let did = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+        let did_doc = DidDoc {
+            id: did.into(),
+            ..Default::default()
+        };
+        let fixtures = LedgerFixtures::new().with_did_doc(did_doc, Default::default());
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = |response_validation| {
+            DidCheqdResolver::new(DidCheqdResolverConfiguration {
+                networks: vec![node.network("testnet")],
+                response_validation,
+                ..Default::default()
+            })
+        };
 
-    match a_secs.cmp(&b_secs) {
-        Ordering::Equal => a_ns.cmp(&b_ns),
-        res => res,
+        let resolved = resolver(ResponseValidation::Off)
+            .resolve_did_doc(DidCheqdParser::parse(did).unwrap())
+            .await
+            .unwrap();
+        assert!(resolved.warnings.is_empty());
+        assert_eq!(resolved.metadata.unwrap().created, None);
+        let e = resolver(ResponseValidation::Strict)
+            .resolve_did_doc(DidCheqdParser::parse(did).unwrap())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(e.root(), DidCheqdError::InvalidResponse(_)),
+            "{e:?}"
+        );
+        let resolved = resolver(ResponseValidation::Lenient)
+            .resolve_did_doc(DidCheqdParser::parse(did).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resolved.warnings.len(), 2, "{:?}", resolved.warnings);
+        assert_eq!(resolved.metadata.unwrap().created, Some(Default::default()));
     }
-}
 
-/// assuming `resources` is sorted by `.created` time in descending order, find
-/// the resource which is closest to `before_time`, but NOT after.
-///
-/// Returns a reference to this resource if it exists.
-///
-/// e.g.:
-/// resources: [{created: 20}, {created: 15}, {created: 10}, {created: 5}]
-/// before_time: 14
-/// returns: {created: 10}
-///
-/// resources: [{created: 20}, {created: 15}, {created: 10}, {created: 5}]
-/// before_time: 4
-/// returns: None
-fn find_resource_just_before_time<'a>(
-    resources: impl Iterator<Item = &'a CheqdResourceMetadata>,
-    before_time: DateTime<Utc>,
-) -> Option<&'a CheqdResourceMetadata> {
-    let before_epoch = before_time.timestamp();
+    #[tokio::test]
+    async fn test_stats() {
+        let did = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+        let fixtures = LedgerFixtures::new().with_did_doc(
+            DidDoc {
+                id: did.into(),
+                ..Default::default()
+            },
+            Default::default(),
+        );
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            cache: Some(Default::default()),
+            ..Default::default()
+        });
+        assert_eq!(resolver.stats().await, ResolverStats::default());
 
-    for r in resources {
-        let Some(created) = r.created else {
-            continue;
-        };
+        let resolve = |did: &'static str| resolver.resolve_did_doc(resolver.parse(did).unwrap());
+        resolve(did).await.unwrap();
+        resolve(did).await.unwrap();
+        resolve("did:cheqd:testnet:missing").await.unwrap_err();
 
-        let created_epoch = created.normalized().seconds;
-        if created_epoch < before_epoch {
-            return Some(r);
-        }
+        let stats = resolver.stats().await;
+        assert_eq!(stats.cached_did_docs, 1);
+        assert!(stats.cached_did_doc_bytes > 0);
+        assert_eq!(stats.cached_resources, 0);
+        assert_eq!(stats.channels, HashMap::from([("testnet".to_string(), 1)]));
+        assert_eq!(stats.in_flight, 0);
+        assert_eq!((stats.resolutions, stats.resolution_failures), (3, 1));
+        assert_eq!((stats.cache_hits, stats.queries), (1, 2));
     }
 
-    None
-}
-
-#[cfg(test)]
-mod unit_tests {
-    use crate::resolution::parser::DidCheqdParser;
+    #[tokio::test]
+    async fn test_provenance() {
+        let did = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+        let fixtures = LedgerFixtures::new().with_did_doc(
+            DidDoc {
+                id: did.into(),
+                ..Default::default()
+            },
+            Default::default(),
+        );
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            cache: Some(Default::default()),
+            ..Default::default()
+        });
 
-    use super::*;
+        let resolve = || resolver.resolve_did_doc(resolver.parse(did).unwrap());
+        let provenance = resolve().await.unwrap().provenance;
+        assert_eq!(provenance.endpoint, Some(node.url()));
+        assert!(!provenance.cached);
+        assert_eq!(provenance.retries, 0);
+        assert!(resolve().await.unwrap().provenance.cached);
+    }
 
     #[tokio::test]
-    async fn test_resolve_fails_if_no_network_config() {
-        let did = "did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN";
-        let resolver = DidCheqdResolver::new(Default::default());
+    async fn test_resolve_with_profile() {
+        let did = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+        let node = |version_id: &str| {
+            let fixtures = LedgerFixtures::new().with_did_doc(
+                DidDoc {
+                    id: did.into(),
+                    ..Default::default()
+                },
+                DidDocMetadata {
+                    version_id: version_id.into(),
+                    ..Default::default()
+                },
+            );
+            MockCheqdNode::start(fixtures)
+        };
+        let (default_node, staging_node) = (
+            node("default").await.unwrap(),
+            node("staging").await.unwrap(),
+        );
+        let staging = DidCheqdResolverConfiguration {
+            networks: vec![staging_node.network("testnet")],
+            ..Default::default()
+        };
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![default_node.network("testnet")],
+            profiles: HashMap::from([("staging".to_string(), staging)]),
+            ..Default::default()
+        });
+        let version = |resolved| match resolved {
+            Ok(Resolved::Document(document)) => document.metadata.unwrap().version_id,
+            other => panic!("expected a DID document, got {other:?}"),
+        };
+
+        assert_eq!(version(resolver.resolve_did_url(did).await), "default");
+        assert_eq!(
+            version(resolver.resolve_with_profile("staging", did).await),
+            "staging"
+        );
         let e = resolver
-            .query_did_doc_by_str(did, DidCheqdParser::parse(did).unwrap())
+            .resolve_with_profile("prod", did)
             .await
             .unwrap_err();
-        assert!(matches!(e, DidCheqdError::NetworkNotSupported(_)));
+        assert!(matches!(e, DidCheqdError::BadConfiguration(_)), "{e:?}");
+
+        resolver.shutdown().await;
+        assert!(resolver.profile("staging").unwrap().is_shut_down());
     }
 
     #[tokio::test]
-    async fn test_resolve_fails_if_bad_network_uri() {
-        let did = "did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN";
-        let config = DidCheqdResolverConfiguration {
+    async fn test_shutdown_drains_queries() {
+        // accepts connections, but never responds
+        let listener = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, 0))
+            .await
+            .unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
             networks: vec![NetworkConfiguration {
-                grpc_url: "@baduri://.".into(),
-                namespace: "devnet".into(),
+                grpc_url: format!("http://{}", listener.local_addr().unwrap()),
+                namespace: "testnet".into(),
+                ..Default::default()
             }],
-        };
+            ..Default::default()
+        });
+        let did = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+
+        let pending = resolver.clone();
+        let parsed = resolver.parse(did).unwrap();
+        let pending = tokio::spawn(async move {
+            let deadline = Instant::now() + Duration::from_millis(200);
+            crate::resolution::deadline::with_deadline(deadline, pending.resolve_did_doc(parsed))
+                .await
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let started = Instant::now();
+        resolver.shutdown().await;
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        let e = pending.await.unwrap().unwrap_err();
+        assert!(matches!(e, DidCheqdError::DeadlineExceeded), "{e:?}");
+        assert!(resolver.is_shut_down());
 
-        let resolver = DidCheqdResolver::new(config);
         let e = resolver
-            .query_did_doc_by_str(did, DidCheqdParser::parse(did).unwrap())
+            .resolve_did_doc(resolver.parse(did).unwrap())
             .await
             .unwrap_err();
-        assert!(matches!(e, DidCheqdError::BadConfiguration(_)));
+        assert!(matches!(e.root(), DidCheqdError::ShutDown), "{e:?}");
     }
 
     #[tokio::test]
@@ -461,7 +4539,7 @@ mod unit_tests {
             .query_resource_by_str(url, DidCheqdParser::parse(url).unwrap())
             .await
             .unwrap_err();
-        assert!(matches!(e, DidCheqdError::InvalidDidUrl(_)));
+        assert!(matches!(e.root(), DidCheqdError::InvalidDidUrl(_)));
     }
 
     #[tokio::test]
@@ -472,7 +4550,7 @@ mod unit_tests {
             .query_resource_by_str(url, DidCheqdParser::parse(url).unwrap())
             .await
             .unwrap_err();
-        assert!(matches!(e, DidCheqdError::InvalidDidUrl(_)));
+        assert!(matches!(e.root(), DidCheqdError::InvalidDidUrl(_)));
     }
 
     #[tokio::test]
@@ -484,14 +4562,26 @@ mod unit_tests {
             .query_resource_by_str(url, DidCheqdParser::parse(url).unwrap())
             .await
             .unwrap_err();
-        assert!(matches!(e, DidCheqdError::InvalidDidUrl(_)));
+        assert!(matches!(e.root(), DidCheqdError::InvalidDidUrl(_)));
+    }
+
+    /// A resolver for tests of testnet DIDs, which replays the responses in `tests/fixtures`
+    /// unless `DID_CHEQD_FIXTURE_MODE` is set, e.g. to `off` to query the live testnet, or to
+    /// `record:tests/fixtures` to re-record the fixtures from it.
+    fn live_network_resolver() -> DidCheqdResolver {
+        let mut config = DidCheqdResolverConfiguration::from_env().unwrap();
+        if std::env::var_os(ENV_FIXTURE_MODE).is_none() {
+            config.fixture_mode =
+                FixtureMode::Replay(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures").into());
+        }
+        DidCheqdResolver::new(config)
     }
 
     #[tokio::test]
     async fn test_resolve_did_success() {
         // use epoch instead of XML DateTime
         let did = "did:cheqd:testnet:f5101dd8-447f-40a7-a9b8-700abeba389a".to_string();
-        let resolver = DidCheqdResolver::new(Default::default());
+        let resolver = live_network_resolver();
         let res = resolver
             .query_did_doc_by_str(&did, DidCheqdParser::parse(&did).unwrap())
             .await;
@@ -503,7 +4593,7 @@ mod unit_tests {
     async fn test_resolve_resource_id_success() {
         // use epoch instead of XML DateTime
         let did_url = "did:cheqd:testnet:f5101dd8-447f-40a7-a9b8-700abeba389a/resources/6155f8bc-d9c9-4e83-a1bb-453744fe5438".to_string();
-        let resolver = DidCheqdResolver::new(Default::default());
+        let resolver = live_network_resolver();
         let res = resolver
             .query_resource_by_str(&did_url, DidCheqdParser::parse(&did_url).unwrap())
             .await;
@@ -515,7 +4605,7 @@ mod unit_tests {
     async fn test_resolve_resource_query_success() {
         // use epoch instead of XML DateTime
         let did_url = "did:cheqd:testnet:f5101dd8-447f-40a7-a9b8-700abeba389a?resourceName=Patient ID 85905-Schema&resourceType=anonCredsSchema".to_string();
-        let resolver = DidCheqdResolver::new(Default::default());
+        let resolver = live_network_resolver();
         let res = resolver
             .query_resource_by_str(&did_url, DidCheqdParser::parse(&did_url).unwrap())
             .await;
@@ -527,7 +4617,7 @@ mod unit_tests {
     async fn test_resolve_did_version_id() {
         // use epoch instead of XML DateTime
         let did = "did:cheqd:testnet:ac2b9027-ec1a-4ee2-aad1-1e316e7d6f59/versions/ff82cc93-25fd-493a-8896-9303a9c8383d".to_string();
-        let resolver = DidCheqdResolver::new(Default::default());
+        let resolver = live_network_resolver();
         let res = resolver
             .query_did_doc_by_str(&did, DidCheqdParser::parse(&did).unwrap())
             .await;
@@ -0,0 +1,159 @@
+//! Checks of node responses for the fields the cheqd DID method requires, see
+//! [ResponseValidation].
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    proto::cheqd::{
+        did::v2::{DidDoc, Metadata as DidDocMetadata},
+        resource::v2::Metadata as ResourceMetadata,
+    },
+};
+
+/// How DID documents & resources returned by nodes are checked for the fields the cheqd DID
+/// method requires, e.g. a `created` time, rather than failing wherever a missing field is
+/// first used
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResponseValidation {
+    /// responses are used as received
+    #[default]
+    Off,
+    /// missing fields are filled with defaults where possible (the DID requested, empty
+    /// metadata, or the Unix epoch), recording a warning for each missing field in the resolved
+    /// DID document's or resource's `warnings`
+    Lenient,
+    /// responses missing required fields fail with [DidCheqdError::InvalidResponse]
+    Strict,
+}
+
+/// The fields of a response found missing, with how they were filled (if they could be)
+struct Missing {
+    mode: ResponseValidation,
+    subject: String,
+    warnings: Vec<String>,
+}
+
+impl Missing {
+    /// Record a missing field, filled with the given default (if any)
+    fn field(&mut self, field: &str, filled_with: Option<&str>) {
+        self.warnings.push(match filled_with {
+            Some(default) => format!("{} is missing `{field}`, assumed {default}", self.subject),
+            None => format!("{} is missing `{field}`", self.subject),
+        });
+    }
+
+    fn finish(self) -> DidCheqdResult<Vec<String>> {
+        match self.mode {
+            ResponseValidation::Strict if !self.warnings.is_empty() => {
+                Err(DidCheqdError::InvalidResponse(self.warnings.join("; ")))
+            }
+            _ => Ok(self.warnings),
+        }
+    }
+}
+
+/// Check a DID document (of the given DID) & its metadata, returning the warnings for any
+/// missing fields filled in
+pub(crate) fn validate_did_doc(
+    mode: ResponseValidation,
+    did: &str,
+    did_doc: &mut DidDoc,
+    metadata: &mut Option<DidDocMetadata>,
+) -> DidCheqdResult<Vec<String>> {
+    if mode == ResponseValidation::Off {
+        return Ok(Vec::new());
+    }
+    let mut missing = Missing {
+        mode,
+        subject: format!("DID document {did}"),
+        warnings: Vec::new(),
+    };
+    if did_doc.id.is_empty() {
+        missing.field("id", Some(&format!("`{did}`")));
+        did_doc.id = did.to_owned();
+    }
+    let metadata = match metadata {
+        Some(metadata) => metadata,
+        None => {
+            missing.field("metadata", Some("empty metadata"));
+            metadata.insert(DidDocMetadata::default())
+        }
+    };
+    if metadata.created.is_none() {
+        missing.field("metadata.created", Some("the Unix epoch"));
+        metadata.created = Some(Default::default());
+    }
+    if metadata.version_id.is_empty() {
+        missing.field("metadata.versionId", None);
+    }
+    missing.finish()
+}
+
+/// Check the metadata of a resource, returning the warnings for any missing fields filled in
+pub(crate) fn validate_resource_metadata(
+    mode: ResponseValidation,
+    metadata: &mut ResourceMetadata,
+) -> DidCheqdResult<Vec<String>> {
+    if mode == ResponseValidation::Off {
+        return Ok(Vec::new());
+    }
+    let mut missing = Missing {
+        mode,
+        subject: format!("resource {}/{}", metadata.collection_id, metadata.id),
+        warnings: Vec::new(),
+    };
+    if metadata.name.is_empty() {
+        missing.field("name", None);
+    }
+    if metadata.resource_type.is_empty() {
+        missing.field("resourceType", None);
+    }
+    if metadata.created.is_none() {
+        missing.field("created", Some("the Unix epoch"));
+        metadata.created = Some(Default::default());
+    }
+    missing.finish()
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_responses() {
+        let did = "did:cheqd:testnet:abc";
+        let (mut did_doc, mut metadata) = (DidDoc::default(), None);
+        let e = validate_did_doc(ResponseValidation::Strict, did, &mut did_doc, &mut metadata)
+            .unwrap_err();
+        assert!(matches!(e, DidCheqdError::InvalidResponse(_)), "{e:?}");
+
+        let (mut did_doc, mut metadata) = (DidDoc::default(), None);
+
+        let warnings = validate_did_doc(
+            ResponseValidation::Lenient,
+            did,
+            &mut did_doc,
+            &mut metadata,
+        )
+        .unwrap();
+        assert_eq!(warnings.len(), 4, "{warnings:?}");
+        assert_eq!(did_doc.id, did);
+        assert_eq!(metadata.unwrap().created, Some(Default::default()));
+
+        let mut resource = ResourceMetadata {
+            collection_id: "abc".into(),
+            id: "r1".into(),
+            name: "schema".into(),
+            resource_type: "JsonSchema".into(),
+            ..Default::default()
+        };
+        let warnings = validate_resource_metadata(ResponseValidation::Off, &mut resource).unwrap();
+        assert!(warnings.is_empty() && resource.created.is_none());
+        let warnings =
+            validate_resource_metadata(ResponseValidation::Lenient, &mut resource).unwrap();
+        assert_eq!(
+            warnings,
+            ["resource abc/r1 is missing `created`, assumed the Unix epoch"]
+        );
+        assert!(validate_resource_metadata(ResponseValidation::Strict, &mut resource).is_ok());
+    }
+}
@@ -0,0 +1,635 @@
+//! A client for the Cosmos REST gRPC-gateway exposed by cheqd nodes, used as an alternative
+//! transport where gRPC (HTTP/2) egress is unavailable, e.g. behind corporate proxies.
+//!
+//! Responses are mapped into the same proto messages the gRPC clients return, and failures into
+//! [tonic::Status]es, so the resolver can treat both transports identically.
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use chrono::DateTime;
+use serde_json::{Map, Value};
+use tonic::{Code, Status};
+use url::Url;
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    proto::{
+        cheqd::{
+            did::v2::{
+                DidDoc, DidDocWithMetadata, Metadata as DidDocMetadata, QueryDidDocRequest,
+                QueryDidDocResponse, QueryDidDocVersionRequest, QueryDidDocVersionResponse,
+                Service, VerificationMethod,
+            },
+            resource::v2::{
+                AlternativeUri, Metadata as ResourceMetadata, QueryCollectionResourcesRequest,
+                QueryCollectionResourcesResponse, QueryResourceRequest, QueryResourceResponse,
+                Resource, ResourceWithMetadata,
+            },
+        },
+        cosmos::base::query::v1beta1::PageResponse,
+    },
+    resolution::connector::{DnsConfiguration, DnsResolve},
+};
+
+type RestResult<T> = Result<T, Status>;
+
+/// How long to wait for a REST gateway response before failing
+const REST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A client for a single node's REST gRPC-gateway
+#[derive(Clone)]
+pub(crate) struct RestClient {
+    base_url: Url,
+    http: reqwest::Client,
+    /// the maximum size of a response body, see
+    /// [crate::resolution::resolver::DidCheqdResolverConfiguration::max_response_size]
+    max_response_size: usize,
+}
+
+impl RestClient {
+    pub(crate) fn new(
+        url: &str,
+        dns: &DnsConfiguration,
+        user_agent: &str,
+        max_response_size: usize,
+    ) -> DidCheqdResult<Self> {
+        let base_url = Url::parse(url)?;
+        let mut builder = reqwest::Client::builder()
+            .timeout(REST_TIMEOUT)
+            .user_agent(user_agent);
+        for (host, ips) in &dns.pinned_hosts {
+            // port 0 uses the port of the request URL
+            let addrs: Vec<_> = ips.iter().map(|ip| SocketAddr::new(*ip, 0)).collect();
+            builder = builder.resolve_to_addrs(host, &addrs);
+        }
+        if let Some(resolver) = &dns.resolver {
+            builder = builder.dns_resolver(Arc::new(ReqwestDnsResolver(resolver.clone())));
+        }
+        let http = builder.build().map_err(|e| {
+            DidCheqdError::BadConfiguration(format!("failed to build REST client: {e}"))
+        })?;
+        Ok(Self {
+            base_url,
+            http,
+            max_response_size,
+        })
+    }
+
+    pub(crate) async fn did_doc(
+        &self,
+        request: QueryDidDocRequest,
+    ) -> RestResult<QueryDidDocResponse> {
+        let json = self.get(&["cheqd", "did", "v2", &request.id], &[]).await?;
+        Ok(QueryDidDocResponse {
+            value: object(&json, &["value"]).map(did_doc_with_metadata),
+        })
+    }
+
+    pub(crate) async fn did_doc_version(
+        &self,
+        request: QueryDidDocVersionRequest,
+    ) -> RestResult<QueryDidDocVersionResponse> {
+        let path = [
+            "cheqd",
+            "did",
+            "v2",
+            &request.id,
+            "version",
+            &request.version,
+        ];
+        let json = self.get(&path, &[]).await?;
+        Ok(QueryDidDocVersionResponse {
+            value: object(&json, &["value"]).map(did_doc_with_metadata),
+        })
+    }
+
+    /// Fetch a resource, failing as soon as the response exceeds what a resource response of
+    /// `max_size` bytes (as a gRPC message) can be once its content is base64 encoded, or the
+    /// client's `max_response_size`
+    pub(crate) async fn resource(
+        &self,
+        request: QueryResourceRequest,
+        max_size: usize,
+    ) -> RestResult<QueryResourceResponse> {
+        let path = [
+            "cheqd",
+            "resource",
+            "v2",
+            &request.collection_id,
+            "resource",
+            &request.id,
+        ];
+        // base64 encodes each 3 bytes as 4 characters
+        let max_body = max_size
+            .saturating_add(max_size / 3 + 3)
+            .min(self.max_response_size);
+        let json = self.get_limited(&path, &[], max_body).await?;
+        let resource = object(&json, &["resource"])
+            .map(|r| {
+                let data = match object(r, &["resource"]) {
+                    Some(resource) => bytes(resource, &["data"])?,
+                    None => Vec::new(),
+                };
+                Ok::<_, String>(ResourceWithMetadata {
+                    resource: Some(Resource { data }),
+                    metadata: object(r, &["metadata"]).map(resource_metadata),
+                })
+            })
+            .transpose()
+            .map_err(Status::internal)?;
+        Ok(QueryResourceResponse { resource })
+    }
+
+    pub(crate) async fn collection_resources(
+        &self,
+        request: QueryCollectionResourcesRequest,
+    ) -> RestResult<QueryCollectionResourcesResponse> {
+        let path = [
+            "cheqd",
+            "resource",
+            "v2",
+            &request.collection_id,
+            "metadata",
+        ];
+        let mut query = Vec::new();
+        if let Some(page) = &request.pagination {
+            if !page.key.is_empty() {
+                query.push(("pagination.key", BASE64.encode(&page.key)));
+            }
+            if page.limit > 0 {
+                query.push(("pagination.limit", page.limit.to_string()));
+            }
+            if page.count_total {
+                query.push(("pagination.count_total", "true".to_string()));
+            }
+        }
+        let json = self.get(&path, &query).await?;
+        let resources = array(&json, &["resources"])
+            .filter_map(Value::as_object)
+            .map(resource_metadata)
+            .collect();
+        let pagination = object(&json, &["pagination"])
+            .map(|p| {
+                Ok::<_, String>(PageResponse {
+                    next_key: bytes(p, &["next_key", "nextKey"])?,
+                    total: uint(p, &["total"]),
+                })
+            })
+            .transpose()
+            .map_err(Status::internal)?;
+        Ok(QueryCollectionResourcesResponse {
+            resources,
+            pagination,
+        })
+    }
+
+    /// GET a gateway path, returning the JSON response object
+    async fn get(&self, path: &[&str], query: &[(&str, String)]) -> RestResult<Map<String, Value>> {
+        self.get_limited(path, query, self.max_response_size).await
+    }
+
+    /// GET a JSON object, failing once the response body exceeds `max_size` bytes
+    async fn get_limited(
+        &self,
+        path: &[&str],
+        query: &[(&str, String)],
+        max_size: usize,
+    ) -> RestResult<Map<String, Value>> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .map_err(|_| Status::invalid_argument(format!("invalid REST URL {}", self.base_url)))?
+            .pop_if_empty()
+            .extend(path);
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+
+        let mut response = self
+            .http
+            .get(url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .await
+            .map_err(|e| Status::unavailable(format!("REST request failed: {e}")))?;
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .cloned();
+        // read the body in chunks, so oversized bodies aren't buffered whole
+        let too_large = |found: u64| {
+            Status::out_of_range(format!(
+                "REST response message length too large: found {found} bytes, the limit is: \
+                 {max_size} bytes"
+            ))
+        };
+        if let Some(length) = response.content_length() {
+            if length > max_size as u64 {
+                return Err(too_large(length));
+            }
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| Status::unavailable(format!("failed to read REST response: {e}")))?
+        {
+            if body.len() + chunk.len() > max_size {
+                return Err(too_large((body.len() + chunk.len()) as u64));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        let json: Option<Map<String, Value>> = serde_json::from_slice(&body).ok();
+
+        if !status.is_success() {
+            return Err(error_status(status, json.as_ref(), retry_after));
+        }
+        json.ok_or_else(|| Status::internal("REST response is not a JSON object"))
+    }
+}
+
+/// Map a gateway error response into a [Status], preferring the gRPC code in the body
+fn error_status(
+    status: reqwest::StatusCode,
+    body: Option<&Map<String, Value>>,
+    retry_after: Option<reqwest::header::HeaderValue>,
+) -> Status {
+    let code = body
+        .and_then(|b| b.get("code"))
+        .and_then(Value::as_i64)
+        .and_then(|c| i32::try_from(c).ok())
+        .map(Code::from_i32)
+        .filter(|c| *c != Code::Ok)
+        .unwrap_or(match status.as_u16() {
+            400 => Code::InvalidArgument,
+            401 => Code::Unauthenticated,
+            403 => Code::PermissionDenied,
+            404 => Code::NotFound,
+            429 => Code::ResourceExhausted,
+            501 => Code::Unimplemented,
+            502 | 503 => Code::Unavailable,
+            504 => Code::DeadlineExceeded,
+            _ => Code::Unknown,
+        });
+    let message = body
+        .and_then(|b| b.get("message"))
+        .and_then(Value::as_str)
+        .map(str::to_owned)
+        .unwrap_or_else(|| format!("REST gateway responded with {status}"));
+
+    let mut error = Status::new(code, message);
+    if let Some(value) = retry_after.and_then(|v| v.to_str().ok()?.parse().ok()) {
+        error.metadata_mut().insert("retry-after", value);
+    }
+    error
+}
+
+/// Adapts a [DnsResolve] for use by reqwest
+struct ReqwestDnsResolver(Arc<dyn DnsResolve>);
+
+impl reqwest::dns::Resolve for ReqwestDnsResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let resolver = self.0.clone();
+        Box::pin(async move {
+            let ips = resolver.resolve(name.as_str()).await?;
+            let addrs: reqwest::dns::Addrs =
+                Box::new(ips.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+            Ok(addrs)
+        })
+    }
+}
+
+fn did_doc_with_metadata(value: &Map<String, Value>) -> DidDocWithMetadata {
+    DidDocWithMetadata {
+        did_doc: object(value, &["did_doc", "didDoc"]).map(did_doc),
+        metadata: object(value, &["metadata"]).map(did_doc_metadata),
+    }
+}
+
+fn did_doc(doc: &Map<String, Value>) -> DidDoc {
+    DidDoc {
+        context: strings(doc, &["context", "@context"]),
+        id: string(doc, &["id"]),
+        controller: strings(doc, &["controller"]),
+        verification_method: array(doc, &["verification_method", "verificationMethod"])
+            .filter_map(Value::as_object)
+            .map(|vm| VerificationMethod {
+                id: string(vm, &["id"]),
+                verification_method_type: string(
+                    vm,
+                    &["verification_method_type", "verificationMethodType", "type"],
+                ),
+                controller: string(vm, &["controller"]),
+                verification_material: string(
+                    vm,
+                    &["verification_material", "verificationMaterial"],
+                ),
+            })
+            .collect(),
+        authentication: strings(doc, &["authentication"]),
+        assertion_method: strings(doc, &["assertion_method", "assertionMethod"]),
+        capability_invocation: strings(doc, &["capability_invocation", "capabilityInvocation"]),
+        capability_delegation: strings(doc, &["capability_delegation", "capabilityDelegation"]),
+        key_agreement: strings(doc, &["key_agreement", "keyAgreement"]),
+        service: array(doc, &["service"])
+            .filter_map(Value::as_object)
+            .map(|svc| Service {
+                id: string(svc, &["id"]),
+                service_type: string(svc, &["service_type", "serviceType", "type"]),
+                service_endpoint: strings(svc, &["service_endpoint", "serviceEndpoint"]),
+                recipient_keys: strings(svc, &["recipient_keys", "recipientKeys"]),
+                routing_keys: strings(svc, &["routing_keys", "routingKeys"]),
+                accept: strings(svc, &["accept"]),
+                priority: uint(svc, &["priority"]).try_into().unwrap_or_default(),
+            })
+            .collect(),
+        also_known_as: strings(doc, &["also_known_as", "alsoKnownAs"]),
+    }
+}
+
+fn did_doc_metadata(meta: &Map<String, Value>) -> DidDocMetadata {
+    DidDocMetadata {
+        created: timestamp(meta, &["created"]),
+        updated: timestamp(meta, &["updated"]),
+        deactivated: field(meta, &["deactivated"])
+            .and_then(Value::as_bool)
+            .unwrap_or_default(),
+        version_id: string(meta, &["version_id", "versionId"]),
+        next_version_id: string(meta, &["next_version_id", "nextVersionId"]),
+        previous_version_id: string(meta, &["previous_version_id", "previousVersionId"]),
+    }
+}
+
+fn resource_metadata(meta: &Map<String, Value>) -> ResourceMetadata {
+    ResourceMetadata {
+        collection_id: string(meta, &["collection_id", "collectionId"]),
+        id: string(meta, &["id"]),
+        name: string(meta, &["name"]),
+        version: string(meta, &["version"]),
+        resource_type: string(meta, &["resource_type", "resourceType"]),
+        also_known_as: array(meta, &["also_known_as", "alsoKnownAs"])
+            .filter_map(Value::as_object)
+            .map(|aka| AlternativeUri {
+                uri: string(aka, &["uri"]),
+                description: string(aka, &["description"]),
+            })
+            .collect(),
+        media_type: string(meta, &["media_type", "mediaType"]),
+        created: timestamp(meta, &["created"]),
+        checksum: string(meta, &["checksum"]),
+        previous_version_id: string(meta, &["previous_version_id", "previousVersionId"]),
+        next_version_id: string(meta, &["next_version_id", "nextVersionId"]),
+    }
+}
+
+/// the first present field of the given names (proto field names, then JSON names)
+fn field<'a>(value: &'a Map<String, Value>, names: &[&str]) -> Option<&'a Value> {
+    names
+        .iter()
+        .find_map(|name| value.get(*name))
+        .filter(|v| !v.is_null())
+}
+
+fn object<'a>(value: &'a Map<String, Value>, names: &[&str]) -> Option<&'a Map<String, Value>> {
+    field(value, names).and_then(Value::as_object)
+}
+
+fn array<'a>(value: &'a Map<String, Value>, names: &[&str]) -> impl Iterator<Item = &'a Value> {
+    field(value, names)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+fn string(value: &Map<String, Value>, names: &[&str]) -> String {
+    field(value, names)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_owned()
+}
+
+fn strings(value: &Map<String, Value>, names: &[&str]) -> Vec<String> {
+    array(value, names)
+        .filter_map(Value::as_str)
+        .map(str::to_owned)
+        .collect()
+}
+
+/// uint64s are encoded as JSON strings by the gateway
+fn uint(value: &Map<String, Value>, names: &[&str]) -> u64 {
+    match field(value, names) {
+        Some(Value::String(s)) => s.parse().unwrap_or_default(),
+        Some(v) => v.as_u64().unwrap_or_default(),
+        None => 0,
+    }
+}
+
+/// bytes are encoded as base64 strings by the gateway
+fn bytes(value: &Map<String, Value>, names: &[&str]) -> Result<Vec<u8>, String> {
+    match field(value, names).and_then(Value::as_str) {
+        Some(encoded) => BASE64
+            .decode(encoded)
+            .map_err(|e| format!("invalid base64 in REST response: {e}")),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// timestamps are encoded as RFC 3339 strings by the gateway
+fn timestamp(value: &Map<String, Value>, names: &[&str]) -> Option<prost_types::Timestamp> {
+    let time = DateTime::parse_from_rfc3339(field(value, names)?.as_str()?).ok()?;
+    Some(prost_types::Timestamp {
+        seconds: time.timestamp(),
+        nanos: time.timestamp_subsec_nanos().try_into().ok()?,
+    })
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::net::Ipv4Addr;
+
+    use serde_json::json;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_queries_resource_from_gateway() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = async {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 4096];
+            let n = stream.read(&mut request).await.unwrap();
+            let body = json!({"resource": {
+                "resource": {"data": BASE64.encode(b"{}")},
+                "metadata": {"collection_id": "abc", "id": "r1", "media_type": "application/json"},
+            }})
+            .to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                 content-length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request[..n]).into_owned()
+        };
+
+        let client = RestClient::new(
+            &format!("http://127.0.0.1:{port}/api/"),
+            &Default::default(),
+            "did-resolver-cheqd/test",
+            1024,
+        )
+        .unwrap();
+        let query = client.resource(
+            QueryResourceRequest {
+                collection_id: "abc".into(),
+                id: "r1".into(),
+            },
+            1024,
+        );
+        let (response, request) = tokio::join!(query, server);
+
+        assert!(request.starts_with("GET /api/cheqd/resource/v2/abc/resource/r1 HTTP/1.1"));
+        assert!(request.contains("user-agent: did-resolver-cheqd/test\r\n"));
+        let resource = response.unwrap().resource.unwrap();
+        assert_eq!(resource.resource.unwrap().data, b"{}");
+        assert_eq!(resource.metadata.unwrap().media_type, "application/json");
+    }
+
+    #[tokio::test]
+    async fn test_rejects_oversized_responses() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = async {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 4096];
+            let n = stream.read(&mut request).await.unwrap();
+            assert!(n > 0);
+            // no content-length, so the limit is enforced while reading
+            let header = "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                          connection: close\r\n\r\n";
+            stream.write_all(header.as_bytes()).await.unwrap();
+            let _ = stream.write_all(&[b' '; 2048]).await;
+        };
+
+        let client = RestClient::new(
+            &format!("http://127.0.0.1:{port}/api/"),
+            &Default::default(),
+            "did-resolver-cheqd/test",
+            1024,
+        )
+        .unwrap();
+        let query = client.did_doc(QueryDidDocRequest {
+            id: "did:cheqd:testnet:abc".into(),
+        });
+        let (response, ()) = tokio::join!(query, server);
+
+        let status = response.unwrap_err();
+        assert_eq!(status.code(), Code::OutOfRange);
+        assert!(status.message().contains("message length too large"));
+    }
+
+    #[tokio::test]
+    async fn test_limits_resource_responses() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = async {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 4096];
+            let n = stream.read(&mut request).await.unwrap();
+            assert!(n > 0);
+            let body = json!({"resource": {"resource": {"data": BASE64.encode([0; 4096])}}});
+            let body = body.to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        };
+
+        let client = RestClient::new(
+            &format!("http://127.0.0.1:{port}/api/"),
+            &Default::default(),
+            "did-resolver-cheqd/test",
+            1 << 20,
+        )
+        .unwrap();
+        let request = QueryResourceRequest {
+            collection_id: "abc".into(),
+            id: "r1".into(),
+        };
+        let (response, ()) = tokio::join!(client.resource(request, 1024), server);
+
+        let status = response.unwrap_err();
+        assert_eq!(status.code(), Code::OutOfRange);
+        assert!(
+            status.message().contains("the limit is: 1368 bytes"),
+            "{status:?}"
+        );
+    }
+
+    #[test]
+    fn test_maps_did_doc_response() {
+        let json = json!({
+            "value": {
+                "did_doc": {
+                    "context": ["https://www.w3.org/ns/did/v1"],
+                    "id": "did:cheqd:testnet:abc",
+                    "verification_method": [{
+                        "id": "did:cheqd:testnet:abc#key-1",
+                        "verification_method_type": "Ed25519VerificationKey2020",
+                        "controller": "did:cheqd:testnet:abc",
+                        "verification_material": "z6Mk",
+                    }],
+                    "authentication": ["did:cheqd:testnet:abc#key-1"],
+                    "service": [{"id": "#svc", "service_type": "LinkedDomains",
+                        "service_endpoint": ["https://example.com"], "priority": 0}],
+                },
+                "metadata": {
+                    "created": "2023-01-01T12:00:00.5Z",
+                    "deactivated": false,
+                    "version_id": "v1",
+                },
+            },
+        });
+        let value = did_doc_with_metadata(json["value"].as_object().unwrap());
+
+        let doc = value.did_doc.unwrap();
+        assert_eq!(doc.id, "did:cheqd:testnet:abc");
+        assert_eq!(
+            doc.verification_method[0].verification_method_type,
+            "Ed25519VerificationKey2020"
+        );
+        assert_eq!(doc.service[0].service_endpoint, ["https://example.com"]);
+        let metadata = value.metadata.unwrap();
+        assert_eq!(metadata.version_id, "v1");
+        assert_eq!(
+            metadata.created,
+            Some(prost_types::Timestamp {
+                seconds: 1672574400,
+                nanos: 500_000_000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_maps_error_status() {
+        let body = json!({"code": 5, "message": "did not found", "details": []});
+        let status = error_status(
+            reqwest::StatusCode::NOT_FOUND,
+            body.as_object(),
+            Some("3".parse().unwrap()),
+        );
+        assert_eq!(status.code(), Code::NotFound);
+        assert_eq!(status.message(), "did not found");
+        assert_eq!(status.metadata().get("retry-after").unwrap(), "3");
+
+        let status = error_status(reqwest::StatusCode::SERVICE_UNAVAILABLE, None, None);
+        assert_eq!(status.code(), Code::Unavailable);
+    }
+}
@@ -0,0 +1,337 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use ssi_dids_core::{
+    document::{self, representation::MediaType},
+    resolution::{Metadata as ResolutionMetadata, Output},
+};
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    resolution::{
+        parser::{DidCheqdParsed, RECOGNIZED_PARAMETERS},
+        resolver::{DidCheqdResolver, ResolvedResource},
+        transformer::{cheqd_diddoc_metadata_to_json, cheqd_diddoc_to_json, to_canonical_json_vec},
+    },
+};
+
+/// The result of resolving a DID, in the shape of a
+/// [DID resolution result](https://w3c-ccg.github.io/did-resolution/#did-resolution-result),
+/// see [DidCheqdResolver::resolve]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolutionResult {
+    /// the DID document, as converted by [cheqd_diddoc_to_json]. `None` if resolution failed.
+    pub did_document: Option<Value>,
+    /// the DID document metadata, e.g. `created`, `updated`, `deactivated` & `versionId`
+    pub did_document_metadata: Map<String, Value>,
+    /// the DID resolution metadata: the `contentType` of the DID document & how the DID was
+    /// interpreted (`didUrl`, see [crate::resolution::parser::DidCheqdParsed::did_url_metadata])
+    pub did_resolution_metadata: Map<String, Value>,
+    /// how the resolution departed from what was asked of it, for integrators to surface in logs
+    /// & UIs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ResolutionWarning>,
+}
+
+/// Something a resolution assumed or worked around, which didn't fail it, see
+/// [ResolutionResult::warnings]. Serialized with its kind as a camelCase `code`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "code",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+#[non_exhaustive]
+pub enum ResolutionWarning {
+    /// the DID has no namespace, so was resolved in the default namespace
+    NamespaceDefaulted { namespace: String },
+    /// the DID URL has query parameters the resolver doesn't recognize, which were ignored
+    UnknownParametersIgnored { parameters: Vec<String> },
+    /// the resource has no media type, so its media type was inferred from its content (see
+    /// [crate::resolution::resolver::DidCheqdResolverConfiguration::sniff_media_types])
+    MediaTypeInferred { media_type: String },
+    /// the node's response was missing a field, which was filled with a default (see
+    /// [crate::resolution::response_validation::ResponseValidation::Lenient])
+    ResponseFixed { message: String },
+}
+
+impl ResolutionWarning {
+    /// The warnings about how a DID URL was interpreted
+    pub fn for_did_url(parsed: &DidCheqdParsed) -> Vec<Self> {
+        let mut warnings = Vec::new();
+        if parsed.namespace_defaulted {
+            warnings.push(ResolutionWarning::NamespaceDefaulted {
+                namespace: parsed.namespace.clone(),
+            });
+        }
+        let mut unknown: Vec<_> = parsed
+            .query
+            .iter()
+            .flatten()
+            .map(|(name, _)| name)
+            .filter(|name| !RECOGNIZED_PARAMETERS.contains(&name.as_str()))
+            .cloned()
+            .collect();
+        if !unknown.is_empty() {
+            unknown.sort();
+            warnings.push(ResolutionWarning::UnknownParametersIgnored {
+                parameters: unknown,
+            });
+        }
+        warnings
+    }
+
+    /// The warnings of the fields filled in a node's response, e.g. [ResolvedResource::warnings]
+    fn response_fixes(fixes: Vec<String>) -> impl Iterator<Item = Self> {
+        fixes
+            .into_iter()
+            .map(|message| ResolutionWarning::ResponseFixed { message })
+    }
+}
+
+impl fmt::Display for ResolutionWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolutionWarning::NamespaceDefaulted { namespace } => {
+                write!(f, "no namespace given, so `{namespace}` was assumed")
+            }
+            ResolutionWarning::UnknownParametersIgnored { parameters } => {
+                write!(f, "ignored unknown parameters: {}", parameters.join(", "))
+            }
+            ResolutionWarning::MediaTypeInferred { media_type } => {
+                write!(f, "media type `{media_type}` was inferred from the content")
+            }
+            ResolutionWarning::ResponseFixed { message } => f.write_str(message),
+        }
+    }
+}
+
+impl ResolutionResult {
+    /// The result of a failed resolution: no DID document, with the error's
+    /// [crate::error::envelope::ErrorEnvelope] properties (`error`, `errorMessage` & `details`) as the resolution metadata
+    pub fn from_error(e: &DidCheqdError) -> Self {
+        let did_resolution_metadata = match serde_json::to_value(e.to_envelope()) {
+            Ok(Value::Object(metadata)) => metadata,
+            _ => Map::new(),
+        };
+        ResolutionResult {
+            did_resolution_metadata,
+            ..Default::default()
+        }
+    }
+
+    /// Convert the result into an [ssi_dids_core] resolution output, with the DID document
+    /// serialized as (canonical) JSON
+    pub fn into_output(self) -> DidCheqdResult<Output<Vec<u8>>> {
+        let did_document = self.did_document.unwrap_or(Value::Null);
+        let document = to_canonical_json_vec(&did_document)?;
+        let deactivated = self
+            .did_document_metadata
+            .get("deactivated")
+            .and_then(Value::as_bool);
+        let content_type = self
+            .did_resolution_metadata
+            .get("contentType")
+            .and_then(Value::as_str)
+            .map(str::to_owned);
+        Ok(Output::new(
+            document,
+            document::Metadata { deactivated },
+            ResolutionMetadata::from_content_type(content_type),
+        ))
+    }
+}
+
+impl DidCheqdResolver {
+    /// Resolve a DID (optionally with a `/versions/<id>` path or `versionId` query) to a
+    /// [ResolutionResult]. Resource DID URLs are rejected with [DidCheqdError::InvalidDidUrl],
+    /// see [DidCheqdResolver::resolve_resource].
+    pub async fn resolve(&self, did_url: &str) -> DidCheqdResult<ResolutionResult> {
+        let parsed = self.parse(did_url)?;
+        if parsed.is_resource() {
+            return Err(DidCheqdError::InvalidDidUrl(format!(
+                "`{did_url}` identifies a resource, not a DID document"
+            )));
+        }
+        let did_url_metadata = parsed.did_url_metadata();
+        let mut warnings = ResolutionWarning::for_did_url(&parsed);
+        let resolved = self.resolve_did_doc(parsed).await?;
+        warnings.extend(ResolutionWarning::response_fixes(resolved.warnings));
+
+        let did_document_metadata = match resolved.metadata {
+            Some(metadata) => match cheqd_diddoc_metadata_to_json(metadata)? {
+                Value::Object(metadata) => metadata,
+                _ => Map::new(),
+            },
+            None => Map::new(),
+        };
+        let mut did_resolution_metadata = Map::new();
+        did_resolution_metadata.insert(
+            "contentType".to_string(),
+            Value::String(MediaType::JsonLd.to_string()),
+        );
+        did_resolution_metadata.insert("didUrl".to_string(), did_url_metadata);
+        Ok(ResolutionResult {
+            did_document: Some(cheqd_diddoc_to_json(resolved.did_doc)?),
+            did_document_metadata,
+            did_resolution_metadata,
+            warnings,
+        })
+    }
+
+    /// The warnings of a resource resolved from a DID URL (see
+    /// [DidCheqdResolver::resolve_resource]), as [ResolutionResult::warnings] are of DIDs
+    pub fn resource_warnings(
+        &self,
+        parsed: &DidCheqdParsed,
+        resolved: &ResolvedResource,
+    ) -> Vec<ResolutionWarning> {
+        let mut warnings = ResolutionWarning::for_did_url(parsed);
+        if let Some((media_type, true)) =
+            self.effective_media_type(&resolved.metadata, &resolved.data)
+        {
+            warnings.push(ResolutionWarning::MediaTypeInferred { media_type });
+        }
+        warnings.extend(ResolutionWarning::response_fixes(resolved.warnings.clone()));
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        proto::cheqd::did::v2::{DidDoc, Metadata as DidDocMetadata},
+        resolution::resolver::DidCheqdResolverConfiguration,
+        test_utils::{LedgerFixtures, MockCheqdNode},
+    };
+
+    const DID: &str = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+
+    #[tokio::test]
+    async fn test_resolve_to_resolution_result() {
+        let fixtures = LedgerFixtures::new().with_did_doc(
+            DidDoc {
+                id: DID.into(),
+                ..Default::default()
+            },
+            DidDocMetadata {
+                version_id: "v1".into(),
+                deactivated: true,
+                ..Default::default()
+            },
+        );
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            ..Default::default()
+        });
+
+        let result = resolver.resolve(DID).await.unwrap();
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["didDocument"]["id"], DID);
+        assert_eq!(
+            json["didDocumentMetadata"],
+            json!({"deactivated": true, "versionId": "v1"})
+        );
+        assert_eq!(
+            json["didResolutionMetadata"]["contentType"],
+            "application/did+ld+json"
+        );
+        assert_eq!(json["didResolutionMetadata"]["didUrl"]["did"], DID);
+        assert_eq!(
+            serde_json::from_value::<ResolutionResult>(json).unwrap(),
+            result
+        );
+
+        let output = result.into_output().unwrap();
+        assert_eq!(output.document_metadata.deactivated, Some(true));
+        let document: Value = serde_json::from_slice(&output.document).unwrap();
+        assert_eq!(document["id"], DID);
+
+        let e = resolver
+            .resolve(&format!("{DID}/resources/r1"))
+            .await
+            .unwrap_err();
+        assert!(matches!(e, DidCheqdError::InvalidDidUrl(_)), "{e:?}");
+        let failed = ResolutionResult::from_error(&e);
+        assert!(failed.did_document.is_none());
+        assert_eq!(failed.did_resolution_metadata["error"], "invalidDidUrl");
+    }
+
+    #[tokio::test]
+    async fn test_resolution_warnings() {
+        use crate::{
+            proto::cheqd::resource::v2::Metadata as ResourceMetadata,
+            resolution::response_validation::ResponseValidation,
+        };
+
+        let did_doc = DidDoc {
+            id: "did:cheqd:mainnet:Ps1ysXP2Ae6GBfxNhNQNKN".into(),
+            ..Default::default()
+        };
+        let resource = ResourceMetadata {
+            collection_id: "Ps1ysXP2Ae6GBfxNhNQNKN".into(),
+            id: "r1".into(),
+            ..Default::default()
+        };
+        let fixtures = LedgerFixtures::new()
+            .with_did_doc(did_doc, Default::default())
+            .with_resource(br#"{"a": 1}"#.to_vec(), resource);
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("mainnet")],
+            response_validation: ResponseValidation::Lenient,
+            sniff_media_types: true,
+            ..Default::default()
+        });
+
+        let result = resolver
+            .resolve("did:cheqd:Ps1ysXP2Ae6GBfxNhNQNKN?b=2&a=1")
+            .await
+            .unwrap();
+        assert_eq!(
+            result.warnings[..2],
+            [
+                ResolutionWarning::NamespaceDefaulted {
+                    namespace: "mainnet".into()
+                },
+                ResolutionWarning::UnknownParametersIgnored {
+                    parameters: vec!["a".into(), "b".into()]
+                },
+            ]
+        );
+        // the fixture's metadata has no timestamps, so lenient validation fills them
+        assert!(matches!(
+            result.warnings[2..],
+            [ResolutionWarning::ResponseFixed { .. }, ..]
+        ));
+        let json = serde_json::to_value(&result.warnings[0]).unwrap();
+        assert_eq!(
+            json,
+            json!({"code": "namespaceDefaulted", "namespace": "mainnet"})
+        );
+
+        let did_url = "did:cheqd:mainnet:Ps1ysXP2Ae6GBfxNhNQNKN/resources/r1";
+        let parsed = resolver.parse(did_url).unwrap();
+        let resolved = resolver
+            .resolve_resource(did_url, parsed.clone())
+            .await
+            .unwrap();
+        let warnings = resolver.resource_warnings(&parsed, &resolved);
+        assert_eq!(
+            warnings[0],
+            ResolutionWarning::MediaTypeInferred {
+                media_type: "application/json".into()
+            }
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "media type `application/json` was inferred from the content"
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use std::task::{Context, Poll};
+
+use futures_util::future::BoxFuture;
+use ssi_dids_core::DIDURLBuf;
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    resolution::resolver::{DidCheqdResolver, ResolvedDidDoc, ResolvedResource},
+};
+
+/// The result of resolving a DID URL through a [CheqdResolverService]
+#[derive(Debug, Clone)]
+pub enum Resolved {
+    /// the DID URL identified a DID document (optionally a version of it)
+    Document(ResolvedDidDoc),
+    /// the DID URL identified a resource, by id or by a name & type query
+    Resource(ResolvedResource),
+}
+
+/// An object-safe interface to did:cheqd resolution, so applications can hold an
+/// `Arc<dyn CheqdResolve>` & swap implementations, e.g. for `test_utils::MockResolver` in tests.
+pub trait CheqdResolve: Send + Sync {
+    /// Resolve a DID (optionally with a `/versions/<id>` path or `versionId` query) to its DID
+    /// document
+    fn resolve_did<'a>(&'a self, did: &'a str) -> BoxFuture<'a, DidCheqdResult<ResolvedDidDoc>>;
+
+    /// Dereference a resource DID URL, by a `/resources/<id>` path or a resource query
+    fn resolve_resource_url<'a>(
+        &'a self,
+        did_url: &'a str,
+    ) -> BoxFuture<'a, DidCheqdResult<ResolvedResource>>;
+
+    /// Resolve a DID URL to whatever it identifies, see [DidCheqdResolver::resolve_did_url]
+    fn resolve_did_url<'a>(&'a self, did_url: &'a str) -> BoxFuture<'a, DidCheqdResult<Resolved>>;
+}
+
+impl CheqdResolve for DidCheqdResolver {
+    fn resolve_did<'a>(&'a self, did: &'a str) -> BoxFuture<'a, DidCheqdResult<ResolvedDidDoc>> {
+        Box::pin(async move { self.resolve_did_doc(self.parse(did)?).await })
+    }
+
+    fn resolve_resource_url<'a>(
+        &'a self,
+        did_url: &'a str,
+    ) -> BoxFuture<'a, DidCheqdResult<ResolvedResource>> {
+        Box::pin(async move { self.resolve_resource(did_url, self.parse(did_url)?).await })
+    }
+
+    fn resolve_did_url<'a>(&'a self, did_url: &'a str) -> BoxFuture<'a, DidCheqdResult<Resolved>> {
+        Box::pin(DidCheqdResolver::resolve_did_url(self, did_url))
+    }
+}
+
+/// A [tower::Service] resolving DID URLs with a [DidCheqdResolver] (sharing its state), so resolution can be
+/// composed with tower middleware (timeouts, load-shedding, instrumentation, ...).
+///
+/// DID URLs are resolved with [DidCheqdResolver::resolve_did_url]. The service is always ready.
+#[derive(Clone)]
+pub struct CheqdResolverService {
+    resolver: DidCheqdResolver,
+}
+
+impl CheqdResolverService {
+    pub fn new(resolver: DidCheqdResolver) -> Self {
+        Self { resolver }
+    }
+
+    /// The resolver shared by the service
+    pub fn resolver(&self) -> &DidCheqdResolver {
+        &self.resolver
+    }
+
+    fn resolve(&self, did_url: String) -> BoxFuture<'static, Result<Resolved, DidCheqdError>> {
+        let resolver = self.resolver.clone();
+        Box::pin(async move { resolver.resolve_did_url(&did_url).await })
+    }
+}
+
+impl tower::Service<DIDURLBuf> for CheqdResolverService {
+    type Response = Resolved;
+    type Error = DidCheqdError;
+    type Future = BoxFuture<'static, Result<Resolved, DidCheqdError>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, did_url: DIDURLBuf) -> Self::Future {
+        self.resolve(did_url.as_str().to_owned())
+    }
+}
+
+impl tower::Service<String> for CheqdResolverService {
+    type Response = Resolved;
+    type Error = DidCheqdError;
+    type Future = BoxFuture<'static, Result<Resolved, DidCheqdError>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, did_url: String) -> Self::Future {
+        self.resolve(did_url)
+    }
+}
+
+impl DidCheqdResolver {
+    /// Wrap the resolver in a [tower::Service], see [CheqdResolverService]
+    pub fn into_service(self) -> CheqdResolverService {
+        CheqdResolverService::new(self)
+    }
+
+    /// Resolve a DID URL to whatever it identifies: the resource for DID URLs with a resource
+    /// path or query (see [crate::resolution::parser::DidCheqdParsed::is_resource]), otherwise
+    /// the DID document. `/metadata` DID URLs resolve to the same, as both carry their metadata.
+    /// Errors are those of [DidCheqdResolver::resolve_did_doc] &
+    /// [DidCheqdResolver::resolve_resource], with their context.
+    pub async fn resolve_did_url(&self, did_url: &str) -> Result<Resolved, DidCheqdError> {
+        let parsed = self.parse(did_url)?;
+        if parsed.is_resource() {
+            self.resolve_resource(did_url, parsed)
+                .await
+                .map(Resolved::Resource)
+        } else {
+            self.resolve_did_doc(parsed).await.map(Resolved::Document)
+        }
+    }
+
+    /// Resolve a DID URL as [DidCheqdResolver::resolve_did_url], with the resolver of the named
+    /// profile (see [crate::resolution::resolver::DidCheqdResolverConfiguration::profiles])
+    pub async fn resolve_with_profile(
+        &self,
+        profile: &str,
+        did_url: &str,
+    ) -> Result<Resolved, DidCheqdError> {
+        self.profile(profile)?.resolve_did_url(did_url).await
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use tower::Service;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_service_routes_to_resolver() {
+        let mut service = DidCheqdResolver::new(Default::default()).into_service();
+
+        let did_url = DIDURLBuf::from_string(
+            "did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN?resourceName=a&resourceType=b".into(),
+        )
+        .unwrap();
+        let e = service.call(did_url).await.unwrap_err();
+        assert!(matches!(e.root(), DidCheqdError::NetworkNotSupported(_)));
+        assert_eq!(e.operation(), Some("query_resource"));
+
+        for did_url in [
+            "did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN",
+            "did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN?versionId=v1",
+        ] {
+            let e = service.call(did_url.to_string()).await.unwrap_err();
+            assert!(matches!(e.root(), DidCheqdError::NetworkNotSupported(_)));
+            assert_eq!(e.operation(), Some("query_did_doc"));
+        }
+    }
+}
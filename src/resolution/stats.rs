@@ -0,0 +1,71 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::resolution::latency::EndpointLatency;
+
+/// A snapshot of a resolver's cache, connections & activity, see
+/// [crate::resolution::resolver::DidCheqdResolver::stats], e.g. for operators' dashboards
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolverStats {
+    /// the number of DID documents (of any version) cached, including expired ones not yet
+    /// evicted
+    pub cached_did_docs: usize,
+    /// the total size in bytes of the cached DID documents
+    pub cached_did_doc_bytes: usize,
+    /// the number of resources cached, including expired ones not yet evicted
+    pub cached_resources: usize,
+    /// the total size in bytes of the cached resources
+    pub cached_resource_bytes: usize,
+    /// the number of connected node clients (gRPC channels or REST clients) per network namespace.
+    /// Networks configured with the same node endpoint share its client.
+    pub channels: HashMap<String, usize>,
+    /// the number of queries to nodes currently in flight
+    pub in_flight: usize,
+    /// the number of DIDs & DID URLs resolved (successfully or not) since the resolver was
+    /// assembled
+    pub resolutions: u64,
+    /// the number of resolutions which failed
+    pub resolution_failures: u64,
+    /// the number of results served from the cache
+    pub cache_hits: u64,
+    /// the number of queries sent to nodes
+    pub queries: u64,
+    /// the rolling latency of each node endpoint queried, by URL
+    pub endpoint_latencies: HashMap<String, EndpointLatency>,
+}
+
+/// The cumulative counters of [ResolverStats]
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    resolutions: AtomicU64,
+    resolution_failures: AtomicU64,
+    cache_hits: AtomicU64,
+    queries: AtomicU64,
+}
+
+impl Counters {
+    pub(crate) fn record_resolution(&self, succeeded: bool) {
+        self.resolutions.fetch_add(1, Ordering::Relaxed);
+        if !succeeded {
+            self.resolution_failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn record_cache_hit(&self) {
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_query(&self) {
+        self.queries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Copy the counters into `stats`
+    pub(crate) fn snapshot(&self, stats: &mut ResolverStats) {
+        stats.resolutions = self.resolutions.load(Ordering::Relaxed);
+        stats.resolution_failures = self.resolution_failures.load(Ordering::Relaxed);
+        stats.cache_hits = self.cache_hits.load(Ordering::Relaxed);
+        stats.queries = self.queries.load(Ordering::Relaxed);
+    }
+}
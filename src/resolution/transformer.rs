@@ -1,14 +1,24 @@
 use chrono::{DateTime, Utc};
+use serde::{Serialize, Serializer, ser::SerializeMap};
 use serde_json::{Value, json};
 
 use crate::{
     error::{DidCheqdError, DidCheqdResult},
     proto::cheqd::{
-        did::v2::{DidDoc as CheqdDidDoc, Metadata as CheqdDidDocMetadata},
+        did::v2::{
+            DidDoc as CheqdDidDoc, Metadata as CheqdDidDocMetadata, Service as CheqdService,
+        },
         resource::v2::Metadata as CheqdResourceMetadata,
     },
+    resolution::parser::DID_CHEQD_PREFIX,
 };
 
+/// The DID URL path segment under which a DID's resources are addressed
+pub const RESOURCES_PATH: &str = "resources";
+
+/// The DID URL path segment, following a resource's DID URL, addressing its metadata
+pub const METADATA_PATH: &str = "metadata";
+
 /// Convert a CheqdDidDoc proto message into a serde_json::Value representing a W3C DID Document.
 /// This avoids depending on external DID Document types and produces a JSON structure that can be
 /// serialized into bytes for the ssi_dids_core `Output<Vec<u8>>` path.
@@ -40,6 +50,8 @@ pub fn cheqd_diddoc_to_json(value: CheqdDidDoc) -> Result<Value, DidCheqdError>
             .verification_method
             .into_iter()
             .map(|vm| {
+                let (property, material) =
+                    verification_material(&vm.verification_method_type, vm.verification_material);
                 let mut o = serde_json::Map::new();
                 o.insert("id".to_string(), Value::String(vm.id));
                 o.insert(
@@ -47,12 +59,7 @@ pub fn cheqd_diddoc_to_json(value: CheqdDidDoc) -> Result<Value, DidCheqdError>
                     Value::String(vm.verification_method_type),
                 );
                 o.insert("controller".to_string(), Value::String(vm.controller));
-                // try to parse verification material as JSON, otherwise keep as string
-                let material = match serde_json::from_str::<Value>(&vm.verification_material) {
-                    Ok(v) => v,
-                    Err(_) => Value::String(vm.verification_material),
-                };
-                o.insert("publicKey".to_string(), material);
+                o.insert(property.to_string(), material);
                 Value::Object(o)
             })
             .collect();
@@ -105,83 +112,99 @@ pub fn cheqd_diddoc_to_json(value: CheqdDidDoc) -> Result<Value, DidCheqdError>
     }
 
     if !value.service.is_empty() {
-        let services: Vec<Value> = value
-            .service
-            .into_iter()
-            .map(|svc| {
-                let mut o = serde_json::Map::new();
+        doc["service"] = Value::Array(
+            value
+                .service
+                .into_iter()
+                .map(cheqd_service_to_json)
+                .collect(),
+        );
+    }
 
-                // required fields
-                o.insert("id".to_string(), Value::String(svc.id));
-                o.insert(
-                    "type".to_string(),
-                    serde_json::from_value(json!(svc.service_type))
-                        .unwrap_or(Value::String(svc.service_type)),
-                );
+    // alsoKnownAs
+    if !value.also_known_as.is_empty() {
+        doc["alsoKnownAs"] =
+            Value::Array(value.also_known_as.into_iter().map(Value::String).collect());
+    }
 
-                // serviceEndpoint (single or multiple)
-                if !svc.service_endpoint.is_empty() {
-                    if svc.service_endpoint.len() == 1 {
-                        o.insert(
-                            "serviceEndpoint".to_string(),
-                            Value::String(svc.service_endpoint[0].clone()),
-                        );
-                    } else {
-                        o.insert(
-                            "serviceEndpoint".to_string(),
-                            Value::Array(
-                                svc.service_endpoint
-                                    .into_iter()
-                                    .map(Value::String)
-                                    .collect(),
-                            ),
-                        );
-                    }
-                }
+    Ok(doc)
+}
 
-                // recipientKeys
-                if !svc.recipient_keys.is_empty() {
-                    o.insert(
-                        "recipientKeys".to_string(),
-                        Value::Array(svc.recipient_keys.into_iter().map(Value::String).collect()),
-                    );
-                }
+/// Convert a CheqdService proto message into the JSON of a DID document service, as in the
+/// documents of [cheqd_diddoc_to_json]
+pub fn cheqd_service_to_json(svc: CheqdService) -> Value {
+    let mut o = serde_json::Map::new();
 
-                // routingKeys
-                if !svc.routing_keys.is_empty() {
-                    o.insert(
-                        "routingKeys".to_string(),
-                        Value::Array(svc.routing_keys.into_iter().map(Value::String).collect()),
-                    );
-                }
+    // required fields
+    o.insert("id".to_string(), Value::String(svc.id));
+    o.insert(
+        "type".to_string(),
+        serde_json::from_value(json!(svc.service_type)).unwrap_or(Value::String(svc.service_type)),
+    );
 
-                // accept
-                if !svc.accept.is_empty() {
-                    o.insert(
-                        "accept".to_string(),
-                        Value::Array(svc.accept.into_iter().map(Value::String).collect()),
-                    );
-                }
+    // serviceEndpoint (single or multiple)
+    if !svc.service_endpoint.is_empty() {
+        if svc.service_endpoint.len() == 1 {
+            o.insert(
+                "serviceEndpoint".to_string(),
+                Value::String(svc.service_endpoint[0].clone()),
+            );
+        } else {
+            o.insert(
+                "serviceEndpoint".to_string(),
+                Value::Array(
+                    svc.service_endpoint
+                        .into_iter()
+                        .map(Value::String)
+                        .collect(),
+                ),
+            );
+        }
+    }
 
-                // priority
-                if svc.priority != 0 {
-                    o.insert("priority".to_string(), Value::Number(svc.priority.into()));
-                }
+    // recipientKeys
+    if !svc.recipient_keys.is_empty() {
+        o.insert(
+            "recipientKeys".to_string(),
+            Value::Array(svc.recipient_keys.into_iter().map(Value::String).collect()),
+        );
+    }
 
-                Value::Object(o)
-            })
-            .collect();
+    // routingKeys
+    if !svc.routing_keys.is_empty() {
+        o.insert(
+            "routingKeys".to_string(),
+            Value::Array(svc.routing_keys.into_iter().map(Value::String).collect()),
+        );
+    }
 
-        doc["service"] = Value::Array(services);
+    // accept
+    if !svc.accept.is_empty() {
+        o.insert(
+            "accept".to_string(),
+            Value::Array(svc.accept.into_iter().map(Value::String).collect()),
+        );
     }
 
-    // alsoKnownAs
-    if !value.also_known_as.is_empty() {
-        doc["alsoKnownAs"] =
-            Value::Array(value.also_known_as.into_iter().map(Value::String).collect());
+    // priority
+    if svc.priority != 0 {
+        o.insert("priority".to_string(), Value::Number(svc.priority.into()));
     }
 
-    Ok(doc)
+    Value::Object(o)
+}
+
+/// The DID document property of a verification method's material, by the ledger's encoding of
+/// each type: JWKs are JSON (`publicKeyJwk`), `Ed25519VerificationKey2018` keys are base58
+/// (`publicKeyBase58`) & other keys are multibase (`publicKeyMultibase`)
+fn verification_material(method_type: &str, material: String) -> (&'static str, Value) {
+    if let Ok(jwk @ Value::Object(_)) = serde_json::from_str::<Value>(&material) {
+        return ("publicKeyJwk", jwk);
+    }
+    match method_type {
+        "Ed25519VerificationKey2018" => ("publicKeyBase58", Value::String(material)),
+        _ => ("publicKeyMultibase", Value::String(material)),
+    }
 }
 
 // Note: We no longer map verification methods into external VerificationMethod types.
@@ -216,14 +239,45 @@ pub fn cheqd_diddoc_metadata_to_json(value: CheqdDidDocMetadata) -> Result<Value
             Value::String(value.next_version_id),
         );
     }
+    if !value.previous_version_id.is_empty() {
+        obj.insert(
+            "previousVersionId".to_string(),
+            Value::String(value.previous_version_id),
+        );
+    }
     Ok(Value::Object(obj))
 }
 
+/// The canonical DID URL of a resource on the network with the given namespace:
+/// `did:cheqd:<namespace>:<collection id>/resources/<resource id>`
+pub fn resource_did_url(namespace: &str, meta: &CheqdResourceMetadata) -> String {
+    format!(
+        "{DID_CHEQD_PREFIX}{namespace}:{}/{RESOURCES_PATH}/{}",
+        meta.collection_id, meta.id
+    )
+}
+
+/// The URI of a resource's metadata on the network with the given namespace:
+/// `did:cheqd:<namespace>:<collection id>/resources/<resource id>/metadata`
+pub fn resource_metadata_uri(namespace: &str, meta: &CheqdResourceMetadata) -> String {
+    format!("{}/{METADATA_PATH}", resource_did_url(namespace, meta))
+}
+
 pub struct CheqdResourceMetadataWithUri {
     pub uri: String,
     pub meta: CheqdResourceMetadata,
 }
 
+impl CheqdResourceMetadataWithUri {
+    /// Resource metadata with its canonical DID URL, see [resource_did_url]
+    pub fn new(namespace: &str, meta: CheqdResourceMetadata) -> Self {
+        Self {
+            uri: resource_did_url(namespace, &meta),
+            meta,
+        }
+    }
+}
+
 pub fn cheqd_resource_metadata_with_uri_to_json(
     value: CheqdResourceMetadataWithUri,
 ) -> Result<Value, DidCheqdError> {
@@ -260,9 +314,139 @@ pub fn cheqd_resource_metadata_with_uri_to_json(
     Ok(Value::Object(obj))
 }
 
+/// Serialize a JSON value with the keys of every object in lexicographic (byte) order.
+///
+/// The output is stable regardless of how the value was built & whether serde_json's
+/// `preserve_order` feature is enabled, so it is suitable for hashing or signing resolved
+/// documents. Resolved DID documents are serialized with this.
+pub fn to_canonical_json_vec(value: &Value) -> serde_json::Result<Vec<u8>> {
+    serde_json::to_vec(&SortedKeys(value))
+}
+
+/// Serializes the wrapped value with object keys sorted, see [to_canonical_json_vec]
+struct SortedKeys<'a>(&'a Value);
+
+impl Serialize for SortedKeys<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Value::Object(map) => {
+                let mut entries: Vec<_> = map.iter().collect();
+                entries.sort_unstable_by_key(|(key, _)| *key);
+                let mut object = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    object.serialize_entry(key, &SortedKeys(value))?;
+                }
+                object.end()
+            }
+            Value::Array(values) => serializer.collect_seq(values.iter().map(SortedKeys)),
+            value => value.serialize(serializer),
+        }
+    }
+}
+
 fn prost_timestamp_to_dt(mut timestamp: prost_types::Timestamp) -> DidCheqdResult<DateTime<Utc>> {
     timestamp.normalize();
     DateTime::from_timestamp(timestamp.seconds, timestamp.nanos.try_into()?).ok_or(
         DidCheqdError::Other(format!("Unknown error, bad timestamp: {timestamp:?}").into()),
     )
 }
+
+#[cfg(test)]
+mod unit_tests {
+    use proptest::{collection::vec, prelude::*};
+
+    use super::*;
+    use crate::proto::cheqd::did::v2::{Service, VerificationMethod};
+
+    /// strings which are sometimes (often malformed) JSON, as ledger data may be
+    fn field() -> impl Strategy<Value = String> {
+        "[\\[\\]{}\":,0-9a-z ]{0,16}"
+    }
+
+    fn diddoc() -> impl Strategy<Value = CheqdDidDoc> {
+        let verification_method =
+            (field(), field(), field(), field()).prop_map(|(id, t, c, m)| VerificationMethod {
+                id,
+                verification_method_type: t,
+                controller: c,
+                verification_material: m,
+            });
+        let service = (field(), field(), vec(field(), 0..3), any::<u32>()).prop_map(
+            |(id, service_type, service_endpoint, priority)| Service {
+                id,
+                service_type,
+                service_endpoint,
+                priority,
+                ..Default::default()
+            },
+        );
+        (
+            vec(field(), 0..3),
+            field(),
+            vec(verification_method, 0..3),
+            vec(field(), 0..3),
+            vec(service, 0..3),
+        )
+            .prop_map(
+                |(context, id, verification_method, assertion_method, service)| CheqdDidDoc {
+                    context,
+                    id,
+                    verification_method,
+                    assertion_method,
+                    service,
+                    ..Default::default()
+                },
+            )
+    }
+
+    proptest! {
+        #[test]
+        fn test_transform_arbitrary_diddoc(doc in diddoc()) {
+            let id = doc.id.clone();
+            let json = cheqd_diddoc_to_json(doc).unwrap();
+            prop_assert_eq!(&json["id"], &Value::String(id));
+            let context = json["@context"].as_array().unwrap();
+            prop_assert!(context.contains(&json!("https://www.w3.org/ns/did/v1")));
+            prop_assert!(to_canonical_json_vec(&json).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_canonical_json_sorts_nested_keys() {
+        let mut inner = serde_json::Map::new();
+        inner.insert("z".into(), json!(1));
+        inner.insert("a".into(), json!([{"y": true, "b": null}]));
+        let mut outer = serde_json::Map::new();
+        outer.insert("id".into(), json!("did:cheqd:mainnet:abc"));
+        outer.insert("@context".into(), json!(["https://www.w3.org/ns/did/v1"]));
+        outer.insert("inner".into(), Value::Object(inner));
+
+        let json = to_canonical_json_vec(&Value::Object(outer)).unwrap();
+        assert_eq!(
+            String::from_utf8(json).unwrap(),
+            r#"{"@context":["https://www.w3.org/ns/did/v1"],"id":"did:cheqd:mainnet:abc","inner":{"a":[{"b":null,"y":true}],"z":1}}"#
+        );
+    }
+    #[test]
+    fn test_resource_uris() {
+        let meta = CheqdResourceMetadata {
+            collection_id: "abc".into(),
+            id: "r1".into(),
+            created: Some(Default::default()),
+            ..Default::default()
+        };
+        assert_eq!(
+            resource_did_url("testnet", &meta),
+            "did:cheqd:testnet:abc/resources/r1"
+        );
+        assert_eq!(
+            resource_metadata_uri("testnet", &meta),
+            "did:cheqd:testnet:abc/resources/r1/metadata"
+        );
+        let json = cheqd_resource_metadata_with_uri_to_json(CheqdResourceMetadataWithUri::new(
+            "testnet", meta,
+        ))
+        .unwrap();
+        assert_eq!(json["uri"], "did:cheqd:testnet:abc/resources/r1");
+    }
+}
@@ -0,0 +1,301 @@
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    error::DidCheqdResult,
+    resolution::resolver::{DidCheqdResolver, did_error_context},
+};
+
+/// The `resourceType` of AnonCreds schemas
+pub const ANONCREDS_SCHEMA: &str = "anonCredsSchema";
+/// The `resourceType` of AnonCreds credential definitions
+pub const ANONCREDS_CRED_DEF: &str = "anonCredsCredDef";
+/// The `resourceType` of Status List 2021 revocation lists
+pub const STATUS_LIST_2021_REVOCATION: &str = "StatusList2021Revocation";
+/// The `resourceType` of Status List 2021 suspension lists
+pub const STATUS_LIST_2021_SUSPENSION: &str = "StatusList2021Suspension";
+/// The `resourceType` of JSON schemas for W3C credentials
+pub const JSON_SCHEMA_2023: &str = "JSONSchema2023";
+
+/// An AnonCreds schema, as published by cheqd issuers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnonCredsSchema {
+    pub name: String,
+    pub version: String,
+    pub attr_names: Vec<String>,
+    /// the issuer's DID, which the resource's collection identifies if omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer_id: Option<String>,
+}
+
+/// An AnonCreds credential definition, as published by cheqd issuers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnonCredsCredDef {
+    /// the DID URL of the schema the credential definition is for
+    pub schema_id: String,
+    /// the signature type, e.g. `CL`
+    #[serde(rename = "type")]
+    pub signature_type: String,
+    pub tag: String,
+    /// the public keys, left as JSON
+    pub value: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer_id: Option<String>,
+}
+
+/// A Status List 2021 published as a resource, with the bitstring encoded in `encodedList`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusList2021 {
+    #[serde(rename = "StatusList2021")]
+    pub status_list: StatusList2021Entry,
+    pub metadata: StatusList2021Metadata,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusList2021Entry {
+    /// `revocation` or `suspension`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_purpose: Option<String>,
+    /// the gzipped & encoded (see [StatusList2021Metadata::encoding]) bitstring
+    pub encoded_list: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusList2021Metadata {
+    /// the resource type of the list, e.g. `StatusList2021Revocation`
+    #[serde(rename = "type")]
+    pub list_type: String,
+    /// the encoding of the bitstring, e.g. `base64url`
+    #[serde(default)]
+    pub encoding: String,
+    /// whether the bitstring is encrypted, so only readable with the issuer's key
+    #[serde(default)]
+    pub encrypted: bool,
+}
+
+/// A resource deserialized according to its `resourceType`, see
+/// [DidCheqdResolver::resolve_resource_typed]
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum TypedResource {
+    AnonCredsSchema(AnonCredsSchema),
+    AnonCredsCredDef(AnonCredsCredDef),
+    StatusList2021(StatusList2021),
+    /// a JSON schema (`JSONSchema2023`)
+    JsonSchema(Value),
+    /// a resource of a JSON type registered by the application, see
+    /// [ResourceTypeRegistry::register_json]
+    Json {
+        resource_type: String,
+        value: Value,
+    },
+    /// a resource of a type without a registered decoder, as its raw content
+    Unknown {
+        resource_type: String,
+        data: Vec<u8>,
+    },
+}
+
+/// Deserializes the content of a resource into a [TypedResource]
+pub type ResourceDecoder = Arc<dyn Fn(&[u8]) -> DidCheqdResult<TypedResource> + Send + Sync>;
+
+/// A registry of [ResourceDecoder]s by `resourceType`, configured with
+/// [crate::resolution::resolver::DidCheqdResolverConfiguration::resource_types].
+///
+/// The default registry decodes AnonCreds schemas & credential definitions, Status List 2021
+/// revocation & suspension lists, and JSON schemas.
+#[derive(Clone)]
+pub struct ResourceTypeRegistry {
+    decoders: HashMap<String, ResourceDecoder>,
+}
+
+impl ResourceTypeRegistry {
+    /// A registry without any decoders, so every resource is [TypedResource::Unknown]
+    pub fn empty() -> Self {
+        Self {
+            decoders: HashMap::new(),
+        }
+    }
+
+    /// Register a decoder for a `resourceType`, replacing any registered before
+    pub fn register(&mut self, resource_type: &str, decoder: ResourceDecoder) -> &mut Self {
+        self.decoders.insert(resource_type.to_owned(), decoder);
+        self
+    }
+
+    /// Register a `resourceType` whose content is JSON, decoded as a [TypedResource::Json]
+    pub fn register_json(&mut self, resource_type: &str) -> &mut Self {
+        let owned = resource_type.to_owned();
+        self.register(
+            resource_type,
+            Arc::new(move |data| {
+                Ok(TypedResource::Json {
+                    resource_type: owned.clone(),
+                    value: serde_json::from_slice(data)?,
+                })
+            }),
+        )
+    }
+
+    /// Decode a resource's content by its `resourceType`
+    pub fn decode(&self, resource_type: &str, data: Vec<u8>) -> DidCheqdResult<TypedResource> {
+        match self.decoders.get(resource_type) {
+            Some(decoder) => decoder(&data),
+            None => Ok(TypedResource::Unknown {
+                resource_type: resource_type.to_owned(),
+                data,
+            }),
+        }
+    }
+}
+
+impl Default for ResourceTypeRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry
+            .register(
+                ANONCREDS_SCHEMA,
+                Arc::new(|data| Ok(TypedResource::AnonCredsSchema(parse(data)?))),
+            )
+            .register(
+                ANONCREDS_CRED_DEF,
+                Arc::new(|data| Ok(TypedResource::AnonCredsCredDef(parse(data)?))),
+            )
+            .register(
+                JSON_SCHEMA_2023,
+                Arc::new(|data| Ok(TypedResource::JsonSchema(parse(data)?))),
+            );
+        for status_list in [STATUS_LIST_2021_REVOCATION, STATUS_LIST_2021_SUSPENSION] {
+            registry.register(
+                status_list,
+                Arc::new(|data| Ok(TypedResource::StatusList2021(parse(data)?))),
+            );
+        }
+        registry
+    }
+}
+
+impl fmt::Debug for ResourceTypeRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut types: Vec<_> = self.decoders.keys().collect();
+        types.sort();
+        f.debug_struct("ResourceTypeRegistry")
+            .field("types", &types)
+            .finish()
+    }
+}
+
+fn parse<T: serde::de::DeserializeOwned>(data: &[u8]) -> DidCheqdResult<T> {
+    Ok(serde_json::from_slice(data)?)
+}
+
+impl DidCheqdResolver {
+    /// Resolve a resource DID URL (see [DidCheqdResolver::query_resource_by_str]), decoding its
+    /// content by its `resourceType` with the configured [ResourceTypeRegistry].
+    ///
+    /// Resources of types without a decoder are returned as [TypedResource::Unknown]; content a
+    /// decoder can't parse is a
+    /// [DidCheqdError::ParsingError](crate::error::DidCheqdError::ParsingError).
+    pub async fn resolve_resource_typed(&self, did_url: &str) -> DidCheqdResult<TypedResource> {
+        let parsed = self.parse(did_url)?;
+        let context = did_error_context(&parsed, "resolve_resource_typed");
+        let (data, metadata) = self
+            .query_resource_with_metadata_by_str(did_url, parsed)
+            .await?;
+        self.resource_types()
+            .decode(&metadata.resource_type, data)
+            .map_err(|e| e.with_context(context))
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use crate::{
+        error::DidCheqdError,
+        proto::cheqd::resource::v2::Metadata as ResourceMetadata,
+        resolution::resolver::DidCheqdResolverConfiguration,
+        test_utils::{LedgerFixtures, MockCheqdNode},
+    };
+
+    const DID: &str = "did:cheqd:testnet:abc";
+
+    fn resource(id: &str, resource_type: &str) -> ResourceMetadata {
+        ResourceMetadata {
+            collection_id: "abc".into(),
+            id: id.into(),
+            name: id.into(),
+            resource_type: resource_type.into(),
+            media_type: "application/json".into(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_resource_typed() {
+        let schema = br#"{"name": "degree", "version": "1.0", "attrNames": ["name"]}"#;
+        let status_list = br#"{
+            "StatusList2021": {"statusPurpose": "revocation", "encodedList": "H4sI"},
+            "metadata": {"type": "StatusList2021Revocation", "encoding": "base64url"}
+        }"#;
+        let fixtures = LedgerFixtures::new()
+            .with_resource(schema.to_vec(), resource("schema", ANONCREDS_SCHEMA))
+            .with_resource(
+                status_list.to_vec(),
+                resource("list", STATUS_LIST_2021_REVOCATION),
+            )
+            .with_resource(b"{}".to_vec(), resource("custom", "customType"))
+            .with_resource(b"{}".to_vec(), resource("other", "otherType"))
+            .with_resource(b"[]".to_vec(), resource("bad", ANONCREDS_SCHEMA));
+        let node = MockCheqdNode::start(fixtures).await.unwrap();
+        let mut resource_types = ResourceTypeRegistry::default();
+        resource_types.register_json("customType");
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            resource_types,
+            ..Default::default()
+        });
+        let resolve = |id: &str| {
+            let did_url = format!("{DID}/resources/{id}");
+            let resolver = &resolver;
+            async move { resolver.resolve_resource_typed(&did_url).await }
+        };
+
+        assert_eq!(
+            resolve("schema").await.unwrap(),
+            TypedResource::AnonCredsSchema(AnonCredsSchema {
+                name: "degree".into(),
+                version: "1.0".into(),
+                attr_names: vec!["name".into()],
+                issuer_id: None,
+            })
+        );
+        let TypedResource::StatusList2021(list) = resolve("list").await.unwrap() else {
+            panic!("not a status list");
+        };
+        assert_eq!(list.status_list.encoded_list, "H4sI");
+        assert_eq!(list.metadata.encoding, "base64url");
+        assert!(matches!(
+            resolve("custom").await.unwrap(),
+            TypedResource::Json { resource_type, .. } if resource_type == "customType"
+        ));
+        assert_eq!(
+            resolve("other").await.unwrap(),
+            TypedResource::Unknown {
+                resource_type: "otherType".into(),
+                data: b"{}".to_vec()
+            }
+        );
+        let e = resolve("bad").await.unwrap_err();
+        assert!(matches!(e.root(), DidCheqdError::ParsingError(_)), "{e:?}");
+    }
+}
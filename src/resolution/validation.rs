@@ -0,0 +1,294 @@
+use std::{collections::HashSet, fmt};
+
+use serde_json::Value;
+use url::Url;
+
+/// the verification relationships of a DID document, which reference verification methods
+const VERIFICATION_RELATIONSHIPS: &[&str] = &[
+    "authentication",
+    "assertionMethod",
+    "capabilityInvocation",
+    "capabilityDelegation",
+    "keyAgreement",
+];
+
+/// How resolved DID documents are checked against the DID Core invariants
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// documents are not validated
+    #[default]
+    Off,
+    /// violations are reported alongside the resolved document
+    Warn,
+    /// any violation fails resolution with [crate::error::DidCheqdError::InvalidDidDocument]
+    Deny,
+}
+
+/// A DID Core invariant which a DID document does not satisfy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// JSON pointer to the offending property, e.g. `/verificationMethod/0/id`
+    pub path: String,
+    /// what is wrong with the property
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Check a DID document (as produced by
+/// [super::transformer::cheqd_diddoc_to_json]) against the DID Core data model invariants:
+///
+/// - `id` & `controller`s are DIDs, and `@context` is present
+/// - verification method & service ids are unique, absolute DID URLs (or relative to `id`)
+/// - verification relationships reference verification methods in the document
+/// - service endpoints & `alsoKnownAs` entries are valid URIs
+///
+/// Returns every violation found, empty if the document is valid.
+pub fn validate_did_document(doc: &Value) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut violation = |path: String, message: &str| {
+        violations.push(Violation {
+            path,
+            message: message.to_owned(),
+        })
+    };
+
+    let Some(doc) = doc.as_object() else {
+        violation(String::new(), "DID document must be a JSON object");
+        return violations;
+    };
+
+    let id = doc.get("id").and_then(Value::as_str).unwrap_or_default();
+    if !is_did(id) {
+        violation("/id".into(), "must be a DID");
+    }
+    if !doc.contains_key("@context") {
+        violation("/@context".into(), "is required");
+    }
+    for (i, controller) in values(doc.get("controller")).enumerate() {
+        if !controller.as_str().is_some_and(is_did) {
+            violation(format!("/controller/{i}"), "must be a DID");
+        }
+    }
+
+    let mut method_ids = HashSet::new();
+    for (i, method) in values(doc.get("verificationMethod")).enumerate() {
+        check_embedded_method(
+            method,
+            id,
+            &format!("/verificationMethod/{i}"),
+            &mut method_ids,
+        )
+        .into_iter()
+        .for_each(|(path, message)| violation(path, message));
+    }
+
+    // methods embedded in a relationship can be referenced by other relationships
+    let mut embedded_ids = HashSet::new();
+    for relationship in VERIFICATION_RELATIONSHIPS {
+        for (i, entry) in values(doc.get(*relationship)).enumerate() {
+            let path = format!("/{relationship}/{i}");
+            if entry.is_object() {
+                check_embedded_method(entry, id, &path, &mut embedded_ids)
+                    .into_iter()
+                    .for_each(|(path, message)| violation(path, message));
+            }
+        }
+    }
+    for relationship in VERIFICATION_RELATIONSHIPS {
+        for (i, entry) in values(doc.get(*relationship)).enumerate() {
+            let Some(reference) = entry.as_str() else {
+                continue;
+            };
+            let path = format!("/{relationship}/{i}");
+            let absolute = absolute_id(reference, id);
+            if !is_did_url(&absolute) {
+                violation(path, "must be a DID URL or an embedded verification method");
+            } else if is_same_document(&absolute, id)
+                && !method_ids.contains(&absolute)
+                && !embedded_ids.contains(&absolute)
+            {
+                violation(path, "references a verification method not in the document");
+            }
+        }
+    }
+
+    let mut service_ids = HashSet::new();
+    for (i, service) in values(doc.get("service")).enumerate() {
+        let path = format!("/service/{i}");
+        match service.get("id").and_then(Value::as_str) {
+            Some(service_id) if Url::parse(&absolute_id(service_id, id)).is_ok() => {
+                if !service_ids.insert(absolute_id(service_id, id)) {
+                    violation(format!("{path}/id"), "duplicate service id");
+                }
+            }
+            _ => violation(format!("{path}/id"), "must be a URI"),
+        }
+        if !service
+            .get("type")
+            .is_some_and(|t| t.is_string() || t.is_array())
+        {
+            violation(format!("{path}/type"), "is required");
+        }
+        match service.get("serviceEndpoint") {
+            Some(Value::String(endpoint)) => {
+                if Url::parse(endpoint).is_err() {
+                    violation(format!("{path}/serviceEndpoint"), "must be a URI");
+                }
+            }
+            Some(Value::Array(endpoints)) => {
+                for (j, endpoint) in endpoints.iter().enumerate() {
+                    // endpoints may also be maps, which are not constrained
+                    if endpoint.as_str().is_some_and(|e| Url::parse(e).is_err()) {
+                        violation(format!("{path}/serviceEndpoint/{j}"), "must be a URI");
+                    }
+                }
+            }
+            Some(Value::Object(_)) => {}
+            _ => violation(format!("{path}/serviceEndpoint"), "is required"),
+        }
+    }
+
+    for (i, aka) in values(doc.get("alsoKnownAs")).enumerate() {
+        if aka.as_str().is_none_or(|a| Url::parse(a).is_err()) {
+            violation(format!("/alsoKnownAs/{i}"), "must be a URI");
+        }
+    }
+
+    violations
+}
+
+/// Check a verification method, recording its absolute id in `ids`. Returns the violations
+/// as `(path, message)`.
+fn check_embedded_method(
+    method: &Value,
+    doc_id: &str,
+    path: &str,
+    ids: &mut HashSet<String>,
+) -> Vec<(String, &'static str)> {
+    let mut violations = Vec::new();
+    match method.get("id").and_then(Value::as_str) {
+        Some(method_id) if is_did_url(&absolute_id(method_id, doc_id)) => {
+            if !ids.insert(absolute_id(method_id, doc_id)) {
+                violations.push((format!("{path}/id"), "duplicate verification method id"));
+            }
+        }
+        _ => violations.push((format!("{path}/id"), "must be a DID URL")),
+    }
+    if !method.get("type").is_some_and(Value::is_string) {
+        violations.push((format!("{path}/type"), "is required"));
+    }
+    if !method
+        .get("controller")
+        .and_then(Value::as_str)
+        .is_some_and(is_did)
+    {
+        violations.push((format!("{path}/controller"), "must be a DID"));
+    }
+    violations
+}
+
+/// iterate a property which may be a single value or an array of values
+fn values(value: Option<&Value>) -> impl Iterator<Item = &Value> {
+    let (single, many) = match value {
+        Some(Value::Array(values)) => (None, values.as_slice()),
+        Some(value) => (Some(value), [].as_slice()),
+        None => (None, [].as_slice()),
+    };
+    single.into_iter().chain(many)
+}
+
+/// resolve an id relative to the document id (e.g. `#key-1`) into an absolute DID URL
+fn absolute_id(id: &str, doc_id: &str) -> String {
+    if id.starts_with('#') || id.starts_with('?') || id.starts_with('/') {
+        format!("{doc_id}{id}")
+    } else {
+        id.to_owned()
+    }
+}
+
+fn is_same_document(did_url: &str, doc_id: &str) -> bool {
+    did_url
+        .strip_prefix(doc_id)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['#', '?', '/']))
+}
+
+/// whether the string is a DID, i.e. `did:<method>:<method specific id>`
+fn is_did(did: &str) -> bool {
+    let mut parts = did.splitn(3, ':');
+    parts.next() == Some("did")
+        && parts.next().is_some_and(|m| {
+            !m.is_empty()
+                && m.chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        })
+        && parts
+            .next()
+            .is_some_and(|id| !id.is_empty() && !id.contains(['#', '?', '/']) && !id.ends_with(':'))
+}
+
+/// whether the string is a DID URL, i.e. a DID optionally followed by a path, query & fragment
+fn is_did_url(did_url: &str) -> bool {
+    let did = did_url.split(['#', '?', '/']).next().unwrap_or_default();
+    is_did(did)
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_valid_document_has_no_violations() {
+        let doc = json!({
+            "@context": ["https://www.w3.org/ns/did/v1"],
+            "id": "did:cheqd:testnet:abc",
+            "controller": ["did:cheqd:testnet:abc"],
+            "verificationMethod": [{
+                "id": "did:cheqd:testnet:abc#key-1",
+                "type": "Ed25519VerificationKey2020",
+                "controller": "did:cheqd:testnet:abc",
+            }],
+            "authentication": ["#key-1"],
+            "assertionMethod": ["did:cheqd:testnet:other#key-1"],
+            "service": [{
+                "id": "#website",
+                "type": "LinkedDomains",
+                "serviceEndpoint": "https://example.com",
+            }],
+        });
+        assert_eq!(validate_did_document(&doc), []);
+    }
+
+    #[test]
+    fn test_reports_violations() {
+        let doc = json!({
+            "id": "did:cheqd:testnet:abc",
+            "verificationMethod": [
+                {"id": "#key-1", "type": "JsonWebKey2020", "controller": "did:cheqd:testnet:abc"},
+                {"id": "did:cheqd:testnet:abc#key-1", "type": "JsonWebKey2020", "controller": "abc"},
+            ],
+            "authentication": ["#key-2"],
+            "service": [{"id": "#svc", "type": "DIDCommMessaging", "serviceEndpoint": "not a uri"}],
+        });
+        let paths: Vec<_> = validate_did_document(&doc)
+            .into_iter()
+            .map(|v| v.path)
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "/@context",
+                "/verificationMethod/1/id",
+                "/verificationMethod/1/controller",
+                "/authentication/0",
+                "/service/0/serviceEndpoint",
+            ]
+        );
+    }
+}
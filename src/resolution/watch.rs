@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use futures_util::{Stream, stream};
+
+use crate::{
+    error::DidCheqdResult,
+    resolution::resolver::{DidCheqdResolver, ResolvedDidDoc, did_error_context},
+};
+
+/// A change to a watched DID's document, see [DidCheqdResolver::watch_did]
+#[derive(Debug, Clone)]
+pub struct DidDocumentUpdate {
+    /// the `versionId` of the previously observed DID document, `None` for the first observation
+    pub previous_version_id: Option<String>,
+    /// the latest DID document, with its metadata
+    pub resolved: ResolvedDidDoc,
+}
+
+impl DidDocumentUpdate {
+    /// The `versionId` of the latest DID document
+    pub fn version_id(&self) -> Option<&str> {
+        self.resolved
+            .metadata
+            .as_ref()
+            .map(|m| m.version_id.as_str())
+    }
+
+    /// Whether the latest DID document is deactivated
+    pub fn deactivated(&self) -> bool {
+        self.resolved
+            .metadata
+            .as_ref()
+            .is_some_and(|m| m.deactivated)
+    }
+}
+
+struct WatchState {
+    resolver: DidCheqdResolver,
+    network: String,
+    did: String,
+    interval: Duration,
+    /// the `versionId` of the last observed DID document, if any has been observed
+    observed: Option<Option<String>>,
+    polled: bool,
+}
+
+impl DidCheqdResolver {
+    /// Watch a DID for updates (e.g. key rotations or deactivation), by polling the ledger for its
+    /// latest DID document every `interval`.
+    ///
+    /// The stream yields the current DID document first, then an update whenever its `versionId`
+    /// changes. Failed polls are yielded as errors, and polling continues. The cache is bypassed,
+    /// though polled DID documents are cached.
+    pub fn watch_did(
+        &self,
+        did: &str,
+        interval: Duration,
+    ) -> DidCheqdResult<impl Stream<Item = DidCheqdResult<DidDocumentUpdate>> + Send + 'static>
+    {
+        let parsed = self.parse(did)?;
+        let context = did_error_context(&parsed, "watch_did");
+        let state = WatchState {
+            resolver: self.clone(),
+            did: self
+                .ledger_did(&parsed)
+                .map_err(|e| e.with_context(context.clone()))?,
+            network: parsed.namespace,
+            interval,
+            observed: None,
+            polled: false,
+        };
+
+        Ok(stream::unfold(state, move |mut state| {
+            let context = context.clone();
+            async move {
+                loop {
+                    if state.polled {
+                        tokio::time::sleep(state.interval).await;
+                    }
+                    state.polled = true;
+
+                    let resolved = match state
+                        .resolver
+                        .fetch_did_doc(&state.network, state.did.clone(), None)
+                        .await
+                    {
+                        Ok(resolved) => resolved,
+                        Err(e) => return Some((Err(e.with_context(context)), state)),
+                    };
+                    let version_id = resolved.metadata.as_ref().map(|m| m.version_id.clone());
+                    if state.observed.as_ref() == Some(&version_id) {
+                        continue;
+                    }
+                    let previous_version_id = state.observed.replace(version_id).flatten();
+                    let update = DidDocumentUpdate {
+                        previous_version_id,
+                        resolved,
+                    };
+                    return Some((Ok(update), state));
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::net::Ipv4Addr;
+
+    use futures_util::StreamExt;
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::{
+        error::DidCheqdError,
+        resolution::resolver::{DidCheqdResolverConfiguration, NetworkConfiguration},
+    };
+
+    #[tokio::test]
+    async fn test_watch_did_yields_poll_errors() {
+        let resolver = DidCheqdResolver::new(Default::default());
+        let e = resolver
+            .watch_did("did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN", Duration::ZERO)
+            .err()
+            .unwrap();
+        assert!(matches!(e.root(), DidCheqdError::NetworkNotSupported(_)));
+
+        // nothing listens on the port once the listener is dropped
+        let closed_port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![NetworkConfiguration {
+                grpc_url: format!("http://127.0.0.1:{closed_port}"),
+                namespace: "devnet".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        let updates = resolver
+            .watch_did("did:cheqd:devnet:Ps1ysXP2Ae6GBfxNhNQNKN", Duration::ZERO)
+            .unwrap();
+        let polls: Vec<_> = updates.take(2).collect().await;
+        for poll in polls {
+            let e = poll.unwrap_err();
+            assert!(matches!(e.root(), DidCheqdError::TransportError(_)));
+            assert_eq!(e.operation(), Some("watch_did"));
+        }
+    }
+}
@@ -0,0 +1,1085 @@
+//! A mock cheqd node for tests: an in-process tonic server implementing the cheqd DID & resource
+//! query services from [LedgerFixtures], so tests don't depend on live networks.
+//!
+//! ```no_run
+//! # async fn example() {
+//! use did_resolver_cheqd::{
+//!     resolution::resolver::{DidCheqdResolver, DidCheqdResolverConfiguration},
+//!     test_utils::{LedgerFixtures, MockCheqdNode},
+//! };
+//!
+//! let node = MockCheqdNode::start(LedgerFixtures::new()).await.unwrap();
+//! let resolver = DidCheqdResolver::new(
+//!     DidCheqdResolverConfiguration::builder()
+//!         .networks(vec![node.network("testnet")])
+//!         .build(),
+//! );
+//! # }
+//! ```
+
+// the fixture handlers return `Status` errors, like tonic's generated service traits
+#![allow(clippy::result_large_err)]
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    future::Ready,
+    io,
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+};
+
+use futures_util::future::BoxFuture as BoxedFuture;
+use prost::Message;
+use tokio::{net::TcpListener, task::JoinHandle};
+use tonic::{
+    Status,
+    body::BoxBody,
+    codec::ProstCodec,
+    codegen::{Body, BoxFuture, Context, Poll, Service, StdError, http},
+    metadata::MetadataMap,
+    server::{Grpc, NamedService, UnaryService},
+    transport::{Server, server::TcpIncoming},
+};
+
+use crate::{
+    error::{DidCheqdError, DidCheqdResult},
+    proto::{
+        cheqd::{
+            did::v2::{
+                DidDoc, DidDocWithMetadata, Metadata as DidDocMetadata,
+                QueryAllDidDocVersionsMetadataRequest, QueryAllDidDocVersionsMetadataResponse,
+                QueryDidDocRequest, QueryDidDocResponse, QueryDidDocVersionRequest,
+                QueryDidDocVersionResponse,
+            },
+            resource::v2::{
+                Metadata as ResourceMetadata, QueryCollectionResourcesRequest,
+                QueryCollectionResourcesResponse, QueryResourceMetadataRequest,
+                QueryResourceMetadataResponse, QueryResourceRequest, QueryResourceResponse,
+                Resource, ResourceWithMetadata,
+            },
+        },
+        cosmos::base::query::v1beta1::{PageRequest, PageResponse},
+    },
+    resolution::{
+        parser::DidCheqdParser,
+        resolver::{NetworkConfiguration, ResolvedDidDoc, ResolvedResource},
+        service::{CheqdResolve, Resolved},
+    },
+};
+#[cfg(feature = "registrar")]
+use crate::{
+    proto::{
+        cheqd::{
+            did::v2::{MsgCreateDidDoc, MsgDeactivateDidDoc, MsgUpdateDidDoc},
+            resource::v2::{AlternativeUri, MsgCreateResource, MsgCreateResourcePayload},
+        },
+        cosmos::{
+            auth::v1beta1::{BaseAccount, QueryAccountRequest, QueryAccountResponse},
+            base::abci::v1beta1::{GasInfo, TxResponse},
+            tx::v1beta1::{
+                AuthInfo, BroadcastTxRequest, BroadcastTxResponse, GetTxRequest, GetTxResponse,
+                SimulateRequest, SimulateResponse, TxBody, TxRaw,
+            },
+        },
+    },
+    registrar::{self, did, resource},
+};
+#[cfg(feature = "registrar")]
+use sha2::{Digest, Sha256};
+#[cfg(feature = "registrar")]
+use std::time::SystemTime;
+
+/// The DID documents & resources served by a [MockCheqdNode]
+#[derive(Debug, Clone, Default)]
+pub struct LedgerFixtures {
+    /// every version of every DID document, oldest first
+    did_docs: Vec<DidDocWithMetadata>,
+    resources: Vec<ResourceWithMetadata>,
+    /// the ids (of DIDs or collections) whose queries are answered with another id's fixtures
+    misdirected: HashMap<String, String>,
+    /// the transactions accepted by the node, oldest first
+    #[cfg(feature = "registrar")]
+    transactions: Vec<TxRaw>,
+    /// the results of the accepted transactions, served by `GetTx`
+    #[cfg(feature = "registrar")]
+    tx_responses: Vec<TxResponse>,
+}
+
+/// The fixtures served by a [MockCheqdNode], updated by the transactions broadcast to it
+type SharedFixtures = Arc<RwLock<LedgerFixtures>>;
+
+impl LedgerFixtures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a version of a DID document. The latest version of a DID is the last one added.
+    pub fn with_did_doc(mut self, did_doc: DidDoc, metadata: DidDocMetadata) -> Self {
+        self.did_docs.push(DidDocWithMetadata {
+            did_doc: Some(did_doc),
+            metadata: Some(metadata),
+        });
+        self
+    }
+
+    /// Add a resource. Collections are listed in the order resources are added.
+    pub fn with_resource(mut self, data: Vec<u8>, metadata: ResourceMetadata) -> Self {
+        self.resources.push(ResourceWithMetadata {
+            resource: Some(Resource { data }),
+            metadata: Some(metadata),
+        });
+        self
+    }
+
+    /// Answer queries for the DID or collection `requested` (as it appears in requests, e.g.
+    /// `did:cheqd:testnet:<id>` for DID documents & `<id>` for resources) with the fixtures of
+    /// `served`, like a faulty or malicious node
+    pub fn with_misdirected(mut self, requested: &str, served: &str) -> Self {
+        self.misdirected
+            .insert(requested.to_owned(), served.to_owned());
+        self
+    }
+
+    /// The id whose fixtures answer a query for `id`
+    fn served_id<'a>(&'a self, id: &'a str) -> &'a str {
+        self.misdirected.get(id).map_or(id, String::as_str)
+    }
+
+    fn did_doc_versions<'a>(&'a self, id: &'a str) -> impl Iterator<Item = &'a DidDocWithMetadata> {
+        let id = self.served_id(id);
+        self.did_docs
+            .iter()
+            .filter(move |doc| doc.did_doc.as_ref().is_some_and(|d| d.id == id))
+    }
+
+    fn did_doc(&self, request: QueryDidDocRequest) -> Result<QueryDidDocResponse, Status> {
+        let value = self.did_doc_versions(&request.id).last().cloned();
+        let value = value.ok_or_else(|| did_not_found(&request.id))?;
+        Ok(QueryDidDocResponse { value: Some(value) })
+    }
+
+    fn did_doc_version(
+        &self,
+        request: QueryDidDocVersionRequest,
+    ) -> Result<QueryDidDocVersionResponse, Status> {
+        let value = self
+            .did_doc_versions(&request.id)
+            .find(|doc| {
+                let metadata = doc.metadata.as_ref();
+                metadata.is_some_and(|m| m.version_id == request.version)
+            })
+            .cloned();
+        let value = value.ok_or_else(|| did_not_found(&request.id))?;
+        Ok(QueryDidDocVersionResponse { value: Some(value) })
+    }
+
+    fn all_did_doc_versions_metadata(
+        &self,
+        request: QueryAllDidDocVersionsMetadataRequest,
+    ) -> Result<QueryAllDidDocVersionsMetadataResponse, Status> {
+        let versions: Vec<_> = self
+            .did_doc_versions(&request.id)
+            .filter_map(|doc| doc.metadata.clone())
+            .collect();
+        if versions.is_empty() {
+            return Err(did_not_found(&request.id));
+        }
+        let (versions, pagination) = paginate(versions, request.pagination)?;
+        Ok(QueryAllDidDocVersionsMetadataResponse {
+            versions,
+            pagination,
+        })
+    }
+
+    fn find_resource(
+        &self,
+        collection_id: &str,
+        id: &str,
+    ) -> Result<&ResourceWithMetadata, Status> {
+        let served = self.served_id(collection_id);
+        self.resources
+            .iter()
+            .find(|resource| {
+                let metadata = resource.metadata.as_ref();
+                metadata.is_some_and(|m| m.collection_id == served && m.id == id)
+            })
+            .ok_or_else(|| Status::not_found(format!("{collection_id}:{id}: resource not found")))
+    }
+
+    fn resource(&self, request: QueryResourceRequest) -> Result<QueryResourceResponse, Status> {
+        let resource = self.find_resource(&request.collection_id, &request.id)?;
+        Ok(QueryResourceResponse {
+            resource: Some(resource.clone()),
+        })
+    }
+
+    fn resource_metadata(
+        &self,
+        request: QueryResourceMetadataRequest,
+    ) -> Result<QueryResourceMetadataResponse, Status> {
+        let resource = self.find_resource(&request.collection_id, &request.id)?;
+        Ok(QueryResourceMetadataResponse {
+            resource: resource.metadata.clone(),
+        })
+    }
+
+    fn collection_resources(
+        &self,
+        request: QueryCollectionResourcesRequest,
+    ) -> Result<QueryCollectionResourcesResponse, Status> {
+        let resources: Vec<_> = self
+            .resources
+            .iter()
+            .filter_map(|resource| resource.metadata.clone())
+            .filter(|metadata| metadata.collection_id == self.served_id(&request.collection_id))
+            .collect();
+        let (resources, pagination) = paginate(resources, request.pagination)?;
+        Ok(QueryCollectionResourcesResponse {
+            resources,
+            pagination,
+        })
+    }
+}
+
+fn did_not_found(id: &str) -> Status {
+    Status::not_found(format!("{id}: DID Doc not found"))
+}
+
+/// A page of `items`, with pagination keys holding the (big endian) offset of the next page
+fn paginate<T>(
+    items: Vec<T>,
+    request: Option<PageRequest>,
+) -> Result<(Vec<T>, Option<PageResponse>), Status> {
+    let request = request.unwrap_or_default();
+    let offset = match request.key.as_slice() {
+        [] => request.offset as usize,
+        key => {
+            let key = key
+                .try_into()
+                .map_err(|_| Status::invalid_argument("invalid pagination key"))?;
+            u64::from_be_bytes(key) as usize
+        }
+    };
+    let total = items.len();
+    let limit = match request.limit {
+        0 => total,
+        limit => limit as usize,
+    };
+    let end = offset.saturating_add(limit).min(total);
+    let next_key = match end < total {
+        true => (end as u64).to_be_bytes().to_vec(),
+        false => Vec::new(),
+    };
+    let page = items.into_iter().skip(offset).take(limit).collect();
+    let pagination = PageResponse {
+        next_key,
+        total: total as u64,
+    };
+    Ok((page, Some(pagination)))
+}
+
+/// An in-process cheqd node serving [LedgerFixtures] over gRPC (without TLS) on a local port.
+/// The server is stopped when dropped.
+///
+/// With the `registrar` feature, the node also accepts DID transactions, applying them to its
+/// fixtures. Transaction signatures aren't verified, and every address shares one account.
+pub struct MockCheqdNode {
+    addr: SocketAddr,
+    server: JoinHandle<()>,
+    requests: SharedRequests,
+    #[cfg_attr(not(feature = "registrar"), allow(dead_code))]
+    fixtures: SharedFixtures,
+}
+
+impl MockCheqdNode {
+    /// Start serving the fixtures on a free local port
+    pub async fn start(fixtures: LedgerFixtures) -> io::Result<Self> {
+        let listener = TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, 0)).await?;
+        let addr = listener.local_addr()?;
+        let incoming =
+            TcpIncoming::from_listener(listener, true, None).map_err(io::Error::other)?;
+        let fixtures = Arc::new(RwLock::new(fixtures));
+        let requests = SharedRequests::default();
+        let router = Server::builder()
+            .layer(tower::layer::layer_fn({
+                let requests = requests.clone();
+                move |inner| RecordRequests {
+                    inner,
+                    requests: requests.clone(),
+                }
+            }))
+            .add_service(DidQueryServer(fixtures.clone()))
+            .add_service(ResourceQueryServer(fixtures.clone()));
+        #[cfg(feature = "registrar")]
+        let router = router
+            .add_service(AuthQueryServer(fixtures.clone()))
+            .add_service(TxServer(fixtures.clone()));
+        let server = tokio::spawn(async move {
+            let _ = router.serve_with_incoming(incoming).await;
+        });
+        Ok(Self {
+            addr,
+            server,
+            requests,
+            fixtures,
+        })
+    }
+
+    /// The requests the node received, oldest first
+    pub fn requests(&self) -> Vec<ReceivedRequest> {
+        self.requests
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// The transactions the node accepted, oldest first
+    #[cfg(feature = "registrar")]
+    pub fn transactions(&self) -> Vec<TxRaw> {
+        read(&self.fixtures).transactions.clone()
+    }
+
+    /// The gRPC URL of the node
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// A configuration for a network with the given namespace, served by this node
+    pub fn network(&self, namespace: &str) -> NetworkConfiguration {
+        NetworkConfiguration {
+            grpc_url: self.url(),
+            namespace: namespace.to_owned(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A request received by a [MockCheqdNode]
+#[derive(Debug, Clone)]
+pub struct ReceivedRequest {
+    /// the gRPC method's path, e.g. `/cheqd.did.v2.Query/DidDoc`
+    pub path: String,
+    /// the request's metadata, e.g. its `user-agent`
+    pub metadata: MetadataMap,
+}
+
+type SharedRequests = Arc<RwLock<Vec<ReceivedRequest>>>;
+
+/// A middleware recording the requests received by a [MockCheqdNode]
+#[derive(Clone)]
+struct RecordRequests<S> {
+    inner: S,
+    requests: SharedRequests,
+}
+
+impl<S, B> Service<http::Request<B>> for RecordRequests<S>
+where
+    S: Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let received = ReceivedRequest {
+            path: request.uri().path().to_owned(),
+            metadata: MetadataMap::from_headers(request.headers().clone()),
+        };
+        self.requests
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(received);
+        self.inner.call(request)
+    }
+}
+
+impl Drop for MockCheqdNode {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+/// A [CheqdResolve] implementation answering directly from [LedgerFixtures], without a node, for
+/// testing code which holds an `Arc<dyn CheqdResolve>`.
+///
+/// DIDs are resolved (optionally at a version) & resources dereferenced by id or by name & type
+/// (the latest version), in any namespace. Caching, retries & other resolver behaviour aren't
+/// simulated.
+#[derive(Debug, Clone)]
+pub struct MockResolver(Arc<LedgerFixtures>);
+
+impl MockResolver {
+    pub fn new(fixtures: LedgerFixtures) -> Self {
+        Self(Arc::new(fixtures))
+    }
+
+    fn did_doc(&self, did: &str) -> DidCheqdResult<ResolvedDidDoc> {
+        let parsed = DidCheqdParser::parse(did)?;
+        let value = match parsed.version {
+            Some(version) => {
+                let request = QueryDidDocVersionRequest {
+                    id: parsed.did,
+                    version,
+                };
+                self.0.did_doc_version(request).map(|r| r.value)
+            }
+            None => {
+                let request = QueryDidDocRequest { id: parsed.did };
+                self.0.did_doc(request).map(|r| r.value)
+            }
+        };
+        let value = value.map_err(Box::new)?.unwrap_or_default();
+        Ok(ResolvedDidDoc {
+            did_doc: value.did_doc.unwrap_or_default(),
+            metadata: value.metadata,
+            transport: Default::default(),
+            stale: false,
+            provenance: Default::default(),
+            warnings: Vec::new(),
+        })
+    }
+
+    fn resource(&self, did_url: &str) -> DidCheqdResult<ResolvedResource> {
+        let parsed = DidCheqdParser::parse(did_url)?;
+        let query = parsed.query.unwrap_or_default();
+        let id = match (
+            query.get("resourceId"),
+            query.get("resourceName"),
+            query.get("resourceType"),
+        ) {
+            (Some(id), _, _) => id.clone(),
+            (None, Some(name), Some(resource_type)) => {
+                let request = QueryCollectionResourcesRequest {
+                    collection_id: parsed.id.clone(),
+                    pagination: None,
+                };
+                let resources = self.0.collection_resources(request).map_err(Box::new)?;
+                resources
+                    .resources
+                    .into_iter()
+                    .filter(|r| &r.name == name && &r.resource_type == resource_type)
+                    .max_by_key(|r| r.created.map(|t| (t.seconds, t.nanos)))
+                    .ok_or_else(|| DidCheqdError::ResourceNotFound(did_url.to_owned()))?
+                    .id
+            }
+            _ => {
+                return Err(DidCheqdError::InvalidDidUrl(format!(
+                    "not a resource DID URL: {did_url}"
+                )));
+            }
+        };
+        let request = QueryResourceRequest {
+            collection_id: parsed.id,
+            id,
+        };
+        let resource = self.0.resource(request).map_err(Box::new)?.resource;
+        let resource = resource.unwrap_or_default();
+        Ok(ResolvedResource {
+            data: resource.resource.unwrap_or_default().data,
+            metadata: resource.metadata.unwrap_or_default(),
+            transport: Default::default(),
+            provenance: Default::default(),
+            warnings: Vec::new(),
+        })
+    }
+}
+
+impl CheqdResolve for MockResolver {
+    fn resolve_did<'a>(&'a self, did: &'a str) -> BoxedFuture<'a, DidCheqdResult<ResolvedDidDoc>> {
+        Box::pin(std::future::ready(self.did_doc(did)))
+    }
+
+    fn resolve_resource_url<'a>(
+        &'a self,
+        did_url: &'a str,
+    ) -> BoxedFuture<'a, DidCheqdResult<ResolvedResource>> {
+        Box::pin(std::future::ready(self.resource(did_url)))
+    }
+
+    fn resolve_did_url<'a>(
+        &'a self,
+        did_url: &'a str,
+    ) -> BoxedFuture<'a, DidCheqdResult<Resolved>> {
+        let resolved =
+            DidCheqdParser::parse(did_url).and_then(|parsed| match parsed.is_resource() {
+                true => self.resource(did_url).map(Resolved::Resource),
+                false => self.did_doc(did_url).map(Resolved::Document),
+            });
+        Box::pin(std::future::ready(resolved))
+    }
+}
+
+/// A unary gRPC method answered synchronously from the fixtures
+struct Unary<F>(F);
+
+impl<Req, Resp, F> UnaryService<Req> for Unary<F>
+where
+    F: Fn(Req) -> Result<Resp, Status>,
+{
+    type Response = Resp;
+    type Future = Ready<Result<tonic::Response<Resp>, Status>>;
+
+    fn call(&mut self, request: tonic::Request<Req>) -> Self::Future {
+        std::future::ready((self.0)(request.into_inner()).map(tonic::Response::new))
+    }
+}
+
+type HttpResponseFuture = BoxFuture<http::Response<BoxBody>, Infallible>;
+
+/// Serve a unary gRPC request with `handler`
+fn unary<B, Req, Resp>(
+    request: http::Request<B>,
+    handler: impl Fn(Req) -> Result<Resp, Status> + Send + 'static,
+) -> HttpResponseFuture
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+    Req: Message + Default + Send + 'static,
+    Resp: Message + Send + 'static,
+{
+    Box::pin(async move {
+        let mut grpc = Grpc::new(ProstCodec::<Resp, Req>::default());
+        Ok(grpc.unary(Unary(handler), request).await)
+    })
+}
+
+fn unimplemented(path: &str) -> HttpResponseFuture {
+    let response = Status::unimplemented(format!("{path} is not implemented")).into_http();
+    Box::pin(std::future::ready(Ok(response)))
+}
+
+fn read(fixtures: &SharedFixtures) -> std::sync::RwLockReadGuard<'_, LedgerFixtures> {
+    fixtures.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The `cheqd.did.v2.Query` service
+#[derive(Clone)]
+struct DidQueryServer(SharedFixtures);
+
+impl NamedService for DidQueryServer {
+    const NAME: &'static str = "cheqd.did.v2.Query";
+}
+
+impl<B> Service<http::Request<B>> for DidQueryServer
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = HttpResponseFuture;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let fixtures = self.0.clone();
+        match request.uri().path() {
+            "/cheqd.did.v2.Query/DidDoc" => unary(request, move |r| read(&fixtures).did_doc(r)),
+            "/cheqd.did.v2.Query/DidDocVersion" => {
+                unary(request, move |r| read(&fixtures).did_doc_version(r))
+            }
+            "/cheqd.did.v2.Query/AllDidDocVersionsMetadata" => unary(request, move |r| {
+                read(&fixtures).all_did_doc_versions_metadata(r)
+            }),
+            path => unimplemented(path),
+        }
+    }
+}
+
+/// The `cheqd.resource.v2.Query` service
+#[derive(Clone)]
+struct ResourceQueryServer(SharedFixtures);
+
+impl NamedService for ResourceQueryServer {
+    const NAME: &'static str = "cheqd.resource.v2.Query";
+}
+
+impl<B> Service<http::Request<B>> for ResourceQueryServer
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = HttpResponseFuture;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let fixtures = self.0.clone();
+        match request.uri().path() {
+            "/cheqd.resource.v2.Query/Resource" => {
+                unary(request, move |r| read(&fixtures).resource(r))
+            }
+            "/cheqd.resource.v2.Query/ResourceMetadata" => {
+                unary(request, move |r| read(&fixtures).resource_metadata(r))
+            }
+            "/cheqd.resource.v2.Query/CollectionResources" => {
+                unary(request, move |r| read(&fixtures).collection_resources(r))
+            }
+            path => unimplemented(path),
+        }
+    }
+}
+
+/// A failed transaction's codespace, code & log
+#[cfg(feature = "registrar")]
+type TxError = (&'static str, u32, String);
+
+/// The gas a [MockCheqdNode] charges per message of a transaction
+#[cfg(feature = "registrar")]
+const GAS_PER_MESSAGE: u64 = 100_000;
+
+#[cfg(feature = "registrar")]
+impl LedgerFixtures {
+    fn account(&self, request: QueryAccountRequest) -> Result<QueryAccountResponse, Status> {
+        let account = BaseAccount {
+            address: request.address,
+            pub_key: None,
+            account_number: 1,
+            sequence: self.transactions.len() as u64,
+        };
+        Ok(QueryAccountResponse {
+            account: Some(registrar::pack(
+                "/cosmos.auth.v1beta1.BaseAccount",
+                &account,
+            )),
+        })
+    }
+
+    fn broadcast_tx(&mut self, request: BroadcastTxRequest) -> Result<BroadcastTxResponse, Status> {
+        let tx = TxRaw::decode(request.tx_bytes.as_slice())
+            .map_err(|e| Status::invalid_argument(format!("invalid tx: {e}")))?;
+        let txhash = Sha256::digest(&request.tx_bytes)
+            .iter()
+            .map(|b| format!("{b:02X}"))
+            .collect();
+        // apply the transaction's messages to a copy, so a failed transaction changes nothing
+        let mut ledger = self.clone();
+        let result = ledger.execute(&tx, false);
+        let mut response = TxResponse {
+            height: self.transactions.len() as i64 + 1,
+            txhash,
+            ..Default::default()
+        };
+        match result {
+            Ok((gas_wanted, gas_used)) => {
+                response.gas_wanted = gas_wanted as i64;
+                response.gas_used = gas_used as i64;
+                ledger.transactions.push(tx);
+                ledger.tx_responses.push(response.clone());
+                *self = ledger;
+            }
+            Err((codespace, code, raw_log)) => {
+                response.codespace = codespace.to_owned();
+                response.code = code;
+                response.raw_log = raw_log;
+            }
+        }
+        Ok(BroadcastTxResponse {
+            tx_response: Some(response),
+        })
+    }
+
+    fn simulate(&self, request: SimulateRequest) -> Result<SimulateResponse, Status> {
+        let tx = TxRaw::decode(request.tx_bytes.as_slice())
+            .map_err(|e| Status::invalid_argument(format!("invalid tx: {e}")))?;
+        let (gas_wanted, gas_used) =
+            self.clone()
+                .execute(&tx, true)
+                .map_err(|(codespace, code, log)| {
+                    Status::unknown(format!("{log}: {codespace} code {code}"))
+                })?;
+        Ok(SimulateResponse {
+            gas_info: Some(GasInfo {
+                gas_wanted,
+                gas_used,
+            }),
+            result: None,
+        })
+    }
+
+    fn get_tx(&self, request: GetTxRequest) -> Result<GetTxResponse, Status> {
+        let response = self
+            .tx_responses
+            .iter()
+            .find(|response| response.txhash.eq_ignore_ascii_case(&request.hash))
+            .ok_or_else(|| Status::not_found(format!("tx not found: {}", request.hash)))?;
+        Ok(GetTxResponse {
+            tx: None,
+            tx_response: Some(response.clone()),
+        })
+    }
+
+    /// Apply a transaction's messages, returning its gas limit & the gas it used. Simulated
+    /// transactions run without a gas limit.
+    fn execute(&mut self, tx: &TxRaw, simulate: bool) -> Result<(u64, u64), TxError> {
+        let invalid = |e: prost::DecodeError| ("sdk", 2, format!("tx parse error: {e}"));
+        let auth_info = AuthInfo::decode(tx.auth_info_bytes.as_slice()).map_err(invalid)?;
+        let sequence = auth_info.signer_infos.first().map(|info| info.sequence);
+        let expected = self.transactions.len() as u64;
+        if sequence != Some(expected) {
+            let log = format!("account sequence mismatch, expected {expected}, got {sequence:?}");
+            return Err(("sdk", 32, log));
+        }
+        let body = TxBody::decode(tx.body_bytes.as_slice()).map_err(invalid)?;
+        let gas_wanted = auth_info.fee.map(|fee| fee.gas_limit).unwrap_or_default();
+        let gas_used = GAS_PER_MESSAGE * body.messages.len() as u64;
+        if gas_used > gas_wanted && !simulate {
+            let log = format!("out of gas: gasWanted: {gas_wanted}, gasUsed: {gas_used}");
+            return Err(("sdk", 11, log));
+        }
+        for msg in body.messages {
+            let value = msg.value.as_slice();
+            match msg.type_url.as_str() {
+                did::MSG_CREATE_DID_DOC => {
+                    let msg = MsgCreateDidDoc::decode(value).map_err(invalid)?;
+                    let payload = msg.payload.unwrap_or_default();
+                    if self.did_doc_versions(&payload.id).next().is_some() {
+                        return Err(("cheqd", 1200, format!("{}: DID Doc exists", payload.id)));
+                    }
+                    let metadata = DidDocMetadata {
+                        created: Some(SystemTime::now().into()),
+                        version_id: payload.version_id,
+                        ..Default::default()
+                    };
+                    let did_doc = DidDoc {
+                        context: payload.context,
+                        id: payload.id,
+                        controller: payload.controller,
+                        verification_method: payload.verification_method,
+                        authentication: payload.authentication,
+                        assertion_method: payload.assertion_method,
+                        capability_invocation: payload.capability_invocation,
+                        capability_delegation: payload.capability_delegation,
+                        key_agreement: payload.key_agreement,
+                        also_known_as: payload.also_known_as,
+                        service: payload.service,
+                    };
+                    *self = std::mem::take(self).with_did_doc(did_doc, metadata);
+                }
+                did::MSG_UPDATE_DID_DOC => {
+                    let msg = MsgUpdateDidDoc::decode(value).map_err(invalid)?;
+                    let payload = msg.payload.unwrap_or_default();
+                    let did_doc = DidDoc {
+                        context: payload.context,
+                        id: payload.id,
+                        controller: payload.controller,
+                        verification_method: payload.verification_method,
+                        authentication: payload.authentication,
+                        assertion_method: payload.assertion_method,
+                        capability_invocation: payload.capability_invocation,
+                        capability_delegation: payload.capability_delegation,
+                        key_agreement: payload.key_agreement,
+                        also_known_as: payload.also_known_as,
+                        service: payload.service,
+                    };
+                    self.add_version(did_doc, payload.version_id, false)?;
+                }
+                did::MSG_DEACTIVATE_DID_DOC => {
+                    let msg = MsgDeactivateDidDoc::decode(value).map_err(invalid)?;
+                    let payload = msg.payload.unwrap_or_default();
+                    let latest = self.did_doc_versions(&payload.id).last();
+                    let did_doc = latest.and_then(|doc| doc.did_doc.clone());
+                    let did_doc = did_doc.ok_or_else(|| did_doc_not_found(&payload.id))?;
+                    self.add_version(did_doc, payload.version_id, true)?;
+                }
+                resource::MSG_CREATE_RESOURCE => {
+                    let msg = MsgCreateResource::decode(value).map_err(invalid)?;
+                    self.add_resource(msg.payload.unwrap_or_default())?;
+                }
+                other => return Err(("sdk", 6, format!("unrecognized message type {other}"))),
+            }
+        }
+        Ok((gas_wanted, gas_used))
+    }
+
+    /// Add a resource to the collection of an existing DID, linking it to the previous version of
+    /// the resource with its name & type
+    fn add_resource(&mut self, payload: MsgCreateResourcePayload) -> Result<(), TxError> {
+        let did = self
+            .did_docs
+            .iter()
+            .filter_map(|doc| doc.did_doc.as_ref())
+            .find(|d| d.id.rsplit(':').next() == Some(payload.collection_id.as_str()))
+            .ok_or_else(|| did_doc_not_found(&payload.collection_id))?;
+        if self
+            .find_resource(&payload.collection_id, &payload.id)
+            .is_ok()
+        {
+            let log = format!("{}: resource exists", payload.id);
+            return Err(("cheqd", 1200, log));
+        }
+        let mut metadata = ResourceMetadata {
+            collection_id: payload.collection_id,
+            id: payload.id,
+            name: payload.name,
+            version: payload.version,
+            resource_type: payload.resource_type,
+            also_known_as: payload.also_known_as,
+            media_type: resource::resource_media_type(&payload.data)
+                .unwrap_or("application/octet-stream")
+                .to_owned(),
+            created: Some(SystemTime::now().into()),
+            checksum: resource::resource_checksum(&payload.data),
+            ..Default::default()
+        };
+        if metadata.also_known_as.is_empty() {
+            metadata.also_known_as.push(AlternativeUri {
+                uri: format!("{}/resources/{}", did.id, metadata.id),
+                description: "did-url".into(),
+            });
+        }
+        let previous = self
+            .resources
+            .iter_mut()
+            .filter_map(|resource| resource.metadata.as_mut())
+            .rfind(|m| {
+                m.collection_id == metadata.collection_id
+                    && m.name == metadata.name
+                    && m.resource_type == metadata.resource_type
+            });
+        if let Some(previous) = previous {
+            previous.next_version_id = metadata.id.clone();
+            metadata.previous_version_id = previous.id.clone();
+        }
+        *self = std::mem::take(self).with_resource(payload.data, metadata);
+        Ok(())
+    }
+
+    /// Add a version of an existing DID, linking it to the previous version
+    fn add_version(
+        &mut self,
+        did_doc: DidDoc,
+        version_id: String,
+        deactivate: bool,
+    ) -> Result<(), TxError> {
+        let previous = self
+            .did_docs
+            .iter_mut()
+            .rfind(|doc| doc.did_doc.as_ref().is_some_and(|d| d.id == did_doc.id))
+            .and_then(|doc| doc.metadata.as_mut())
+            .ok_or_else(|| did_doc_not_found(&did_doc.id))?;
+        if previous.deactivated {
+            return Err((
+                "cheqd",
+                2100,
+                format!("{}: DID Doc is deactivated", did_doc.id),
+            ));
+        }
+        previous.next_version_id = version_id.clone();
+        let metadata = DidDocMetadata {
+            created: previous.created,
+            updated: Some(SystemTime::now().into()),
+            deactivated: deactivate,
+            version_id,
+            previous_version_id: previous.version_id.clone(),
+            ..Default::default()
+        };
+        *self = std::mem::take(self).with_did_doc(did_doc, metadata);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "registrar")]
+fn did_doc_not_found(id: &str) -> TxError {
+    ("cheqd", 1201, format!("{id}: DID Doc not found"))
+}
+
+/// The `cosmos.auth.v1beta1.Query` service
+#[cfg(feature = "registrar")]
+#[derive(Clone)]
+struct AuthQueryServer(SharedFixtures);
+
+#[cfg(feature = "registrar")]
+impl NamedService for AuthQueryServer {
+    const NAME: &'static str = "cosmos.auth.v1beta1.Query";
+}
+
+#[cfg(feature = "registrar")]
+impl<B> Service<http::Request<B>> for AuthQueryServer
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = HttpResponseFuture;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let fixtures = self.0.clone();
+        match request.uri().path() {
+            "/cosmos.auth.v1beta1.Query/Account" => {
+                unary(request, move |r| read(&fixtures).account(r))
+            }
+            path => unimplemented(path),
+        }
+    }
+}
+
+/// The `cosmos.tx.v1beta1.Service` service
+#[cfg(feature = "registrar")]
+#[derive(Clone)]
+struct TxServer(SharedFixtures);
+
+#[cfg(feature = "registrar")]
+impl NamedService for TxServer {
+    const NAME: &'static str = "cosmos.tx.v1beta1.Service";
+}
+
+#[cfg(feature = "registrar")]
+impl<B> Service<http::Request<B>> for TxServer
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = HttpResponseFuture;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let fixtures = self.0.clone();
+        match request.uri().path() {
+            "/cosmos.tx.v1beta1.Service/BroadcastTx" => unary(request, move |r| {
+                let mut fixtures = fixtures.write().unwrap_or_else(|e| e.into_inner());
+                fixtures.broadcast_tx(r)
+            }),
+            "/cosmos.tx.v1beta1.Service/Simulate" => {
+                unary(request, move |r| read(&fixtures).simulate(r))
+            }
+            "/cosmos.tx.v1beta1.Service/GetTx" => {
+                unary(request, move |r| read(&fixtures).get_tx(r))
+            }
+            path => unimplemented(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use crate::{
+        error::DidCheqdError,
+        resolution::resolver::{DidCheqdResolver, DidCheqdResolverConfiguration},
+    };
+
+    const DID: &str = "did:cheqd:testnet:Ps1ysXP2Ae6GBfxNhNQNKN";
+
+    fn fixtures() -> LedgerFixtures {
+        let version = |version_id: &str| DidDocMetadata {
+            version_id: version_id.into(),
+            ..Default::default()
+        };
+        let resource = |id: &str, created: i64| ResourceMetadata {
+            collection_id: "Ps1ysXP2Ae6GBfxNhNQNKN".into(),
+            id: id.into(),
+            name: "schema".into(),
+            resource_type: "JsonSchema".into(),
+            media_type: "application/json".into(),
+            created: Some(prost_types::Timestamp {
+                seconds: created,
+                nanos: 0,
+            }),
+            ..Default::default()
+        };
+        let did_doc = DidDoc {
+            id: DID.into(),
+            ..Default::default()
+        };
+        LedgerFixtures::new()
+            .with_did_doc(did_doc.clone(), version("v1"))
+            .with_did_doc(did_doc, version("v2"))
+            .with_resource(b"{\"v\":1}".to_vec(), resource("r1", 1))
+            .with_resource(b"{\"v\":2}".to_vec(), resource("r2", 2))
+    }
+
+    #[tokio::test]
+    async fn test_resolve_against_mock_node() {
+        let node = MockCheqdNode::start(fixtures()).await.unwrap();
+        let resolver = DidCheqdResolver::new(DidCheqdResolverConfiguration {
+            networks: vec![node.network("testnet")],
+            ..Default::default()
+        });
+        let resolver = &resolver;
+        let resolve_version = |did_url: &str| {
+            let parsed = resolver.parse(did_url).unwrap();
+            async move {
+                let resolved = resolver.resolve_did_doc(parsed).await.unwrap();
+                resolved.metadata.unwrap().version_id
+            }
+        };
+
+        assert_eq!(resolve_version(DID).await, "v2");
+        assert_eq!(resolve_version(&format!("{DID}/versions/v1")).await, "v1");
+
+        let resource = resolver
+            .resolve_resource_json(&format!("{DID}/resources/r1"))
+            .await
+            .unwrap();
+        assert_eq!(resource["v"], 1);
+        let latest = resolver
+            .resolve_resource_json(&format!(
+                "{DID}?resourceName=schema&resourceType=JsonSchema"
+            ))
+            .await
+            .unwrap();
+        assert_eq!(latest["v"], 2);
+
+        let e = resolver
+            .resolve_resource_json(&format!("{DID}/resources/r3"))
+            .await
+            .unwrap_err();
+        assert!(matches!(e.root(), DidCheqdError::NonSuccessResponse(_)));
+        assert_eq!(e.status_code(), Some(tonic::Code::NotFound));
+    }
+
+    #[tokio::test]
+    async fn test_mock_resolver_is_interchangeable() {
+        let node = MockCheqdNode::start(fixtures()).await.unwrap();
+        let resolvers: Vec<Arc<dyn CheqdResolve>> = vec![
+            Arc::new(DidCheqdResolver::new(DidCheqdResolverConfiguration {
+                networks: vec![node.network("testnet")],
+                ..Default::default()
+            })),
+            Arc::new(MockResolver::new(fixtures())),
+        ];
+        for resolver in resolvers {
+            let resolved = resolver.resolve_did(&format!("{DID}?versionId=v1")).await;
+            assert_eq!(resolved.unwrap().metadata.unwrap().version_id, "v1");
+
+            let latest = format!("{DID}?resourceName=schema&resourceType=JsonSchema");
+            let resolved = resolver.resolve_resource_url(&latest).await.unwrap();
+            assert_eq!(resolved.metadata.id, "r2");
+
+            let resolved = resolver
+                .resolve_did_url(&format!("{DID}/resources/r1"))
+                .await;
+            assert!(matches!(resolved, Ok(Resolved::Resource(r)) if r.data == b"{\"v\":1}"));
+            let resolved = resolver.resolve_did_url(DID).await;
+            assert!(matches!(resolved, Ok(Resolved::Document(d)) if d.did_doc.id == DID));
+
+            let e = resolver
+                .resolve_did("did:cheqd:testnet:missing")
+                .await
+                .unwrap_err();
+            assert_eq!(e.status_code(), Some(tonic::Code::NotFound));
+        }
+    }
+}
@@ -0,0 +1,309 @@
+//! Maintainer tasks for `did-resolver-cheqd`.
+//!
+//! Usage: `cargo xtask <task> [--ref <buf commit>] [--feemarket-ref <buf commit>]`
+//!
+//! Tasks:
+//! - `proto-gen`: export the pinned protos from Buf, regenerate the rust code & the encoded file
+//!   descriptor set & write them into `src/proto`.
+//! - `proto-check`: regenerate into a scratch directory & diff against the checked-in code, failing
+//!   with a report if they differ.
+//!
+//! Requires the [buf](https://buf.build/docs/installation) CLI & `protoc` to be installed.
+//!
+//! The protos are exported at pinned Buf commits, which (unlike branches & labels) are immutable,
+//! so regenerating & checking the code gives the same result until a pin is bumped. To bump the
+//! cheqd protos to a newer ledger release:
+//! 1. find the commit of the release with `buf registry module commit list buf.build/cheqd/proto`
+//! 2. regenerate the code with `cargo xtask proto-gen --ref <commit>`
+//! 3. set [PINNED_PROTO_COMMIT] to the commit, and commit it with the regenerated code
+//!
+//! The feemarket protos are bumped likewise, with `--feemarket-ref <commit>` &
+//! [PINNED_FEEMARKET_COMMIT], to the commit of the feemarket version the ledger release uses.
+
+use std::{
+    collections::BTreeSet,
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode},
+};
+
+/// The Buf module containing the cheqd protos. Dependencies (cosmos, googleapis) are exported with it.
+const CHEQD_PROTO_MODULE: &str = "buf.build/cheqd/proto";
+/// The Buf commit of [CHEQD_PROTO_MODULE] the checked-in code is generated from, see the module
+/// docs for bumping it. Until it's set, `--ref <commit>` must be given.
+const PINNED_PROTO_COMMIT: Option<&str> = None;
+/// The Buf module containing the feemarket protos, for the `fees` feature. Exported alongside the
+/// cheqd protos, at the version used by the ledger.
+const FEEMARKET_PROTO_MODULE: &str = "buf.build/skip-mev/feemarket";
+/// The Buf commit of [FEEMARKET_PROTO_MODULE] the checked-in code is generated from. Until it's
+/// set, `--feemarket-ref <commit>` must be given.
+const PINNED_FEEMARKET_COMMIT: Option<&str> = None;
+/// The proto files which the crate is generated from. Their imports are generated alongside.
+const PROTO_FILES: &[&str] = &[
+    "cheqd/did/v2/query.proto",
+    "cheqd/did/v2/tx.proto",
+    "cheqd/resource/v2/query.proto",
+    "cheqd/resource/v2/tx.proto",
+    "cosmos/auth/v1beta1/query.proto",
+    "cosmos/crypto/secp256k1/keys.proto",
+    "cosmos/tx/v1beta1/service.proto",
+    "feemarket/feemarket/v1/query.proto",
+];
+
+/// The encoded file descriptor set of the proto files & their imports, embedded by the crate's
+/// `reflection` feature.
+const DESCRIPTOR_SET_FILE: &str = "file_descriptor_set.bin";
+/// The hand-written files of `src/proto`, which aren't generated.
+const HAND_WRITTEN_FILES: &[&str] = &["mod.rs", "reflection.rs"];
+
+type TaskResult<T> = Result<T, String>;
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let task = args.next();
+    let mut proto_ref = PINNED_PROTO_COMMIT.map(str::to_owned);
+    let mut feemarket_ref = PINNED_FEEMARKET_COMMIT.map(str::to_owned);
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--ref", Some(r)) => proto_ref = Some(r),
+            ("--feemarket-ref", Some(r)) => feemarket_ref = Some(r),
+            _ => return usage(),
+        }
+    }
+
+    let refs = pinned_commit(CHEQD_PROTO_MODULE, "--ref", proto_ref).and_then(|proto_ref| {
+        let feemarket_ref =
+            pinned_commit(FEEMARKET_PROTO_MODULE, "--feemarket-ref", feemarket_ref)?;
+        Ok(Refs {
+            proto: proto_ref,
+            feemarket: feemarket_ref,
+        })
+    });
+    let result = match task.as_deref() {
+        Some("proto-gen") => refs.and_then(|refs| proto_gen(&refs)),
+        Some("proto-check") => refs.and_then(|refs| proto_check(&refs)),
+        _ => return usage(),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn usage() -> ExitCode {
+    eprintln!(
+        "usage: cargo xtask <proto-gen|proto-check> [--ref <buf commit>] \
+         [--feemarket-ref <buf commit>]"
+    );
+    ExitCode::FAILURE
+}
+
+/// The Buf commits the protos are exported at
+struct Refs {
+    /// the commit of [CHEQD_PROTO_MODULE]
+    proto: String,
+    /// the commit of [FEEMARKET_PROTO_MODULE]
+    feemarket: String,
+}
+
+impl Refs {
+    /// The pinned modules, for reports
+    fn modules(&self) -> String {
+        format!(
+            "{CHEQD_PROTO_MODULE}:{} & {FEEMARKET_PROTO_MODULE}:{}",
+            self.proto, self.feemarket
+        )
+    }
+}
+
+/// The Buf commit a module is exported at, refusing branches & labels, which move as the module
+/// is updated
+fn pinned_commit(module: &str, flag: &str, commit: Option<String>) -> TaskResult<String> {
+    let Some(commit) = commit else {
+        return Err(format!(
+            "no commit of {module} is pinned, give one with `{flag} <commit>` (see the xtask docs)"
+        ));
+    };
+    // Buf commit ids are 32 lowercase hex digits
+    let is_commit = commit.len() == 32
+        && commit
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, 'a'..='f'));
+    match is_commit {
+        true => Ok(commit),
+        false => Err(format!(
+            "`{commit}` is not a Buf commit of {module}; pin a commit (listed by \
+             `buf registry module commit list {module}`) so the generated code doesn't follow a \
+             moving branch or label"
+        )),
+    }
+}
+
+/// Regenerate the proto code into `src/proto`
+fn proto_gen(refs: &Refs) -> TaskResult<()> {
+    let scratch = scratch_dir()?;
+    let generated = generate(&scratch, refs)?;
+    let target = checked_in_dir();
+    for file in proto_files(&generated)? {
+        fs::copy(generated.join(&file), target.join(&file))
+            .map_err(|e| format!("failed to copy {file}: {e}"))?;
+        println!("wrote src/proto/{file}");
+    }
+    let _ = fs::remove_dir_all(scratch);
+    Ok(())
+}
+
+/// Regenerate the proto code into a scratch dir and compare against `src/proto`
+fn proto_check(refs: &Refs) -> TaskResult<()> {
+    let scratch = scratch_dir()?;
+    let generated = generate(&scratch, refs)?;
+    let report = diff_dirs(&generated, &checked_in_dir())?;
+    let _ = fs::remove_dir_all(scratch);
+
+    if report.is_empty() {
+        println!(
+            "checked-in proto code is up to date with {}",
+            refs.modules()
+        );
+        return Ok(());
+    }
+
+    println!("checked-in proto code differs from {}\n", refs.modules());
+    for line in &report {
+        println!("{line}");
+    }
+    println!(
+        "\nrun `cargo xtask proto-gen --ref {} --feemarket-ref {}` to update the checked-in code",
+        refs.proto, refs.feemarket
+    );
+    Err(format!("{} file(s) out of date", report.len()))
+}
+
+/// Export the protos from Buf & compile them with tonic-build. Returns the output dir.
+fn generate(scratch: &Path, refs: &Refs) -> TaskResult<PathBuf> {
+    let protos = scratch.join("protos");
+    let out = scratch.join("out");
+    fs::create_dir_all(&out).map_err(|e| format!("failed to create {}: {e}", out.display()))?;
+
+    for module in [
+        format!("{CHEQD_PROTO_MODULE}:{}", refs.proto),
+        format!("{FEEMARKET_PROTO_MODULE}:{}", refs.feemarket),
+    ] {
+        println!("exporting {module}");
+        let status = Command::new("buf")
+            .args(["export", &module, "--output"])
+            .arg(&protos)
+            .status()
+            .map_err(|e| format!("failed to run `buf` (is it installed?): {e}"))?;
+        if !status.success() {
+            return Err(format!("`buf export {module}` failed with {status}"));
+        }
+    }
+
+    let files: Vec<PathBuf> = PROTO_FILES.iter().map(|f| protos.join(f)).collect();
+    tonic_build::configure()
+        .build_server(false)
+        .build_client(true)
+        .out_dir(&out)
+        .file_descriptor_set_path(out.join(DESCRIPTOR_SET_FILE))
+        .compile_protos(&files, &[&protos])
+        .map_err(|e| format!("failed to compile protos (is `protoc` installed?): {e}"))?;
+
+    Ok(out)
+}
+
+/// Compare the generated files against the checked-in ones, producing one report line per
+/// differing file.
+fn diff_dirs(generated: &Path, checked_in: &Path) -> TaskResult<Vec<String>> {
+    let generated_files = proto_files(generated)?;
+    let checked_in_files = proto_files(checked_in)?;
+    let mut report = Vec::new();
+
+    for file in generated_files.union(&checked_in_files) {
+        match (
+            generated_files.contains(file),
+            checked_in_files.contains(file),
+        ) {
+            (true, false) => report.push(format!("  added:    {file}")),
+            (false, true) if HAND_WRITTEN_FILES.contains(&file.as_str()) => {}
+            (false, true) => report.push(format!("  removed:  {file}")),
+            _ if file == DESCRIPTOR_SET_FILE => {
+                let new = read_bytes(&generated.join(file))?;
+                let old = read_bytes(&checked_in.join(file))?;
+                if new != old {
+                    report.push(format!(
+                        "  changed:  {file} ({} -> {} bytes)",
+                        old.len(),
+                        new.len()
+                    ));
+                }
+            }
+            _ => {
+                let new = read(&generated.join(file))?;
+                let old = read(&checked_in.join(file))?;
+                if let Some(summary) = diff_summary(&old, &new) {
+                    report.push(format!("  changed:  {file} ({summary})"));
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Summarise the differences between two files, or `None` if they are identical
+fn diff_summary(old: &str, new: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let first_diff = old_lines
+        .iter()
+        .zip(&new_lines)
+        .position(|(a, b)| a != b)
+        .unwrap_or(old_lines.len().min(new_lines.len()));
+    Some(format!(
+        "{} -> {} lines, first difference at line {}",
+        old_lines.len(),
+        new_lines.len(),
+        first_diff + 1
+    ))
+}
+
+/// The rust files & descriptor set in a directory
+fn proto_files(dir: &Path) -> TaskResult<BTreeSet<String>> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("failed to read {}: {e}", dir.display()))?;
+    Ok(entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(".rs") || name == DESCRIPTOR_SET_FILE)
+        .collect())
+}
+
+fn read(path: &Path) -> TaskResult<String> {
+    fs::read_to_string(path).map_err(|e| format!("failed to read {}: {e}", path.display()))
+}
+
+fn read_bytes(path: &Path) -> TaskResult<Vec<u8>> {
+    fs::read(path).map_err(|e| format!("failed to read {}: {e}", path.display()))
+}
+
+fn checked_in_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("src")
+        .join("proto")
+}
+
+fn scratch_dir() -> TaskResult<PathBuf> {
+    let dir = env::temp_dir().join(format!("cheqd-proto-gen-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+    Ok(dir)
+}